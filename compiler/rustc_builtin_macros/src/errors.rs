@@ -666,6 +666,14 @@ pub(crate) struct TestCaseNonItem {
     pub(crate) span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(builtin_macros_test_timeout_invalid)]
+pub(crate) struct TestTimeoutInvalid {
+    #[primary_span]
+    #[label]
+    pub(crate) span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(builtin_macros_test_bad_fn)]
 pub(crate) struct TestBadFn {
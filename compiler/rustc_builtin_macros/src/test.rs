@@ -359,6 +359,15 @@ pub(crate) fn expand_test_or_bench(
                                             }
                                         },
                                     ),
+                                    // timeout: Some(secs) | None
+                                    field(
+                                        "timeout",
+                                        if let Some(secs) = test_timeout(cx, &item) {
+                                            cx.expr_some(sp, cx.expr_u64(sp, secs))
+                                        } else {
+                                            cx.expr_none(sp)
+                                        },
+                                    ),
                                     // },
                                 ],
                             ),
@@ -377,6 +386,17 @@ pub(crate) fn expand_test_or_bench(
         tc
     });
 
+    // Move `#[test_timeout]` to the test case, so that any left on a function marks it as not
+    // being a test.
+    let mut item = item;
+    if let Some(i) = item.attrs.iter().position(|attr| attr.has_name(sym::test_timeout)) {
+        let attr = item.attrs.remove(i);
+        test_const = test_const.map(|mut tc| {
+            tc.attrs.push(attr);
+            tc
+        });
+    }
+
     // extern crate test
     let test_extern = cx.item(sp, test_id, ast::AttrVec::new(), ast::ItemKind::ExternCrate(None));
 
@@ -514,6 +534,26 @@ fn should_panic(cx: &ExtCtxt<'_>, i: &ast::Item) -> ShouldPanic {
     }
 }
 
+/// Returns the time limit set with `#[test_timeout(secs)]`, if any.
+fn test_timeout(cx: &ExtCtxt<'_>, i: &ast::Item) -> Option<u64> {
+    let attr = attr::find_by_name(&i.attrs, sym::test_timeout)?;
+    let secs = match attr.meta_item_list().as_deref() {
+        Some(
+            [
+                ast::NestedMetaItem::Lit(ast::MetaItemLit {
+                    kind: ast::LitKind::Int(secs, ast::LitIntType::Unsuffixed),
+                    ..
+                }),
+            ],
+        ) => u64::try_from(secs.get()).ok().filter(|&secs| secs > 0),
+        _ => None,
+    };
+    if secs.is_none() {
+        cx.dcx().emit_err(errors::TestTimeoutInvalid { span: attr.span });
+    }
+    secs
+}
+
 enum TestType {
     UnitTest,
     IntegrationTest,
@@ -19,6 +19,7 @@
 mod shared_utils;
 mod tests;
 mod utils;
+mod x86_intrinsics;
 
 fn usage() {
     eprintln!("{}", include_str!("usage.txt"));
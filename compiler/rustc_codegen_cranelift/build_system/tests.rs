@@ -1,7 +1,7 @@
 use std::ffi::OsStr;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
 
 use crate::build_sysroot;
 use crate::config;
@@ -10,6 +10,7 @@
 use crate::rustc_info::get_default_sysroot;
 use crate::shared_utils::rustflags_from_env;
 use crate::utils::{spawn_and_wait, CargoProject, Compiler, LogGroup};
+use crate::x86_intrinsics;
 use crate::{CodegenBackend, SysrootKind};
 
 static BUILD_EXAMPLE_OUT_DIR: RelPath = RelPath::BUILD.join("example");
@@ -99,6 +100,59 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
         runner.run_out_command("polymorphize_coroutine", &[]);
     }),
     TestCase::build_bin_and_run("aot.neon", "example/neon.rs", &[]),
+    TestCase::build_bin_and_run("aot.x86_simd", "example/x86_simd.rs", &[]),
+    TestCase::custom("aot.x86_intrinsics", &|runner| {
+        if !runner.target_compiler.triple.starts_with("x86_64") {
+            eprintln!("Skipping x86_intrinsics test for non-x86_64 target");
+            return;
+        }
+
+        let out_dir = BUILD_EXAMPLE_OUT_DIR.to_path(&runner.dirs);
+        let source = out_dir.join("x86_intrinsics.rs");
+        fs::write(
+            &source,
+            x86_intrinsics::generate(
+                &runner.stdlib_source.join("library/stdarch/crates/core_arch/src"),
+            ),
+        )
+        .unwrap();
+        runner.run_rustc([&source]);
+
+        if !runner.is_native {
+            runner.run_out_command("x86_intrinsics", &[]);
+            return;
+        }
+
+        // Compare the results with those of the same program compiled by LLVM.
+        let llvm_bin = out_dir.join("x86_intrinsics_llvm");
+        let mut llvm_build = Command::new(&runner.host_compiler.rustc);
+        llvm_build.arg(&source).arg("-o").arg(&llvm_bin);
+        spawn_and_wait(llvm_build);
+
+        let run = |bin: &Path| {
+            let output = Command::new(bin).output().unwrap();
+            if !output.status.success() {
+                eprintln!("{bin:?} exited with status {:?}", output.status);
+                process::exit(1);
+            }
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let clif_output = run(&out_dir.join("x86_intrinsics"));
+        let llvm_output = run(&llvm_bin);
+
+        let mut mismatches = 0;
+        for (clif, llvm) in clif_output.lines().zip(llvm_output.lines()) {
+            if clif != llvm {
+                eprintln!("cg_clif: {clif}\nllvm:    {llvm}");
+                mismatches += 1;
+            }
+        }
+        assert_eq!(clif_output.lines().count(), llvm_output.lines().count());
+        if mismatches != 0 {
+            eprintln!("{mismatches} intrinsics returned different results than with LLVM");
+            process::exit(1);
+        }
+    }),
     TestCase::custom("aot.gen_block_iterate", &|runner| {
         runner.run_rustc([
             "example/gen_block_iterate.rs",
@@ -272,6 +326,7 @@ pub(crate) fn run_tests(
         let runner = TestRunner::new(
             dirs.clone(),
             target_compiler,
+            bootstrap_host_compiler.clone(),
             use_unstable_features,
             skip_tests,
             bootstrap_host_compiler.triple == target_triple,
@@ -303,6 +358,7 @@ pub(crate) fn run_tests(
         let mut runner = TestRunner::new(
             dirs.clone(),
             target_compiler,
+            bootstrap_host_compiler.clone(),
             use_unstable_features,
             skip_tests,
             bootstrap_host_compiler.triple == target_triple,
@@ -332,6 +388,7 @@ struct TestRunner<'a> {
     skip_tests: &'a [&'a str],
     dirs: Dirs,
     target_compiler: Compiler,
+    host_compiler: Compiler,
     stdlib_source: PathBuf,
 }
 
@@ -339,6 +396,7 @@ impl<'a> TestRunner<'a> {
     fn new(
         dirs: Dirs,
         mut target_compiler: Compiler,
+        host_compiler: Compiler,
         use_unstable_features: bool,
         skip_tests: &'a [&'a str],
         is_native: bool,
@@ -360,7 +418,15 @@ fn new(
             && target_compiler.triple.contains("x86_64")
             && !target_compiler.triple.contains("windows");
 
-        Self { is_native, jit_supported, skip_tests, dirs, target_compiler, stdlib_source }
+        Self {
+            is_native,
+            jit_supported,
+            skip_tests,
+            dirs,
+            target_compiler,
+            host_compiler,
+            stdlib_source,
+        }
     }
 
     fn run_testsuite(&self, tests: &[TestCase]) {
@@ -0,0 +1,301 @@
+//! Generates a test program calling every stable `sse` through `avx2` intrinsic exported by
+//! `core::arch::x86_64`.
+//!
+//! The signatures are read from the `x86` and `x86_64` modules of the stdarch sources of the
+//! sysroot. Each intrinsic is called once with deterministic arguments and the bytes of its result
+//! (and of any memory it writes to) are printed, so that the output of a cg_clif build can be
+//! compared with the output of an LLVM build.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const MODULES: &[&str] = &["sse", "sse2", "sse3", "ssse3", "sse41", "sse42", "avx", "avx2"];
+
+/// Intrinsics whose result is only an approximation, which may differ between implementations.
+const APPROXIMATE: &[&str] = &["_mm_rcp_", "_mm_rsqrt_", "_mm256_rcp_", "_mm256_rsqrt_"];
+
+const PRELUDE: &str = r#"#![allow(deprecated, unused_mut, unused_unsafe)]
+
+use std::arch::x86_64::*;
+use std::mem::size_of;
+
+const FLOATS: [f64; 8] = [1.5, -2.25, 3.0, -0.5, 100.75, 0.125, -7.0, 2.5];
+
+#[repr(C, align(64))]
+struct Mem([u8; 64]);
+
+impl Mem {
+    fn new(seed: usize) -> Mem {
+        let mut mem = Mem([0; 64]);
+        for (i, chunk) in mem.0.chunks_mut(4).enumerate() {
+            chunk.copy_from_slice(&(FLOATS[(seed + i) % FLOATS.len()] as f32).to_le_bytes());
+        }
+        mem
+    }
+}
+
+trait Arg {
+    fn arg(seed: usize) -> Self;
+}
+
+macro_rules! int_args {
+    ($($ty:ty),*) => {$(
+        impl Arg for $ty {
+            fn arg(seed: usize) -> Self {
+                (seed * 7 + 3) as $ty
+            }
+        }
+    )*};
+}
+
+int_args!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl Arg for f32 {
+    fn arg(seed: usize) -> Self {
+        FLOATS[seed % FLOATS.len()] as f32
+    }
+}
+
+impl Arg for f64 {
+    fn arg(seed: usize) -> Self {
+        FLOATS[seed % FLOATS.len()]
+    }
+}
+
+macro_rules! float_vector_args {
+    ($($ty:ty: $elem:ty),*) => {$(
+        impl Arg for $ty {
+            fn arg(seed: usize) -> Self {
+                let mut lanes = [0.0; size_of::<$ty>() / size_of::<$elem>()];
+                for (i, lane) in lanes.iter_mut().enumerate() {
+                    *lane = FLOATS[(seed * 3 + i) % FLOATS.len()] as $elem;
+                }
+                unsafe { std::mem::transmute::<[$elem; size_of::<$ty>() / size_of::<$elem>()], $ty>(lanes) }
+            }
+        }
+    )*};
+}
+
+float_vector_args!(__m128: f32, __m128d: f64, __m256: f32, __m256d: f64);
+
+macro_rules! int_vector_args {
+    ($($ty:ty),*) => {$(
+        impl Arg for $ty {
+            fn arg(seed: usize) -> Self {
+                let mut bytes = [0u8; size_of::<$ty>()];
+                for (i, byte) in bytes.iter_mut().enumerate() {
+                    *byte = (i * 0x9d + seed * 0x31 + 7) as u8;
+                }
+                unsafe { std::mem::transmute::<[u8; size_of::<$ty>()], $ty>(bytes) }
+            }
+        }
+    )*};
+}
+
+int_vector_args!(__m128i, __m256i);
+
+fn hex<T>(value: &T) -> String {
+    let bytes =
+        unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) };
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn main() {
+"#;
+
+struct Intrinsic {
+    name: String,
+    const_params: usize,
+    params: Vec<String>,
+    features: Vec<String>,
+}
+
+pub(crate) fn generate(core_arch_source_dir: &Path) -> String {
+    let mut intrinsics = vec![];
+    for arch in ["x86", "x86_64"] {
+        for module in MODULES {
+            let path = core_arch_source_dir.join(arch).join(format!("{module}.rs"));
+            // Not every module has 64-bit only intrinsics.
+            if let Ok(source) = fs::read_to_string(&path) {
+                intrinsics.extend(parse_intrinsics(&source));
+            }
+        }
+    }
+    assert!(!intrinsics.is_empty(), "no intrinsics found in {}", core_arch_source_dir.display());
+
+    let mut program = PRELUDE.to_owned();
+    for intrinsic in &intrinsics {
+        write_call(&mut program, intrinsic);
+    }
+    program.push_str("}\n");
+    program
+}
+
+fn parse_intrinsics(source: &str) -> Vec<Intrinsic> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut intrinsics = vec![];
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(rest) =
+            line.strip_prefix("pub unsafe fn ").or_else(|| line.strip_prefix("pub fn "))
+        else {
+            continue;
+        };
+
+        // Attributes are the lines directly above the signature.
+        let attrs = lines[..i]
+            .iter()
+            .rev()
+            .take_while(|line| {
+                !line.is_empty() && !line.starts_with('}') && !line.starts_with("//")
+            })
+            .copied()
+            .collect::<String>();
+        if attrs.contains("#[unstable(") {
+            continue;
+        }
+        let features = match attrs.split_once("target_feature(enable = \"") {
+            Some((_, features)) => features
+                .split('"')
+                .next()
+                .unwrap()
+                .split(',')
+                .map(|f| f.trim().to_owned())
+                .collect(),
+            None => continue,
+        };
+
+        // The signature may be split across lines, but never contains a `{`.
+        let mut signature = rest.to_owned();
+        for line in &lines[i + 1..] {
+            if signature.contains('{') {
+                break;
+            }
+            signature.push(' ');
+            signature.push_str(line.trim());
+        }
+        let signature = signature.split('{').next().unwrap();
+
+        if let Some(intrinsic) = parse_signature(signature, features) {
+            intrinsics.push(intrinsic);
+        }
+    }
+
+    intrinsics
+}
+
+fn parse_signature(signature: &str, features: Vec<String>) -> Option<Intrinsic> {
+    let name_end = signature.find(['<', '('])?;
+    let name = signature[..name_end].trim().to_owned();
+    if name.starts_with("_MM_")
+        || name.contains("undefined")
+        || name.contains("csr")
+        || name.contains("gather")
+    {
+        return None;
+    }
+
+    let mut rest = &signature[name_end..];
+    let mut const_params = 0;
+    if let Some(generics) = rest.strip_prefix('<') {
+        let (generics, after) = generics.split_once('>')?;
+        for param in generics.split(',').map(str::trim).filter(|param| !param.is_empty()) {
+            if !param.starts_with("const ") {
+                return None;
+            }
+            const_params += 1;
+        }
+        rest = after;
+    }
+
+    let params = rest.trim_start().strip_prefix('(')?.split_once(')')?.0;
+    let params = params
+        .split(',')
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .map(|param| Some(param.split_once(':')?.1.trim().to_owned()))
+        .collect::<Option<Vec<_>>>()?;
+    if !params.iter().all(|ty| is_supported_type(ty)) {
+        return None;
+    }
+
+    Some(Intrinsic { name, const_params, params, features })
+}
+
+fn is_supported_type(ty: &str) -> bool {
+    let ty = ty
+        .strip_prefix("*const ")
+        .or_else(|| ty.strip_prefix("*mut "))
+        .or_else(|| ty.strip_prefix('&'))
+        .unwrap_or(ty)
+        .trim();
+    matches!(
+        ty,
+        "i8" | "i16"
+            | "i32"
+            | "i64"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "f32"
+            | "f64"
+            | "__m128"
+            | "__m128d"
+            | "__m128i"
+            | "__m256"
+            | "__m256d"
+            | "__m256i"
+    )
+}
+
+fn write_call(program: &mut String, intrinsic: &Intrinsic) {
+    let Intrinsic { name, const_params, params, features } = intrinsic;
+
+    let condition = features
+        .iter()
+        .map(|feature| format!("is_x86_feature_detected!(\"{feature}\")"))
+        .collect::<Vec<_>>()
+        .join(" && ");
+    writeln!(program, "    if {condition} {{").unwrap();
+
+    let mut args = vec![];
+    let mut mems = vec![];
+    for (i, ty) in params.iter().enumerate() {
+        if ty.starts_with('*') {
+            writeln!(program, "        let mut mem{i} = Mem::new({i});").unwrap();
+            args.push(format!("mem{i}.0.as_mut_ptr() as {ty}"));
+            if ty.starts_with("*mut ") {
+                mems.push(format!("mem{i}"));
+            }
+        } else if let Some(ty) = ty.strip_prefix('&') {
+            args.push(format!("&<{ty} as Arg>::arg({i})"));
+        } else {
+            args.push(format!("<{ty} as Arg>::arg({i})"));
+        }
+    }
+
+    let generics = if *const_params == 0 {
+        String::new()
+    } else {
+        format!("::<{}>", vec!["1"; *const_params].join(", "))
+    };
+    writeln!(program, "        let result = unsafe {{ {name}{generics}({}) }};", args.join(", "))
+        .unwrap();
+
+    if APPROXIMATE.iter().any(|prefix| name.starts_with(prefix)) {
+        writeln!(program, "        std::hint::black_box(result);").unwrap();
+        writeln!(program, "        println!(\"{name}: approximate\");").unwrap();
+    } else {
+        let mut format = format!("{name}: {{}}");
+        let mut format_args = "hex(&result)".to_owned();
+        for mem in mems {
+            format.push_str(" {}");
+            write!(format_args, ", hex(&{mem}.0)").unwrap();
+        }
+        writeln!(program, "        println!(\"{format}\", {format_args});").unwrap();
+    }
+
+    writeln!(program, "    }}").unwrap();
+}
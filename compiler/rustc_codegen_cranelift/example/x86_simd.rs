@@ -0,0 +1,812 @@
+// Most of these tests are adapted from the tests of https://github.com/rust-lang/stdarch/tree/master/crates/core_arch/src/x86
+// They cover the `core::arch::x86_64` intrinsics which are implemented using `llvm.x86.*`
+// intrinsics rather than the portable `simd_*` intrinsics.
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+#[cfg(target_arch = "x86_64")]
+use std::mem::transmute;
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_eq_m128(a: __m128, b: __m128) {
+    assert_eq!(transmute::<_, [u32; 4]>(a), transmute::<_, [u32; 4]>(b), "{a:?} != {b:?}");
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_eq_m128d(a: __m128d, b: __m128d) {
+    assert_eq!(transmute::<_, [u64; 2]>(a), transmute::<_, [u64; 2]>(b), "{a:?} != {b:?}");
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_eq_m128i(a: __m128i, b: __m128i) {
+    assert_eq!(transmute::<_, [u64; 2]>(a), transmute::<_, [u64; 2]>(b), "{a:?} != {b:?}");
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_eq_m256(a: __m256, b: __m256) {
+    assert_eq!(transmute::<_, [u32; 8]>(a), transmute::<_, [u32; 8]>(b), "{a:?} != {b:?}");
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_eq_m256d(a: __m256d, b: __m256d) {
+    assert_eq!(transmute::<_, [u64; 4]>(a), transmute::<_, [u64; 4]>(b), "{a:?} != {b:?}");
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_eq_m256i(a: __m256i, b: __m256i) {
+    assert_eq!(transmute::<_, [u64; 4]>(a), transmute::<_, [u64; 4]>(b), "{a:?} != {b:?}");
+}
+
+// The hardware only computes an approximation with a relative error of at most 1.5 * 2^-12.
+#[cfg(target_arch = "x86_64")]
+unsafe fn assert_approx_eq_f32<const N: usize>(a: [f32; N], b: [f32; N]) {
+    for (a, b) in a.into_iter().zip(b) {
+        assert!((a - b).abs() <= b.abs() * 0.001, "{a} != {b}");
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+unsafe fn test_mm_max_min_ps() {
+    // `maxps` and `minps` return the second operand when either operand is NaN or both are zero.
+    let a = _mm_setr_ps(f32::NAN, 1.0, -0.0, 3.0);
+    let b = _mm_setr_ps(1.0, f32::NAN, 0.0, 2.0);
+    assert_eq_m128(_mm_max_ps(a, b), _mm_setr_ps(1.0, f32::NAN, 0.0, 3.0));
+    assert_eq_m128(_mm_min_ps(a, b), _mm_setr_ps(1.0, f32::NAN, 0.0, 2.0));
+
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_setr_ps(5.0, 6.0, 7.0, 8.0);
+    assert_eq_m128(_mm_max_ss(a, b), _mm_setr_ps(5.0, 2.0, 3.0, 4.0));
+    assert_eq_m128(_mm_min_ss(a, b), _mm_setr_ps(1.0, 2.0, 3.0, 4.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+unsafe fn test_mm_rcp_rsqrt_sqrt() {
+    let r: [f32; 4] = transmute(_mm_rcp_ps(_mm_setr_ps(1.0, 2.0, 4.0, 8.0)));
+    assert_approx_eq_f32(r, [1.0, 0.5, 0.25, 0.125]);
+
+    let r: [f32; 4] = transmute(_mm_rsqrt_ps(_mm_setr_ps(1.0, 4.0, 16.0, 64.0)));
+    assert_approx_eq_f32(r, [1.0, 0.5, 0.25, 0.125]);
+
+    let r: [f32; 4] = transmute(_mm_rcp_ss(_mm_setr_ps(2.0, 5.0, 6.0, 7.0)));
+    assert_approx_eq_f32(r, [0.5, 5.0, 6.0, 7.0]);
+
+    let r = _mm_sqrt_ss(_mm_setr_ps(4.0, 9.0, 16.0, 25.0));
+    assert_eq_m128(r, _mm_setr_ps(2.0, 9.0, 16.0, 25.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+unsafe fn test_mm_cmp_ps() {
+    let a = _mm_setr_ps(1.0, 5.0, f32::NAN, 3.0);
+    let b = _mm_setr_ps(2.0, 5.0, 1.0, 4.0);
+    assert_eq!(transmute::<_, [u32; 4]>(_mm_cmplt_ps(a, b)), [!0, 0, 0, !0]);
+    assert_eq!(transmute::<_, [u32; 4]>(_mm_cmpunord_ps(a, b)), [0, 0, !0, 0]);
+    assert_eq!(transmute::<_, [u32; 4]>(_mm_cmpnge_ps(a, b)), [!0, 0, !0, !0]);
+
+    let r = _mm_cmplt_ss(_mm_setr_ps(1.0, 2.0, 3.0, 4.0), _mm_setr_ps(5.0, 0.0, 0.0, 0.0));
+    assert_eq!(
+        transmute::<_, [u32; 4]>(r),
+        [!0, 2.0f32.to_bits(), 3.0f32.to_bits(), 4.0f32.to_bits()]
+    );
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+unsafe fn test_mm_comi_ss() {
+    let one = _mm_setr_ps(1.0, 0.0, 0.0, 0.0);
+    let two = _mm_setr_ps(2.0, 0.0, 0.0, 0.0);
+    let nan = _mm_setr_ps(f32::NAN, 0.0, 0.0, 0.0);
+
+    assert_eq!(_mm_comieq_ss(one, one), 1);
+    assert_eq!(_mm_comieq_ss(nan, nan), 0);
+    assert_eq!(_mm_comilt_ss(one, two), 1);
+    assert_eq!(_mm_comile_ss(two, one), 0);
+    assert_eq!(_mm_comigt_ss(two, one), 1);
+    assert_eq!(_mm_comige_ss(nan, one), 0);
+    assert_eq!(_mm_comineq_ss(nan, one), 1);
+    assert_eq!(_mm_ucomieq_ss(one, one), 1);
+    assert_eq!(_mm_ucomilt_ss(nan, one), 0);
+    assert_eq!(_mm_ucomineq_ss(one, one), 0);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+unsafe fn test_mm_cvtss_si() {
+    assert_eq!(_mm_cvtss_si32(_mm_setr_ps(1.5, 0.0, 0.0, 0.0)), 2);
+    assert_eq!(_mm_cvtss_si32(_mm_setr_ps(2.5, 0.0, 0.0, 0.0)), 2);
+    assert_eq!(_mm_cvtss_si32(_mm_setr_ps(-1.5, 0.0, 0.0, 0.0)), -2);
+    assert_eq!(_mm_cvtss_si32(_mm_setr_ps(f32::NAN, 0.0, 0.0, 0.0)), i32::MIN);
+    assert_eq!(_mm_cvtss_si32(_mm_setr_ps(3e9, 0.0, 0.0, 0.0)), i32::MIN);
+    assert_eq!(_mm_cvttss_si32(_mm_setr_ps(-1.9, 0.0, 0.0, 0.0)), -1);
+    assert_eq!(_mm_cvttss_si32(_mm_setr_ps(3e9, 0.0, 0.0, 0.0)), i32::MIN);
+    assert_eq!(_mm_cvtss_si64(_mm_setr_ps(-2.5, 0.0, 0.0, 0.0)), -2);
+    assert_eq!(_mm_cvtss_si64(_mm_setr_ps(1e20, 0.0, 0.0, 0.0)), i64::MIN);
+    assert_eq!(_mm_cvttss_si64(_mm_setr_ps(2.9, 0.0, 0.0, 0.0)), 2);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse")]
+#[allow(deprecated)]
+unsafe fn test_mm_getcsr_setcsr() {
+    let csr = _mm_getcsr();
+    _mm_setcsr(csr);
+    assert_eq!(_mm_getcsr(), csr);
+    _mm_sfence();
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn test_mm_sll_srl_sra() {
+    let a = _mm_setr_epi16(1, 2, -1, 0x4000, 0, 0, 0, 0);
+    let r = _mm_sll_epi16(a, _mm_set_epi64x(0, 4));
+    assert_eq_m128i(r, _mm_setr_epi16(16, 32, -16, 0, 0, 0, 0, 0));
+    // Only the lower 64 bits of the count are used.
+    let r = _mm_sll_epi16(a, _mm_set_epi64x(1, 4));
+    assert_eq_m128i(r, _mm_setr_epi16(16, 32, -16, 0, 0, 0, 0, 0));
+    let r = _mm_sll_epi16(a, _mm_set_epi64x(0, 16));
+    assert_eq_m128i(r, _mm_setzero_si128());
+
+    let a = _mm_setr_epi32(-16, 16, -1, i32::MIN);
+    let r = _mm_sra_epi32(a, _mm_set_epi64x(0, 2));
+    assert_eq_m128i(r, _mm_setr_epi32(-4, 4, -1, i32::MIN >> 2));
+    let r = _mm_sra_epi32(a, _mm_set_epi64x(0, 40));
+    assert_eq_m128i(r, _mm_setr_epi32(-1, 0, -1, -1));
+
+    let a = _mm_set_epi64x(-1, 16);
+    let r = _mm_srl_epi64(a, _mm_set_epi64x(0, 4));
+    assert_eq_m128i(r, _mm_set_epi64x(0x0fff_ffff_ffff_ffff, 1));
+    let r = _mm_srl_epi64(a, _mm_set_epi64x(0, 64));
+    assert_eq_m128i(r, _mm_setzero_si128());
+
+    let r = _mm_slli_epi32::<3>(_mm_setr_epi32(1, -1, 2, 0));
+    assert_eq_m128i(r, _mm_setr_epi32(8, -8, 16, 0));
+    let r = _mm_srai_epi16::<20>(_mm_setr_epi16(-5, 5, 0, 0, 0, 0, 0, 0));
+    assert_eq_m128i(r, _mm_setr_epi16(-1, 0, 0, 0, 0, 0, 0, 0));
+    let r = _mm_srli_epi16::<4>(_mm_setr_epi16(-1, 0x10, 0, 0, 0, 0, 0, 0));
+    assert_eq_m128i(r, _mm_setr_epi16(0x0fff, 1, 0, 0, 0, 0, 0, 0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn test_mm_cvtpd() {
+    let r = _mm_cvtpd_epi32(_mm_setr_pd(1.5, -2.5));
+    assert_eq_m128i(r, _mm_setr_epi32(2, -2, 0, 0));
+    let r = _mm_cvttpd_epi32(_mm_setr_pd(f64::NAN, -1.9));
+    assert_eq_m128i(r, _mm_setr_epi32(i32::MIN, -1, 0, 0));
+
+    assert_eq!(_mm_cvtsd_si32(_mm_setr_pd(-1.5, 0.0)), -2);
+    assert_eq!(_mm_cvtsd_si32(_mm_setr_pd(f64::NAN, 0.0)), i32::MIN);
+    assert_eq!(_mm_cvttsd_si32(_mm_setr_pd(1e10, 0.0)), i32::MIN);
+    assert_eq!(_mm_cvtsd_si64(_mm_setr_pd(1e10, 0.0)), 10_000_000_000);
+    assert_eq!(_mm_cvttsd_si64(_mm_setr_pd(-1.9, 0.0)), -1);
+
+    let r = _mm_cvtsd_ss(_mm_setr_ps(1.0, 2.0, 3.0, 4.0), _mm_setr_pd(5.5, 6.0));
+    assert_eq_m128(r, _mm_setr_ps(5.5, 2.0, 3.0, 4.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn test_mm_maskmoveu_si128() {
+    let a = _mm_set1_epi8(9);
+    #[rustfmt::skip]
+    let mask = _mm_setr_epi8(!0, 0, !0, 0, !0, 0, !0, 0, !0, 0, !0, 0, !0, 0, !0, 0);
+    let mut r = [0i8; 16];
+    _mm_maskmoveu_si128(a, mask, r.as_mut_ptr());
+    assert_eq!(r, [9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn test_mm_clflush_fences() {
+    let x = 0u8;
+    _mm_clflush(&x as *const u8);
+    _mm_lfence();
+    _mm_mfence();
+    assert_eq!(_mm_movemask_pd(_mm_setr_pd(-1.0, 5.0)), 0b01);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse3")]
+unsafe fn test_mm_hadd_addsub() {
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_setr_ps(5.0, 6.0, 7.0, 8.0);
+    assert_eq_m128(_mm_hadd_ps(a, b), _mm_setr_ps(3.0, 7.0, 11.0, 15.0));
+    assert_eq_m128(_mm_addsub_ps(a, b), _mm_setr_ps(-4.0, 8.0, -4.0, 12.0));
+    assert_eq_m128d(
+        _mm_hsub_pd(_mm_setr_pd(1.0, 2.0), _mm_setr_pd(5.0, 3.0)),
+        _mm_setr_pd(-1.0, 2.0),
+    );
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn test_mm_sign() {
+    let a = _mm_setr_epi8(1, -2, 3, -4, 5, 6, 7, 8, 1, 1, 1, 1, 1, 1, 1, 1);
+    let b = _mm_setr_epi8(-1, -1, 0, 0, 1, 1, -1, 1, 0, 0, 0, 0, 0, 0, 0, 0);
+    let r = _mm_sign_epi8(a, b);
+    assert_eq_m128i(r, _mm_setr_epi8(-1, 2, 0, 0, 5, 6, -7, 8, 0, 0, 0, 0, 0, 0, 0, 0));
+
+    let r = _mm_sign_epi32(_mm_setr_epi32(1, -2, 3, i32::MIN), _mm_setr_epi32(-1, -1, 1, -1));
+    assert_eq_m128i(r, _mm_setr_epi32(-1, 2, 3, i32::MIN));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn test_mm_hadd_epi() {
+    let a = _mm_setr_epi16(32767, 1, -32768, -1, 1, 2, 3, 4);
+    let b = _mm_setr_epi16(1, 1, 2, 2, 3, 3, 4, 4);
+    assert_eq_m128i(_mm_hadds_epi16(a, b), _mm_setr_epi16(32767, -32768, 3, 7, 2, 4, 6, 8));
+    assert_eq_m128i(_mm_hadd_epi16(a, b), _mm_setr_epi16(-32768, 32767, 3, 7, 2, 4, 6, 8));
+
+    let a = _mm_setr_epi16(-32768, 1, 32767, -1, 5, 2, 0, 0);
+    assert_eq_m128i(
+        _mm_hsubs_epi16(a, _mm_setzero_si128()),
+        _mm_setr_epi16(-32768, 32767, 3, 0, 0, 0, 0, 0),
+    );
+
+    let a = _mm_setr_epi32(1, 2, 3, 4);
+    let b = _mm_setr_epi32(5, 6, 7, 8);
+    assert_eq_m128i(_mm_hadd_epi32(a, b), _mm_setr_epi32(3, 7, 11, 15));
+    assert_eq_m128i(_mm_hsub_epi32(a, b), _mm_setr_epi32(-1, -1, -1, -1));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn test_mm_dp() {
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_setr_ps(5.0, 6.0, 7.0, 8.0);
+    assert_eq_m128(_mm_dp_ps::<0xF1>(a, b), _mm_setr_ps(70.0, 0.0, 0.0, 0.0));
+    assert_eq_m128(_mm_dp_ps::<0x3F>(a, b), _mm_setr_ps(17.0, 17.0, 17.0, 17.0));
+
+    let r = _mm_dp_pd::<0x31>(_mm_setr_pd(1.0, 2.0), _mm_setr_pd(3.0, 4.0));
+    assert_eq_m128d(r, _mm_setr_pd(11.0, 0.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn test_mm_insert_ps() {
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_setr_ps(5.0, 6.0, 7.0, 8.0);
+    let r = _mm_insert_ps::<0b01_10_0001>(a, b);
+    assert_eq_m128(r, _mm_setr_ps(0.0, 2.0, 6.0, 4.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn test_mm_mpsadbw_minpos() {
+    let a = _mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+    let r = _mm_mpsadbw_epu8::<0b101>(a, a);
+    assert_eq_m128i(r, _mm_setr_epi16(0, 4, 8, 12, 16, 20, 24, 28));
+
+    let r = _mm_minpos_epu16(_mm_setr_epi16(23, 18, 44, 97, 50, 13, 67, 66));
+    assert_eq_m128i(r, _mm_setr_epi16(13, 5, 0, 0, 0, 0, 0, 0));
+    // The lowest index is returned when multiple lanes contain the minimum.
+    let r = _mm_minpos_epu16(_mm_setr_epi16(0, 18, 44, 97, 50, 13, 67, 0));
+    assert_eq_m128i(r, _mm_setr_epi16(0, 0, 0, 0, 0, 0, 0, 0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn test_mm_round() {
+    let a = _mm_setr_ps(1.5, 2.5, -1.5, 0.4);
+    let r = _mm_round_ps::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>(a);
+    assert_eq_m128(r, _mm_setr_ps(2.0, 2.0, -2.0, 0.0));
+    assert_eq_m128(_mm_floor_ps(a), _mm_setr_ps(1.0, 2.0, -2.0, 0.0));
+    assert_eq_m128d(_mm_ceil_pd(_mm_setr_pd(1.2, -1.5)), _mm_setr_pd(2.0, -1.0));
+
+    let r = _mm_round_ss::<_MM_FROUND_TO_ZERO>(
+        _mm_setr_ps(1.0, 2.0, 3.0, 4.0),
+        _mm_setr_ps(-2.7, 0.0, 0.0, 0.0),
+    );
+    assert_eq_m128(r, _mm_setr_ps(-2.0, 2.0, 3.0, 4.0));
+    let r = _mm_round_sd::<_MM_FROUND_CUR_DIRECTION>(_mm_setr_pd(1.0, 2.0), _mm_setr_pd(2.5, 0.0));
+    assert_eq_m128d(r, _mm_setr_pd(2.0, 2.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn test_mm_test_blendv() {
+    assert_eq!(_mm_testz_si128(_mm_set_epi64x(0, 1), _mm_set_epi64x(1, 2)), 1);
+    assert_eq!(_mm_testz_si128(_mm_set_epi64x(0, 1), _mm_set_epi64x(0, 1)), 0);
+    assert_eq!(_mm_testc_si128(_mm_set1_epi8(-1), _mm_set_epi64x(5, 7)), 1);
+    assert_eq!(_mm_testc_si128(_mm_set_epi64x(0, 1), _mm_set_epi64x(0, 3)), 0);
+    assert_eq!(_mm_testnzc_si128(_mm_set_epi64x(0, 0b011), _mm_set_epi64x(0, 0b110)), 1);
+    assert_eq!(_mm_testnzc_si128(_mm_set_epi64x(0, 0b011), _mm_set_epi64x(0, 0b100)), 0);
+
+    let mask = _mm_setr_epi8(0, -1, 0, -1, 0, -1, 0, -1, 0, -1, 0, -1, 0, -1, 0, -1);
+    let r = _mm_blendv_epi8(_mm_set1_epi8(1), _mm_set1_epi8(2), mask);
+    assert_eq_m128i(r, _mm_setr_epi8(1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2));
+
+    let a = _mm_setr_ps(1.0, 2.0, 3.0, 4.0);
+    let b = _mm_setr_ps(5.0, 6.0, 7.0, 8.0);
+    let r = _mm_blendv_ps(a, b, _mm_setr_ps(0.0, -1.0, 0.0, -0.0));
+    assert_eq_m128(r, _mm_setr_ps(1.0, 6.0, 3.0, 8.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+fn str_to_m128i(s: &[u8]) -> __m128i {
+    assert!(s.len() <= 16);
+    let mut array = [0u8; 16];
+    array[..s.len()].copy_from_slice(s);
+    unsafe { transmute(array) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn test_mm_cmpistr() {
+    let a = str_to_m128i(b"Hello! Good-Bye!");
+    let b = str_to_m128i(b"hello! good-bye!");
+    let i = _mm_cmpistrm::<_SIDD_UNIT_MASK>(a, b);
+    #[rustfmt::skip]
+    let res = _mm_setr_epi8(
+        0x00, !0, !0, !0, !0, !0, !0, 0x00,
+        !0, !0, !0, !0, 0x00, !0, !0, !0,
+    );
+    assert_eq_m128i(i, res);
+
+    let a = str_to_m128i(b"Hello");
+    let b = str_to_m128i(b"   Hello        ");
+    assert_eq!(_mm_cmpistri::<_SIDD_CMP_EQUAL_ORDERED>(a, b), 3);
+
+    let a = str_to_m128i(b"");
+    let b = str_to_m128i(b"Hello");
+    assert_eq!(_mm_cmpistrz::<_SIDD_CMP_EQUAL_ORDERED>(a, b), 1);
+
+    let a = str_to_m128i(b"                ");
+    let b = str_to_m128i(b"       !        ");
+    assert_eq!(_mm_cmpistrc::<_SIDD_UNIT_MASK>(a, b), 1);
+
+    let a = str_to_m128i(b"Hello");
+    let b = str_to_m128i(b"");
+    assert_eq!(_mm_cmpistrs::<_SIDD_CMP_EQUAL_ORDERED>(a, b), 1);
+
+    #[rustfmt::skip]
+    let a = _mm_setr_epi8(
+        0x00, 0x47, 0x00, 0x65, 0x00, 0x6c, 0x00, 0x6c,
+        0x00, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    );
+    #[rustfmt::skip]
+    let b = _mm_setr_epi8(
+        0x00, 0x48, 0x00, 0x65, 0x00, 0x6c, 0x00, 0x6c,
+        0x00, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    );
+    assert_eq!(_mm_cmpistro::<{ _SIDD_UWORD_OPS | _SIDD_UNIT_MASK }>(a, b), 0);
+
+    let a = str_to_m128i(b"");
+    let b = str_to_m128i(b"Hello!!!!!!!!!!!");
+    assert_eq!(_mm_cmpistra::<_SIDD_UNIT_MASK>(a, b), 1);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn test_mm_cmpestr() {
+    let a = str_to_m128i(b"");
+    let b = str_to_m128i(b"Hello");
+    assert_eq!(_mm_cmpestrz::<_SIDD_CMP_EQUAL_ORDERED>(a, 16, b, 6), 1);
+
+    let a = str_to_m128i(b"!!!!!!!!");
+    let b = str_to_m128i(b"        ");
+    assert_eq!(_mm_cmpestrc::<_SIDD_UNIT_MASK>(a, 7, b, 7), 0);
+
+    #[rustfmt::skip]
+    let a = _mm_setr_epi8(
+        0x00, 0x48, 0x00, 0x65, 0x00, 0x6c, 0x00, 0x6c,
+        0x00, 0x6f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    );
+    assert_eq!(_mm_cmpestrs::<_SIDD_UWORD_OPS>(a, 8, _mm_set1_epi8(0x00), 0), 0);
+
+    let a = str_to_m128i(b"Hello");
+    let b = str_to_m128i(b"World");
+    assert_eq!(_mm_cmpestro::<_SIDD_UBYTE_OPS>(a, 5, b, 5), 0);
+
+    let a = str_to_m128i(b"Hello");
+    let b = str_to_m128i(b"Hello");
+    assert_eq!(_mm_cmpestra::<_SIDD_CMP_EQUAL_EACH>(a, 5, b, 5), 0);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_max_min_cmp() {
+    let a = _mm256_setr_ps(1.0, 5.0, f32::NAN, 3.0, -0.0, 8.0, 7.0, 6.0);
+    let b = _mm256_setr_ps(2.0, 5.0, 1.0, 4.0, 0.0, 2.0, 9.0, 6.0);
+    assert_eq_m256(_mm256_max_ps(a, b), _mm256_setr_ps(2.0, 5.0, 1.0, 4.0, 0.0, 8.0, 9.0, 6.0));
+    assert_eq_m256(_mm256_min_ps(a, b), _mm256_setr_ps(1.0, 5.0, 1.0, 3.0, 0.0, 2.0, 7.0, 6.0));
+
+    let r = _mm256_cmp_ps::<_CMP_GE_OS>(a, b);
+    assert_eq!(transmute::<_, [u32; 8]>(r), [0, !0, 0, 0, !0, !0, 0, !0]);
+    let r = _mm256_cmp_ps::<_CMP_TRUE_UQ>(a, b);
+    assert_eq!(transmute::<_, [u32; 8]>(r), [!0; 8]);
+    let r = _mm256_cmp_pd::<_CMP_FALSE_OQ>(_mm256_set1_pd(1.0), _mm256_set1_pd(1.0));
+    assert_eq!(transmute::<_, [u64; 4]>(r), [0; 4]);
+
+    let r = _mm256_sqrt_pd(_mm256_setr_pd(4.0, 9.0, 16.0, 25.0));
+    assert_eq_m256d(r, _mm256_setr_pd(2.0, 3.0, 4.0, 5.0));
+    let r: [f32; 8] = transmute(_mm256_rcp_ps(_mm256_set1_ps(4.0)));
+    assert_approx_eq_f32(r, [0.25; 8]);
+    let r = _mm256_round_ps::<_MM_FROUND_FLOOR>(_mm256_setr_ps(
+        1.5, -1.5, 2.0, 0.1, 7.9, -7.9, 0.0, 3.5,
+    ));
+    assert_eq_m256(r, _mm256_setr_ps(1.0, -2.0, 2.0, 0.0, 7.0, -8.0, 0.0, 3.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_cvt() {
+    let a = _mm256_setr_ps(1.5, 2.5, -1.5, f32::NAN, 3e9, 0.0, 1.0, 7.7);
+    assert_eq_m256i(
+        _mm256_cvtps_epi32(a),
+        _mm256_setr_epi32(2, 2, -2, i32::MIN, i32::MIN, 0, 1, 8),
+    );
+    assert_eq_m256i(
+        _mm256_cvttps_epi32(a),
+        _mm256_setr_epi32(1, 2, -1, i32::MIN, i32::MIN, 0, 1, 7),
+    );
+
+    let a = _mm256_setr_pd(1.5, -2.5, f64::NAN, 4.0);
+    assert_eq_m128i(_mm256_cvtpd_epi32(a), _mm_setr_epi32(2, -2, i32::MIN, 4));
+    assert_eq_m128i(_mm256_cvttpd_epi32(a), _mm_setr_epi32(1, -2, i32::MIN, 4));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_dp_hadd_addsub() {
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    let r = _mm256_dp_ps::<0xF1>(a, a);
+    assert_eq_m256(r, _mm256_setr_ps(30.0, 0.0, 0.0, 0.0, 174.0, 0.0, 0.0, 0.0));
+
+    let b = _mm256_setr_ps(9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0);
+    let r = _mm256_hadd_ps(a, b);
+    assert_eq_m256(r, _mm256_setr_ps(3.0, 7.0, 19.0, 23.0, 11.0, 15.0, 27.0, 31.0));
+
+    let a = _mm256_setr_pd(1.0, 2.0, 3.0, 4.0);
+    let b = _mm256_setr_pd(5.0, 6.0, 7.0, 8.0);
+    assert_eq_m256d(_mm256_addsub_pd(a, b), _mm256_setr_pd(-4.0, 8.0, -4.0, 12.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_permutevar() {
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    let r = _mm256_permutevar_ps(a, _mm256_setr_epi32(3, 2, 1, 0, 4, 5, 6, 7));
+    assert_eq_m256(r, _mm256_setr_ps(4.0, 3.0, 2.0, 1.0, 5.0, 6.0, 7.0, 8.0));
+
+    // `vpermilpd` uses bit 1 of every control lane.
+    let r = _mm_permutevar_pd(_mm_setr_pd(1.0, 2.0), _mm_set_epi64x(0, 2));
+    assert_eq_m128d(r, _mm_setr_pd(2.0, 1.0));
+    let r =
+        _mm256_permutevar_pd(_mm256_setr_pd(1.0, 2.0, 3.0, 4.0), _mm256_setr_epi64x(2, 0, 0, 2));
+    assert_eq_m256d(r, _mm256_setr_pd(2.0, 1.0, 3.0, 4.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_test() {
+    assert_eq!(
+        _mm256_testz_si256(_mm256_setr_epi64x(1, 0, 0, 0), _mm256_setr_epi64x(2, 1, 0, 0)),
+        1
+    );
+    assert_eq!(_mm256_testc_si256(_mm256_set1_epi8(-1), _mm256_set1_epi64x(7)), 1);
+    assert_eq!(_mm256_testnzc_si256(_mm256_set1_epi64x(0b011), _mm256_set1_epi64x(0b110)), 1);
+
+    let a = _mm256_setr_ps(-1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0);
+    let b = _mm256_setr_ps(1.0, -1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0);
+    assert_eq!(_mm256_testz_ps(a, b), 1);
+    assert_eq!(_mm256_testc_ps(a, b), 0);
+    assert_eq!(_mm_testc_pd(_mm_setr_pd(-1.0, -1.0), _mm_setr_pd(-1.0, 1.0)), 1);
+    let a = _mm256_setr_pd(-1.0, 1.0, -1.0, 1.0);
+    let b = _mm256_setr_pd(-1.0, -1.0, 1.0, 1.0);
+    assert_eq!(_mm256_testnzc_pd(a, b), 1);
+    assert_eq!(_mm_testnzc_ps(_mm_set1_ps(-1.0), _mm_set1_ps(-1.0)), 0);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_movemask_blendv() {
+    let a = _mm256_setr_ps(-1.0, 1.0, -1.0, 1.0, -1.0, -1.0, 1.0, 1.0);
+    assert_eq!(_mm256_movemask_ps(a), 0b0011_0101);
+    assert_eq!(_mm256_movemask_pd(_mm256_setr_pd(1.0, -1.0, -0.0, 0.0)), 0b0110);
+
+    let b = _mm256_set1_ps(2.0);
+    let r = _mm256_blendv_ps(b, a, a);
+    assert_eq_m256(r, _mm256_setr_ps(-1.0, 2.0, -1.0, 2.0, -1.0, -1.0, 2.0, 2.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn test_mm256_maskload_maskstore() {
+    let mem = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    let mask = _mm256_setr_epi32(-1, 0, -1, 0, -1, 0, -1, 0);
+    let r = _mm256_maskload_ps(mem.as_ptr(), mask);
+    assert_eq_m256(r, _mm256_setr_ps(1.0, 0.0, 3.0, 0.0, 5.0, 0.0, 7.0, 0.0));
+
+    let mut r = [0.0f32; 8];
+    _mm256_maskstore_ps(r.as_mut_ptr(), mask, _mm256_loadu_ps(mem.as_ptr()));
+    assert_eq!(r, [1.0, 0.0, 3.0, 0.0, 5.0, 0.0, 7.0, 0.0]);
+
+    let mem = [1.0f64, 2.0];
+    let r = _mm_maskload_pd(mem.as_ptr(), _mm_set_epi64x(-1, 0));
+    assert_eq_m128d(r, _mm_setr_pd(0.0, 2.0));
+
+    _mm256_zeroall();
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn test_mm256_shifts() {
+    let r = _mm256_sll_epi32(_mm256_set1_epi32(1), _mm_set_epi64x(0, 5));
+    assert_eq_m256i(r, _mm256_set1_epi32(32));
+    let r = _mm256_sra_epi16(_mm256_set1_epi16(-8), _mm_set_epi64x(0, 100));
+    assert_eq_m256i(r, _mm256_set1_epi16(-1));
+    let r = _mm256_srl_epi64(_mm256_set1_epi64x(-1), _mm_set_epi64x(0, 60));
+    assert_eq_m256i(r, _mm256_set1_epi64x(15));
+
+    let r = _mm256_sllv_epi32(_mm256_set1_epi32(1), _mm256_setr_epi32(0, 1, 2, 3, 31, 32, 33, -1));
+    assert_eq_m256i(r, _mm256_setr_epi32(1, 2, 4, 8, i32::MIN, 0, 0, 0));
+    let r = _mm256_srav_epi32(_mm256_set1_epi32(-256), _mm256_setr_epi32(0, 4, 8, 40, 0, 4, 8, 40));
+    assert_eq_m256i(r, _mm256_setr_epi32(-256, -16, -1, -1, -256, -16, -1, -1));
+    let r = _mm_srlv_epi64(_mm_set1_epi64x(16), _mm_set_epi64x(64, 4));
+    assert_eq_m128i(r, _mm_set_epi64x(0, 1));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn test_mm256_permutevar8x32_ps() {
+    let a = _mm256_setr_ps(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0);
+    // Only the lowest 3 bits of every index are used.
+    let r = _mm256_permutevar8x32_ps(a, _mm256_setr_epi32(7, 6, 5, 4, 3, 2, 1, 8));
+    assert_eq_m256(r, _mm256_setr_ps(8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn test_mm256_integer_ops() {
+    let r = _mm256_mulhrs_epi16(_mm256_set1_epi16(0x4000), _mm256_set1_epi16(0x4000));
+    assert_eq_m256i(r, _mm256_set1_epi16(0x2000));
+
+    let r = _mm256_sign_epi16(
+        _mm256_set1_epi16(3),
+        _mm256_setr_epi16(-1, 0, 1, -5, 0, 0, 0, 0, 1, 1, 1, 1, -1, -1, -1, -1),
+    );
+    assert_eq_m256i(r, _mm256_setr_epi16(-3, 0, 3, -3, 0, 0, 0, 0, 3, 3, 3, 3, -3, -3, -3, -3));
+
+    #[rustfmt::skip]
+    let a = _mm256_setr_epi8(
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    );
+    let r = _mm256_mpsadbw_epu8::<0b101_000>(a, a);
+    #[rustfmt::skip]
+    let e = _mm256_setr_epi16(
+        0, 4, 8, 12, 16, 20, 24, 28,
+        0, 4, 8, 12, 16, 20, 24, 28,
+    );
+    assert_eq_m256i(r, e);
+
+    let a = _mm256_setr_epi32(1, 2, 3, 4, 5, 6, 7, 8);
+    let b = _mm256_setr_epi32(9, 10, 11, 12, 13, 14, 15, 16);
+    assert_eq_m256i(_mm256_hadd_epi32(a, b), _mm256_setr_epi32(3, 7, 19, 23, 11, 15, 27, 31));
+
+    #[rustfmt::skip]
+    let a = _mm256_setr_epi16(
+        -32768, 1, -32768, 1, -32768, 1, -32768, 1,
+        -32768, 1, -32768, 1, -32768, 1, -32768, 1,
+    );
+    let r = _mm256_hsubs_epi16(a, _mm256_set1_epi16(1));
+    #[rustfmt::skip]
+    let e = _mm256_setr_epi16(
+        -32768, -32768, -32768, -32768, 0, 0, 0, 0,
+        -32768, -32768, -32768, -32768, 0, 0, 0, 0,
+    );
+    assert_eq_m256i(r, e);
+
+    let r = _mm256_maskload_epi64([1i64, 2, 3, 4].as_ptr(), _mm256_setr_epi64x(0, -1, 0, -1));
+    assert_eq_m256i(r, _mm256_setr_epi64x(0, 2, 0, 4));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn test_mm_i32gather() {
+    let i32s: [i32; 8] = [0, 10, 20, 30, 40, 50, 60, 70];
+    let i64s: [i64; 8] = [0, 10, 20, 30, 40, 50, 60, 70];
+    let f32s: [f32; 8] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+    let f64s: [f64; 8] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+
+    let r = _mm_i32gather_epi32::<4>(i32s.as_ptr(), _mm_setr_epi32(0, 2, 4, 7));
+    assert_eq_m128i(r, _mm_setr_epi32(0, 20, 40, 70));
+    // The scale is applied to the offsets, so a scale of 8 skips every other element.
+    let r = _mm_i32gather_epi32::<8>(i32s.as_ptr(), _mm_setr_epi32(0, 1, 2, 3));
+    assert_eq_m128i(r, _mm_setr_epi32(0, 20, 40, 60));
+    // Only the lanes with the highest bit of the mask set are loaded.
+    let r = _mm_mask_i32gather_epi32::<4>(
+        _mm_set1_epi32(-1),
+        i32s.as_ptr(),
+        _mm_setr_epi32(1, 2, 3, 4),
+        _mm_setr_epi32(-1, 0, -1, 0),
+    );
+    assert_eq_m128i(r, _mm_setr_epi32(10, -1, 30, -1));
+
+    let r = _mm_i32gather_epi64::<8>(i64s.as_ptr(), _mm_setr_epi32(1, 3, 0, 0));
+    assert_eq_m128i(r, _mm_set_epi64x(30, 10));
+
+    let r = _mm_i32gather_ps::<4>(f32s.as_ptr(), _mm_setr_epi32(3, 2, 1, 0));
+    assert_eq_m128(r, _mm_setr_ps(3.0, 2.0, 1.0, 0.0));
+
+    let r = _mm_i32gather_pd::<8>(f64s.as_ptr(), _mm_setr_epi32(7, 5, 0, 0));
+    assert_eq_m128d(r, _mm_setr_pd(7.0, 5.0));
+
+    let r = _mm256_i32gather_epi32::<4>(i32s.as_ptr(), _mm256_setr_epi32(7, 6, 5, 4, 3, 2, 1, 0));
+    assert_eq_m256i(r, _mm256_setr_epi32(70, 60, 50, 40, 30, 20, 10, 0));
+
+    let r = _mm256_i32gather_epi64::<8>(i64s.as_ptr(), _mm_setr_epi32(0, 2, 4, 6));
+    assert_eq_m256i(r, _mm256_setr_epi64x(0, 20, 40, 60));
+
+    let r = _mm256_mask_i32gather_ps::<4>(
+        _mm256_set1_ps(-1.0),
+        f32s.as_ptr(),
+        _mm256_setr_epi32(0, 1, 2, 3, 4, 5, 6, 7),
+        _mm256_setr_ps(-1.0, 0.0, -1.0, 0.0, -1.0, 0.0, -1.0, 0.0),
+    );
+    assert_eq_m256(r, _mm256_setr_ps(0.0, -1.0, 2.0, -1.0, 4.0, -1.0, 6.0, -1.0));
+
+    let r = _mm256_i32gather_pd::<8>(f64s.as_ptr(), _mm_setr_epi32(1, 3, 5, 7));
+    assert_eq_m256d(r, _mm256_setr_pd(1.0, 3.0, 5.0, 7.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn test_mm_i64gather() {
+    let i32s: [i32; 8] = [0, 10, 20, 30, 40, 50, 60, 70];
+    let i64s: [i64; 8] = [0, 10, 20, 30, 40, 50, 60, 70];
+    let f32s: [f32; 8] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+    let f64s: [f64; 8] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+
+    // There are only two offsets, so the upper half of the result is zeroed.
+    let r = _mm_i64gather_epi32::<4>(i32s.as_ptr(), _mm_set_epi64x(6, 5));
+    assert_eq_m128i(r, _mm_setr_epi32(50, 60, 0, 0));
+
+    let r = _mm_mask_i64gather_epi64::<8>(
+        _mm_set1_epi64x(-1),
+        i64s.as_ptr(),
+        _mm_set_epi64x(3, 2),
+        _mm_set_epi64x(-1, 0),
+    );
+    assert_eq_m128i(r, _mm_set_epi64x(30, -1));
+
+    let r = _mm_i64gather_ps::<4>(f32s.as_ptr(), _mm_set_epi64x(1, 4));
+    assert_eq_m128(r, _mm_setr_ps(4.0, 1.0, 0.0, 0.0));
+
+    let r = _mm_i64gather_pd::<8>(f64s.as_ptr(), _mm_set_epi64x(2, 6));
+    assert_eq_m128d(r, _mm_setr_pd(6.0, 2.0));
+
+    let r = _mm256_i64gather_epi32::<4>(i32s.as_ptr(), _mm256_setr_epi64x(1, 3, 5, 7));
+    assert_eq_m128i(r, _mm_setr_epi32(10, 30, 50, 70));
+
+    let r = _mm256_i64gather_epi64::<8>(i64s.as_ptr(), _mm256_setr_epi64x(7, 0, 3, 4));
+    assert_eq_m256i(r, _mm256_setr_epi64x(70, 0, 30, 40));
+
+    let r = _mm256_i64gather_ps::<4>(f32s.as_ptr(), _mm256_setr_epi64x(0, 2, 4, 6));
+    assert_eq_m128(r, _mm_setr_ps(0.0, 2.0, 4.0, 6.0));
+
+    let r = _mm256_mask_i64gather_pd::<8>(
+        _mm256_set1_pd(-1.0),
+        f64s.as_ptr(),
+        _mm256_setr_epi64x(0, 1, 2, 3),
+        _mm256_setr_pd(0.0, -1.0, -1.0, 0.0),
+    );
+    assert_eq_m256d(r, _mm256_setr_pd(-1.0, 1.0, 2.0, -1.0));
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq")]
+unsafe fn test_mm_clmulepi64_si128() {
+    // Test vectors from the Intel Carry-Less Multiplication Instruction whitepaper.
+    let a = _mm_set_epi64x(0x7b5b546573745665, 0x63746f725d53475d);
+    let b = _mm_set_epi64x(0x4869285368617929, 0x5b477565726f6e5d);
+
+    let r = _mm_clmulepi64_si128::<0x00>(a, b);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0x929633d5d36f0451, 0x1d4d84c85c3440c0]);
+    let r = _mm_clmulepi64_si128::<0x01>(a, b);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0xbabf262df4b7d5c9, 0x1a2bf6db3a30862f]);
+    let r = _mm_clmulepi64_si128::<0x10>(a, b);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0x7fa540ac2a281315, 0x1bd17c8d556ab5a1]);
+    let r = _mm_clmulepi64_si128::<0x11>(a, b);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0xd66ee03e410fd4ed, 0x1d1e1f2c592e7c45]);
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "aes")]
+unsafe fn test_mm_aes() {
+    let a = _mm_set_epi64x(0x0123456789abcdef, 0x0899aabbccddeeff);
+    let k = _mm_set_epi64x(0x1133557799bbddff, 0x0022446688aaccee);
+
+    let r = _mm_aesenc_si128(a, k);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0x28e4ee1884504333, 0x16ab0e572cc3b619]);
+    let r = _mm_aesenclast_si128(a, k);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0x4b04f98cf4c860f8, 0xb6dd7df2a97ab320]);
+    let r = _mm_aesdec_si128(a, k);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0xb57ecfa32af9ea9d, 0x044e4f5176fec48f]);
+    let r = _mm_aesdeclast_si128(a, k);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0xf210dd9837a4a493, 0x36cad57d9072bf9e]);
+    let r = _mm_aesimc_si128(a);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0x27c49efd22770055, 0xc66c82284ee40aa0]);
+    let r = _mm_aeskeygenassist_si128::<5>(a);
+    assert_eq!(transmute::<_, [u64; 2]>(r), [0xea30eea930eeacea, 0x857c266b7c266e85]);
+
+    // The last decryption round undoes the last encryption round.
+    let r = _mm_aesdeclast_si128(_mm_xor_si128(_mm_aesenclast_si128(a, k), k), _mm_setzero_si128());
+    assert_eq_m128i(r, a);
+}
+
+#[cfg(target_arch = "x86_64")]
+fn main() {
+    unsafe {
+        if is_x86_feature_detected!("sse") {
+            test_mm_max_min_ps();
+            test_mm_rcp_rsqrt_sqrt();
+            test_mm_cmp_ps();
+            test_mm_comi_ss();
+            test_mm_cvtss_si();
+            test_mm_getcsr_setcsr();
+        }
+
+        if is_x86_feature_detected!("sse2") {
+            test_mm_sll_srl_sra();
+            test_mm_cvtpd();
+            test_mm_maskmoveu_si128();
+            test_mm_clflush_fences();
+        }
+
+        if is_x86_feature_detected!("sse3") {
+            test_mm_hadd_addsub();
+        }
+
+        if is_x86_feature_detected!("ssse3") {
+            test_mm_sign();
+            test_mm_hadd_epi();
+        }
+
+        if is_x86_feature_detected!("sse4.1") {
+            test_mm_dp();
+            test_mm_insert_ps();
+            test_mm_mpsadbw_minpos();
+            test_mm_round();
+            test_mm_test_blendv();
+        }
+
+        if is_x86_feature_detected!("sse4.2") {
+            test_mm_cmpistr();
+            test_mm_cmpestr();
+        }
+
+        if is_x86_feature_detected!("avx") {
+            test_mm256_max_min_cmp();
+            test_mm256_cvt();
+            test_mm256_dp_hadd_addsub();
+            test_mm256_permutevar();
+            test_mm256_test();
+            test_mm256_movemask_blendv();
+            test_mm256_maskload_maskstore();
+        }
+
+        if is_x86_feature_detected!("avx2") {
+            test_mm256_shifts();
+            test_mm256_permutevar8x32_ps();
+            test_mm256_integer_ops();
+            test_mm_i32gather();
+            test_mm_i64gather();
+        }
+
+        if is_x86_feature_detected!("pclmulqdq") {
+            test_mm_clmulepi64_si128();
+        }
+
+        if is_x86_feature_detected!("aes") {
+            test_mm_aes();
+        }
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn main() {}
@@ -1,5 +1,6 @@
 //! Emulate x86 LLVM intrinsics
 
+use cranelift_codegen::ir::immediates::{Ieee32, Ieee64};
 use rustc_ast::ast::{InlineAsmOptions, InlineAsmTemplatePiece};
 use rustc_target::asm::*;
 
@@ -26,6 +27,11 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
             // Do nothing. It is a perf hint anyway.
         }
 
+        "llvm.x86.avx.vzeroall" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_zeroall
+            // Do nothing. Cranelift doesn't keep values in vector registers across intrinsic calls.
+        }
+
         // Used by is_x86_feature_detected!();
         "llvm.x86.xgetbv" => {
             intrinsic_args!(fx, args => (xcr_no); intrinsic);
@@ -193,7 +199,10 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
             ret.place_lane(fx, 0).write_cvalue(fx, res_lane);
         }
 
-        "llvm.x86.sse.sqrt.ps" => {
+        "llvm.x86.sse.sqrt.ps"
+        | "llvm.x86.sse2.sqrt.pd"
+        | "llvm.x86.avx.sqrt.ps.256"
+        | "llvm.x86.avx.sqrt.pd.256" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sqrt_ps&ig_expand=6245
             intrinsic_args!(fx, args => (a); intrinsic);
 
@@ -203,7 +212,40 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
             });
         }
 
-        "llvm.x86.sse.max.ps" => {
+        "llvm.x86.sse.sqrt.ss" | "llvm.x86.sse2.sqrt.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sqrt_ss
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            scalar_lane_op(fx, a, a, ret, &|fx, a_lane, _| fx.bcx.ins().sqrt(a_lane));
+        }
+
+        "llvm.x86.sse.rcp.ps"
+        | "llvm.x86.avx.rcp.ps.256"
+        | "llvm.x86.sse.rsqrt.ps"
+        | "llvm.x86.avx.rsqrt.ps.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rcp_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rsqrt_ps
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let sqrt = intrinsic.contains(".rsqrt.");
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                x86_reciprocal(fx, lane, sqrt)
+            });
+        }
+
+        "llvm.x86.sse.rcp.ss" | "llvm.x86.sse.rsqrt.ss" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rcp_ss
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rsqrt_ss
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let sqrt = intrinsic.contains(".rsqrt.");
+            scalar_lane_op(fx, a, a, ret, &|fx, a_lane, _| x86_reciprocal(fx, a_lane, sqrt));
+        }
+
+        "llvm.x86.sse.max.ps"
+        | "llvm.x86.sse2.max.pd"
+        | "llvm.x86.avx.max.ps.256"
+        | "llvm.x86.avx.max.pd.256" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_max_ps&ig_expand=4357
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
@@ -212,11 +254,14 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
                 a,
                 b,
                 ret,
-                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| fx.bcx.ins().fmax(a_lane, b_lane),
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| x86_fmax(fx, a_lane, b_lane),
             );
         }
 
-        "llvm.x86.sse.min.ps" => {
+        "llvm.x86.sse.min.ps"
+        | "llvm.x86.sse2.min.pd"
+        | "llvm.x86.avx.min.ps.256"
+        | "llvm.x86.avx.min.pd.256" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_min_ps&ig_expand=4489
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
@@ -225,122 +270,102 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
                 a,
                 b,
                 ret,
-                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| fx.bcx.ins().fmin(a_lane, b_lane),
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| x86_fmin(fx, a_lane, b_lane),
             );
         }
 
-        "llvm.x86.sse.cmp.ps" | "llvm.x86.sse2.cmp.pd" => {
-            let (x, y, kind) = match args {
-                [x, y, kind] => (x, y, kind),
-                _ => bug!("wrong number of args for intrinsic {intrinsic}"),
-            };
-            let x = codegen_operand(fx, &x.node);
-            let y = codegen_operand(fx, &y.node);
-            let kind = match &kind.node {
-                Operand::Constant(const_) => crate::constant::eval_mir_constant(fx, const_).0,
-                Operand::Copy(_) | Operand::Move(_) => unreachable!("{kind:?}"),
-            };
+        "llvm.x86.sse.max.ss" | "llvm.x86.sse2.max.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_max_ss
+            intrinsic_args!(fx, args => (a, b); intrinsic);
 
-            let flt_cc = match kind
-                .try_to_bits(Size::from_bytes(1))
-                .unwrap_or_else(|| panic!("kind not scalar: {:?}", kind))
-                .try_into()
-                .unwrap()
-            {
-                _CMP_EQ_OQ | _CMP_EQ_OS => FloatCC::Equal,
-                _CMP_LT_OS | _CMP_LT_OQ => FloatCC::LessThan,
-                _CMP_LE_OS | _CMP_LE_OQ => FloatCC::LessThanOrEqual,
-                _CMP_UNORD_Q | _CMP_UNORD_S => FloatCC::Unordered,
-                _CMP_NEQ_UQ | _CMP_NEQ_US => FloatCC::NotEqual,
-                _CMP_NLT_US | _CMP_NLT_UQ => FloatCC::UnorderedOrGreaterThanOrEqual,
-                _CMP_NLE_US | _CMP_NLE_UQ => FloatCC::UnorderedOrGreaterThan,
-                _CMP_ORD_Q | _CMP_ORD_S => FloatCC::Ordered,
-                _CMP_EQ_UQ | _CMP_EQ_US => FloatCC::UnorderedOrEqual,
-                _CMP_NGE_US | _CMP_NGE_UQ => FloatCC::UnorderedOrLessThan,
-                _CMP_NGT_US | _CMP_NGT_UQ => FloatCC::UnorderedOrLessThanOrEqual,
-                _CMP_FALSE_OQ | _CMP_FALSE_OS => todo!(),
-                _CMP_NEQ_OQ | _CMP_NEQ_OS => FloatCC::OrderedNotEqual,
-                _CMP_GE_OS | _CMP_GE_OQ => FloatCC::GreaterThanOrEqual,
-                _CMP_GT_OS | _CMP_GT_OQ => FloatCC::GreaterThan,
-                _CMP_TRUE_UQ | _CMP_TRUE_US => todo!(),
-
-                kind => unreachable!("kind {:?}", kind),
-            };
+            scalar_lane_op(fx, a, b, ret, &|fx, a_lane, b_lane| x86_fmax(fx, a_lane, b_lane));
+        }
+
+        "llvm.x86.sse.min.ss" | "llvm.x86.sse2.min.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_min_ss
+            intrinsic_args!(fx, args => (a, b); intrinsic);
 
-            // Copied from stdarch
-            /// Equal (ordered, non-signaling)
-            const _CMP_EQ_OQ: i32 = 0x00;
-            /// Less-than (ordered, signaling)
-            const _CMP_LT_OS: i32 = 0x01;
-            /// Less-than-or-equal (ordered, signaling)
-            const _CMP_LE_OS: i32 = 0x02;
-            /// Unordered (non-signaling)
-            const _CMP_UNORD_Q: i32 = 0x03;
-            /// Not-equal (unordered, non-signaling)
-            const _CMP_NEQ_UQ: i32 = 0x04;
-            /// Not-less-than (unordered, signaling)
-            const _CMP_NLT_US: i32 = 0x05;
-            /// Not-less-than-or-equal (unordered, signaling)
-            const _CMP_NLE_US: i32 = 0x06;
-            /// Ordered (non-signaling)
-            const _CMP_ORD_Q: i32 = 0x07;
-            /// Equal (unordered, non-signaling)
-            const _CMP_EQ_UQ: i32 = 0x08;
-            /// Not-greater-than-or-equal (unordered, signaling)
-            const _CMP_NGE_US: i32 = 0x09;
-            /// Not-greater-than (unordered, signaling)
-            const _CMP_NGT_US: i32 = 0x0a;
-            /// False (ordered, non-signaling)
-            const _CMP_FALSE_OQ: i32 = 0x0b;
-            /// Not-equal (ordered, non-signaling)
-            const _CMP_NEQ_OQ: i32 = 0x0c;
-            /// Greater-than-or-equal (ordered, signaling)
-            const _CMP_GE_OS: i32 = 0x0d;
-            /// Greater-than (ordered, signaling)
-            const _CMP_GT_OS: i32 = 0x0e;
-            /// True (unordered, non-signaling)
-            const _CMP_TRUE_UQ: i32 = 0x0f;
-            /// Equal (ordered, signaling)
-            const _CMP_EQ_OS: i32 = 0x10;
-            /// Less-than (ordered, non-signaling)
-            const _CMP_LT_OQ: i32 = 0x11;
-            /// Less-than-or-equal (ordered, non-signaling)
-            const _CMP_LE_OQ: i32 = 0x12;
-            /// Unordered (signaling)
-            const _CMP_UNORD_S: i32 = 0x13;
-            /// Not-equal (unordered, signaling)
-            const _CMP_NEQ_US: i32 = 0x14;
-            /// Not-less-than (unordered, non-signaling)
-            const _CMP_NLT_UQ: i32 = 0x15;
-            /// Not-less-than-or-equal (unordered, non-signaling)
-            const _CMP_NLE_UQ: i32 = 0x16;
-            /// Ordered (signaling)
-            const _CMP_ORD_S: i32 = 0x17;
-            /// Equal (unordered, signaling)
-            const _CMP_EQ_US: i32 = 0x18;
-            /// Not-greater-than-or-equal (unordered, non-signaling)
-            const _CMP_NGE_UQ: i32 = 0x19;
-            /// Not-greater-than (unordered, non-signaling)
-            const _CMP_NGT_UQ: i32 = 0x1a;
-            /// False (ordered, signaling)
-            const _CMP_FALSE_OS: i32 = 0x1b;
-            /// Not-equal (ordered, signaling)
-            const _CMP_NEQ_OS: i32 = 0x1c;
-            /// Greater-than-or-equal (ordered, non-signaling)
-            const _CMP_GE_OQ: i32 = 0x1d;
-            /// Greater-than (ordered, non-signaling)
-            const _CMP_GT_OQ: i32 = 0x1e;
-            /// True (unordered, signaling)
-            const _CMP_TRUE_US: i32 = 0x1f;
+            scalar_lane_op(fx, a, b, ret, &|fx, a_lane, b_lane| x86_fmin(fx, a_lane, b_lane));
+        }
+
+        "llvm.x86.sse.cmp.ps"
+        | "llvm.x86.sse2.cmp.pd"
+        | "llvm.x86.avx.cmp.ps.256"
+        | "llvm.x86.avx.cmp.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_cmp_ps
+            intrinsic_args!(fx, args => (x, y, _kind); intrinsic);
+
+            let kind = imm_arg(fx, intrinsic, &args[2], span);
 
             simd_pair_for_each_lane(fx, x, y, ret, &|fx, lane_ty, res_lane_ty, x_lane, y_lane| {
                 let res_lane = match lane_ty.kind() {
-                    ty::Float(_) => fx.bcx.ins().fcmp(flt_cc, x_lane, y_lane),
+                    ty::Float(_) => x86_fcmp(fx, kind, x_lane, y_lane),
                     _ => unreachable!("{:?}", lane_ty),
                 };
                 bool_to_zero_or_max_uint(fx, res_lane_ty, res_lane)
             });
         }
+
+        "llvm.x86.sse.cmp.ss" | "llvm.x86.sse2.cmp.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cmp_ss
+            intrinsic_args!(fx, args => (x, y, _kind); intrinsic);
+
+            let kind = imm_arg(fx, intrinsic, &args[2], span);
+            let (_, lane_ty) = x.layout().ty.simd_size_and_type(fx.tcx);
+
+            scalar_lane_op(fx, x, y, ret, &|fx, x_lane, y_lane| {
+                let res_lane = x86_fcmp(fx, kind, x_lane, y_lane);
+                bool_to_zero_or_max_uint(fx, lane_ty, res_lane)
+            });
+        }
+
+        "llvm.x86.sse.comieq.ss"
+        | "llvm.x86.sse.comilt.ss"
+        | "llvm.x86.sse.comile.ss"
+        | "llvm.x86.sse.comigt.ss"
+        | "llvm.x86.sse.comige.ss"
+        | "llvm.x86.sse.comineq.ss"
+        | "llvm.x86.sse.ucomieq.ss"
+        | "llvm.x86.sse.ucomilt.ss"
+        | "llvm.x86.sse.ucomile.ss"
+        | "llvm.x86.sse.ucomigt.ss"
+        | "llvm.x86.sse.ucomige.ss"
+        | "llvm.x86.sse.ucomineq.ss"
+        | "llvm.x86.sse2.comieq.sd"
+        | "llvm.x86.sse2.comilt.sd"
+        | "llvm.x86.sse2.comile.sd"
+        | "llvm.x86.sse2.comigt.sd"
+        | "llvm.x86.sse2.comige.sd"
+        | "llvm.x86.sse2.comineq.sd"
+        | "llvm.x86.sse2.ucomieq.sd"
+        | "llvm.x86.sse2.ucomilt.sd"
+        | "llvm.x86.sse2.ucomile.sd"
+        | "llvm.x86.sse2.ucomigt.sd"
+        | "llvm.x86.sse2.ucomige.sd"
+        | "llvm.x86.sse2.ucomineq.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_comieq_ss
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_ucomieq_sd
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let a_lane = a.value_lane(fx, 0).load_scalar(fx);
+            let b_lane = b.value_lane(fx, 0).load_scalar(fx);
+
+            // The signaling `comi` and quiet `ucomi` variants only differ in the floating point
+            // exceptions they raise. Only `neq` returns 1 when an operand is NaN.
+            let flt_cc = match intrinsic.split('.').nth(3).unwrap().trim_start_matches('u') {
+                "comieq" => FloatCC::Equal,
+                "comilt" => FloatCC::LessThan,
+                "comile" => FloatCC::LessThanOrEqual,
+                "comigt" => FloatCC::GreaterThan,
+                "comige" => FloatCC::GreaterThanOrEqual,
+                "comineq" => FloatCC::NotEqual,
+                _ => unreachable!(),
+            };
+
+            let res = fx.bcx.ins().fcmp(flt_cc, a_lane, b_lane);
+            let res = fx.bcx.ins().uextend(types::I32, res);
+            ret.write_cvalue(fx, CValue::by_val(res, fx.layout_of(fx.tcx.types.i32)));
+        }
         "llvm.x86.ssse3.pshuf.b.128" | "llvm.x86.avx2.pshuf.b" => {
             let (a, b) = match args {
                 [a, b] => (a, b),
@@ -374,12 +399,14 @@ pub(crate) fn codegen_x86_llvm_intrinsic_call<'tcx>(
                 }
             }
         }
-        "llvm.x86.avx2.permd" => {
+        "llvm.x86.avx2.permd" | "llvm.x86.avx2.permps" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar8x32_epi32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar8x32_ps
             intrinsic_args!(fx, args => (a, idx); intrinsic);
 
             for j in 0..=7 {
                 let index = idx.value_typed_lane(fx, fx.tcx.types.u32, j).load_scalar(fx);
+                let index = fx.bcx.ins().band_imm(index, 7);
                 let index = fx.bcx.ins().uextend(fx.pointer_type, index);
                 let value = a.value_lane_dyn(fx, index).load_scalar(fx);
                 ret.place_typed_lane(fx, fx.tcx.types.u32, j).to_ptr().store(
@@ -447,7 +474,12 @@ fn select4(
                 MemFlags::trusted(),
             );
         }
-        "llvm.x86.ssse3.pabs.b.128" | "llvm.x86.ssse3.pabs.w.128" | "llvm.x86.ssse3.pabs.d.128" => {
+        "llvm.x86.ssse3.pabs.b.128"
+        | "llvm.x86.ssse3.pabs.w.128"
+        | "llvm.x86.ssse3.pabs.d.128"
+        | "llvm.x86.avx2.pabs.b"
+        | "llvm.x86.avx2.pabs.w"
+        | "llvm.x86.avx2.pabs.d" => {
             intrinsic_args!(fx, args => (a); intrinsic);
 
             simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
@@ -511,7 +543,10 @@ fn select4(
             let val = CValue::by_val_pair(cb_out, c, layout);
             ret.write_cvalue(fx, val);
         }
-        "llvm.x86.sse2.pavg.b" | "llvm.x86.sse2.pavg.w" => {
+        "llvm.x86.sse2.pavg.b"
+        | "llvm.x86.sse2.pavg.w"
+        | "llvm.x86.avx2.pavg.b"
+        | "llvm.x86.avx2.pavg.w" => {
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
             // FIXME use vector instructions when possible
@@ -532,19 +567,91 @@ fn select4(
                 },
             );
         }
-        "llvm.x86.sse2.psra.w" => {
+        "llvm.x86.sse2.psll.w"
+        | "llvm.x86.sse2.psll.d"
+        | "llvm.x86.sse2.psll.q"
+        | "llvm.x86.sse2.psrl.w"
+        | "llvm.x86.sse2.psrl.d"
+        | "llvm.x86.sse2.psrl.q"
+        | "llvm.x86.sse2.psra.w"
+        | "llvm.x86.sse2.psra.d"
+        | "llvm.x86.avx2.psll.w"
+        | "llvm.x86.avx2.psll.d"
+        | "llvm.x86.avx2.psll.q"
+        | "llvm.x86.avx2.psrl.w"
+        | "llvm.x86.avx2.psrl.d"
+        | "llvm.x86.avx2.psrl.q"
+        | "llvm.x86.avx2.psra.w"
+        | "llvm.x86.avx2.psra.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sll_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_sra_epi32
+            // ...
             intrinsic_args!(fx, args => (a, count); intrinsic);
 
+            // The shift count is the lower 64 bits of `count`.
             let count_lane = count.force_stack(fx).0.load(fx, types::I64, MemFlags::trusted());
-            let lane_ty = fx.clif_type(a.layout().ty.simd_size_and_type(fx.tcx).1).unwrap();
-            let max_count = fx.bcx.ins().iconst(types::I64, i64::from(lane_ty.bits() - 1));
-            let saturated_count = fx.bcx.ins().umin(count_lane, max_count);
 
             // FIXME use vector instructions when possible
             simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
-                fx.bcx.ins().sshr(a_lane, saturated_count)
+                x86_shift_lane(fx, intrinsic, a_lane, count_lane)
             });
         }
+
+        "llvm.x86.sse2.pslli.w"
+        | "llvm.x86.sse2.pslli.d"
+        | "llvm.x86.sse2.pslli.q"
+        | "llvm.x86.sse2.psrli.w"
+        | "llvm.x86.sse2.psrli.d"
+        | "llvm.x86.sse2.psrli.q"
+        | "llvm.x86.sse2.psrai.w"
+        | "llvm.x86.sse2.psrai.d"
+        | "llvm.x86.avx2.pslli.w"
+        | "llvm.x86.avx2.pslli.d"
+        | "llvm.x86.avx2.pslli.q"
+        | "llvm.x86.avx2.psrli.w"
+        | "llvm.x86.avx2.psrli.d"
+        | "llvm.x86.avx2.psrli.q"
+        | "llvm.x86.avx2.psrai.w"
+        | "llvm.x86.avx2.psrai.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_slli_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_srai_epi32
+            // ...
+            intrinsic_args!(fx, args => (a, imm8); intrinsic);
+
+            let count = imm8.load_scalar(fx);
+
+            // FIXME use vector instructions when possible
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
+                x86_shift_lane(fx, intrinsic, a_lane, count)
+            });
+        }
+
+        "llvm.x86.avx2.psllv.d"
+        | "llvm.x86.avx2.psllv.d.256"
+        | "llvm.x86.avx2.psllv.q"
+        | "llvm.x86.avx2.psllv.q.256"
+        | "llvm.x86.avx2.psrlv.d"
+        | "llvm.x86.avx2.psrlv.d.256"
+        | "llvm.x86.avx2.psrlv.q"
+        | "llvm.x86.avx2.psrlv.q.256"
+        | "llvm.x86.avx2.psrav.d"
+        | "llvm.x86.avx2.psrav.d.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sllv_epi32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_srav_epi32
+            // ...
+            intrinsic_args!(fx, args => (a, count); intrinsic);
+
+            // FIXME use vector instructions when possible
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                count,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, count_lane| {
+                    x86_shift_lane(fx, intrinsic, a_lane, count_lane)
+                },
+            );
+        }
         "llvm.x86.sse2.psad.bw" | "llvm.x86.avx2.psad.bw" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sad_epu8&ig_expand=5770
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_sad_epu8&ig_expand=5771
@@ -657,8 +764,9 @@ fn select4(
             }
         }
 
-        "llvm.x86.ssse3.pmul.hr.sw.128" => {
+        "llvm.x86.ssse3.pmul.hr.sw.128" | "llvm.x86.avx2.pmul.hr.sw" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mulhrs_epi16&ig_expand=4782
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_mulhrs_epi16
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
             assert_eq!(a.layout(), b.layout());
@@ -995,6 +1103,151 @@ fn select4(
             );
         }
 
+        "llvm.x86.sse42.pcmpistri128" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cmpistri
+            intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
+
+            let a = a.load_scalar(fx);
+            let b = b.load_scalar(fx);
+
+            let imm8 = imm_arg(fx, intrinsic, &args[2], span);
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(format!("pcmpistri xmm0, xmm1, {imm8}"))],
+                &[
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm0)),
+                        value: a,
+                    },
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm1)),
+                        value: b,
+                    },
+                    // Implicit result of the pcmpistri intrinsic
+                    CInlineAsmOperand::Out {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::cx)),
+                        late: true,
+                        place: Some(ret),
+                    },
+                ],
+                InlineAsmOptions::NOSTACK | InlineAsmOptions::PURE | InlineAsmOptions::NOMEM,
+            );
+        }
+
+        "llvm.x86.sse42.pcmpistrm128" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cmpistrm
+            intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
+
+            let a = a.load_scalar(fx);
+            let b = b.load_scalar(fx);
+
+            let imm8 = imm_arg(fx, intrinsic, &args[2], span);
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(format!("pcmpistrm xmm0, xmm1, {imm8}"))],
+                &[
+                    CInlineAsmOperand::InOut {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm0)),
+                        _late: true,
+                        in_value: a,
+                        out_place: Some(ret),
+                    },
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm1)),
+                        value: b,
+                    },
+                ],
+                InlineAsmOptions::NOSTACK | InlineAsmOptions::PURE | InlineAsmOptions::NOMEM,
+            );
+        }
+
+        "llvm.x86.sse42.pcmpestria128"
+        | "llvm.x86.sse42.pcmpestric128"
+        | "llvm.x86.sse42.pcmpestrio128"
+        | "llvm.x86.sse42.pcmpestris128"
+        | "llvm.x86.sse42.pcmpestriz128"
+        | "llvm.x86.sse42.pcmpistria128"
+        | "llvm.x86.sse42.pcmpistric128"
+        | "llvm.x86.sse42.pcmpistrio128"
+        | "llvm.x86.sse42.pcmpistris128"
+        | "llvm.x86.sse42.pcmpistriz128" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cmpestra
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cmpistrz
+            // ...
+            let explicit_len = intrinsic.contains(".pcmpestri");
+            let (a, b, la, lb, imm8) = if explicit_len {
+                intrinsic_args!(fx, args => (a, la, b, lb, _imm8); intrinsic);
+                let imm8 = imm_arg(fx, intrinsic, &args[4], span);
+                (a, b, Some(la.load_scalar(fx)), Some(lb.load_scalar(fx)), imm8)
+            } else {
+                intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
+                let imm8 = imm_arg(fx, intrinsic, &args[2], span);
+                (a, b, None, None, imm8)
+            };
+            let a = a.load_scalar(fx);
+            let b = b.load_scalar(fx);
+
+            // The last character before the `128` suffix names the flag to return.
+            let flag = intrinsic.strip_suffix("128").unwrap().chars().last().unwrap();
+            let instruction = if explicit_len { "pcmpestri" } else { "pcmpistri" };
+
+            let mut operands = vec![
+                CInlineAsmOperand::In {
+                    reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm0)),
+                    value: a,
+                },
+                CInlineAsmOperand::In {
+                    reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm1)),
+                    value: b,
+                },
+                // Implicit result of the pcmpistri and pcmpestri instructions
+                CInlineAsmOperand::Out {
+                    reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::cx)),
+                    late: true,
+                    place: None,
+                },
+            ];
+            if let (Some(la), Some(lb)) = (la, lb) {
+                operands.push(
+                    // Implicit argument to the pcmpestri instruction
+                    CInlineAsmOperand::InOut {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::ax)),
+                        _late: true,
+                        in_value: la,
+                        out_place: Some(ret),
+                    },
+                );
+                operands.push(
+                    // Implicit argument to the pcmpestri instruction
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::dx)),
+                        value: lb,
+                    },
+                );
+            } else {
+                operands.push(CInlineAsmOperand::Out {
+                    reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::ax)),
+                    late: true,
+                    place: Some(ret),
+                });
+            }
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(format!(
+                    "
+                    {instruction} xmm0, xmm1, {imm8}
+                    set{flag} al
+                    movzx eax, al
+                    "
+                ))],
+                &operands,
+                InlineAsmOptions::NOSTACK | InlineAsmOptions::PURE | InlineAsmOptions::NOMEM,
+            );
+        }
+
         "llvm.x86.pclmulqdq" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_clmulepi64_si128&ig_expand=772
             intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
@@ -1387,37 +1640,66 @@ fn select4(
             );
         }
 
-        "llvm.x86.avx.ptestz.256" => {
+        "llvm.x86.sse41.ptestz"
+        | "llvm.x86.sse41.ptestc"
+        | "llvm.x86.sse41.ptestnzc"
+        | "llvm.x86.avx.ptestz.256"
+        | "llvm.x86.avx.ptestc.256"
+        | "llvm.x86.avx.ptestnzc.256"
+        | "llvm.x86.avx.vtestz.ps"
+        | "llvm.x86.avx.vtestc.ps"
+        | "llvm.x86.avx.vtestnzc.ps"
+        | "llvm.x86.avx.vtestz.pd"
+        | "llvm.x86.avx.vtestc.pd"
+        | "llvm.x86.avx.vtestnzc.pd"
+        | "llvm.x86.avx.vtestz.ps.256"
+        | "llvm.x86.avx.vtestc.ps.256"
+        | "llvm.x86.avx.vtestnzc.ps.256"
+        | "llvm.x86.avx.vtestz.pd.256"
+        | "llvm.x86.avx.vtestc.pd.256"
+        | "llvm.x86.avx.vtestnzc.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_testz_si128
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_testz_si256&ig_expand=6945
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_testnzc_ps
+            // ...
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
             assert_eq!(a.layout(), b.layout());
-            let layout = a.layout();
-
-            let (lane_count, lane_ty) = layout.ty.simd_size_and_type(fx.tcx);
-            assert_eq!(lane_ty, fx.tcx.types.i64);
             assert_eq!(ret.layout().ty, fx.tcx.types.i32);
-            assert_eq!(lane_count, 4);
-
-            let a_lane0 = a.value_lane(fx, 0).load_scalar(fx);
-            let a_lane1 = a.value_lane(fx, 1).load_scalar(fx);
-            let a_lane2 = a.value_lane(fx, 2).load_scalar(fx);
-            let a_lane3 = a.value_lane(fx, 3).load_scalar(fx);
-            let b_lane0 = b.value_lane(fx, 0).load_scalar(fx);
-            let b_lane1 = b.value_lane(fx, 1).load_scalar(fx);
-            let b_lane2 = b.value_lane(fx, 2).load_scalar(fx);
-            let b_lane3 = b.value_lane(fx, 3).load_scalar(fx);
-
-            let zero0 = fx.bcx.ins().band(a_lane0, b_lane0);
-            let zero1 = fx.bcx.ins().band(a_lane1, b_lane1);
-            let zero2 = fx.bcx.ins().band(a_lane2, b_lane2);
-            let zero3 = fx.bcx.ins().band(a_lane3, b_lane3);
-
-            let all_zero0 = fx.bcx.ins().bor(zero0, zero1);
-            let all_zero1 = fx.bcx.ins().bor(zero2, zero3);
-            let all_zero = fx.bcx.ins().bor(all_zero0, all_zero1);
-
-            let res = fx.bcx.ins().icmp_imm(IntCC::Equal, all_zero, 0);
+
+            // `vtest*` only looks at the sign bit of every lane.
+            let mask = if intrinsic.contains(".ps") {
+                0x8000_0000_8000_0000u64 as i64
+            } else if intrinsic.contains(".pd") {
+                0x8000_0000_0000_0000u64 as i64
+            } else {
+                -1
+            };
+
+            let mut and = fx.bcx.ins().iconst(types::I64, 0);
+            let mut and_not = fx.bcx.ins().iconst(types::I64, 0);
+            for lane_idx in 0..a.layout().size.bytes() / 8 {
+                let a_lane = a.value_typed_lane(fx, fx.tcx.types.u64, lane_idx).load_scalar(fx);
+                let b_lane = b.value_typed_lane(fx, fx.tcx.types.u64, lane_idx).load_scalar(fx);
+                let and_lane = fx.bcx.ins().band(a_lane, b_lane);
+                and = fx.bcx.ins().bor(and, and_lane);
+                let and_not_lane = fx.bcx.ins().band_not(b_lane, a_lane);
+                and_not = fx.bcx.ins().bor(and_not, and_not_lane);
+            }
+            let and = fx.bcx.ins().band_imm(and, mask);
+            let and_not = fx.bcx.ins().band_imm(and_not, mask);
+
+            let zf = fx.bcx.ins().icmp_imm(IntCC::Equal, and, 0);
+            let cf = fx.bcx.ins().icmp_imm(IntCC::Equal, and_not, 0);
+            let res = if intrinsic.contains("testz") {
+                zf
+            } else if intrinsic.contains("testc") {
+                cf
+            } else {
+                let zf_or_cf = fx.bcx.ins().bor(zf, cf);
+                fx.bcx.ins().bxor_imm(zf_or_cf, 1)
+            };
+
             let res = CValue::by_val(
                 fx.bcx.ins().uextend(types::I32, res),
                 fx.layout_of(fx.tcx.types.i32),
@@ -1425,6 +1707,597 @@ fn select4(
             ret.write_cvalue(fx, res);
         }
 
+        "llvm.x86.sse.cvtss2si"
+        | "llvm.x86.sse.cvttss2si"
+        | "llvm.x86.sse.cvtss2si64"
+        | "llvm.x86.sse.cvttss2si64"
+        | "llvm.x86.sse2.cvtsd2si"
+        | "llvm.x86.sse2.cvttsd2si"
+        | "llvm.x86.sse2.cvtsd2si64"
+        | "llvm.x86.sse2.cvttsd2si64" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cvtss_si32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cvttsd_si64
+            // ...
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let a_lane = a.value_lane(fx, 0).load_scalar(fx);
+            let int_ty = fx.clif_type(ret.layout().ty).unwrap();
+            let res = x86_float_to_int(fx, a_lane, int_ty, intrinsic.contains(".cvtt"));
+            ret.write_cvalue(fx, CValue::by_val(res, ret.layout()));
+        }
+
+        "llvm.x86.sse2.cvtpd2dq"
+        | "llvm.x86.sse2.cvttpd2dq"
+        | "llvm.x86.avx.cvt.pd2dq.256"
+        | "llvm.x86.avx.cvtt.pd2dq.256"
+        | "llvm.x86.avx.cvt.ps2dq.256"
+        | "llvm.x86.avx.cvtt.ps2dq.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cvtpd_epi32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_cvttps_epi32
+            // ...
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let (lane_count, _lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let (ret_lane_count, ret_lane_ty) = ret.layout().ty.simd_size_and_type(fx.tcx);
+            assert_eq!(ret_lane_ty, fx.tcx.types.i32);
+            let ret_lane_layout = fx.layout_of(ret_lane_ty);
+            let truncate = intrinsic.contains(".cvtt");
+
+            for lane_idx in 0..lane_count {
+                let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let res = x86_float_to_int(fx, lane, types::I32, truncate);
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, CValue::by_val(res, ret_lane_layout));
+            }
+
+            // `cvtpd2dq` zeroes the upper half of the result.
+            for lane_idx in lane_count..ret_lane_count {
+                let zero = fx.bcx.ins().iconst(types::I32, 0);
+                ret.place_lane(fx, lane_idx)
+                    .write_cvalue(fx, CValue::by_val(zero, ret_lane_layout));
+            }
+        }
+
+        "llvm.x86.sse2.cvtsd2ss" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_cvtsd_ss
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let b_lane = b.value_lane(fx, 0).load_scalar(fx);
+            let res = fx.bcx.ins().fdemote(types::F32, b_lane);
+
+            ret.write_cvalue(fx, a);
+            ret.place_lane(fx, 0)
+                .write_cvalue(fx, CValue::by_val(res, fx.layout_of(fx.tcx.types.f32)));
+        }
+
+        "llvm.x86.sse41.round.ps"
+        | "llvm.x86.sse41.round.pd"
+        | "llvm.x86.avx.round.ps.256"
+        | "llvm.x86.avx.round.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_round_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_round_pd
+            // ...
+            intrinsic_args!(fx, args => (a, _rounding); intrinsic);
+
+            let rounding = imm_arg(fx, intrinsic, &args[1], span);
+
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                x86_round(fx, rounding, lane)
+            });
+        }
+
+        "llvm.x86.sse41.round.ss" | "llvm.x86.sse41.round.sd" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_round_ss
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_round_sd
+            intrinsic_args!(fx, args => (a, b, _rounding); intrinsic);
+
+            let rounding = imm_arg(fx, intrinsic, &args[2], span);
+
+            scalar_lane_op(fx, a, b, ret, &|fx, _a_lane, b_lane| x86_round(fx, rounding, b_lane));
+        }
+
+        "llvm.x86.sse41.dpps" | "llvm.x86.sse41.dppd" | "llvm.x86.avx.dp.ps.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_dp_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_dp_pd
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_dp_ps
+            intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
+
+            assert_eq!(a.layout(), b.layout());
+            assert_eq!(a.layout(), ret.layout());
+
+            let imm8 = imm_arg(fx, intrinsic, &args[2], span);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let lane_clif_ty = fx.clif_type(lane_ty).unwrap();
+            let ret_lane_layout = fx.layout_of(lane_ty);
+            let lanes_per_half = lane_count / (a.layout().size.bytes() / 16);
+
+            // The 256-bit variant computes two independent dot products, one per 128-bit half.
+            for half_start in (0..lane_count).step_by(lanes_per_half as usize) {
+                let zero = float_const(fx, lane_clif_ty, 0.0);
+
+                let mut products = vec![];
+                for i in 0..lanes_per_half {
+                    if imm8 & (1 << (4 + i)) != 0 {
+                        let a_lane = a.value_lane(fx, half_start + i).load_scalar(fx);
+                        let b_lane = b.value_lane(fx, half_start + i).load_scalar(fx);
+                        products.push(fx.bcx.ins().fmul(a_lane, b_lane));
+                    } else {
+                        products.push(zero);
+                    }
+                }
+
+                // Sum in the same order as the hardware does to get identical rounding.
+                let sum = if let [p0, p1, p2, p3] = products[..] {
+                    let sum01 = fx.bcx.ins().fadd(p0, p1);
+                    let sum23 = fx.bcx.ins().fadd(p2, p3);
+                    fx.bcx.ins().fadd(sum01, sum23)
+                } else if let [p0, p1] = products[..] {
+                    fx.bcx.ins().fadd(p0, p1)
+                } else {
+                    unreachable!()
+                };
+
+                for i in 0..lanes_per_half {
+                    let res = if imm8 & (1 << i) != 0 { sum } else { zero };
+                    ret.place_lane(fx, half_start + i)
+                        .write_cvalue(fx, CValue::by_val(res, ret_lane_layout));
+                }
+            }
+        }
+
+        "llvm.x86.sse41.insertps" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_insert_ps
+            intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
+
+            let imm8 = imm_arg(fx, intrinsic, &args[2], span);
+            let src_idx = u64::from(imm8 >> 6);
+            let dst_idx = u64::from((imm8 >> 4) & 0b11);
+
+            let b_lane = b.value_lane(fx, src_idx).load_scalar(fx);
+            let ret_lane_layout = fx.layout_of(fx.tcx.types.f32);
+
+            ret.write_cvalue(fx, a);
+            ret.place_lane(fx, dst_idx).write_cvalue(fx, CValue::by_val(b_lane, ret_lane_layout));
+
+            for lane_idx in 0..4 {
+                if imm8 & (1 << lane_idx) != 0 {
+                    let zero = float_const(fx, types::F32, 0.0);
+                    ret.place_lane(fx, lane_idx)
+                        .write_cvalue(fx, CValue::by_val(zero, ret_lane_layout));
+                }
+            }
+        }
+
+        "llvm.x86.sse41.mpsadbw" | "llvm.x86.avx2.mpsadbw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mpsadbw_epu8
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_mpsadbw_epu8
+            intrinsic_args!(fx, args => (a, b, _imm8); intrinsic);
+
+            assert_eq!(a.layout(), b.layout());
+            let imm8 = imm_arg(fx, intrinsic, &args[2], span);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let (_ret_lane_count, ret_lane_ty) = ret.layout().ty.simd_size_and_type(fx.tcx);
+            assert_eq!(lane_ty, fx.tcx.types.u8);
+            assert_eq!(ret_lane_ty, fx.tcx.types.u16);
+            let ret_lane_layout = fx.layout_of(ret_lane_ty);
+
+            // The upper 128-bit half of the 256-bit variant uses bits 3 to 5 of the immediate.
+            for half in 0..lane_count / 16 {
+                let control = imm8 >> (half * 3);
+                let a_offset = half * 16 + u64::from((control >> 2) & 0b1) * 4;
+                let b_offset = half * 16 + u64::from(control & 0b11) * 4;
+
+                for j in 0..8 {
+                    let mut sum = fx.bcx.ins().iconst(types::I16, 0);
+                    for k in 0..4 {
+                        let a_lane = a.value_lane(fx, a_offset + j + k).load_scalar(fx);
+                        let a_lane = fx.bcx.ins().uextend(types::I16, a_lane);
+                        let b_lane = b.value_lane(fx, b_offset + k).load_scalar(fx);
+                        let b_lane = fx.bcx.ins().uextend(types::I16, b_lane);
+
+                        let diff = fx.bcx.ins().isub(a_lane, b_lane);
+                        let abs_diff = fx.bcx.ins().iabs(diff);
+                        sum = fx.bcx.ins().iadd(sum, abs_diff);
+                    }
+
+                    ret.place_lane(fx, half * 8 + j)
+                        .write_cvalue(fx, CValue::by_val(sum, ret_lane_layout));
+                }
+            }
+        }
+
+        "llvm.x86.sse41.phminposuw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_minpos_epu16
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let ret_lane_layout = fx.layout_of(fx.tcx.types.u16);
+
+            let mut min = a.value_lane(fx, 0).load_scalar(fx);
+            let mut min_idx = fx.bcx.ins().iconst(types::I16, 0);
+            for lane_idx in 1..8 {
+                let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                // Use the lowest index when multiple lanes contain the minimum.
+                let is_less = fx.bcx.ins().icmp(IntCC::UnsignedLessThan, lane, min);
+                let lane_idx = fx.bcx.ins().iconst(types::I16, lane_idx as i64);
+                min = fx.bcx.ins().select(is_less, lane, min);
+                min_idx = fx.bcx.ins().select(is_less, lane_idx, min_idx);
+            }
+
+            ret.place_lane(fx, 0).write_cvalue(fx, CValue::by_val(min, ret_lane_layout));
+            ret.place_lane(fx, 1).write_cvalue(fx, CValue::by_val(min_idx, ret_lane_layout));
+            for lane_idx in 2..8 {
+                let zero = fx.bcx.ins().iconst(types::I16, 0);
+                ret.place_lane(fx, lane_idx)
+                    .write_cvalue(fx, CValue::by_val(zero, ret_lane_layout));
+            }
+        }
+
+        "llvm.x86.ssse3.psign.b.128"
+        | "llvm.x86.ssse3.psign.w.128"
+        | "llvm.x86.ssse3.psign.d.128"
+        | "llvm.x86.avx2.psign.b"
+        | "llvm.x86.avx2.psign.w"
+        | "llvm.x86.avx2.psign.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sign_epi8
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_sign_epi32
+            // ...
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                b,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| {
+                    let lane_ty = fx.bcx.func.dfg.value_type(a_lane);
+                    let zero = fx.bcx.ins().iconst(lane_ty, 0);
+                    let neg_a = fx.bcx.ins().ineg(a_lane);
+                    let is_neg = fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, b_lane, 0);
+                    let is_zero = fx.bcx.ins().icmp_imm(IntCC::Equal, b_lane, 0);
+                    let res = fx.bcx.ins().select(is_neg, neg_a, a_lane);
+                    fx.bcx.ins().select(is_zero, zero, res)
+                },
+            );
+        }
+
+        "llvm.x86.avx.vpermilvar.ps"
+        | "llvm.x86.avx.vpermilvar.ps.256"
+        | "llvm.x86.avx.vpermilvar.pd"
+        | "llvm.x86.avx.vpermilvar.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_permutevar_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar_pd
+            // ...
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let ret_lane_layout = fx.layout_of(lane_ty);
+            let lanes_per_half = lane_count / (a.layout().size.bytes() / 16);
+
+            // Every lane selects a lane from the same 128-bit half of `a`. For `pd` the selector
+            // is bit 1 rather than bit 0 of the control lane.
+            let mut res = vec![];
+            for lane_idx in 0..lane_count {
+                let control = b.value_lane(fx, lane_idx).load_scalar(fx);
+                let control =
+                    if lanes_per_half == 2 { fx.bcx.ins().ushr_imm(control, 1) } else { control };
+                let index = fx.bcx.ins().band_imm(control, lanes_per_half as i64 - 1);
+                let index = clif_intcast(fx, index, fx.pointer_type, false);
+                let half_start = lane_idx / lanes_per_half * lanes_per_half;
+                let index = fx.bcx.ins().iadd_imm(index, half_start as i64);
+                res.push(a.value_lane_dyn(fx, index).load_scalar(fx));
+            }
+
+            for (lane_idx, res_lane) in res.into_iter().enumerate() {
+                ret.place_lane(fx, lane_idx as u64)
+                    .write_cvalue(fx, CValue::by_val(res_lane, ret_lane_layout));
+            }
+        }
+
+        "llvm.x86.sse3.hadd.ps"
+        | "llvm.x86.sse3.hadd.pd"
+        | "llvm.x86.avx.hadd.ps.256"
+        | "llvm.x86.avx.hadd.pd.256"
+        | "llvm.x86.ssse3.phadd.w.128"
+        | "llvm.x86.ssse3.phadd.d.128"
+        | "llvm.x86.avx2.phadd.w"
+        | "llvm.x86.avx2.phadd.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadd_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_hadd_epi32
+            // ...
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            x86_horizontal_op(fx, a, b, ret, &|fx, lhs, rhs| {
+                if fx.bcx.func.dfg.value_type(lhs).is_float() {
+                    fx.bcx.ins().fadd(lhs, rhs)
+                } else {
+                    fx.bcx.ins().iadd(lhs, rhs)
+                }
+            });
+        }
+
+        "llvm.x86.sse3.hsub.ps"
+        | "llvm.x86.sse3.hsub.pd"
+        | "llvm.x86.avx.hsub.ps.256"
+        | "llvm.x86.avx.hsub.pd.256"
+        | "llvm.x86.ssse3.phsub.w.128"
+        | "llvm.x86.ssse3.phsub.d.128"
+        | "llvm.x86.avx2.phsub.w"
+        | "llvm.x86.avx2.phsub.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hsub_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_hsub_epi32
+            // ...
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            x86_horizontal_op(fx, a, b, ret, &|fx, lhs, rhs| {
+                if fx.bcx.func.dfg.value_type(lhs).is_float() {
+                    fx.bcx.ins().fsub(lhs, rhs)
+                } else {
+                    fx.bcx.ins().isub(lhs, rhs)
+                }
+            });
+        }
+
+        "llvm.x86.ssse3.phadd.sw.128"
+        | "llvm.x86.avx2.phadd.sw"
+        | "llvm.x86.ssse3.phsub.sw.128"
+        | "llvm.x86.avx2.phsub.sw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadds_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_hsubs_epi16
+            // ...
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let is_add = intrinsic.contains(".phadd.");
+            x86_horizontal_op(fx, a, b, ret, &|fx, lhs, rhs| {
+                let lhs = fx.bcx.ins().sextend(types::I32, lhs);
+                let rhs = fx.bcx.ins().sextend(types::I32, rhs);
+                let res =
+                    if is_add { fx.bcx.ins().iadd(lhs, rhs) } else { fx.bcx.ins().isub(lhs, rhs) };
+                x86_saturate_i16(fx, res)
+            });
+        }
+
+        "llvm.x86.sse3.addsub.ps"
+        | "llvm.x86.sse3.addsub.pd"
+        | "llvm.x86.avx.addsub.ps.256"
+        | "llvm.x86.avx.addsub.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_addsub_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_addsub_pd
+            // ...
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            assert_eq!(a.layout(), b.layout());
+            assert_eq!(a.layout(), ret.layout());
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let ret_lane_layout = fx.layout_of(lane_ty);
+
+            for lane_idx in 0..lane_count {
+                let a_lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let b_lane = b.value_lane(fx, lane_idx).load_scalar(fx);
+
+                let res = if lane_idx % 2 == 0 {
+                    fx.bcx.ins().fsub(a_lane, b_lane)
+                } else {
+                    fx.bcx.ins().fadd(a_lane, b_lane)
+                };
+
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, CValue::by_val(res, ret_lane_layout));
+            }
+        }
+
+        "llvm.x86.sse41.pblendvb"
+        | "llvm.x86.sse41.blendvps"
+        | "llvm.x86.sse41.blendvpd"
+        | "llvm.x86.avx.blendv.ps.256"
+        | "llvm.x86.avx.blendv.pd.256"
+        | "llvm.x86.avx2.pblendvb" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_blendv_epi8
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_blendv_ps
+            // ...
+            intrinsic_args!(fx, args => (a, b, mask); intrinsic);
+
+            simd_trio_for_each_lane(
+                fx,
+                a,
+                b,
+                mask,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane, mask_lane| {
+                    let use_b = x86_sign_bit(fx, mask_lane);
+                    fx.bcx.ins().select(use_b, b_lane, a_lane)
+                },
+            );
+        }
+
+        "llvm.x86.sse.movmsk.ps"
+        | "llvm.x86.sse2.movmsk.pd"
+        | "llvm.x86.sse2.pmovmskb.128"
+        | "llvm.x86.avx.movmsk.ps.256"
+        | "llvm.x86.avx.movmsk.pd.256"
+        | "llvm.x86.avx2.pmovmskb" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_movemask_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_movemask_epi8
+            // ...
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let (lane_count, _lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+
+            let mut res = fx.bcx.ins().iconst(types::I32, 0);
+            for lane_idx in 0..lane_count {
+                let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let sign_bit = x86_sign_bit(fx, lane);
+                let sign_bit = fx.bcx.ins().uextend(types::I32, sign_bit);
+                let sign_bit = fx.bcx.ins().ishl_imm(sign_bit, lane_idx as i64);
+                res = fx.bcx.ins().bor(res, sign_bit);
+            }
+
+            ret.write_cvalue(fx, CValue::by_val(res, fx.layout_of(fx.tcx.types.i32)));
+        }
+
+        "llvm.x86.avx.maskload.ps"
+        | "llvm.x86.avx.maskload.pd"
+        | "llvm.x86.avx.maskload.ps.256"
+        | "llvm.x86.avx.maskload.pd.256"
+        | "llvm.x86.avx2.maskload.d"
+        | "llvm.x86.avx2.maskload.q"
+        | "llvm.x86.avx2.maskload.d.256"
+        | "llvm.x86.avx2.maskload.q.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_maskload_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_maskload_epi64
+            // ...
+            intrinsic_args!(fx, args => (ptr, mask); intrinsic);
+
+            let (lane_count, lane_ty) = ret.layout().ty.simd_size_and_type(fx.tcx);
+            let lane_clif_ty = fx.clif_type(lane_ty).unwrap();
+            let ret_lane_layout = fx.layout_of(lane_ty);
+            let lane_size = ret_lane_layout.size.bytes();
+
+            let ptr = ptr.load_scalar(fx);
+            for lane_idx in 0..lane_count {
+                let mask_lane = mask.value_lane(fx, lane_idx).load_scalar(fx);
+                let is_enabled = x86_sign_bit(fx, mask_lane);
+
+                let if_enabled = fx.bcx.create_block();
+                let if_disabled = fx.bcx.create_block();
+                let next = fx.bcx.create_block();
+                let res_lane = fx.bcx.append_block_param(next, lane_clif_ty);
+
+                // Disabled lanes must not be accessed as they may be out of bounds.
+                fx.bcx.ins().brif(is_enabled, if_enabled, &[], if_disabled, &[]);
+                fx.bcx.seal_block(if_enabled);
+                fx.bcx.seal_block(if_disabled);
+
+                fx.bcx.switch_to_block(if_enabled);
+                let offset = i32::try_from(lane_idx * lane_size).unwrap();
+                let res = fx.bcx.ins().load(lane_clif_ty, MemFlags::new(), ptr, offset);
+                fx.bcx.ins().jump(next, &[res]);
+
+                fx.bcx.switch_to_block(if_disabled);
+                let zero_lane = fx.bcx.ins().iconst(lane_clif_ty.as_int(), 0);
+                let zero_lane = fx.bcx.ins().bitcast(lane_clif_ty, MemFlags::new(), zero_lane);
+                fx.bcx.ins().jump(next, &[zero_lane]);
+
+                fx.bcx.seal_block(next);
+                fx.bcx.switch_to_block(next);
+
+                ret.place_lane(fx, lane_idx)
+                    .write_cvalue(fx, CValue::by_val(res_lane, ret_lane_layout));
+            }
+        }
+
+        "llvm.x86.avx.maskstore.ps"
+        | "llvm.x86.avx.maskstore.pd"
+        | "llvm.x86.avx.maskstore.ps.256"
+        | "llvm.x86.avx.maskstore.pd.256"
+        | "llvm.x86.avx2.maskstore.d"
+        | "llvm.x86.avx2.maskstore.q"
+        | "llvm.x86.avx2.maskstore.d.256"
+        | "llvm.x86.avx2.maskstore.q.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_maskstore_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_maskstore_epi64
+            // ...
+            intrinsic_args!(fx, args => (ptr, mask, a); intrinsic);
+
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let lane_size = fx.layout_of(lane_ty).size.bytes();
+
+            let ptr = ptr.load_scalar(fx);
+            for lane_idx in 0..lane_count {
+                let mask_lane = mask.value_lane(fx, lane_idx).load_scalar(fx);
+                let a_lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let is_enabled = x86_sign_bit(fx, mask_lane);
+
+                let if_enabled = fx.bcx.create_block();
+                let next = fx.bcx.create_block();
+
+                // Disabled lanes must not be accessed as they may be out of bounds.
+                fx.bcx.ins().brif(is_enabled, if_enabled, &[], next, &[]);
+                fx.bcx.seal_block(if_enabled);
+
+                fx.bcx.switch_to_block(if_enabled);
+                let offset = i32::try_from(lane_idx * lane_size).unwrap();
+                fx.bcx.ins().store(MemFlags::new(), a_lane, ptr, offset);
+                fx.bcx.ins().jump(next, &[]);
+
+                fx.bcx.seal_block(next);
+                fx.bcx.switch_to_block(next);
+            }
+        }
+
+        "llvm.x86.sse.ldmxcsr" | "llvm.x86.sse.stmxcsr" | "llvm.x86.sse2.clflush" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_setcsr
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_getcsr
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_clflush
+            intrinsic_args!(fx, args => (p); intrinsic);
+
+            let p = p.load_scalar(fx);
+            let instruction = match intrinsic {
+                "llvm.x86.sse.ldmxcsr" => "ldmxcsr",
+                "llvm.x86.sse.stmxcsr" => "stmxcsr",
+                "llvm.x86.sse2.clflush" => "clflush",
+                _ => unreachable!(),
+            };
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(format!("{instruction} [rdi]"))],
+                &[CInlineAsmOperand::In {
+                    reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::di)),
+                    value: p,
+                }],
+                InlineAsmOptions::NOSTACK,
+            );
+        }
+
+        "llvm.x86.sse.sfence" | "llvm.x86.sse2.lfence" | "llvm.x86.sse2.mfence" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sfence
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_lfence
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mfence
+            let instruction = match intrinsic {
+                "llvm.x86.sse.sfence" => "sfence",
+                "llvm.x86.sse2.lfence" => "lfence",
+                "llvm.x86.sse2.mfence" => "mfence",
+                _ => unreachable!(),
+            };
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(instruction.to_string())],
+                &[],
+                InlineAsmOptions::NOSTACK,
+            );
+        }
+
+        "llvm.x86.sse2.maskmov.dqu" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_maskmoveu_si128
+            intrinsic_args!(fx, args => (a, mask, mem_addr); intrinsic);
+
+            let a = a.load_scalar(fx);
+            let mask = mask.load_scalar(fx);
+            let mem_addr = mem_addr.load_scalar(fx);
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String("maskmovdqu xmm0, xmm1".to_string())],
+                &[
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm0)),
+                        value: a,
+                    },
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::xmm1)),
+                        value: mask,
+                    },
+                    // Implicit argument to the maskmovdqu instruction
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::X86(X86InlineAsmReg::di)),
+                        value: mem_addr,
+                    },
+                ],
+                InlineAsmOptions::NOSTACK,
+            );
+        }
+
         "llvm.x86.rdtsc" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_rdtsc&ig_expand=5273
 
@@ -1611,3 +2484,270 @@ fn pack_instruction<'tcx>(
         round(b, 1, 3);
     }
 }
+
+fn imm_arg<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    intrinsic: &str,
+    arg: &Spanned<mir::Operand<'tcx>>,
+    span: Span,
+) -> u8 {
+    if let Some(imm) = crate::constant::mir_operand_get_const_val(fx, &arg.node) {
+        // Depending on the intrinsic the immediate is passed as `i8`, `u8` or `i32`.
+        imm.to_bits_unchecked() as u8
+    } else {
+        fx.tcx
+            .dcx()
+            .span_fatal(span, format!("Immediate argument for `{intrinsic}` is not a constant"));
+    }
+}
+
+fn float_const(fx: &mut FunctionCx<'_, '_, '_>, ty: Type, val: f64) -> Value {
+    match ty {
+        types::F32 => fx.bcx.ins().f32const(Ieee32::with_bits((val as f32).to_bits())),
+        types::F64 => fx.bcx.ins().f64const(Ieee64::with_bits(val.to_bits())),
+        _ => unreachable!("{ty:?}"),
+    }
+}
+
+/// Computes the lowest lane from `a` and `b` and copies all other lanes from `a`, like the
+/// `*ss` and `*sd` instructions do.
+fn scalar_lane_op<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    a: CValue<'tcx>,
+    b: CValue<'tcx>,
+    ret: CPlace<'tcx>,
+    f: &dyn Fn(&mut FunctionCx<'_, '_, 'tcx>, Value, Value) -> Value,
+) {
+    assert_eq!(a.layout(), ret.layout());
+
+    let (_, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+    let ret_lane_layout = fx.layout_of(lane_ty);
+
+    let a_lane = a.value_lane(fx, 0).load_scalar(fx);
+    let b_lane = b.value_lane(fx, 0).load_scalar(fx);
+    let res = f(fx, a_lane, b_lane);
+
+    ret.write_cvalue(fx, a);
+    ret.place_lane(fx, 0).write_cvalue(fx, CValue::by_val(res, ret_lane_layout));
+}
+
+/// Unlike `fmax`, `maxps` returns the second operand when either operand is NaN or both are zero.
+fn x86_fmax(fx: &mut FunctionCx<'_, '_, '_>, a: Value, b: Value) -> Value {
+    let a_is_greater = fx.bcx.ins().fcmp(FloatCC::GreaterThan, a, b);
+    fx.bcx.ins().select(a_is_greater, a, b)
+}
+
+/// Unlike `fmin`, `minps` returns the second operand when either operand is NaN or both are zero.
+fn x86_fmin(fx: &mut FunctionCx<'_, '_, '_>, a: Value, b: Value) -> Value {
+    let a_is_less = fx.bcx.ins().fcmp(FloatCC::LessThan, a, b);
+    fx.bcx.ins().select(a_is_less, a, b)
+}
+
+fn x86_reciprocal(fx: &mut FunctionCx<'_, '_, '_>, val: Value, sqrt: bool) -> Value {
+    // The hardware only computes an approximation. Computing the exact value is allowed too.
+    let ty = fx.bcx.func.dfg.value_type(val);
+    let one = float_const(fx, ty, 1.0);
+    let val = if sqrt { fx.bcx.ins().sqrt(val) } else { val };
+    fx.bcx.ins().fdiv(one, val)
+}
+
+fn x86_fcmp(fx: &mut FunctionCx<'_, '_, '_>, kind: u8, x: Value, y: Value) -> Value {
+    // Copied from stdarch
+    /// Equal (ordered, non-signaling)
+    const _CMP_EQ_OQ: i32 = 0x00;
+    /// Less-than (ordered, signaling)
+    const _CMP_LT_OS: i32 = 0x01;
+    /// Less-than-or-equal (ordered, signaling)
+    const _CMP_LE_OS: i32 = 0x02;
+    /// Unordered (non-signaling)
+    const _CMP_UNORD_Q: i32 = 0x03;
+    /// Not-equal (unordered, non-signaling)
+    const _CMP_NEQ_UQ: i32 = 0x04;
+    /// Not-less-than (unordered, signaling)
+    const _CMP_NLT_US: i32 = 0x05;
+    /// Not-less-than-or-equal (unordered, signaling)
+    const _CMP_NLE_US: i32 = 0x06;
+    /// Ordered (non-signaling)
+    const _CMP_ORD_Q: i32 = 0x07;
+    /// Equal (unordered, non-signaling)
+    const _CMP_EQ_UQ: i32 = 0x08;
+    /// Not-greater-than-or-equal (unordered, signaling)
+    const _CMP_NGE_US: i32 = 0x09;
+    /// Not-greater-than (unordered, signaling)
+    const _CMP_NGT_US: i32 = 0x0a;
+    /// False (ordered, non-signaling)
+    const _CMP_FALSE_OQ: i32 = 0x0b;
+    /// Not-equal (ordered, non-signaling)
+    const _CMP_NEQ_OQ: i32 = 0x0c;
+    /// Greater-than-or-equal (ordered, signaling)
+    const _CMP_GE_OS: i32 = 0x0d;
+    /// Greater-than (ordered, signaling)
+    const _CMP_GT_OS: i32 = 0x0e;
+    /// True (unordered, non-signaling)
+    const _CMP_TRUE_UQ: i32 = 0x0f;
+    /// Equal (ordered, signaling)
+    const _CMP_EQ_OS: i32 = 0x10;
+    /// Less-than (ordered, non-signaling)
+    const _CMP_LT_OQ: i32 = 0x11;
+    /// Less-than-or-equal (ordered, non-signaling)
+    const _CMP_LE_OQ: i32 = 0x12;
+    /// Unordered (signaling)
+    const _CMP_UNORD_S: i32 = 0x13;
+    /// Not-equal (unordered, signaling)
+    const _CMP_NEQ_US: i32 = 0x14;
+    /// Not-less-than (unordered, non-signaling)
+    const _CMP_NLT_UQ: i32 = 0x15;
+    /// Not-less-than-or-equal (unordered, non-signaling)
+    const _CMP_NLE_UQ: i32 = 0x16;
+    /// Ordered (signaling)
+    const _CMP_ORD_S: i32 = 0x17;
+    /// Equal (unordered, signaling)
+    const _CMP_EQ_US: i32 = 0x18;
+    /// Not-greater-than-or-equal (unordered, non-signaling)
+    const _CMP_NGE_UQ: i32 = 0x19;
+    /// Not-greater-than (unordered, non-signaling)
+    const _CMP_NGT_UQ: i32 = 0x1a;
+    /// False (ordered, signaling)
+    const _CMP_FALSE_OS: i32 = 0x1b;
+    /// Not-equal (ordered, signaling)
+    const _CMP_NEQ_OS: i32 = 0x1c;
+    /// Greater-than-or-equal (ordered, non-signaling)
+    const _CMP_GE_OQ: i32 = 0x1d;
+    /// Greater-than (ordered, non-signaling)
+    const _CMP_GT_OQ: i32 = 0x1e;
+    /// True (unordered, signaling)
+    const _CMP_TRUE_US: i32 = 0x1f;
+
+    let flt_cc = match i32::from(kind) {
+        _CMP_EQ_OQ | _CMP_EQ_OS => FloatCC::Equal,
+        _CMP_LT_OS | _CMP_LT_OQ => FloatCC::LessThan,
+        _CMP_LE_OS | _CMP_LE_OQ => FloatCC::LessThanOrEqual,
+        _CMP_UNORD_Q | _CMP_UNORD_S => FloatCC::Unordered,
+        _CMP_NEQ_UQ | _CMP_NEQ_US => FloatCC::NotEqual,
+        _CMP_NLT_US | _CMP_NLT_UQ => FloatCC::UnorderedOrGreaterThanOrEqual,
+        _CMP_NLE_US | _CMP_NLE_UQ => FloatCC::UnorderedOrGreaterThan,
+        _CMP_ORD_Q | _CMP_ORD_S => FloatCC::Ordered,
+        _CMP_EQ_UQ | _CMP_EQ_US => FloatCC::UnorderedOrEqual,
+        _CMP_NGE_US | _CMP_NGE_UQ => FloatCC::UnorderedOrLessThan,
+        _CMP_NGT_US | _CMP_NGT_UQ => FloatCC::UnorderedOrLessThanOrEqual,
+        _CMP_FALSE_OQ | _CMP_FALSE_OS => return fx.bcx.ins().iconst(types::I8, 0),
+        _CMP_NEQ_OQ | _CMP_NEQ_OS => FloatCC::OrderedNotEqual,
+        _CMP_GE_OS | _CMP_GE_OQ => FloatCC::GreaterThanOrEqual,
+        _CMP_GT_OS | _CMP_GT_OQ => FloatCC::GreaterThan,
+        _CMP_TRUE_UQ | _CMP_TRUE_US => return fx.bcx.ins().iconst(types::I8, 1),
+
+        kind => unreachable!("kind {:?}", kind),
+    };
+
+    fx.bcx.ins().fcmp(flt_cc, x, y)
+}
+
+/// Converts like `cvtps2dq` and `cvttps2dq`, which return the minimum integer value for NaN and
+/// out of range inputs.
+fn x86_float_to_int(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    val: Value,
+    int_ty: Type,
+    truncate: bool,
+) -> Value {
+    // FIXME respect the rounding mode in MXCSR instead of assuming round to nearest even
+    let float_ty = fx.bcx.func.dfg.value_type(val);
+    let rounded = if truncate { fx.bcx.ins().trunc(val) } else { fx.bcx.ins().nearest(val) };
+
+    let limit = 2f64.powi(int_ty.bits() as i32 - 1);
+    let min = float_const(fx, float_ty, -limit);
+    let max = float_const(fx, float_ty, limit);
+    let ge_min = fx.bcx.ins().fcmp(FloatCC::GreaterThanOrEqual, rounded, min);
+    let lt_max = fx.bcx.ins().fcmp(FloatCC::LessThan, rounded, max);
+    let in_range = fx.bcx.ins().band(ge_min, lt_max);
+
+    let res = fx.bcx.ins().fcvt_to_sint_sat(int_ty, rounded);
+    let int_min = fx.bcx.ins().iconst(int_ty, (1u64 << (int_ty.bits() - 1)) as i64);
+    fx.bcx.ins().select(in_range, res, int_min)
+}
+
+fn x86_round(fx: &mut FunctionCx<'_, '_, '_>, rounding: u8, val: Value) -> Value {
+    // When bit 2 is set the rounding mode in MXCSR is used, which is assumed to be round to
+    // nearest even.
+    let rounding = if rounding & 0b100 != 0 { 0 } else { rounding & 0b11 };
+    match rounding {
+        0 => fx.bcx.ins().nearest(val),
+        1 => fx.bcx.ins().floor(val),
+        2 => fx.bcx.ins().ceil(val),
+        3 => fx.bcx.ins().trunc(val),
+        _ => unreachable!(),
+    }
+}
+
+/// Shifts a single lane for the `psll*`, `psrl*` and `psra*` family of intrinsics. Unlike the
+/// Cranelift shift instructions, these don't mask the shift count.
+fn x86_shift_lane(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    intrinsic: &str,
+    lane: Value,
+    count: Value,
+) -> Value {
+    let lane_ty = fx.bcx.func.dfg.value_type(lane);
+    let count_ty = fx.bcx.func.dfg.value_type(count);
+    let max_count = fx.bcx.ins().iconst(count_ty, i64::from(lane_ty.bits() - 1));
+
+    if intrinsic.contains(".psra") {
+        // Shifting by more than the lane width fills the lane with the sign bit.
+        let saturated_count = fx.bcx.ins().umin(count, max_count);
+        fx.bcx.ins().sshr(lane, saturated_count)
+    } else {
+        // Shifting by more than the lane width zeroes the lane.
+        let res = if intrinsic.contains(".psll") {
+            fx.bcx.ins().ishl(lane, count)
+        } else {
+            fx.bcx.ins().ushr(lane, count)
+        };
+        let zero = fx.bcx.ins().iconst(lane_ty, 0);
+        let out_of_range = fx.bcx.ins().icmp(IntCC::UnsignedGreaterThan, count, max_count);
+        fx.bcx.ins().select(out_of_range, zero, res)
+    }
+}
+
+/// Applies `f` to adjacent pairs of lanes within each 128-bit half, taking the pairs from `a` for
+/// the lower half of the result lanes and from `b` for the upper half.
+fn x86_horizontal_op<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    a: CValue<'tcx>,
+    b: CValue<'tcx>,
+    ret: CPlace<'tcx>,
+    f: &dyn Fn(&mut FunctionCx<'_, '_, 'tcx>, Value, Value) -> Value,
+) {
+    assert_eq!(a.layout(), b.layout());
+    assert_eq!(a.layout(), ret.layout());
+
+    let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+    let ret_lane_layout = fx.layout_of(lane_ty);
+    let lanes_per_half = lane_count / (a.layout().size.bytes() / 16);
+
+    for half_start in (0..lane_count).step_by(lanes_per_half as usize) {
+        for i in 0..lanes_per_half {
+            let (src, pair) =
+                if i < lanes_per_half / 2 { (a, i) } else { (b, i - lanes_per_half / 2) };
+            let lhs = src.value_lane(fx, half_start + pair * 2).load_scalar(fx);
+            let rhs = src.value_lane(fx, half_start + pair * 2 + 1).load_scalar(fx);
+            let res = f(fx, lhs, rhs);
+            ret.place_lane(fx, half_start + i)
+                .write_cvalue(fx, CValue::by_val(res, ret_lane_layout));
+        }
+    }
+}
+
+fn x86_saturate_i16(fx: &mut FunctionCx<'_, '_, '_>, val: Value) -> Value {
+    let min = fx.bcx.ins().iconst(types::I32, i64::from(i32::from(i16::MIN) as u32));
+    let max = fx.bcx.ins().iconst(types::I32, i64::from(i16::MAX));
+    let val = fx.bcx.ins().smax(val, min);
+    let val = fx.bcx.ins().smin(val, max);
+    fx.bcx.ins().ireduce(types::I16, val)
+}
+
+/// Returns the sign bit of an integer or float lane as an `i8` boolean.
+fn x86_sign_bit(fx: &mut FunctionCx<'_, '_, '_>, lane: Value) -> Value {
+    let lane_ty = fx.bcx.func.dfg.value_type(lane);
+    let lane = if lane_ty.is_float() { codegen_bitcast(fx, lane_ty.as_int(), lane) } else { lane };
+    fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, lane, 0)
+}
@@ -996,7 +996,9 @@ pub(crate) fn assert_assignable<'tcx>(
                 }
             }
         }
-        (ty::Param(_), _) | (_, ty::Param(_)) if fx.tcx.sess.opts.unstable_opts.polymorphize => {
+        (ty::Param(_), _) | (_, ty::Param(_))
+            if fx.tcx.sess.opts.unstable_opts.polymorphize.is_enabled() =>
+        {
             // No way to check if it is correct or not with polymorphization enabled
         }
         _ => {
@@ -55,6 +55,30 @@ pub fn iter_globals(llmod: &llvm::Module) -> ValueIter<'_> {
     unsafe { ValueIter { cur: llvm::LLVMGetFirstGlobal(llmod), step: llvm::LLVMGetNextGlobal } }
 }
 
+/// Counts the instructions of the function named `symbol_name` in `llmod`,
+/// for `-Zdump-mono-stats`. Returns `None` if `llmod` doesn't define it.
+pub fn function_instruction_count(llmod: &llvm::Module, symbol_name: &str) -> Option<usize> {
+    let llval = unsafe {
+        llvm::LLVMRustGetNamedValue(llmod, symbol_name.as_ptr().cast(), symbol_name.len())
+    };
+    let llfn = unsafe { llvm::LLVMIsAFunction(llval?) }?;
+    if unsafe { llvm::LLVMIsDeclaration(llfn) } != 0 {
+        return None;
+    }
+
+    let mut count = 0;
+    let mut llbb = Some(unsafe { llvm::LLVMGetFirstBasicBlock(llfn) });
+    while let Some(bb) = llbb {
+        let instructions = ValueIter {
+            cur: unsafe { llvm::LLVMGetFirstInstruction(bb) },
+            step: llvm::LLVMGetNextInstruction,
+        };
+        count += instructions.count();
+        llbb = unsafe { llvm::LLVMGetNextBasicBlock(bb) };
+    }
+    Some(count)
+}
+
 pub fn compile_codegen_unit(tcx: TyCtxt<'_>, cgu_name: Symbol) -> (ModuleCodegen<ModuleLlvm>, u64) {
     let start_time = Instant::now();
 
@@ -529,11 +529,17 @@ fn get_containing_scope<'ll, 'tcx>(
             if let Some(impl_def_id) = cx.tcx.impl_of_method(instance.def_id()) {
                 // If the method does *not* belong to a trait, proceed
                 if cx.tcx.trait_id_of_impl(impl_def_id).is_none() {
-                    let impl_self_ty = cx.tcx.instantiate_and_normalize_erasing_regions(
-                        instance.args,
-                        ty::ParamEnv::reveal_all(),
-                        cx.tcx.type_of(impl_def_id),
-                    );
+                    let impl_self_ty = if let ty::InstanceKind::ColdPathShim(..) = instance.def {
+                        // The outlined cold paths of a method are shared by all instances of the
+                        // impl, so they have no generic arguments.
+                        cx.tcx.type_of(impl_def_id).instantiate_identity()
+                    } else {
+                        cx.tcx.instantiate_and_normalize_erasing_regions(
+                            instance.args,
+                            ty::ParamEnv::reveal_all(),
+                            cx.tcx.type_of(impl_def_id),
+                        )
+                    };
 
                     // Only "class" methods are generally understood by LLVM,
                     // so avoid methods on other types (e.g., `<*mut T>::null`).
@@ -130,6 +130,13 @@ fn compile_codegen_unit(
     ) -> (ModuleCodegen<ModuleLlvm>, u64) {
         base::compile_codegen_unit(tcx, cgu_name)
     }
+    fn function_instruction_count(
+        &self,
+        module: &ModuleCodegen<ModuleLlvm>,
+        symbol_name: &str,
+    ) -> Option<usize> {
+        base::function_instruction_count(module.module_llvm.llmod(), symbol_name)
+    }
     fn target_machine_factory(
         &self,
         sess: &Session,
@@ -1002,6 +1002,7 @@ pub fn LLVMCreateStringAttribute(
     ) -> &Attribute;
 
     // Operations on functions
+    pub fn LLVMIsAFunction(Val: &Value) -> Option<&Value>;
     pub fn LLVMSetFunctionCallConv(Fn: &Value, CC: c_uint);
 
     // Operations on parameters
@@ -1011,6 +1012,8 @@ pub fn LLVMCreateStringAttribute(
 
     // Operations on basic blocks
     pub fn LLVMGetBasicBlockParent(BB: &BasicBlock) -> &Value;
+    pub fn LLVMGetNextBasicBlock(BB: &BasicBlock) -> Option<&BasicBlock>;
+    pub fn LLVMGetFirstInstruction(BB: &BasicBlock) -> Option<&Value>;
     pub fn LLVMAppendBasicBlockInContext<'a>(
         C: &'a Context,
         Fn: &'a Value,
@@ -1020,6 +1023,7 @@ pub fn LLVMAppendBasicBlockInContext<'a>(
     // Operations on instructions
     pub fn LLVMIsAInstruction(Val: &Value) -> Option<&Value>;
     pub fn LLVMGetFirstBasicBlock(Fn: &Value) -> &BasicBlock;
+    pub fn LLVMGetNextInstruction(Inst: &Value) -> Option<&Value>;
 
     // Operations on call sites
     pub fn LLVMSetInstructionCallConv(Instr: &Value, CC: c_uint);
@@ -34,6 +34,7 @@
 use rustc_middle::query::Providers;
 use rustc_middle::ty::layout::{HasTyCtxt, LayoutOf, TyAndLayout};
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
+use rustc_monomorphize::CodegenUnitStats;
 use rustc_session::config::{self, CrateType, EntryFnType, OptLevel, OutputType};
 use rustc_session::Session;
 use rustc_span::symbol::sym;
@@ -722,8 +723,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
             let start_time = Instant::now();
 
             let pre_compiled_cgus = par_map(cgus, |(i, _)| {
+                let start_time = Instant::now();
                 let module = backend.compile_codegen_unit(tcx, codegen_units[i].name());
-                (i, module)
+                (i, (module, start_time.elapsed()))
             });
 
             total_codegen_time += start_time.elapsed();
@@ -734,6 +736,8 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
         FxHashMap::default()
     };
 
+    let mut codegen_stats = tcx.sess.opts.unstable_opts.dump_mono_stats.enabled().then(Vec::new);
+
     for (i, cgu) in codegen_units.iter().enumerate() {
         ongoing_codegen.wait_for_signal_to_codegen_item();
         ongoing_codegen.check_for_errors(tcx.sess);
@@ -742,14 +746,21 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 
         match cgu_reuse {
             CguReuse::No => {
-                let (module, cost) = if let Some(cgu) = pre_compiled_cgus.remove(&i) {
-                    cgu
-                } else {
-                    let start_time = Instant::now();
-                    let module = backend.compile_codegen_unit(tcx, cgu.name());
-                    total_codegen_time += start_time.elapsed();
-                    module
+                let ((module, cost), codegen_time) = match pre_compiled_cgus.remove(&i) {
+                    Some(compiled) => compiled,
+                    None => {
+                        let start_time = Instant::now();
+                        let module = backend.compile_codegen_unit(tcx, cgu.name());
+                        let codegen_time = start_time.elapsed();
+                        total_codegen_time += codegen_time;
+                        (module, codegen_time)
+                    }
                 };
+                // The module must be inspected before it's handed to the backend,
+                // which starts optimizing it right away.
+                if let Some(stats) = &mut codegen_stats {
+                    stats.push(codegen_unit_stats(&backend, tcx, cgu, &module, codegen_time));
+                }
                 // This will unwind if there are errors, which triggers our `AbortCodegenOnDrop`
                 // guard. Unfortunately, just skipping the `submit_codegened_module_to_llvm` makes
                 // compilation hang on post-monomorphization errors.
@@ -788,6 +799,10 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
 
     ongoing_codegen.codegen_finished(tcx);
 
+    if let Some(codegen_stats) = codegen_stats {
+        rustc_monomorphize::dump_codegen_stats(tcx, &codegen_stats);
+    }
+
     // Since the main thread is sometimes blocked during codegen, we keep track
     // -Ztime-passes output manually.
     if tcx.sess.opts.unstable_opts.time_passes {
@@ -806,6 +821,27 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
     ongoing_codegen
 }
 
+/// Gathers the `-Zdump-mono-stats` numbers of a freshly generated codegen unit.
+fn codegen_unit_stats<'tcx, B: ExtraBackendMethods>(
+    backend: &B,
+    tcx: TyCtxt<'tcx>,
+    cgu: &CodegenUnit<'tcx>,
+    module: &ModuleCodegen<B::Module>,
+    codegen_time: Duration,
+) -> CodegenUnitStats<'tcx> {
+    let instruction_counts = cgu
+        .items_in_deterministic_order(tcx)
+        .into_iter()
+        .filter(|(mono_item, _)| matches!(mono_item, MonoItem::Fn(_)))
+        .filter_map(|(mono_item, _)| {
+            let symbol_name = mono_item.symbol_name(tcx);
+            let count = backend.function_instruction_count(module, symbol_name.name)?;
+            Some((mono_item, count))
+        })
+        .collect();
+    CodegenUnitStats { name: cgu.name(), codegen_time, instruction_counts }
+}
+
 impl CrateInfo {
     pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
         let crate_types = tcx.crate_types().to_vec();
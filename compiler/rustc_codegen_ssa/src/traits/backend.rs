@@ -128,6 +128,17 @@ fn compile_codegen_unit(
         tcx: TyCtxt<'_>,
         cgu_name: Symbol,
     ) -> (ModuleCodegen<Self::Module>, u64);
+    /// Returns the number of IR instructions the freshly generated `module`
+    /// contains for the function named `symbol_name`, for `-Zdump-mono-stats`.
+    /// Returns `None` if the function isn't defined in `module`, or if the
+    /// backend can't tell.
+    fn function_instruction_count(
+        &self,
+        _module: &ModuleCodegen<Self::Module>,
+        _symbol_name: &str,
+    ) -> Option<usize> {
+        None
+    }
     fn target_machine_factory(
         &self,
         sess: &Session,
@@ -586,6 +586,7 @@ pub(crate) fn eval_fn_call(
             | ty::InstanceKind::CloneShim(..)
             | ty::InstanceKind::FnPtrAddrShim(..)
             | ty::InstanceKind::ThreadLocalShim(..)
+            | ty::InstanceKind::ColdPathShim(..)
             | ty::InstanceKind::AsyncDropGlueCtorShim(..)
             | ty::InstanceKind::Item(_) => {
                 // We need MIR for this fn
@@ -79,25 +79,37 @@
 //! invocation) and allocate the corresponding strings together with a mapping
 //! for `DepNodeIndex as StringId`.
 //!
+//!
+//! ## Summaries
+//!
+//! Independently of `measureme`, `-Zself-profile-summary` makes the
+//! `SelfProfilerRef` aggregate the time spent in each query and generic
+//! activity in memory, and write the totals to a small JSON file at the end of
+//! compilation. Query invocations are attributed to query names the same way
+//! `event_id`s are: by walking the query caches just before the query context
+//! is dropped, see `SelfProfileSummary::map_query_invocations_to_query_name()`.
+//!
 //! [mm]: https://github.com/rust-lang/measureme/
 
 use crate::fx::FxHashMap;
 use crate::outline;
 
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::error::Error;
 use std::fmt::Display;
 use std::fs;
 use std::intrinsics::unlikely;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 pub use measureme::EventId;
 use measureme::{EventIdBuilder, Profiler, SerializableString, StringId};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use smallvec::SmallVec;
 use tracing::warn;
 
@@ -124,6 +136,13 @@ struct EventFilter: u16 {
                         Self::ARTIFACT_SIZES.bits();
 
         const ARGS = Self::QUERY_KEYS.bits() | Self::FUNCTION_ARGS.bits();
+
+        // The events aggregated by `-Z self-profile-summary`.
+        const SUMMARY = Self::GENERIC_ACTIVITIES.bits() |
+                        Self::QUERY_PROVIDERS.bits() |
+                        Self::QUERY_CACHE_HITS.bits() |
+                        Self::INCR_CACHE_LOADS.bits() |
+                        Self::INCR_RESULT_HASHING.bits();
     }
 }
 
@@ -170,6 +189,9 @@ pub struct SelfProfilerRef {
     // actually enabled.
     event_filter_mask: EventFilter,
 
+    // This field is `None` unless `-Z self-profile-summary` is passed.
+    summary: Option<Arc<SelfProfileSummary>>,
+
     // Print verbose generic activities to stderr.
     print_verbose_generic_activities: Option<TimePassesFormat>,
 }
@@ -177,14 +199,18 @@ pub struct SelfProfilerRef {
 impl SelfProfilerRef {
     pub fn new(
         profiler: Option<Arc<SelfProfiler>>,
+        summary: Option<Arc<SelfProfileSummary>>,
         print_verbose_generic_activities: Option<TimePassesFormat>,
     ) -> SelfProfilerRef {
-        // If there is no SelfProfiler then the filter mask is set to NONE,
-        // ensuring that nothing ever tries to actually access it.
-        let event_filter_mask =
+        // If there is no SelfProfiler and no summary then the filter mask is
+        // set to NONE, ensuring that nothing ever tries to actually access them.
+        let mut event_filter_mask =
             profiler.as_ref().map_or(EventFilter::empty(), |p| p.event_filter_mask);
+        if summary.is_some() {
+            event_filter_mask |= EventFilter::SUMMARY;
+        }
 
-        SelfProfilerRef { profiler, event_filter_mask, print_verbose_generic_activities }
+        SelfProfilerRef { profiler, event_filter_mask, summary, print_verbose_generic_activities }
     }
 
     /// This shim makes sure that calls only get executed if the filter mask
@@ -192,23 +218,43 @@ pub fn new(
     /// code is optimized for non-profiling compilation sessions, i.e. anything
     /// past the filter check is never inlined so it doesn't clutter the fast
     /// path.
+    ///
+    /// `summary_event` is what the event counts as in the summary, if any.
     #[inline(always)]
-    fn exec<F>(&self, event_filter: EventFilter, f: F) -> TimingGuard<'_>
+    fn exec<F>(
+        &self,
+        event_filter: EventFilter,
+        summary_event: SummaryEvent,
+        f: F,
+    ) -> TimingGuard<'_>
     where
         F: for<'a> FnOnce(&'a SelfProfiler) -> TimingGuard<'a>,
     {
         #[inline(never)]
         #[cold]
-        fn cold_call<F>(profiler_ref: &SelfProfilerRef, f: F) -> TimingGuard<'_>
+        fn cold_call<F>(
+            profiler_ref: &SelfProfilerRef,
+            event_filter: EventFilter,
+            summary_event: SummaryEvent,
+            f: F,
+        ) -> TimingGuard<'_>
         where
             F: for<'a> FnOnce(&'a SelfProfiler) -> TimingGuard<'a>,
         {
-            let profiler = profiler_ref.profiler.as_ref().unwrap();
-            f(profiler)
+            // The filter mask may only let the event pass because of the
+            // summary, so check the profiler's own mask as well.
+            let mut guard = match &profiler_ref.profiler {
+                Some(profiler) if profiler.event_filter_mask.contains(event_filter) => f(profiler),
+                _ => TimingGuard::none(),
+            };
+            if let Some(summary) = &profiler_ref.summary {
+                guard.summary = summary.start(summary_event);
+            }
+            guard
         }
 
         if self.event_filter_mask.contains(event_filter) {
-            cold_call(self, f)
+            cold_call(self, event_filter, summary_event, f)
         } else {
             TimingGuard::none()
         }
@@ -248,7 +294,8 @@ pub fn verbose_generic_activity_with_arg<A>(
     /// TimingGuard returned from this call is dropped.
     #[inline(always)]
     pub fn generic_activity(&self, event_label: &'static str) -> TimingGuard<'_> {
-        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+        let summary_event = SummaryEvent::GenericActivity(event_label);
+        self.exec(EventFilter::GENERIC_ACTIVITIES, summary_event, |profiler| {
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = EventId::from_label(event_label);
             TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
@@ -259,7 +306,7 @@ pub fn generic_activity(&self, event_label: &'static str) -> TimingGuard<'_> {
     /// TimingGuard returned from this call is dropped.
     #[inline(always)]
     pub fn generic_activity_with_event_id(&self, event_id: EventId) -> TimingGuard<'_> {
-        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+        self.exec(EventFilter::GENERIC_ACTIVITIES, SummaryEvent::None, |profiler| {
             TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
         })
     }
@@ -275,7 +322,8 @@ pub fn generic_activity_with_arg<A>(
     where
         A: Borrow<str> + Into<String>,
     {
-        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+        let summary_event = SummaryEvent::GenericActivity(event_label);
+        self.exec(EventFilter::GENERIC_ACTIVITIES, summary_event, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
@@ -318,7 +366,8 @@ pub fn generic_activity_with_arg_recorder<F>(
         F: FnMut(&mut EventArgRecorder<'_>),
     {
         // Ensure this event will only be recorded when self-profiling is turned on.
-        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+        let summary_event = SummaryEvent::GenericActivity(event_label);
+        self.exec(EventFilter::GENERIC_ACTIVITIES, summary_event, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
 
@@ -357,7 +406,7 @@ pub fn artifact_size<A>(&self, artifact_kind: &str, artifact_name: A, size: u64)
     where
         A: Borrow<str> + Into<String>,
     {
-        drop(self.exec(EventFilter::ARTIFACT_SIZES, |profiler| {
+        drop(self.exec(EventFilter::ARTIFACT_SIZES, SummaryEvent::None, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
             let event_label = profiler.get_or_alloc_cached_string(artifact_kind);
             let event_arg = profiler.get_or_alloc_cached_string(artifact_name);
@@ -381,7 +430,8 @@ pub fn generic_activity_with_args(
         event_label: &'static str,
         event_args: &[String],
     ) -> TimingGuard<'_> {
-        self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
+        let summary_event = SummaryEvent::GenericActivity(event_label);
+        self.exec(EventFilter::GENERIC_ACTIVITIES, summary_event, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
             let event_label = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
@@ -401,7 +451,7 @@ pub fn generic_activity_with_args(
     /// TimingGuard returned from this call is dropped.
     #[inline(always)]
     pub fn query_provider(&self) -> TimingGuard<'_> {
-        self.exec(EventFilter::QUERY_PROVIDERS, |profiler| {
+        self.exec(EventFilter::QUERY_PROVIDERS, SummaryEvent::Query, |profiler| {
             TimingGuard::start(profiler, profiler.query_event_kind, EventId::INVALID)
         })
     }
@@ -412,10 +462,19 @@ pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
         #[inline(never)]
         #[cold]
         fn cold_call(profiler_ref: &SelfProfilerRef, query_invocation_id: QueryInvocationId) {
-            profiler_ref.instant_query_event(
-                |profiler| profiler.query_cache_hit_event_kind,
-                query_invocation_id,
-            );
+            if let Some(summary) = &profiler_ref.summary {
+                summary.record_query_cache_hit(query_invocation_id);
+            }
+            if profiler_ref
+                .profiler
+                .as_ref()
+                .is_some_and(|p| p.event_filter_mask.contains(EventFilter::QUERY_CACHE_HITS))
+            {
+                profiler_ref.instant_query_event(
+                    |profiler| profiler.query_cache_hit_event_kind,
+                    query_invocation_id,
+                );
+            }
         }
 
         if unlikely(self.event_filter_mask.contains(EventFilter::QUERY_CACHE_HITS)) {
@@ -428,7 +487,7 @@ fn cold_call(profiler_ref: &SelfProfilerRef, query_invocation_id: QueryInvocatio
     /// dropped.
     #[inline(always)]
     pub fn query_blocked(&self) -> TimingGuard<'_> {
-        self.exec(EventFilter::QUERY_BLOCKED, |profiler| {
+        self.exec(EventFilter::QUERY_BLOCKED, SummaryEvent::None, |profiler| {
             TimingGuard::start(profiler, profiler.query_blocked_event_kind, EventId::INVALID)
         })
     }
@@ -438,7 +497,7 @@ pub fn query_blocked(&self) -> TimingGuard<'_> {
     /// TimingGuard returned from this call is dropped.
     #[inline(always)]
     pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
-        self.exec(EventFilter::INCR_CACHE_LOADS, |profiler| {
+        self.exec(EventFilter::INCR_CACHE_LOADS, SummaryEvent::IncrCacheLoad, |profiler| {
             TimingGuard::start(
                 profiler,
                 profiler.incremental_load_result_event_kind,
@@ -451,7 +510,7 @@ pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
     /// Profiling continues until the TimingGuard returned from this call is dropped.
     #[inline(always)]
     pub fn incr_result_hashing(&self) -> TimingGuard<'_> {
-        self.exec(EventFilter::INCR_RESULT_HASHING, |profiler| {
+        self.exec(EventFilter::INCR_RESULT_HASHING, SummaryEvent::IncrResultHashing, |profiler| {
             TimingGuard::start(
                 profiler,
                 profiler.incremental_result_hashing_event_kind,
@@ -503,6 +562,17 @@ pub fn llvm_recording_enabled(&self) -> bool {
     pub fn get_self_profiler(&self) -> Option<Arc<SelfProfiler>> {
         self.profiler.clone()
     }
+
+    #[inline]
+    pub fn summary_enabled(&self) -> bool {
+        self.summary.is_some()
+    }
+
+    pub fn with_summary(&self, f: impl FnOnce(&SelfProfileSummary)) {
+        if let Some(summary) = &self.summary {
+            f(summary)
+        }
+    }
 }
 
 /// A helper for recording costly arguments to self-profiling events. Used with
@@ -680,7 +750,10 @@ pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
 }
 
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a> {
+    guard: Option<measureme::TimingGuard<'a>>,
+    summary: Option<SummaryTimer<'a>>,
+}
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -693,23 +766,27 @@ pub fn start(
         let raw_profiler = &profiler.profiler;
         let timing_guard =
             raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        TimingGuard { guard: Some(timing_guard), summary: None }
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard { guard, summary } = self;
+        if let Some(guard) = guard {
             outline(|| {
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
                 guard.finish_with_override_event_id(event_id);
             });
         }
+        if let Some(mut summary) = summary {
+            summary.query_invocation_id = Some(query_invocation_id);
+        }
     }
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard { guard: None, summary: None }
     }
 
     #[inline(always)]
@@ -719,6 +796,228 @@ pub fn run<R>(self, f: impl FnOnce() -> R) -> R {
     }
 }
 
+/// What an event counts as in the `SelfProfileSummary`.
+#[derive(Clone, Copy)]
+enum SummaryEvent {
+    /// The event is not part of the summary.
+    None,
+    GenericActivity(&'static str),
+    Query,
+    IncrCacheLoad,
+    IncrResultHashing,
+}
+
+/// The statistics the summary aggregates for a query or generic activity.
+#[derive(Clone, Copy, Default)]
+struct SummaryStats {
+    invocations: u64,
+    /// The time spent in the query or generic activity itself, i.e. excluding
+    /// the time spent in the events nested in it.
+    self_time: Duration,
+    cache_hits: u64,
+    incr_load_time: Duration,
+    incr_result_hashing_time: Duration,
+}
+
+impl SummaryStats {
+    fn add(&mut self, other: &SummaryStats) {
+        self.invocations += other.invocations;
+        self.self_time += other.self_time;
+        self.cache_hits += other.cache_hits;
+        self.incr_load_time += other.incr_load_time;
+        self.incr_result_hashing_time += other.incr_result_hashing_time;
+    }
+}
+
+thread_local! {
+    /// For each summary event in progress on this thread, innermost last, the
+    /// time spent in the events nested in it so far.
+    static SUMMARY_CHILD_TIMES: RefCell<Vec<Duration>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Aggregates the time spent in each query and generic activity for
+/// `-Z self-profile-summary`, without going through `measureme`.
+pub struct SelfProfileSummary {
+    path: PathBuf,
+    crate_name: String,
+    start_time: Instant,
+
+    generic_activities: Mutex<FxHashMap<&'static str, SummaryStats>>,
+    /// The statistics of the query invocations that have not been mapped to
+    /// a query name yet.
+    query_invocations: Mutex<FxHashMap<u32, SummaryStats>>,
+    queries: Mutex<FxHashMap<&'static str, SummaryStats>>,
+}
+
+impl SelfProfileSummary {
+    pub fn new(output_directory: &Path, crate_name: Option<&str>) -> SelfProfileSummary {
+        let crate_name = crate_name.unwrap_or("unknown-crate");
+        // Name the file like the `measureme` profile, see `SelfProfiler::new`.
+        let pid: u32 = process::id();
+        let filename = format!("{crate_name}-{pid:07}.summary.json");
+        SelfProfileSummary {
+            path: output_directory.join(filename),
+            crate_name: crate_name.to_owned(),
+            start_time: Instant::now(),
+            generic_activities: Default::default(),
+            query_invocations: Default::default(),
+            queries: Default::default(),
+        }
+    }
+
+    fn start(&self, event: SummaryEvent) -> Option<SummaryTimer<'_>> {
+        if let SummaryEvent::None = event {
+            return None;
+        }
+        SUMMARY_CHILD_TIMES.with_borrow_mut(|child_times| child_times.push(Duration::ZERO));
+        Some(SummaryTimer {
+            summary: self,
+            event,
+            start_time: Instant::now(),
+            query_invocation_id: None,
+        })
+    }
+
+    fn record_query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
+        self.query_invocations.lock().entry(query_invocation_id.0).or_default().cache_hits += 1;
+    }
+
+    /// Attributes the statistics of the given query invocations to the query
+    /// named `query_name`.
+    pub fn map_query_invocations_to_query_name(
+        &self,
+        query_name: &'static str,
+        query_invocation_ids: impl Iterator<Item = QueryInvocationId>,
+    ) {
+        let mut query_invocations = self.query_invocations.lock();
+        let mut stats = SummaryStats::default();
+        for query_invocation_id in query_invocation_ids {
+            if let Some(invocation_stats) = query_invocations.remove(&query_invocation_id.0) {
+                stats.add(&invocation_stats);
+            }
+        }
+        self.queries.lock().entry(query_name).or_default().add(&stats);
+    }
+
+    /// The file the summary is written to: `<crate name>-<pid>.summary.json`
+    /// in the output directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.to_json().to_string())
+    }
+
+    fn to_json(&self) -> JsonSelfProfileSummary<'_> {
+        let mut events: Vec<_> = self
+            .generic_activities
+            .lock()
+            .iter()
+            .map(|(&label, &stats)| ("generic_activity", label, stats))
+            .collect();
+        let mut queries = self.queries.lock().clone();
+        // Invocations of queries that ran after the query names were mapped
+        // show up as "<unknown>", like in the `measureme` profile.
+        for stats in self.query_invocations.lock().values() {
+            queries.entry("<unknown>").or_default().add(stats);
+        }
+        events.extend(queries.into_iter().map(|(label, stats)| ("query", label, stats)));
+        events.sort_by(|(_, label_a, a), (_, label_b, b)| {
+            b.self_time.cmp(&a.self_time).then(label_a.cmp(label_b))
+        });
+        JsonSelfProfileSummary {
+            crate_name: &self.crate_name,
+            total_time: self.start_time.elapsed().as_secs_f64(),
+            events,
+        }
+    }
+}
+
+/// Records a summary event when dropped.
+struct SummaryTimer<'a> {
+    summary: &'a SelfProfileSummary,
+    event: SummaryEvent,
+    start_time: Instant,
+    /// The query invocation the event belongs to, for all events but generic
+    /// activities. Set by `TimingGuard::finish_with_query_invocation_id`.
+    query_invocation_id: Option<QueryInvocationId>,
+}
+
+impl Drop for SummaryTimer<'_> {
+    fn drop(&mut self) {
+        let time = self.start_time.elapsed();
+        let child_time = SUMMARY_CHILD_TIMES.with_borrow_mut(|child_times| {
+            let child_time = child_times.pop().unwrap();
+            if let Some(parent_child_time) = child_times.last_mut() {
+                *parent_child_time += time;
+            }
+            child_time
+        });
+        let self_time = time.saturating_sub(child_time);
+
+        let mut generic_activities;
+        let mut query_invocations;
+        let stats = match (self.event, &self.query_invocation_id) {
+            (SummaryEvent::GenericActivity(label), _) => {
+                generic_activities = self.summary.generic_activities.lock();
+                generic_activities.entry(label).or_default()
+            }
+            (_, Some(query_invocation_id)) => {
+                query_invocations = self.summary.query_invocations.lock();
+                query_invocations.entry(query_invocation_id.0).or_default()
+            }
+            // A query that did not finish, e.g. because of a panic.
+            (_, None) => return,
+        };
+        match self.event {
+            SummaryEvent::GenericActivity(_) | SummaryEvent::Query => {
+                stats.invocations += 1;
+                stats.self_time += self_time;
+            }
+            SummaryEvent::IncrCacheLoad => stats.incr_load_time += time,
+            SummaryEvent::IncrResultHashing => stats.incr_result_hashing_time += time,
+            SummaryEvent::None => unreachable!(),
+        }
+    }
+}
+
+struct JsonSelfProfileSummary<'a> {
+    crate_name: &'a str,
+    total_time: f64,
+    /// The kind, label and statistics of each query and generic activity.
+    events: Vec<(&'static str, &'static str, SummaryStats)>,
+}
+
+impl Display for JsonSelfProfileSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { crate_name, total_time, events } = self;
+        write!(f, r#"{{"crate":"{crate_name}","total_time":{total_time},"events":["#)?;
+        for (i, (kind, label, stats)) in events.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(
+                f,
+                r#"{{"kind":"{kind}","label":"{label}","invocations":{},"self_time":{},"#,
+                stats.invocations,
+                stats.self_time.as_secs_f64(),
+            )?;
+            write!(
+                f,
+                r#""cache_hits":{},"incr_load_time":{},"incr_result_hashing_time":{}}}"#,
+                stats.cache_hits,
+                stats.incr_load_time.as_secs_f64(),
+                stats.incr_result_hashing_time.as_secs_f64(),
+            )?;
+        }
+        write!(f, "]}}")
+    }
+}
+
 struct VerboseInfo {
     start_time: Instant,
     start_rss: Option<usize>,
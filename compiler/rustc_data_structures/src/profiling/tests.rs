@@ -1,4 +1,6 @@
-use super::JsonTimePassesEntry;
+use std::time::Duration;
+
+use super::{JsonSelfProfileSummary, JsonTimePassesEntry, SummaryStats};
 
 #[test]
 fn with_rss() {
@@ -17,3 +19,39 @@ fn no_rss() {
         r#"{"pass":"typeck","time":56.1,"rss_start":null,"rss_end":null}"#
     )
 }
+
+#[test]
+fn summary() {
+    let typeck = SummaryStats {
+        invocations: 3,
+        self_time: Duration::from_millis(500),
+        cache_hits: 7,
+        incr_load_time: Duration::ZERO,
+        incr_result_hashing_time: Duration::from_millis(250),
+    };
+    let expand = SummaryStats {
+        invocations: 1,
+        self_time: Duration::from_millis(125),
+        ..Default::default()
+    };
+    let summary = JsonSelfProfileSummary {
+        crate_name: "foo",
+        total_time: 1.5,
+        events: vec![
+            ("query", "typeck", typeck),
+            ("generic_activity", "macro_expand_crate", expand),
+        ],
+    };
+
+    assert_eq!(
+        summary.to_string(),
+        concat!(
+            r#"{"crate":"foo","total_time":1.5,"events":["#,
+            r#"{"kind":"query","label":"typeck","invocations":3,"self_time":0.5,"cache_hits":7,"#,
+            r#""incr_load_time":0,"incr_result_hashing_time":0.25},"#,
+            r#"{"kind":"generic_activity","label":"macro_expand_crate","invocations":1,"#,
+            r#""self_time":0.125,"cache_hits":0,"incr_load_time":0,"incr_result_hashing_time":0}"#,
+            r#"]}"#,
+        )
+    );
+}
@@ -13,6 +13,7 @@
 use rustc_macros::{Decodable, Encodable};
 use rustc_span::Span;
 use std::borrow::Cow;
+use std::env;
 use std::error::Error;
 use std::fmt;
 use std::fs;
@@ -115,7 +116,8 @@ fn from(mut errs: Vec<FluentError>) -> Self {
 }
 
 /// Returns Fluent bundle with the user's locale resources from
-/// `$sysroot/share/locale/$requested_locale/*.ftl`.
+/// `$sysroot/share/locale/$requested_locale/*.ftl`. If there are no resources for the requested
+/// locale, the resources of a more general locale are used, see [`available_locale`].
 ///
 /// If `-Z additional-ftl-path` was provided, load that resource and add it  to the bundle
 /// (overriding any conflicting messages).
@@ -155,11 +157,17 @@ pub fn fluent_bundle(
 
     // If the user requests the default locale then don't try to load anything.
     if let Some(requested_locale) = requested_locale {
-        let mut found_resources = false;
+        let sysroots: Vec<_> =
+            user_provided_sysroot.iter().chain(sysroot_candidates.iter()).cloned().collect();
+        let Some(available_locale) = available_locale(&sysroots, &requested_locale) else {
+            return Err(TranslationBundleError::MissingLocale);
+        };
+        trace!(?available_locale);
+
         for sysroot in user_provided_sysroot.iter_mut().chain(sysroot_candidates.iter_mut()) {
             sysroot.push("share");
             sysroot.push("locale");
-            sysroot.push(requested_locale.to_string());
+            sysroot.push(available_locale.to_string());
             trace!(?sysroot);
 
             if !sysroot.exists() {
@@ -186,13 +194,8 @@ pub fn fluent_bundle(
                     FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
                 trace!(?resource);
                 bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
-                found_resources = true;
             }
         }
-
-        if !found_resources {
-            return Err(TranslationBundleError::MissingLocale);
-        }
     }
 
     if let Some(additional_ftl_path) = additional_ftl_path {
@@ -208,6 +211,41 @@ pub fn fluent_bundle(
     Ok(Some(bundle))
 }
 
+/// Returns the locale whose resources are used for `requested_locale`: the first of the
+/// requested locale and its more general locales (e.g. `es-ES` for `es-ES-valencia`, then `es`)
+/// that has a `$sysroot/share/locale/$locale` directory in one of the `sysroots`.
+pub fn available_locale(
+    sysroots: &[PathBuf],
+    requested_locale: &LanguageIdentifier,
+) -> Option<LanguageIdentifier> {
+    let LanguageIdentifier { language, script, region, .. } = *requested_locale;
+    let candidates = [
+        requested_locale.clone(),
+        LanguageIdentifier::from_parts(language, script, region, &[]),
+        LanguageIdentifier::from_parts(language, script, None, &[]),
+        LanguageIdentifier::from_parts(language, None, None, &[]),
+    ];
+    candidates.into_iter().find(|locale| {
+        sysroots
+            .iter()
+            .any(|sysroot| sysroot.join("share").join("locale").join(locale.to_string()).exists())
+    })
+}
+
+/// Returns the language of the messages requested by the environment, from the `LC_ALL`,
+/// `LC_MESSAGES` or `LANG` variables like in POSIX, or `None` for the default locale.
+pub fn locale_from_env() -> Option<LanguageIdentifier> {
+    let var = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))?;
+    // POSIX locales look like `language[_territory][.codeset][@modifier]`, e.g. `es_ES.UTF-8`.
+    let locale = var.split(['.', '@']).next().unwrap_or_default();
+    if locale == "C" || locale == "POSIX" {
+        return None;
+    }
+    locale.replace('_', "-").parse().ok()
+}
+
 fn register_functions(bundle: &mut FluentBundle) {
     bundle
         .add_function("STREQ", |positional, _named| match positional {
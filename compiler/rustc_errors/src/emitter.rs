@@ -200,6 +200,10 @@ fn emit_unused_externs(
     ) {
     }
 
+    /// Emit how each `--extern` crate is used, as `(name, usage)` pairs.
+    /// Currently only supported for the JSON format.
+    fn emit_extern_usage(&mut self, _externs: &[(&str, &str)]) {}
+
     /// Checks if should show explanations about "rustc --explain"
     fn should_show_explain(&self) -> bool {
         true
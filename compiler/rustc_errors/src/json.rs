@@ -102,6 +102,7 @@ enum EmitTyped<'a> {
     Artifact(ArtifactNotification<'a>),
     FutureIncompat(FutureIncompatReport<'a>),
     UnusedExtern(UnusedExterns<'a>),
+    ExternUsage(ExternUsageReport<'a>),
 }
 
 impl Translate for JsonEmitter {
@@ -166,6 +167,15 @@ fn emit_unused_externs(&mut self, lint_level: rustc_lint_defs::Level, unused_ext
         }
     }
 
+    fn emit_extern_usage(&mut self, externs: &[(&str, &str)]) {
+        let externs =
+            externs.iter().map(|&(name, usage)| ExternUsageEntry { name, usage }).collect();
+        let result = self.emit(EmitTyped::ExternUsage(ExternUsageReport { externs }));
+        if let Err(e) = result {
+            panic!("failed to print extern usage: {e:?}");
+        }
+    }
+
     fn source_map(&self) -> Option<&Lrc<SourceMap>> {
         Some(&self.sm)
     }
@@ -283,6 +293,20 @@ struct UnusedExterns<'a> {
     unused_extern_names: &'a [&'a str],
 }
 
+#[derive(Serialize)]
+struct ExternUsageReport<'a> {
+    /// The usage of every reported `--extern` crate, sorted by name.
+    externs: Vec<ExternUsageEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExternUsageEntry<'a> {
+    /// The name the crate was passed with to `--extern`.
+    name: &'a str,
+    /// One of `unused`, `test-only`, `macros-only` or `used`.
+    usage: &'a str,
+}
+
 impl Diagnostic {
     /// Converts from `rustc_errors::DiagInner` to `Diagnostic`.
     fn from_errors_diagnostic(diag: crate::DiagInner, je: &JsonEmitter) -> Diagnostic {
@@ -39,8 +39,8 @@
 };
 pub use emitter::ColorConfig;
 pub use rustc_error_messages::{
-    fallback_fluent_bundle, fluent_bundle, DiagMessage, FluentBundle, LanguageIdentifier,
-    LazyFallbackBundle, MultiSpan, SpanLabel, SubdiagMessage,
+    available_locale, fallback_fluent_bundle, fluent_bundle, locale_from_env, DiagMessage,
+    FluentBundle, LanguageIdentifier, LazyFallbackBundle, MultiSpan, SpanLabel, SubdiagMessage,
 };
 pub use rustc_lint_defs::{pluralize, Applicability};
 pub use rustc_span::fatal_error::{FatalError, FatalErrorMarker};
@@ -86,6 +86,7 @@
 mod lock;
 pub mod markdown;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -486,6 +487,10 @@ struct DiagCtxtInner {
 
     future_breakage_diagnostics: Vec<DiagInner>,
 
+    /// The suggestions collected for `-Z apply-suggestions`, see
+    /// [`DiagCtxtFlags::collect_suggestions`].
+    collected_suggestions: Vec<Substitution>,
+
     /// The [`Self::unstable_expect_diagnostics`] should be empty when this struct is
     /// dropped. However, it can have values if the compilation is stopped early
     /// or is only partially executed. To avoid ICEs, like in rust#94953 we only
@@ -565,6 +570,10 @@ pub struct DiagCtxtFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// If Some, the suggestions of the emitted diagnostics that are at least as likely to be
+    /// correct as this applicability are collected, so that they can be applied to the source.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub collect_suggestions: Option<Applicability>,
 }
 
 impl Drop for DiagCtxtInner {
@@ -729,6 +738,7 @@ pub fn reset_err_count(&self) {
             emitted_diagnostics,
             stashed_diagnostics,
             future_breakage_diagnostics,
+            collected_suggestions,
             check_unstable_expect_diagnostics,
             unstable_expect_diagnostics,
             fulfilled_expectations,
@@ -750,6 +760,7 @@ pub fn reset_err_count(&self) {
         *emitted_diagnostics = Default::default();
         *stashed_diagnostics = Default::default();
         *future_breakage_diagnostics = Default::default();
+        *collected_suggestions = Default::default();
         *check_unstable_expect_diagnostics = false;
         *unstable_expect_diagnostics = Default::default();
         *fulfilled_expectations = Default::default();
@@ -1054,6 +1065,12 @@ pub fn emit_future_breakage_report(&self) {
         }
     }
 
+    /// Takes the suggestions collected for `-Z apply-suggestions`, in the order in which their
+    /// diagnostics were emitted.
+    pub fn take_collected_suggestions(&self) -> Vec<Substitution> {
+        std::mem::take(&mut self.inner.borrow_mut().collected_suggestions)
+    }
+
     pub fn emit_unused_externs(
         &self,
         lint_level: rustc_lint_defs::Level,
@@ -1083,6 +1100,12 @@ pub fn emit_unused_externs(
         inner.emitter.emit_unused_externs(lint_level, unused_externs)
     }
 
+    /// Emits the `-Z extern-usage-report=json` message. Like the unused externs, it is only
+    /// produced with JSON output.
+    pub fn emit_extern_usage(&self, externs: &[(&str, &str)]) {
+        self.inner.borrow_mut().emitter.emit_extern_usage(externs)
+    }
+
     pub fn update_unstable_expectation_id(
         &self,
         unstable_to_stable: &FxIndexMap<LintExpectationId, LintExpectationId>,
@@ -1429,6 +1452,7 @@ fn new(emitter: Box<DynEmitter>) -> Self {
             emitted_diagnostics: Default::default(),
             stashed_diagnostics: Default::default(),
             future_breakage_diagnostics: Vec::new(),
+            collected_suggestions: Vec::new(),
             check_unstable_expect_diagnostics: false,
             unstable_expect_diagnostics: Vec::new(),
             fulfilled_expectations: Default::default(),
@@ -1611,6 +1635,9 @@ fn emit_diagnostic(
                 }
                 self.has_printed = true;
 
+                if let Some(applicability) = self.flags.collect_suggestions {
+                    self.collect_suggestions(&diagnostic, applicability);
+                }
                 self.emitter.emit_diagnostic(diagnostic);
             }
 
@@ -1645,6 +1672,20 @@ fn emit_diagnostic(
         })
     }
 
+    /// Collects the suggestions of `diagnostic` for `-Z apply-suggestions`. Suggestions with
+    /// several alternatives are skipped, as there is no way to choose between them.
+    fn collect_suggestions(&mut self, diagnostic: &DiagInner, applicability: Applicability) {
+        let Ok(suggestions) = &diagnostic.suggestions else { return };
+        for suggestion in suggestions {
+            // Applicabilities are ordered from the most to the least likely to be correct.
+            if let [substitution] = &suggestion.substitutions[..]
+                && suggestion.applicability <= applicability
+            {
+                self.collected_suggestions.push(substitution.clone());
+            }
+        }
+    }
+
     fn treat_err_as_bug(&self) -> bool {
         self.flags
             .treat_err_as_bug
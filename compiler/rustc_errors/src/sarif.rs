@@ -0,0 +1,518 @@
+//! A SARIF emitter for errors.
+//!
+//! [SARIF] (Static Analysis Results Interchange Format) is the format many code
+//! scanning services ingest. Unlike the JSON emitter, which prints one object per
+//! diagnostic, a SARIF log is a single document, so this emitter collects results
+//! as they are emitted and writes the log, containing one run for the crate being
+//! compiled, when it is dropped.
+//!
+//! Diagnostics are mapped as follows:
+//!
+//! * every top-level diagnostic becomes a `result`, with its error code or lint
+//!   name as the `ruleId`;
+//! * every distinct error code or lint name becomes a `rule` of the tool driver,
+//!   with the `--explain` text as its description where one is available;
+//! * primary spans become `locations`, secondary span labels and subdiagnostics
+//!   with spans become `relatedLocations`, and subdiagnostics without spans are
+//!   appended to the result message;
+//! * every suggested substitution becomes a `fix`.
+//!
+//! As with the JSON output, the exact output should be considered *unstable*.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{to_fluent_args, Translate};
+use crate::{
+    diagnostic::IsLint, CodeSuggestion, DiagInner, ErrCode, FluentBundle, LazyFallbackBundle,
+    Level, MultiSpan, Subdiag,
+};
+use derive_setters::Setters;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::sync::{IntoDynSyncSend, Lrc};
+use rustc_error_messages::FluentArgs;
+use rustc_lint_defs::Applicability;
+use rustc_span::source_map::SourceMap;
+use rustc_span::Span;
+use serde::Serialize;
+use std::error::Report;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Setters)]
+pub struct SarifEmitter {
+    #[setters(skip)]
+    dst: IntoDynSyncSend<Box<dyn Write + Send>>,
+    registry: Option<Registry>,
+    #[setters(skip)]
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    #[setters(skip)]
+    fallback_bundle: LazyFallbackBundle,
+    #[setters(skip)]
+    pretty: bool,
+    /// Name of the crate being compiled, used to identify the run.
+    crate_name: Option<String>,
+    /// Version of the compiler, reported as the version of the tool driver.
+    tool_version: Option<&'static str>,
+    /// Whether to write a log when no results were emitted. Emitters that only
+    /// live for part of the compilation (such as the early one) turn this off so
+    /// that a successful compilation produces exactly one log.
+    emit_empty_log: bool,
+    #[setters(skip)]
+    rules: Vec<Rule>,
+    #[setters(skip)]
+    rule_indices: FxHashMap<String, usize>,
+    #[setters(skip)]
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        sm: Lrc<SourceMap>,
+        fallback_bundle: LazyFallbackBundle,
+        pretty: bool,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: IntoDynSyncSend(dst),
+            registry: None,
+            sm,
+            fluent_bundle: None,
+            fallback_bundle,
+            pretty,
+            crate_name: None,
+            tool_version: None,
+            emit_empty_log: true,
+            rules: Vec::new(),
+            rule_indices: FxHashMap::default(),
+            results: Vec::new(),
+        }
+    }
+
+    fn emit_log(&mut self) -> io::Result<()> {
+        let run = Run {
+            tool: Tool {
+                driver: ToolComponent {
+                    name: "rustc",
+                    information_uri: "https://www.rust-lang.org/",
+                    version: self.tool_version,
+                    rules: &self.rules,
+                },
+            },
+            automation_details: self
+                .crate_name
+                .as_ref()
+                .map(|crate_name| RunAutomationDetails { id: format!("rustc/{crate_name}/") }),
+            column_kind: "unicodeCodePoints",
+            results: &self.results,
+        };
+        let log = Log { schema: SARIF_SCHEMA, version: SARIF_VERSION, runs: [run] };
+        if self.pretty {
+            serde_json::to_writer_pretty(&mut *self.dst, &log)?
+        } else {
+            serde_json::to_writer(&mut *self.dst, &log)?
+        };
+        self.dst.write_all(b"\n")?;
+        self.dst.flush()
+    }
+
+    /// Returns the index of the rule describing `id`, registering it on first use.
+    fn rule_index(&mut self, id: &str, code: Option<ErrCode>) -> usize {
+        if let Some(&index) = self.rule_indices.get(id) {
+            return index;
+        }
+        let full_description = code.and_then(|code| {
+            let description = self.registry.as_ref()?.try_find_description(code).ok()?;
+            Some(MultiformatMessage { text: description.to_owned(), markdown: description })
+        });
+        let help_uri =
+            code.map(|code| format!("https://doc.rust-lang.org/error_codes/{code}.html"));
+        let index = self.rules.len();
+        self.rules.push(Rule { id: id.to_owned(), full_description, help_uri });
+        self.rule_indices.insert(id.to_owned(), index);
+        index
+    }
+
+    fn artifact_location(&self, span: Span) -> ArtifactLocation {
+        let file = self.sm.lookup_source_file(span.lo());
+        let name = self.sm.filename_for_diagnostics(&file.name).to_string();
+        artifact_location_for_path(&name)
+    }
+
+    fn region(&self, span: Span) -> Region {
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset: byte_start,
+            byte_length: byte_end - byte_start,
+        }
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Option<Location> {
+        if span.is_dummy() {
+            return None;
+        }
+        Some(Location {
+            physical_location: PhysicalLocation {
+                artifact_location: self.artifact_location(span),
+                region: self.region(span),
+            },
+            message: message.map(|text| Message { text }),
+        })
+    }
+
+    /// Splits the labels of `msp` into primary locations and related locations.
+    fn locations(
+        &self,
+        msp: &MultiSpan,
+        args: &FluentArgs<'_>,
+        prefix: Option<&str>,
+    ) -> (Vec<Location>, Vec<Location>) {
+        let mut primary = vec![];
+        let mut related = vec![];
+        for label in msp.span_labels() {
+            let message = label
+                .label
+                .as_ref()
+                .map(|m| self.translate_message(m, args).map_err(Report::new).unwrap())
+                .map(|m| match prefix {
+                    Some(prefix) => format!("{prefix}: {m}"),
+                    None => m.to_string(),
+                });
+            let Some(location) = self.location(label.span, message) else { continue };
+            if label.is_primary { primary.push(location) } else { related.push(location) }
+        }
+        (primary, related)
+    }
+
+    fn fixes(&self, suggestion: &CodeSuggestion, args: &FluentArgs<'_>) -> Vec<Fix> {
+        let description =
+            self.translate_message(&suggestion.msg, args).map_err(Report::new).unwrap();
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut changes: FxIndexMap<String, ArtifactChange> = FxIndexMap::default();
+                for part in &substitution.parts {
+                    let artifact_location = self.artifact_location(part.span);
+                    changes
+                        .entry(artifact_location.uri.clone())
+                        .or_insert_with(|| ArtifactChange {
+                            artifact_location,
+                            replacements: vec![],
+                        })
+                        .replacements
+                        .push(Replacement {
+                            deleted_region: self.region(part.span),
+                            inserted_content: ArtifactContent { text: part.snippet.clone() },
+                        });
+                }
+                Fix {
+                    description: Message { text: description.to_string() },
+                    artifact_changes: changes.into_values().collect(),
+                    properties: FixProperties { applicability: suggestion.applicability },
+                }
+            })
+            .collect()
+    }
+
+    fn result_from_diagnostic(&mut self, diag: &DiagInner) -> SarifResult {
+        let args = to_fluent_args(diag.args.iter());
+        let mut text = self.translate_messages(&diag.messages, &args).into_owned();
+
+        let rule_id = if let Some(code) = diag.code {
+            Some(code.to_string())
+        } else if let Some(IsLint { name, .. }) = &diag.is_lint {
+            Some(name.clone())
+        } else {
+            None
+        };
+        let rule_index = rule_id.as_deref().map(|id| self.rule_index(id, diag.code));
+
+        let (locations, mut related_locations) = self.locations(&diag.span, &args, None);
+        for child in &diag.children {
+            self.add_subdiagnostic(child, &args, &mut text, &mut related_locations);
+        }
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|suggestion| self.fixes(suggestion, &args))
+            .collect();
+
+        SarifResult {
+            rule_id,
+            rule_index,
+            level: sarif_level(diag.level),
+            message: Message { text },
+            locations,
+            related_locations,
+            fixes,
+        }
+    }
+
+    /// Subdiagnostics with spans become related locations, the others are
+    /// appended to the message of the result, the way they would be rendered
+    /// by the human emitter.
+    fn add_subdiagnostic(
+        &self,
+        subdiag: &Subdiag,
+        args: &FluentArgs<'_>,
+        text: &mut String,
+        related_locations: &mut Vec<Location>,
+    ) {
+        let level = subdiag.level.to_str();
+        let message = self.translate_messages(&subdiag.messages, args);
+        let (primary, related) = self.locations(&subdiag.span, args, Some(level));
+        if primary.is_empty() {
+            text.push_str(&format!("\n{level}: {message}"));
+        } else {
+            related_locations.extend(primary.into_iter().map(|mut location| {
+                let label = location.message.take();
+                let text = match label {
+                    Some(label) => format!("{level}: {message}\n{}", label.text),
+                    None => format!("{level}: {message}"),
+                };
+                location.message = Some(Message { text });
+                location
+            }));
+        }
+        related_locations.extend(related);
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner) {
+        // Failure notes such as "aborting due to 2 previous errors" summarize the
+        // other results rather than being results of their own.
+        if diag.level.is_failure_note() {
+            return;
+        }
+        let result = self.result_from_diagnostic(&diag);
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if self.results.is_empty() && !self.emit_empty_log {
+            return;
+        }
+        if let Err(e) = self.emit_log()
+            && !std::thread::panicking()
+        {
+            panic!("failed to print diagnostics: {e:?}");
+        }
+    }
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error => "error",
+        Level::ForceWarning(_) | Level::Warning => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::OnceHelp | Level::FailureNote => {
+            "note"
+        }
+        Level::Allow | Level::Expect(_) => unreachable!(),
+    }
+}
+
+/// SARIF identifies artifacts by URI. Relative paths are resolved against the
+/// `%SRCROOT%` base so that consumers can map them onto their checkout.
+fn artifact_location_for_path(name: &str) -> ArtifactLocation {
+    let uri = name.replace('\\', "/");
+    if Path::new(name).is_absolute() {
+        let uri =
+            if uri.starts_with('/') { format!("file://{uri}") } else { format!("file:///{uri}") };
+        ArtifactLocation { uri, uri_base_id: None }
+    } else {
+        ArtifactLocation { uri, uri_base_id: Some("%SRCROOT%") }
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    automation_details: Option<RunAutomationDetails>,
+    /// Columns are reported the same way as in the JSON output: 1-based
+    /// character offsets.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'static str>,
+    rules: &'a [Rule],
+}
+
+#[derive(Serialize)]
+struct RunAutomationDetails {
+    /// "rustc/<crate name>/", so that results of different crates can be told
+    /// apart when logs are merged.
+    id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    /// The error code (e.g. "E1234") or the lint name.
+    id: String,
+    /// The explanation for the error code, if there is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<MultiformatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MultiformatMessage {
+    text: String,
+    markdown: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    /// The label of the span, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    /// How confident rustc is in the fix, as in the JSON output.
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
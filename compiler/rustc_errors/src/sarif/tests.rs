@@ -0,0 +1,138 @@
+use super::*;
+
+use crate::{DiagCtxt, E0308};
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::BytePos;
+
+use std::str;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Runs `f` with a `DiagCtxt` using a SARIF emitter for a source file containing `code`,
+/// and returns the log written once the `DiagCtxt` is dropped.
+fn emit_log(code: &str, f: impl FnOnce(&DiagCtxt)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            sm,
+            fallback_bundle,
+            true, // pretty
+        )
+        .crate_name(Some("test".to_owned()));
+
+        let dcx = DiagCtxt::new(Box::new(se));
+        f(&dcx);
+        drop(dcx);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn empty_log() {
+    let log = emit_log("", |_| {});
+    assert_eq!(log["version"], "2.1.0");
+    let runs = log["runs"].as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0]["tool"]["driver"]["name"], "rustc");
+    assert_eq!(runs[0]["automationDetails"]["id"], "rustc/test/");
+    assert_eq!(runs[0]["results"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn error_with_code() {
+    let log = emit_log("fn main() {\n    let x: u8 = 'a';\n}\n", |dcx| {
+        dcx.handle()
+            .struct_span_err(span(28, 31), "mismatched types")
+            .with_code(E0308)
+            .with_span_label(span(23, 25), "expected due to this")
+            .with_note("char literals are not integers")
+            .emit();
+    });
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "E0308");
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E0308");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "mismatched types\nnote: char literals are not integers");
+
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.rs");
+    assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+    assert_eq!(location["region"]["startLine"], 2);
+    assert_eq!(location["region"]["startColumn"], 17);
+    assert_eq!(location["region"]["endColumn"], 20);
+    assert_eq!(location["region"]["byteOffset"], 28);
+    assert_eq!(location["region"]["byteLength"], 3);
+
+    let related = &result["relatedLocations"][0];
+    assert_eq!(related["message"]["text"], "expected due to this");
+    assert_eq!(related["physicalLocation"]["region"]["startColumn"], 12);
+}
+
+#[test]
+fn warning_with_fix() {
+    let log = emit_log("fn main() {\n    let x = 1;\n}\n", |dcx| {
+        dcx.handle()
+            .struct_span_warn(span(20, 21), "unused variable: `x`")
+            .with_span_suggestion(
+                span(20, 21),
+                "if this is intentional, prefix it with an underscore",
+                "_x",
+                Applicability::MachineApplicable,
+            )
+            .emit();
+    });
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 0);
+
+    let result = &run["results"][0];
+    assert_eq!(result.get("ruleId"), None);
+    assert_eq!(result["level"], "warning");
+
+    let fix = &result["fixes"][0];
+    assert_eq!(fix["description"]["text"], "if this is intentional, prefix it with an underscore");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let change = &fix["artifactChanges"][0];
+    assert_eq!(change["artifactLocation"]["uri"], "test.rs");
+    let replacement = &change["replacements"][0];
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 20);
+    assert_eq!(replacement["deletedRegion"]["byteLength"], 1);
+    assert_eq!(replacement["insertedContent"]["text"], "_x");
+}
+
+#[test]
+#[cfg(unix)]
+fn absolute_paths_are_file_uris() {
+    assert_eq!(artifact_location_for_path("/src/lib.rs").uri, "file:///src/lib.rs");
+    assert_eq!(artifact_location_for_path("src/lib.rs").uri, "src/lib.rs");
+    assert_eq!(artifact_location_for_path("src/lib.rs").uri_base_id, Some("%SRCROOT%"));
+}
@@ -2,6 +2,7 @@
 use crate::errors;
 use crate::expand::{self, AstFragment, Invocation};
 use crate::module::DirOwnership;
+use crate::stats::{count_tokens, MacroStats};
 
 use rustc_ast::attr::MarkedAttrs;
 use rustc_ast::ptr::P;
@@ -1148,6 +1149,8 @@ pub struct ExtCtxt<'a> {
     /// in the AST, but insert it here so that we know
     /// not to expand it again.
     pub(super) expanded_inert_attrs: MarkedAttrs,
+    /// Statistics about the expanded macros, only collected for `-Zmacro-expansion-report`.
+    pub macro_stats: Option<MacroStats>,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -1177,6 +1180,12 @@ pub fn new(
             expansions: FxIndexMap::default(),
             expanded_inert_attrs: MarkedAttrs::new(),
             buffered_early_lint: vec![],
+            macro_stats: sess
+                .opts
+                .unstable_opts
+                .macro_expansion_report
+                .is_some()
+                .then(MacroStats::default),
         }
     }
 
@@ -1251,6 +1260,13 @@ pub fn trace_macros_diag(&mut self) {
         // Fixme: does this result in errors?
         self.expansions.clear();
     }
+    /// Adds the tokens produced by the macro that is being expanded to the statistics of
+    /// `-Zmacro-expansion-report`.
+    pub(crate) fn record_produced_tokens(&mut self, tokens: &TokenStream) {
+        if let Some(stats) = &mut self.macro_stats {
+            stats.produced_tokens += count_tokens(tokens);
+        }
+    }
     pub fn trace_macros(&self) -> bool {
         self.ecfg.trace_mac
     }
@@ -8,6 +8,7 @@
 use crate::mbe::diagnostics::annotate_err_with_kind;
 use crate::module::{mod_dir_path, parse_external_mod, DirOwnership, ParsedExternalMod};
 use crate::placeholders::{placeholder, PlaceholderExpander};
+use crate::stats::{InvocationStart, MacroStats};
 
 use rustc_ast as ast;
 use rustc_ast::mut_visit::*;
@@ -486,8 +487,10 @@ pub fn fully_expand_fragment(&mut self, input_fragment: AstFragment) -> AstFragm
             self.cx.force_mode = force;
 
             let fragment_kind = invoc.fragment_kind;
+            let stats_start = self.start_macro_stats(&ext);
             match self.expand_invoc(invoc, &ext.kind) {
                 ExpandResult::Ready(fragment) => {
+                    self.finish_macro_stats(stats_start, Some(expn_id));
                     let mut derive_invocations = Vec::new();
                     let derive_placeholders = self
                         .cx
@@ -533,6 +536,7 @@ pub fn fully_expand_fragment(&mut self, input_fragment: AstFragment) -> AstFragm
                     invocations.extend(derive_invocations.into_iter().rev());
                 }
                 ExpandResult::Retry(invoc) => {
+                    self.finish_macro_stats(stats_start, None);
                     if force {
                         self.cx.dcx().span_bug(
                             invoc.span(),
@@ -561,6 +565,29 @@ pub fn fully_expand_fragment(&mut self, input_fragment: AstFragment) -> AstFragm
         fragment_with_placeholders
     }
 
+    /// Starts measuring the expansion of an invocation of `ext` for `-Zmacro-expansion-report`.
+    fn start_macro_stats(&mut self, ext: &SyntaxExtension) -> Option<InvocationStart> {
+        let stats = self.cx.macro_stats.as_mut()?;
+        let kind = MacroStats::macro_kind(ext)?;
+        Some(stats.start_invocation(kind))
+    }
+
+    /// Finishes measuring the expansion of an invocation, recording it if it was expanded.
+    fn finish_macro_stats(
+        &mut self,
+        start: Option<InvocationStart>,
+        expanded: Option<LocalExpnId>,
+    ) {
+        let (Some(start), Some(stats)) = (start, &mut self.cx.macro_stats) else {
+            return;
+        };
+        let macro_def = expanded.and_then(|expn_id| {
+            let expn_data = expn_id.expn_data();
+            Some((expn_data.macro_def_id?, expn_data.call_site))
+        });
+        stats.finish_invocation(start, macro_def);
+    }
+
     fn resolve_imports(&mut self) {
         if self.monotonic {
             self.cx.resolver.resolve_imports();
@@ -673,6 +700,7 @@ fn expand_invoc(
                 SyntaxExtensionKind::Bang(expander) => {
                     match expander.expand(self.cx, span, mac.args.tokens.clone()) {
                         Ok(tok_result) => {
+                            self.cx.record_produced_tokens(&tok_result);
                             self.parse_ast_fragment(tok_result, fragment_kind, &mac.path, span)
                         }
                         Err(guar) => return ExpandResult::Ready(fragment_kind.dummy(span, guar)),
@@ -732,12 +760,15 @@ fn expand_invoc(
                     }
                     let inner_tokens = attr_item.args.inner_tokens();
                     match expander.expand(self.cx, span, inner_tokens, tokens) {
-                        Ok(tok_result) => self.parse_ast_fragment(
-                            tok_result,
-                            fragment_kind,
-                            &attr_item.path,
-                            span,
-                        ),
+                        Ok(tok_result) => {
+                            self.cx.record_produced_tokens(&tok_result);
+                            self.parse_ast_fragment(
+                                tok_result,
+                                fragment_kind,
+                                &attr_item.path,
+                                span,
+                            )
+                        }
                         Err(guar) => return ExpandResult::Ready(fragment_kind.dummy(span, guar)),
                     }
                 }
@@ -30,6 +30,7 @@
 pub mod config;
 pub mod expand;
 pub mod module;
+pub mod stats;
 // FIXME(Nilstrieb) Translate proc_macro diagnostics
 #[allow(rustc::untranslatable_diagnostic)]
 pub mod proc_macro;
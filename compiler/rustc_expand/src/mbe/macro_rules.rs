@@ -238,6 +238,7 @@ fn expand_macro<'cx>(
                 let msg = format!("to `{}`", pprust::tts_to_string(&tts));
                 trace_macros_note(&mut cx.expansions, sp, msg);
             }
+            cx.record_produced_tokens(&tts);
 
             let p = Parser::new(psess, tts, None);
 
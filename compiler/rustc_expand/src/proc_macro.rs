@@ -153,6 +153,7 @@ fn expand(
             }
         };
 
+        ecx.record_produced_tokens(&stream);
         let error_count_before = ecx.dcx().err_count();
         let mut parser = Parser::new(&ecx.sess.psess, stream, Some("proc-macro derive"));
         let mut items = vec![];
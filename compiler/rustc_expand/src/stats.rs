@@ -0,0 +1,204 @@
+//! Statistics for `-Zmacro-expansion-report`: how long the expansion of each macro invocation
+//! took and how many tokens it produced, summed up per macro definition and per invocation site.
+
+use std::mem;
+use std::time::{Duration, Instant};
+
+use rustc_ast::tokenstream::{TokenStream, TokenTree};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_session::config::MacroExpansionReportFormat;
+use rustc_session::Session;
+use rustc_span::def_id::DefId;
+use rustc_span::Span;
+
+use crate::base::{SyntaxExtension, SyntaxExtensionKind};
+
+/// The statistics of a single expanded macro invocation.
+struct InvocationStats {
+    macro_def_id: DefId,
+    kind: &'static str,
+    call_site: Span,
+    time: Duration,
+    tokens: usize,
+}
+
+#[derive(Default)]
+pub struct MacroStats {
+    invocations: Vec<InvocationStats>,
+    /// The number of tokens produced by the macro that is being expanded. Only the expanders
+    /// know about the tokens they produce, so they add them up here through
+    /// `ExtCtxt::record_produced_tokens`.
+    pub(crate) produced_tokens: usize,
+    /// The time spent expanding other macros eagerly while expanding the macro that is being
+    /// expanded, which is not counted towards the time of the latter.
+    child_time: Duration,
+}
+
+/// The state of the surrounding invocation while another one is being expanded, returned by
+/// `MacroStats::start_invocation`.
+pub(crate) struct InvocationStart {
+    kind: &'static str,
+    start: Instant,
+    outer_tokens: usize,
+    outer_child_time: Duration,
+}
+
+#[derive(serde::Serialize)]
+struct MacroTotals {
+    #[serde(rename = "macro")]
+    path: String,
+    kind: &'static str,
+    invocations: usize,
+    time_ms: f64,
+    tokens: usize,
+}
+
+#[derive(serde::Serialize)]
+struct SiteTotals {
+    site: String,
+    #[serde(rename = "macro")]
+    path: String,
+    invocations: usize,
+    time_ms: f64,
+    tokens: usize,
+}
+
+#[derive(serde::Serialize)]
+struct Report {
+    macros: Vec<MacroTotals>,
+    sites: Vec<SiteTotals>,
+}
+
+impl MacroStats {
+    /// The kind of macro to report `ext` as, or `None` for the built-in macros, which produce
+    /// AST instead of tokens and are not reported.
+    pub(crate) fn macro_kind(ext: &SyntaxExtension) -> Option<&'static str> {
+        if ext.builtin_name.is_some() {
+            return None;
+        }
+        match ext.kind {
+            SyntaxExtensionKind::LegacyBang(..) => Some("declarative"),
+            SyntaxExtensionKind::Bang(..) => Some("proc-macro"),
+            SyntaxExtensionKind::Attr(..) => Some("proc-macro-attribute"),
+            SyntaxExtensionKind::Derive(..) => Some("proc-macro-derive"),
+            _ => None,
+        }
+    }
+
+    /// Starts measuring the expansion of an invocation of a macro of the given kind. Eager
+    /// expansion can expand other invocations in the meantime, so the tokens and child time
+    /// counted so far for the surrounding invocation are set aside until it's finished.
+    pub(crate) fn start_invocation(&mut self, kind: &'static str) -> InvocationStart {
+        InvocationStart {
+            kind,
+            start: Instant::now(),
+            outer_tokens: mem::take(&mut self.produced_tokens),
+            outer_child_time: mem::take(&mut self.child_time),
+        }
+    }
+
+    /// Finishes measuring the expansion of an invocation, recording it for the given macro
+    /// definition and call site if it was expanded.
+    pub(crate) fn finish_invocation(
+        &mut self,
+        start: InvocationStart,
+        macro_def: Option<(DefId, Span)>,
+    ) {
+        let InvocationStart { kind, start, outer_tokens, outer_child_time } = start;
+        let elapsed = start.elapsed();
+        let tokens = mem::replace(&mut self.produced_tokens, outer_tokens);
+        let child_time = mem::replace(&mut self.child_time, outer_child_time);
+        // The whole expansion, including its own children, is a child of the surrounding one.
+        self.child_time += elapsed;
+        if let Some((macro_def_id, call_site)) = macro_def {
+            let time = elapsed.saturating_sub(child_time);
+            self.invocations.push(InvocationStats { macro_def_id, kind, call_site, time, tokens });
+        }
+    }
+
+    /// Prints the report to stdout, sorted from the slowest to the fastest macro and invocation
+    /// site. `def_path` prints the crate and path of a macro definition.
+    pub fn print(
+        &self,
+        sess: &Session,
+        format: MacroExpansionReportFormat,
+        def_path: impl Fn(DefId) -> String,
+    ) {
+        let mut macros: FxIndexMap<DefId, MacroTotals> = FxIndexMap::default();
+        let mut sites: FxIndexMap<(Span, DefId), SiteTotals> = FxIndexMap::default();
+        for invoc in &self.invocations {
+            let totals = macros.entry(invoc.macro_def_id).or_insert_with(|| MacroTotals {
+                path: def_path(invoc.macro_def_id),
+                kind: invoc.kind,
+                invocations: 0,
+                time_ms: 0.0,
+                tokens: 0,
+            });
+            totals.invocations += 1;
+            totals.time_ms += millis(invoc.time);
+            totals.tokens += invoc.tokens;
+
+            let totals = sites.entry((invoc.call_site, invoc.macro_def_id)).or_insert_with(|| {
+                let loc = sess.source_map().lookup_char_pos(invoc.call_site.lo());
+                SiteTotals {
+                    site: format!(
+                        "{}:{}:{}",
+                        loc.file.name.prefer_local(),
+                        loc.line,
+                        loc.col.to_usize() + 1
+                    ),
+                    path: def_path(invoc.macro_def_id),
+                    invocations: 0,
+                    time_ms: 0.0,
+                    tokens: 0,
+                }
+            });
+            totals.invocations += 1;
+            totals.time_ms += millis(invoc.time);
+            totals.tokens += invoc.tokens;
+        }
+
+        let mut macros: Vec<_> = macros.into_values().collect();
+        macros.sort_by(|a, b| b.time_ms.total_cmp(&a.time_ms).then_with(|| a.path.cmp(&b.path)));
+        let mut sites: Vec<_> = sites.into_values().collect();
+        sites.sort_by(|a, b| b.time_ms.total_cmp(&a.time_ms).then_with(|| a.site.cmp(&b.site)));
+
+        match format {
+            MacroExpansionReportFormat::Json => {
+                println!("{}", serde_json::to_string(&Report { macros, sites }).unwrap());
+            }
+            MacroExpansionReportFormat::Text => {
+                let path_width = macros.iter().map(|m| m.path.len()).max().unwrap_or(0);
+                for MacroTotals { path, kind, invocations, time_ms, tokens } in &macros {
+                    println!(
+                        "macro-expansion macro: {path:path_width$} {kind:20} {invocations:>6} \
+                        invocations {time_ms:>10.3}ms {tokens:>8} tokens"
+                    );
+                }
+                let site_width = sites.iter().map(|s| s.site.len()).max().unwrap_or(0);
+                for SiteTotals { site, path, invocations, time_ms, tokens } in &sites {
+                    println!(
+                        "macro-expansion site: {site:site_width$} {path:path_width$} \
+                        {invocations:>6} invocations {time_ms:>10.3}ms {tokens:>8} tokens"
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
+
+/// Counts the tokens of `stream`, including the delimiters of delimited groups. Fragments that
+/// a declarative macro passes on as they were matched, e.g. `$e:expr`, count as one token.
+pub(crate) fn count_tokens(stream: &TokenStream) -> usize {
+    stream
+        .trees()
+        .map(|tree| match tree {
+            TokenTree::Token(..) => 1,
+            TokenTree::Delimited(.., inner) => 2 + count_tokens(inner),
+        })
+        .sum()
+}
@@ -336,6 +336,10 @@ pub struct BuiltinAttribute {
         template!(Word, List: r#"expected = "reason""#, NameValueStr: "reason"), FutureWarnFollowing,
         EncodeCrossCrate::No,
     ),
+    gated!(
+        test_timeout, Normal, template!(List: "secs"), ErrorFollowing,
+        EncodeCrossCrate::No, experimental!(test_timeout)
+    ),
     // FIXME(Centril): This can be used on stable but shouldn't.
     ungated!(
         reexport_test_harness_main, CrateLevel, template!(NameValueStr: "name"), ErrorFollowing,
@@ -446,6 +450,10 @@ pub struct BuiltinAttribute {
         move_size_limit, CrateLevel, template!(NameValueStr: "N"), ErrorFollowing,
         EncodeCrossCrate::No, large_assignments, experimental!(move_size_limit)
     ),
+    gated!(
+        future_size_limit, CrateLevel, template!(NameValueStr: "N"), ErrorFollowing,
+        EncodeCrossCrate::No, large_futures, experimental!(future_size_limit)
+    ),
 
     // Entry point:
     ungated!(start, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::No),
@@ -505,6 +505,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, intra_doc_pointers, "1.51.0", Some(80896)),
     // Allows setting the threshold for the `large_assignments` lint.
     (unstable, large_assignments, "1.52.0", Some(83518)),
+    // Allows setting the threshold for the `large_futures` lint.
+    (unstable, large_futures, "CURRENT_RUSTC_VERSION", None),
     /// Allow to have type alias types for inter-crate use.
     (incomplete, lazy_type_alias, "1.72.0", Some(112792)),
     /// Allows `if/while p && let q = r && ...` chains.
@@ -604,6 +606,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, string_deref_patterns, "1.67.0", Some(87121)),
     /// Allows the use of `#[target_feature]` on safe functions.
     (unstable, target_feature_11, "1.45.0", Some(69098)),
+    /// Allows setting a time limit for a test with `#[test_timeout(secs)]`.
+    (unstable, test_timeout, "CURRENT_RUSTC_VERSION", None),
     /// Allows using `#[thread_local]` on `static` items.
     (unstable, thread_local, "1.0.0", Some(29594)),
     /// Allows defining `trait X = A + B;` alias items.
@@ -1,3 +1,9 @@
+interface_applied_suggestions =
+    applied {$count ->
+        [one] {$count} suggestion
+        *[other] {$count} suggestions
+    } to `{$path}`
+
 interface_cant_emit_mir =
     could not emit MIR: {$error}
 
@@ -34,6 +40,13 @@ interface_multiple_output_types_adaption =
     due to multiple output types requested, the explicitly specified output file name will be adapted for each output type
 
 interface_multiple_output_types_to_stdout = can't use option `-o` or `--emit` to write multiple output types to stdout
+interface_overlapping_suggestions =
+    {$count ->
+        [one] {$count} suggestion was not applied because it overlaps with another suggestion
+        *[other] {$count} suggestions were not applied because they overlap with other suggestions
+    }
+    .note = compiling again applies the remaining suggestions
+
 interface_out_dir_error =
     failed to find or create the directory specified by `--out-dir`
 
@@ -46,5 +59,8 @@ interface_rustc_error_fatal =
 interface_rustc_error_unexpected_annotation =
     unexpected annotation used with `#[rustc_error(...)]`!
 
+interface_suggestions_file_changed =
+    not applying suggestions to `{$path}`, because it changed since it was compiled
+
 interface_temps_dir_error =
     failed to find or create the directory specified by `--temps-dir`
@@ -0,0 +1,254 @@
+//! Applies the suggestions of the emitted diagnostics to the source files, for
+//! `-Z apply-suggestions`.
+//!
+//! The suggestions are collected by the `DiagCtxt` as the diagnostics are emitted. A suggestion
+//! is applied in full or not at all: it is skipped if one of its parts is in a macro expansion,
+//! is outside of a local source file, or overlaps with a suggestion that was accepted before it.
+//! Running the compiler again applies the skipped suggestions that are still relevant.
+
+use crate::errors;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_errors::Substitution;
+use rustc_session::Session;
+use rustc_span::{FileName, SourceFile};
+
+use std::fmt::Write as _;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// The number of unchanged lines around the changes of a `dry-run` diff.
+const CONTEXT_LINES: usize = 3;
+
+/// A replacement of a byte range of a source file, as it is on disk.
+#[derive(Clone, PartialEq, Eq)]
+struct Edit {
+    range: Range<usize>,
+    snippet: String,
+}
+
+impl Edit {
+    fn overlaps(&self, other: &Edit) -> bool {
+        // Two insertions at the same position conflict as well, as their order is unknown.
+        self.range.start == other.range.start
+            || (self.range.start < other.range.end && other.range.start < self.range.end)
+    }
+}
+
+/// The accepted suggestions of a source file.
+struct FileEdits {
+    file: Lrc<SourceFile>,
+    suggestions: Vec<Vec<Edit>>,
+}
+
+pub(crate) fn apply_suggestions(sess: &Session) {
+    let Some(options) = sess.opts.unstable_opts.apply_suggestions else { return };
+    let suggestions = sess.dcx().take_collected_suggestions();
+    // The suggestions may not make sense if the code does not compile.
+    if sess.dcx().has_errors().is_some() {
+        return;
+    }
+
+    let mut files: FxIndexMap<PathBuf, FileEdits> = Default::default();
+    let mut overlapping = 0;
+    for suggestion in &suggestions {
+        let Some((path, file, edits)) = resolve_suggestion(sess, suggestion) else { continue };
+        let file_edits =
+            files.entry(path).or_insert_with(|| FileEdits { file, suggestions: vec![] });
+        // The same suggestion is often emitted several times, e.g. for each use of a macro.
+        if file_edits.suggestions.contains(&edits) {
+            continue;
+        }
+        let mut accepted = file_edits.suggestions.iter().flatten();
+        if accepted.any(|accepted| edits.iter().any(|edit| edit.overlaps(accepted))) {
+            overlapping += 1;
+            continue;
+        }
+        file_edits.suggestions.push(edits);
+    }
+
+    for (path, file_edits) in files {
+        let FileEdits { file, suggestions } = file_edits;
+        let display_path = file.name.prefer_local().to_string();
+        // The spans refer to the source as it was compiled, so the file must not have changed.
+        let src = match fs::read_to_string(&path) {
+            Ok(src) if file.src_hash.matches(&src) => src,
+            _ => {
+                sess.dcx().emit_warn(errors::SuggestionsFileChanged { path: &display_path });
+                continue;
+            }
+        };
+        let mut edits: Vec<Edit> = suggestions.iter().flatten().cloned().collect();
+        edits.sort_by_key(|edit| edit.range.start);
+        let fixed = apply_edits(&src, 0, &edits);
+        if options.dry_run {
+            print!("{}", diff(&display_path, &src, &edits));
+        } else if let Err(error) = fs::write(&path, fixed) {
+            sess.dcx().emit_err(errors::FailedWritingFile { path: &path, error });
+        } else {
+            sess.dcx().emit_note(errors::AppliedSuggestions {
+                count: suggestions.len(),
+                path: &display_path,
+            });
+        }
+    }
+
+    if overlapping > 0 {
+        sess.dcx().emit_note(errors::OverlappingSuggestions { count: overlapping });
+    }
+}
+
+/// Resolves the parts of `suggestion` to edits of a local source file, or returns `None` if the
+/// suggestion cannot be applied.
+fn resolve_suggestion(
+    sess: &Session,
+    suggestion: &Substitution,
+) -> Option<(PathBuf, Lrc<SourceFile>, Vec<Edit>)> {
+    let mut file: Option<Lrc<SourceFile>> = None;
+    let mut edits = vec![];
+    for part in &suggestion.parts {
+        if part.span.is_dummy() || part.span.from_expansion() {
+            return None;
+        }
+        let sf = sess.source_map().lookup_byte_offset(part.span.lo()).sf;
+        if !sf.contains(part.span.hi())
+            || file.as_ref().is_some_and(|file| file.start_pos != sf.start_pos)
+        {
+            return None;
+        }
+        let start = sf.original_relative_byte_pos(part.span.lo()).to_usize();
+        let end = sf.original_relative_byte_pos(part.span.hi()).to_usize();
+        edits.push(Edit { range: start..end, snippet: part.snippet.clone() });
+        file = Some(sf);
+    }
+    let file = file?;
+    let FileName::Real(name) = &file.name else { return None };
+    let path = name.local_path()?.to_path_buf();
+    edits.sort_by_key(|edit| edit.range.start);
+    // The parts of a suggestion must not overlap each other either.
+    if edits.windows(2).any(|pair| pair[0].overlaps(&pair[1])) {
+        return None;
+    }
+    Some((path, file, edits))
+}
+
+/// Applies the sorted, non-overlapping `edits` to `src`, which starts at the byte offset `base`
+/// of the file.
+fn apply_edits(src: &str, base: usize, edits: &[Edit]) -> String {
+    let mut fixed = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        fixed.push_str(&src[pos..edit.range.start - base]);
+        fixed.push_str(&edit.snippet);
+        pos = edit.range.end - base;
+    }
+    fixed.push_str(&src[pos..]);
+    fixed
+}
+
+/// A run of changed lines: the original lines `lines`, and the edits within them.
+struct Change<'a> {
+    lines: Range<usize>,
+    edits: &'a [Edit],
+}
+
+/// Returns the unified diff of applying the sorted, non-overlapping `edits` to `src`.
+fn diff(path: &str, src: &str, edits: &[Edit]) -> String {
+    let mut line_starts = vec![0];
+    line_starts.extend(src.match_indices('\n').map(|(i, _)| i + 1).filter(|&i| i < src.len()));
+    let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos) - 1;
+    let line_range = |lines: &Range<usize>| {
+        let end = line_starts.get(lines.end).copied().unwrap_or(src.len());
+        line_starts[lines.start]..end
+    };
+
+    // Group the edits on the same or adjacent lines, like `diff -u` does.
+    let mut changes: Vec<Change<'_>> = vec![];
+    let mut first_edit = 0;
+    for (i, edit) in edits.iter().enumerate() {
+        let start = line_of(edit.range.start);
+        let end = if edit.range.is_empty() { start } else { line_of(edit.range.end - 1) } + 1;
+        match changes.last_mut() {
+            Some(change) if start <= change.lines.end => {
+                change.lines.end = change.lines.end.max(end);
+                change.edits = &edits[first_edit..=i];
+            }
+            _ => {
+                first_edit = i;
+                changes.push(Change { lines: start..end, edits: &edits[i..=i] });
+            }
+        }
+    }
+
+    let mut out = format!("--- {path}\n+++ {path}\n");
+    // The difference between the line numbers of the fixed and the original file.
+    let mut offset = 0isize;
+    let mut rest = &changes[..];
+    while let [first, ..] = rest {
+        // Changes whose context lines touch are shown in the same hunk.
+        let mut len = 1;
+        while len < rest.len()
+            && rest[len].lines.start - rest[len - 1].lines.end <= 2 * CONTEXT_LINES
+        {
+            len += 1;
+        }
+        let (hunk, remaining) = rest.split_at(len);
+        rest = remaining;
+
+        let start = first.lines.start.saturating_sub(CONTEXT_LINES);
+        let end = (hunk[len - 1].lines.end + CONTEXT_LINES).min(line_starts.len());
+        let mut body = String::new();
+        let (mut old_len, mut new_len) = (0, 0);
+        let mut line = start;
+        for change in hunk {
+            for context in src[line_range(&(line..change.lines.start))].split_inclusive('\n') {
+                push_line(&mut body, ' ', context);
+                old_len += 1;
+                new_len += 1;
+            }
+            let range = line_range(&change.lines);
+            for old in src[range.clone()].split_inclusive('\n') {
+                push_line(&mut body, '-', old);
+                old_len += 1;
+            }
+            let new = apply_edits(&src[range.clone()], range.start, change.edits);
+            for new in new.split_inclusive('\n') {
+                push_line(&mut body, '+', new);
+                new_len += 1;
+            }
+            line = change.lines.end;
+        }
+        for context in src[line_range(&(line..end))].split_inclusive('\n') {
+            push_line(&mut body, ' ', context);
+            old_len += 1;
+            new_len += 1;
+        }
+
+        let new_start = start as isize + offset;
+        writeln!(
+            out,
+            "@@ -{} +{} @@",
+            hunk_range(start as isize, old_len),
+            hunk_range(new_start, new_len)
+        )
+        .unwrap();
+        out.push_str(&body);
+        offset += new_len as isize - old_len as isize;
+    }
+    out
+}
+
+/// Formats the range of a hunk header. Like in the output of `diff -u`, an empty range starts
+/// at the line before it.
+fn hunk_range(start: isize, len: usize) -> String {
+    if len == 0 { format!("{start},0") } else { format!("{},{len}", start + 1) }
+}
+
+fn push_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
@@ -103,3 +103,23 @@ pub struct FailedWritingFile<'a> {
 #[derive(Diagnostic)]
 #[diag(interface_multiple_output_types_to_stdout)]
 pub struct MultipleOutputTypesToStdout;
+
+#[derive(Diagnostic)]
+#[diag(interface_applied_suggestions)]
+pub struct AppliedSuggestions<'a> {
+    pub count: usize,
+    pub path: &'a str,
+}
+
+#[derive(Diagnostic)]
+#[diag(interface_overlapping_suggestions)]
+#[note]
+pub struct OverlappingSuggestions {
+    pub count: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(interface_suggestions_file_changed)]
+pub struct SuggestionsFileChanged<'a> {
+    pub path: &'a str,
+}
@@ -1,3 +1,4 @@
+use crate::errors;
 use crate::util;
 
 use rustc_ast::token;
@@ -505,6 +506,8 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
                 // normally when `sess_abort_guard` is dropped.
                 drop(sess_abort_guard);
 
+                apply_suggestions::apply_suggestions(&compiler.sess);
+
                 // If error diagnostics have been emitted, we can't return an
                 // error directly, because the return type of this function
                 // is `R`, not `Result<R, E>`. But we need to communicate the
@@ -517,6 +520,13 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
                 res
             };
 
+            compiler.sess.prof.with_summary(|summary| {
+                if let Err(error) = summary.write() {
+                    let path = summary.path();
+                    compiler.sess.dcx().emit_warn(errors::FailedWritingFile { path, error });
+                }
+            });
+
             let prof = compiler.sess.prof.clone();
             prof.generic_activity("drop_compiler").run(move || drop(compiler));
 
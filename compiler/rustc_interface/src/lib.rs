@@ -5,6 +5,7 @@
 #![feature(try_blocks)]
 // tidy-alphabetical-end
 
+mod apply_suggestions;
 mod callbacks;
 mod errors;
 pub mod interface;
@@ -222,6 +222,15 @@ fn configure_and_expand(
             ecx.check_unused_macros();
         });
 
+        if let Some(format) = sess.opts.unstable_opts.macro_expansion_report
+            && let Some(stats) = &ecx.macro_stats
+        {
+            stats.print(sess, format, |def_id| {
+                let path = tcx.def_path(def_id).to_string_no_crate_verbose();
+                format!("{}{path}", tcx.crate_name(def_id.krate))
+            });
+        }
+
         // If we hit a recursion limit, exit early to avoid later passes getting overwhelmed
         // with a large AST
         if ecx.reduced_recursion_limit.is_some() {
@@ -878,6 +887,11 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         let _ = tcx.all_diagnostic_items(());
     });
 
+    let semver_baseline = CStore::from_tcx(tcx).semver_baseline();
+    if let Some(baseline) = semver_baseline {
+        sess.time("semver_check", || rustc_passes::semver_check::check_crate(tcx, baseline));
+    }
+
     if sess.opts.unstable_opts.print_vtable_sizes {
         let traits = tcx.traits(LOCAL_CRATE);
 
@@ -130,7 +130,10 @@ pub fn codegen_and_build_linker(
         // This must run after monomorphization so that all generic types
         // have been instantiated.
         if tcx.sess.opts.unstable_opts.print_type_sizes {
-            tcx.sess.code_stats.print_type_sizes();
+            tcx.sess.code_stats.print_type_sizes(
+                tcx.sess.opts.unstable_opts.print_type_sizes_format,
+                tcx.sess.opts.unstable_opts.print_type_sizes_threshold,
+            );
         }
 
         if tcx.sess.opts.unstable_opts.print_vtable_sizes {
@@ -1,23 +1,25 @@
 #![allow(rustc::bad_opt_access)]
 use crate::interface::{initialize_checked_jobserver, parse_cfg};
 use rustc_data_structures::profiling::TimePassesFormat;
-use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
+use rustc_errors::{emitter::HumanReadableErrorType, registry, Applicability, ColorConfig};
+use rustc_session::code_stats::PrintTypeSizesFormat;
 use rustc_session::config::{build_configuration, build_session_options, rustc_optgroups};
 use rustc_session::config::{
-    BranchProtection, CFGuard, Cfg, CollapseMacroDebuginfo, CoverageLevel, CoverageOptions,
-    DebugInfo, DumpMonoStatsFormat, ErrorOutputType,
+    ApplySuggestions, BranchProtection, CFGuard, Cfg, CollapseMacroDebuginfo, CoverageLevel,
+    CoverageOptions, DebugInfo, DumpMonoStatsFormat, ErrorOutputType,
 };
 use rustc_session::config::{
-    ExternEntry, ExternLocation, Externs, FunctionReturn, InliningThreshold, Input,
-    InstrumentCoverage, InstrumentXRay, LinkSelfContained, LinkerPluginLto,
+    ExternEntry, ExternLocation, ExternUsageReportFormat, Externs, FunctionReturn,
+    InliningThreshold, Input, InstrumentCoverage, InstrumentXRay, LinkSelfContained,
+    LinkerPluginLto, LintConfig,
 };
 use rustc_session::config::{
-    LocationDetail, LtoCli, NextSolverConfig, OomStrategy, Options, OutFileName, OutputType,
-    OutputTypes, PAuthKey, PacRet, Passes, PatchableFunctionEntry,
+    LocationDetail, LtoCli, MacroExpansionReportFormat, NextSolverConfig, OomStrategy, Options,
+    OutFileName, OutputType, OutputTypes, PAuthKey, PacRet, Passes, PatchableFunctionEntry,
 };
 use rustc_session::config::{
-    Polonius, ProcMacroExecutionStrategy, Strip, SwitchWithOptPath, SymbolManglingVersion,
-    WasiExecModel,
+    Polonius, Polymorphize, ProcMacroExecutionStrategy, Strip, SwitchWithOptPath,
+    SymbolManglingVersion, WasiExecModel,
 };
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
@@ -662,6 +664,7 @@ macro_rules! tracked {
 
     // Make sure that changing a [TRACKED_NO_CRATE_HASH] option leaves the crate hash unchanged but changes the incremental hash.
     // tidy-alphabetical-start
+    tracked!(lint_config, LintConfig::parse("[lints.rust]\ndead_code = \"deny\"").unwrap());
     tracked!(
         real_rust_source_base_dir,
         Some("/home/bors/rust/.rustup/toolchains/nightly/lib/rustlib/src/rust".into())
@@ -685,6 +688,10 @@ macro_rules! untracked {
 
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
+    untracked!(
+        apply_suggestions,
+        Some(ApplySuggestions { applicability: Applicability::MaybeIncorrect, dry_run: true })
+    );
     untracked!(assert_incr_state, Some(String::from("loaded")));
     untracked!(deduplicate_diagnostics, false);
     untracked!(dump_dep_graph, true);
@@ -697,6 +704,7 @@ macro_rules! untracked {
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
+    untracked!(extern_usage_report, Some(ExternUsageReportFormat::Json));
     untracked!(future_incompat_test, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
@@ -707,6 +715,7 @@ macro_rules! untracked {
     untracked!(llvm_time_trace, true);
     untracked!(ls, vec!["all".to_owned()]);
     untracked!(macro_backtrace, true);
+    untracked!(macro_expansion_report, Some(MacroExpansionReportFormat::Json));
     untracked!(meta_stats, true);
     untracked!(mir_include_spans, true);
     untracked!(nll_facts, true);
@@ -720,12 +729,15 @@ macro_rules! untracked {
     untracked!(print_llvm_passes, true);
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
+    untracked!(print_type_sizes_format, PrintTypeSizesFormat::Json);
+    untracked!(print_type_sizes_threshold, 1024);
     untracked!(proc_macro_backtrace, true);
     untracked!(proc_macro_execution_strategy, ProcMacroExecutionStrategy::CrossThread);
     untracked!(profile_closures, true);
     untracked!(query_dep_graph, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(self_profile_summary, SwitchWithOptPath::Enabled(None));
     untracked!(shell_argfiles, true);
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
@@ -786,6 +798,7 @@ macro_rules! tracked {
     tracked!(fuel, Some(("abc".to_string(), 99)));
     tracked!(function_return, FunctionReturn::ThunkExtern);
     tracked!(function_sections, Some(false));
+    tracked!(future_size_limit, Some(4096));
     tracked!(human_readable_cgu_names, true);
     tracked!(incremental_ignore_spans, true);
     tracked!(inline_in_all_cgus, Some(true));
@@ -827,6 +840,7 @@ macro_rules! tracked {
     );
     tracked!(plt, Some(true));
     tracked!(polonius, Polonius::Legacy);
+    tracked!(polymorphize, Polymorphize::Layout);
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(print_fuel, Some("abc".to_string()));
     tracked!(profile, true);
@@ -843,6 +857,7 @@ macro_rules! tracked {
     tracked!(sanitizer_memory_track_origins, 2);
     tracked!(sanitizer_recover, SanitizerSet::ADDRESS);
     tracked!(saturating_float_casts, Some(true));
+    tracked!(semver_baseline, Some(String::from("old")));
     tracked!(share_generics, Some(true));
     tracked!(show_span, Some(String::from("abc")));
     tracked!(simulate_remapped_rust_src_base, Some(PathBuf::from("/rustc/abc")));
@@ -19,6 +19,7 @@
 use rustc_errors::{Diag, LintDiagnostic, MultiSpan};
 use rustc_feature::{Features, GateIssue};
 use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
 use rustc_hir::intravisit::{self, Visitor};
 use rustc_hir::HirId;
 use rustc_index::IndexVec;
@@ -30,6 +31,7 @@
 };
 use rustc_middle::query::Providers;
 use rustc_middle::ty::{RegisteredTools, TyCtxt};
+use rustc_session::config::{path_matches, LintConfig};
 use rustc_session::lint::{
     builtin::{
         self, FORBIDDEN_LINT_GROUPS, RENAMED_AND_REMOVED_LINTS, SINGLE_USE_LIFETIMES,
@@ -142,6 +144,7 @@ fn lint_expectations(tcx: TyCtxt<'_>, (): ()) -> Vec<(LintExpectationId, LintExp
         registered_tools: tcx.registered_tools(()),
     };
 
+    builder.add_lint_config();
     builder.add_command_line();
     builder.add_id(hir::CRATE_HIR_ID);
     tcx.hir().walk_toplevel_module(&mut builder);
@@ -172,8 +175,10 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
     };
 
     if owner == hir::CRATE_OWNER_ID {
+        levels.add_lint_config();
         levels.add_command_line();
     }
+    levels.add_lint_config_paths(owner);
 
     match attrs.map.range(..) {
         // There is only something to do if there are attributes at all.
@@ -304,6 +309,55 @@ fn add_id(&mut self, hir_id: HirId) {
             Some(hir_id),
         );
     }
+
+    /// Adds the levels of the `[paths]` table of the `--lint-config` file that match `owner`.
+    /// Levels that also match the parent of `owner` are inherited from it instead, so that the
+    /// attributes of the parent still override them. The attributes of `owner` are added later,
+    /// so they override the file too.
+    fn add_lint_config_paths(&mut self, owner: hir::OwnerId) {
+        let path_levels = &self.sess.opts.lint_config.path_levels;
+        if path_levels.is_empty() {
+            return;
+        }
+        let tcx = self.provider.tcx;
+        let def_path = |def_id: LocalDefId| {
+            format!("crate{}", tcx.def_path(def_id.to_def_id()).to_string_no_crate_verbose())
+        };
+        let path = def_path(owner.def_id);
+        let parent_path = tcx.opt_local_parent(owner.def_id).map(def_path);
+
+        self.provider.cur = owner.into();
+        for (pattern, levels) in path_levels {
+            if !path_matches(pattern, &path)
+                || parent_path.as_ref().is_some_and(|parent| path_matches(pattern, parent))
+            {
+                continue;
+            }
+            for &(ref lint_name, level) in levels {
+                let ids = if lint_name == LintConfig::ALL_LINTS {
+                    self.store.get_lints().iter().map(|&lint| LintId::of(lint)).collect()
+                } else {
+                    // Unknown lints were already reported by `add_lint_config`.
+                    let Ok(ids) = self.store.find_lints(lint_name) else { continue };
+                    ids
+                };
+                let src = LintLevelSource::LintConfig {
+                    name: Symbol::intern(lint_name),
+                    level,
+                    path: Some(Symbol::intern(pattern)),
+                };
+                for id in ids {
+                    // Forbid cannot be overridden, there are no attributes to report it at.
+                    if let (Level::Forbid, _) = self.provider.get_lint_level(id.lint, self.sess) {
+                        continue;
+                    }
+                    if self.check_gated_lint(id, DUMMY_SP, true) {
+                        self.insert_spec(id, (level, src));
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl<'tcx> Visitor<'tcx> for LintLevelsBuilder<'_, LintLevelQueryMap<'tcx>> {
@@ -490,6 +544,7 @@ fn process_command_line(&mut self) {
             .sets
             .list
             .push(LintSet { specs: FxIndexMap::default(), parent: COMMAND_LINE });
+        self.add_lint_config();
         self.add_command_line();
     }
 
@@ -560,8 +615,7 @@ fn insert(&mut self, id: LintId, lvl: LevelAndSource) {
 
     fn add_command_line(&mut self) {
         for &(ref lint_name, level) in &self.sess.opts.lint_opts {
-            // Checks the validity of lint names derived from the command line.
-            let (tool_name, lint_name_only) = parse_lint_and_tool_name(lint_name);
+            let (_, lint_name_only) = parse_lint_and_tool_name(lint_name);
             if lint_name_only == crate::WARNINGS.name_lower()
                 && matches!(level, Level::ForceWarn(_))
             {
@@ -569,63 +623,95 @@ fn add_command_line(&mut self) {
                     .dcx()
                     .emit_err(UnsupportedGroup { lint_group: crate::WARNINGS.name_lower() });
             }
-            match self.store.check_lint_name(lint_name_only, tool_name, self.registered_tools) {
-                CheckLintNameResult::Renamed(ref replace) => {
-                    let name = lint_name.as_str();
-                    let suggestion = RenamedLintSuggestion::WithoutSpan { replace };
-                    let requested_level = RequestedLevel { level, lint_name };
-                    let lint = RenamedLintFromCommandLine { name, suggestion, requested_level };
-                    self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
-                }
-                CheckLintNameResult::Removed(ref reason) => {
-                    let name = lint_name.as_str();
-                    let requested_level = RequestedLevel { level, lint_name };
-                    let lint = RemovedLintFromCommandLine { name, reason, requested_level };
-                    self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
-                }
-                CheckLintNameResult::NoLint(suggestion) => {
-                    let name = lint_name.clone();
-                    let suggestion = suggestion.map(|(replace, from_rustc)| {
-                        UnknownLintSuggestion::WithoutSpan { replace, from_rustc }
-                    });
-                    let requested_level = RequestedLevel { level, lint_name };
-                    let lint = UnknownLintFromCommandLine { name, suggestion, requested_level };
-                    self.emit_lint(UNKNOWN_LINTS, lint);
-                }
-                CheckLintNameResult::Tool(_, Some(ref replace)) => {
-                    let name = lint_name.clone();
-                    let requested_level = RequestedLevel { level, lint_name };
-                    let lint = DeprecatedLintNameFromCommandLine { name, replace, requested_level };
-                    self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
-                }
-                CheckLintNameResult::NoTool => {
-                    self.sess.dcx().emit_err(CheckNameUnknownTool {
-                        tool_name: tool_name.unwrap(),
-                        sub: RequestedLevel { level, lint_name },
-                    });
+            self.check_requested_lint_name(
+                lint_name,
+                RequestedLevel::CommandLine { level, lint_name },
+            );
+            let src = LintLevelSource::CommandLine(Symbol::intern(lint_name), level);
+            self.insert_requested_level(lint_name, level, src);
+        }
+    }
+
+    /// Adds the crate-wide levels of the `--lint-config` file. This is done before adding the
+    /// command line flags, so that the flags override the file.
+    fn add_lint_config(&mut self) {
+        let lint_config = &self.sess.opts.lint_config;
+        for &(ref lint_name, level) in &lint_config.levels {
+            self.check_requested_lint_name(
+                lint_name,
+                RequestedLevel::LintConfig { level: level.as_str(), lint_name },
+            );
+            let src =
+                LintLevelSource::LintConfig { name: Symbol::intern(lint_name), level, path: None };
+            self.insert_requested_level(lint_name, level, src);
+        }
+        // The levels of the `[paths]` table are added by `add_lint_config_paths`, once for every
+        // matching item, so only check their names here.
+        for (_, levels) in &lint_config.path_levels {
+            for &(ref lint_name, level) in levels {
+                if lint_name == LintConfig::ALL_LINTS {
+                    continue;
                 }
-                _ => {}
-            };
+                self.check_requested_lint_name(
+                    lint_name,
+                    RequestedLevel::LintConfig { level: level.as_str(), lint_name },
+                );
+            }
+        }
+    }
 
-            let orig_level = level;
-            let lint_flag_val = Symbol::intern(lint_name);
+    /// Checks the validity of a lint name from the command line or the `--lint-config` file.
+    fn check_requested_lint_name(&mut self, lint_name: &str, requested_level: RequestedLevel<'_>) {
+        let (tool_name, lint_name_only) = parse_lint_and_tool_name(lint_name);
+        match self.store.check_lint_name(lint_name_only, tool_name, self.registered_tools) {
+            CheckLintNameResult::Renamed(ref replace) => {
+                let suggestion = RenamedLintSuggestion::WithoutSpan { replace };
+                let lint =
+                    RenamedLintFromCommandLine { name: lint_name, suggestion, requested_level };
+                self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
+            }
+            CheckLintNameResult::Removed(ref reason) => {
+                let lint = RemovedLintFromCommandLine { name: lint_name, reason, requested_level };
+                self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
+            }
+            CheckLintNameResult::NoLint(suggestion) => {
+                let name = lint_name.to_owned();
+                let suggestion = suggestion.map(|(replace, from_rustc)| {
+                    UnknownLintSuggestion::WithoutSpan { replace, from_rustc }
+                });
+                let lint = UnknownLintFromCommandLine { name, suggestion, requested_level };
+                self.emit_lint(UNKNOWN_LINTS, lint);
+            }
+            CheckLintNameResult::Tool(_, Some(ref replace)) => {
+                let name = lint_name.to_owned();
+                let lint = DeprecatedLintNameFromCommandLine { name, replace, requested_level };
+                self.emit_lint(RENAMED_AND_REMOVED_LINTS, lint);
+            }
+            CheckLintNameResult::NoTool => {
+                self.sess.dcx().emit_err(CheckNameUnknownTool {
+                    tool_name: tool_name.unwrap(),
+                    sub: requested_level,
+                });
+            }
+            _ => {}
+        };
+    }
 
-            let Ok(ids) = self.store.find_lints(lint_name) else {
-                // errors already handled above
+    /// Sets the level of the lints named `lint_name`, unless they are already set to
+    /// `ForceWarn` or `Forbid`.
+    fn insert_requested_level(&mut self, lint_name: &str, level: Level, src: LintLevelSource) {
+        let Ok(ids) = self.store.find_lints(lint_name) else {
+            // errors already handled by `check_requested_lint_name`
+            return;
+        };
+        for id in ids {
+            // ForceWarn and Forbid cannot be overridden
+            if let Some((Level::ForceWarn(_) | Level::Forbid, _)) = self.current_specs().get(&id) {
                 continue;
-            };
-            for id in ids {
-                // ForceWarn and Forbid cannot be overridden
-                if let Some((Level::ForceWarn(_) | Level::Forbid, _)) =
-                    self.current_specs().get(&id)
-                {
-                    continue;
-                }
+            }
 
-                if self.check_gated_lint(id, DUMMY_SP, true) {
-                    let src = LintLevelSource::CommandLine(lint_flag_val, orig_level);
-                    self.insert(id, (level, src));
-                }
+            if self.check_gated_lint(id, DUMMY_SP, true) {
+                self.insert(id, (level, src));
             }
         }
     }
@@ -658,6 +744,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                 LintLevelSource::Default => false,
                 LintLevelSource::Node { name, .. } => self.store.is_lint_group(name),
                 LintLevelSource::CommandLine(symbol, _) => self.store.is_lint_group(symbol),
+                LintLevelSource::LintConfig { name, .. } => self.store.is_lint_group(name),
             };
             debug!(
                 "fcw_warning={:?}, specs.get(&id) = {:?}, old_src={:?}, id_name={:?}",
@@ -674,6 +761,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                     OverruledAttributeSub::NodeSource { span, reason }
                 }
                 LintLevelSource::CommandLine(_, _) => OverruledAttributeSub::CommandLineSource,
+                LintLevelSource::LintConfig { .. } => OverruledAttributeSub::LintConfigSource,
             };
             if !fcw_warning {
                 self.sess.dcx().emit_err(OverruledAttribute {
@@ -58,6 +58,7 @@
         INVALID_TYPE_PARAM_DEFAULT,
         IRREFUTABLE_LET_PATTERNS,
         LARGE_ASSIGNMENTS,
+        LARGE_FUTURES,
         LATE_BOUND_LIFETIME_ARGUMENTS,
         LEGACY_DERIVE_HELPERS,
         LONG_RUNNING_CONST_EVAL,
@@ -3179,6 +3180,73 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `large_futures` lint detects when futures created by `async fn`s
+    /// and `async` blocks are larger than the limit set with the
+    /// `future_size_limit` crate attribute.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs the `large_futures` feature and a post-monomorphization lint)
+    /// #![deny(large_futures)]
+    /// #![feature(large_futures)]
+    /// #![future_size_limit = "1000"]
+    ///
+    /// async fn wait() {}
+    ///
+    /// async fn big() {
+    ///     let buf = [0u8; 2048];
+    ///     wait().await;
+    ///     drop(buf);
+    /// }
+    ///
+    /// fn main() {
+    ///     let _ = big();
+    /// }
+    /// ```
+    ///
+    /// produces:
+    ///
+    /// ```text
+    /// error: this future is 2050 bytes large
+    ///   --> $DIR/large_futures.rs:14:13
+    ///    |
+    /// 14 |     let _ = big();
+    ///    |             ^^^^^ `{async fn body of big()}` created here
+    ///    |
+    ///    = note: the current maximum size is 1000, but it can be customized with the future_size_limit attribute: `#![future_size_limit = "..."]`
+    /// note: 2049 bytes are held across this await point
+    ///   --> $DIR/large_futures.rs:9:12
+    ///    |
+    /// 9  |     wait().await;
+    ///    |            ^^^^^
+    ///    = note: `buf` takes 2048 bytes
+    ///    = note: the awaited future `{async fn body of wait()}` takes 1 byte
+    /// note: the lint level is defined here
+    ///   --> $DIR/large_futures.rs:1:9
+    ///    |
+    /// 1  | #![deny(large_futures)]
+    ///    |         ^^^^^^^^^^^^^
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// A future stores all locals that are held across an `.await` point,
+    /// including the futures it awaits. This can make futures so large that
+    /// moving them around is slow, or that they overflow the stack before
+    /// they are boxed or spawned. The lint fires after monomorphization, at
+    /// the point where a future is created, and points out which locals held
+    /// across which `.await` points take up the most space. Such locals can
+    /// often be dropped before the `.await`, or boxed.
+    ///
+    /// The lint is only emitted if a limit is set with the
+    /// `future_size_limit` crate attribute, `-Zfuture-size-limit` or the
+    /// `size_limit` option of the lint in a `--lint-config` file.
+    pub LARGE_FUTURES,
+    Warn,
+    "detects large futures",
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
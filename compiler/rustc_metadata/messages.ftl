@@ -63,6 +63,18 @@ metadata_extern_location_not_exist =
 metadata_extern_location_not_file =
     extern location for {$crate_name} is not a file: {$location}
 
+metadata_extern_usage =
+    {$usage ->
+        [unused] extern crate `{$crate_name}` is unused
+        [test-only] extern crate `{$crate_name}` is only used by tests
+        *[macros-only] only macros of extern crate `{$crate_name}` are used
+    }
+    .help = {$usage ->
+        [unused] remove the dependency
+        [test-only] consider making it a dev-dependency
+        *[macros-only] it is only needed at build time and does not have to be linked
+    }
+
 metadata_fail_create_file_encoder =
     failed to create file encoder: {$err}
 
@@ -1,6 +1,7 @@
 //! Validates all used crates and extern libraries and loads their metadata
 
 use crate::errors;
+use crate::extern_usage;
 use crate::locator::{CrateError, CrateLocator, CratePaths};
 use crate::rmeta::{CrateDep, CrateMetadata, CrateNumMap, CrateRoot, MetadataBlob};
 
@@ -31,7 +32,7 @@
 
 use proc_macro::bridge::client::ProcMacro;
 use std::error::Error;
-use std::ops::Fn;
+use std::ops::{Fn, Range};
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
@@ -66,6 +67,14 @@ pub struct CStore {
 
     /// Unused externs of the crate
     unused_externs: Vec<Symbol>,
+    /// Used externs of the crate and the crates they resolved to, only recorded for
+    /// `-Zextern-usage-report`.
+    used_externs: Vec<(Symbol, CrateNum)>,
+    /// The previous release of the crate that `-Zsemver-baseline` compares against.
+    semver_baseline: Option<CrateNum>,
+    /// The crates that were only loaded for `-Zsemver-baseline`, i.e. the baseline and those of
+    /// its dependencies that the local crate does not depend on.
+    semver_baseline_only_crates: Range<CrateNum>,
 }
 
 impl std::fmt::Debug for CStore {
@@ -270,7 +279,26 @@ pub(crate) fn has_alloc_error_handler(&self) -> bool {
         self.has_alloc_error_handler
     }
 
+    pub fn semver_baseline(&self) -> Option<CrateNum> {
+        self.semver_baseline
+    }
+
+    /// Whether `cnum` was only loaded for `-Zsemver-baseline`. Such crates are not dependencies
+    /// of the local crate, so they are not recorded in its metadata.
+    pub fn is_semver_baseline_only(&self, cnum: CrateNum) -> bool {
+        self.semver_baseline_only_crates.contains(&cnum)
+    }
+
     pub fn report_unused_deps(&self, tcx: TyCtxt<'_>) {
+        if let Some(format) = tcx.sess.opts.unstable_opts.extern_usage_report {
+            extern_usage::report_extern_usage(
+                tcx,
+                &self.unused_externs,
+                &self.used_externs,
+                format,
+            );
+        }
+
         let json_unused_externs = tcx.sess.opts.json_unused_externs;
 
         // We put the check for the option before the lint_level_at_node call
@@ -304,6 +332,9 @@ pub fn new(metadata_loader: Box<MetadataLoaderDyn>) -> CStore {
             has_global_allocator: false,
             has_alloc_error_handler: false,
             unused_externs: Vec::new(),
+            used_externs: Vec::new(),
+            semver_baseline: None,
+            semver_baseline_only_crates: LOCAL_CRATE..LOCAL_CRATE,
         }
     }
 }
@@ -910,6 +941,24 @@ fn inject_forced_externs(&mut self) {
         }
     }
 
+    /// Loads the baseline of `-Zsemver-baseline`. This has to happen after all other crates
+    /// have been loaded, so that the crates that are only loaded for the baseline come last and
+    /// can be left out of the dependencies of the local crate.
+    fn load_semver_baseline(&mut self) {
+        if let Some(name) = &self.sess.opts.unstable_opts.semver_baseline {
+            let name = Symbol::intern(name);
+            let first_new = self.cstore.metas.next_index();
+            // The baseline is only inspected, never linked.
+            let baseline = self.resolve_crate(name, DUMMY_SP, CrateDepKind::MacrosOnly);
+            // If the baseline was already loaded, the local crate really depends on it.
+            if baseline.is_some_and(|cnum| cnum >= first_new) {
+                self.cstore.semver_baseline_only_crates =
+                    first_new..self.cstore.metas.next_index();
+            }
+            self.cstore.semver_baseline = baseline;
+        }
+    }
+
     fn inject_dependency_if(
         &mut self,
         krate: CrateNum,
@@ -964,13 +1013,21 @@ fn report_unused_deps(&mut self, krate: &ast::Crate) {
                 continue;
             }
             let name_interned = Symbol::intern(name);
+            let report_extern_usage = self.sess.opts.unstable_opts.extern_usage_report.is_some();
             if self.used_extern_options.contains(&name_interned) {
+                if report_extern_usage
+                    && let Some(cnum) = self.existing_match(name_interned, None, PathKind::Crate)
+                {
+                    self.cstore.used_externs.push((name_interned, cnum));
+                }
                 continue;
             }
 
             // Got a real unused --extern
-            if self.sess.opts.json_unused_externs.is_enabled() {
+            if self.sess.opts.json_unused_externs.is_enabled() || report_extern_usage {
                 self.cstore.unused_externs.push(name_interned);
+            }
+            if self.sess.opts.json_unused_externs.is_enabled() {
                 continue;
             }
 
@@ -1029,6 +1086,7 @@ pub fn postprocess(&mut self, krate: &ast::Crate) {
         self.inject_profiler_runtime(krate);
         self.inject_allocator_crate(krate);
         self.inject_panic_runtime(krate);
+        self.load_semver_baseline();
 
         self.report_unused_deps(krate);
         self.report_future_incompatible_deps(krate);
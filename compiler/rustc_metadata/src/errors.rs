@@ -486,6 +486,14 @@ pub struct ExternLocationNotExist<'a> {
     pub location: &'a Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(metadata_extern_usage)]
+#[help]
+pub struct ExternUsage {
+    pub crate_name: Symbol,
+    pub usage: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(metadata_extern_location_not_file)]
 pub struct ExternLocationNotFile<'a> {
@@ -0,0 +1,265 @@
+//! Implementation of `-Zextern-usage-report`, which reports how each crate passed with
+//! `--extern` is used by the local crate.
+//!
+//! Whether an extern crate is used at all is tracked while resolving, see
+//! `CrateLoader::report_unused_deps`. For the crates that are used, we walk the HIR to find
+//! out whether any of their items are used outside of tests, or only their macros.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir as hir;
+use rustc_hir::def_id::{CrateNum, DefId, LocalDefId};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::config::ExternUsageReportFormat;
+use rustc_span::hygiene::{ExpnKind, MacroKind};
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::{Span, SyntaxContext};
+
+use crate::errors;
+
+/// How an `--extern` crate is used by the local crate.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ExternUsage {
+    /// The crate is not used, not even by a `use`, a path or a macro.
+    Unused,
+    /// The crate is only used by tests, so it could be a dev-dependency.
+    TestOnly,
+    /// Only macros of the crate are used, so it is only needed at build time.
+    MacrosOnly,
+    /// Items of the crate are used outside of tests.
+    Used,
+}
+
+impl ExternUsage {
+    fn as_str(self) -> &'static str {
+        match self {
+            ExternUsage::Unused => "unused",
+            ExternUsage::TestOnly => "test-only",
+            ExternUsage::MacrosOnly => "macros-only",
+            ExternUsage::Used => "used",
+        }
+    }
+}
+
+pub(crate) fn report_extern_usage(
+    tcx: TyCtxt<'_>,
+    unused_externs: &[Symbol],
+    used_externs: &[(Symbol, CrateNum)],
+    format: ExternUsageReportFormat,
+) {
+    let mut collector = UsesCollector {
+        tcx,
+        maybe_typeck_results: None,
+        in_test: false,
+        uses: FxHashMap::default(),
+        seen_ctxts: FxHashSet::default(),
+    };
+    tcx.hir().visit_all_item_likes_in_crate(&mut collector);
+
+    let mut externs: Vec<_> =
+        unused_externs.iter().map(|&name| (name, ExternUsage::Unused)).collect();
+    for &(name, cnum) in used_externs {
+        let uses = collector.uses.get(&cnum).copied().unwrap_or_default();
+        // Proc macro crates and `#[no_link]` crates only provide macros anyway.
+        let macros_only_crate = tcx.dep_kind(cnum).macros_only();
+        let usage = if uses.code || (uses.macros && macros_only_crate) {
+            ExternUsage::Used
+        } else if uses.macros {
+            ExternUsage::MacrosOnly
+        } else if uses.tests {
+            ExternUsage::TestOnly
+        } else {
+            // The crate was resolved, but nothing in the HIR refers to it, e.g. because it is
+            // only named by an `extern crate` item that is kept for its side effects.
+            ExternUsage::Used
+        };
+        externs.push((name, usage));
+    }
+    externs.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+    match format {
+        ExternUsageReportFormat::Text => {
+            for (crate_name, usage) in externs {
+                if usage != ExternUsage::Used {
+                    tcx.dcx().emit_note(errors::ExternUsage { crate_name, usage: usage.as_str() });
+                }
+            }
+        }
+        ExternUsageReportFormat::Json => {
+            let externs = externs
+                .iter()
+                .map(|(name, usage)| (name.as_str(), usage.as_str()))
+                .collect::<Vec<_>>();
+            tcx.dcx().emit_extern_usage(&externs);
+        }
+    }
+}
+
+/// The uses of an extern crate found in the HIR.
+#[derive(Clone, Copy, Default)]
+struct CrateUses {
+    /// An item of the crate is used outside of tests.
+    code: bool,
+    /// A macro of the crate is used outside of tests.
+    macros: bool,
+    /// An item or a macro of the crate is used by tests.
+    tests: bool,
+}
+
+struct UsesCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    /// Whether the item-like being visited is only compiled for tests.
+    in_test: bool,
+    uses: FxHashMap<CrateNum, CrateUses>,
+    /// Syntax contexts whose macro backtrace was already recorded.
+    seen_ctxts: FxHashSet<(SyntaxContext, bool)>,
+}
+
+impl<'tcx> UsesCollector<'tcx> {
+    fn record_item(&mut self, def_id: DefId) {
+        if def_id.is_local() {
+            return;
+        }
+        let uses = self.uses.entry(def_id.krate).or_default();
+        if self.in_test {
+            uses.tests = true;
+        } else {
+            uses.code = true;
+        }
+    }
+
+    fn record_macros(&mut self, span: Span) {
+        if !span.from_expansion() || !self.seen_ctxts.insert((span.ctxt(), self.in_test)) {
+            return;
+        }
+        for expn_data in span.macro_backtrace() {
+            let Some(def_id) = expn_data.macro_def_id else { continue };
+            if def_id.is_local() {
+                continue;
+            }
+            let uses = self.uses.entry(def_id.krate).or_default();
+            if self.in_test {
+                uses.tests = true;
+            } else {
+                uses.macros = true;
+            }
+        }
+    }
+
+    fn enter_item_like(&mut self, def_id: LocalDefId, span: Span) {
+        self.in_test = self.is_test_code(def_id);
+        self.record_macros(span);
+    }
+
+    /// Whether `def_id` is only compiled for tests: it is a `#[test]` function or it is nested
+    /// in an item with a `#[cfg(test)]` attribute.
+    fn is_test_code(&self, mut def_id: LocalDefId) -> bool {
+        let tcx = self.tcx;
+        loop {
+            if let ExpnKind::Macro(MacroKind::Attr, sym::test | sym::bench) =
+                tcx.expn_that_defined(def_id).expn_data().kind
+            {
+                return true;
+            }
+            let attrs = tcx.hir().attrs(tcx.local_def_id_to_hir_id(def_id));
+            if attrs.iter().any(is_cfg_test) {
+                return true;
+            }
+            match tcx.opt_local_parent(def_id) {
+                Some(parent) => def_id = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+fn is_cfg_test(attr: &rustc_ast::Attribute) -> bool {
+    if !attr.has_name(sym::cfg) {
+        return false;
+    }
+    let Some(list) = attr.meta_item_list() else {
+        return false;
+    };
+    matches!(&list[..], [item] if item.is_word() && item.has_name(sym::test))
+}
+
+impl<'tcx> Visitor<'tcx> for UsesCollector<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_nested_body(&mut self, body_id: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body_id));
+        self.visit_body(self.tcx.hir().body(body_id));
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item<'tcx>) {
+        self.enter_item_like(item.owner_id.def_id, item.span);
+        intravisit::walk_item(self, item);
+    }
+
+    fn visit_trait_item(&mut self, item: &'tcx hir::TraitItem<'tcx>) {
+        self.enter_item_like(item.owner_id.def_id, item.span);
+        intravisit::walk_trait_item(self, item);
+    }
+
+    fn visit_impl_item(&mut self, item: &'tcx hir::ImplItem<'tcx>) {
+        self.enter_item_like(item.owner_id.def_id, item.span);
+        intravisit::walk_impl_item(self, item);
+    }
+
+    fn visit_foreign_item(&mut self, item: &'tcx hir::ForeignItem<'tcx>) {
+        self.enter_item_like(item.owner_id.def_id, item.span);
+        intravisit::walk_foreign_item(self, item);
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, _id: hir::HirId) {
+        if let Some(def_id) = path.res.opt_def_id() {
+            self.record_item(def_id);
+        }
+        self.record_macros(path.span);
+        intravisit::walk_path(self, path);
+    }
+
+    fn visit_use(&mut self, path: &'tcx hir::UsePath<'tcx>, hir_id: hir::HirId) {
+        for res in &path.res {
+            if let Some(def_id) = res.opt_def_id() {
+                self.record_item(def_id);
+            }
+        }
+        intravisit::walk_use(self, path, hir_id);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        // Methods and associated items resolved during type checking, e.g. `x.method()` and
+        // `Type::method()`, do not show up as paths.
+        if let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(def_id) = typeck_results.type_dependent_def_id(expr.hir_id)
+        {
+            self.record_item(def_id);
+        }
+        self.record_macros(expr.span);
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'tcx hir::Stmt<'tcx>) {
+        self.record_macros(stmt.span);
+        intravisit::walk_stmt(self, stmt);
+    }
+
+    fn visit_ty(&mut self, ty: &'tcx hir::Ty<'tcx>) {
+        self.record_macros(ty.span);
+        intravisit::walk_ty(self, ty);
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        self.record_macros(pat.span);
+        intravisit::walk_pat(self, pat);
+    }
+}
@@ -23,6 +23,7 @@
 pub use rmeta::provide;
 
 mod dependency_format;
+mod extern_usage;
 mod foreign_modules;
 mod native_libs;
 mod rmeta;
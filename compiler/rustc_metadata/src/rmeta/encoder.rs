@@ -1,3 +1,4 @@
+use crate::creader::CStore;
 use crate::errors::{FailCreateFileEncoder, FailWriteFile};
 use crate::rmeta::*;
 
@@ -1904,10 +1905,13 @@ fn encode_debugger_visualizers(&mut self) -> LazyArray<DebuggerVisualizerFile> {
     fn encode_crate_deps(&mut self) -> LazyArray<CrateDep> {
         empty_proc_macro!(self);
 
-        let deps = self
-            .tcx
-            .crates(())
+        let crates = self.tcx.crates(());
+        // The crates that were only loaded for `-Zsemver-baseline` come last, and the local crate
+        // does not depend on them.
+        let cstore = CStore::from_tcx(self.tcx);
+        let deps = crates
             .iter()
+            .filter(|&&cnum| !cstore.is_semver_baseline_only(cnum))
             .map(|&cnum| {
                 let dep = CrateDep {
                     name: self.tcx.crate_name(cnum),
@@ -2113,7 +2117,11 @@ fn encode_dylib_dependency_formats(&mut self) -> LazyArray<Option<LinkagePrefere
             if *ty != CrateType::Dylib {
                 continue;
             }
-            return self.lazy_array(arr.iter().map(|slot| match *slot {
+            let cstore = CStore::from_tcx(self.tcx);
+            let arr = arr.iter().enumerate().filter_map(|(i, slot)| {
+                (!cstore.is_semver_baseline_only(CrateNum::new(i + 1))).then_some(slot)
+            });
+            return self.lazy_array(arr.map(|slot| match *slot {
                 Linkage::NotLinked | Linkage::IncludedFromDylib => None,
 
                 Linkage::Dynamic => Some(LinkagePreference::RequireDynamic),
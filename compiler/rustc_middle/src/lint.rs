@@ -34,6 +34,10 @@ pub enum LintLevelSource {
     /// The provided `Level` is the level specified on the command line.
     /// (The actual level may be lower due to `--cap-lints`.)
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by the `--lint-config` file, either for the whole crate or for the
+    /// items matching `path`. `name` is the lint or lint group the level was set for.
+    LintConfig { name: Symbol, level: Level, path: Option<Symbol> },
 }
 
 impl LintLevelSource {
@@ -42,6 +46,7 @@ pub fn name(&self) -> Symbol {
             LintLevelSource::Default => symbol::kw::Default,
             LintLevelSource::Node { name, .. } => name,
             LintLevelSource::CommandLine(name, _) => name,
+            LintLevelSource::LintConfig { name, .. } => name,
         }
     }
 
@@ -50,6 +55,7 @@ pub fn span(&self) -> Span {
             LintLevelSource::Default => DUMMY_SP,
             LintLevelSource::Node { span, .. } => span,
             LintLevelSource::CommandLine(_, _) => DUMMY_SP,
+            LintLevelSource::LintConfig { .. } => DUMMY_SP,
         }
     }
 }
@@ -235,6 +241,24 @@ pub fn explain_lint_level_source(
                 ));
             }
         }
+        LintLevelSource::LintConfig { name: config_name, level: orig_level, path } => {
+            let orig_level = orig_level.as_str();
+            // A level for all lints at `path` is named `*`.
+            let all_lints = config_name.as_str() == "*";
+            let entry = match path {
+                Some(path) if all_lints => format!("\"{path}\" = \"{orig_level}\""),
+                Some(path) => format!("\"{path}\" = {{ {config_name} = \"{orig_level}\" }}"),
+                None => format!("{config_name} = \"{orig_level}\""),
+            };
+            if all_lints || config_name.as_str() == name {
+                err.note_once(format!("requested in the lint configuration file with `{entry}`"));
+            } else {
+                err.note_once(format!(
+                    "`{name} = \"{orig_level}\"` implied by `{entry}` in the lint configuration file"
+                ));
+                err.help_once(format!("to override `{entry}` add `#[allow({name})]`"));
+            }
+        }
         LintLevelSource::Node { name: lint_attr_name, span, reason, .. } => {
             if let Some(rationale) = reason {
                 err.note(rationale.to_string());
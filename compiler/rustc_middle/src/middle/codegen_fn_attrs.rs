@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+
 use crate::mir::mono::Linkage;
+use crate::ty::{InstanceKind, TyCtxt};
 use rustc_attr::{InlineAttr, InstructionSetAttr, OptimizeAttr};
 use rustc_macros::{HashStable, TyDecodable, TyEncodable};
 use rustc_span::symbol::Symbol;
@@ -167,3 +170,23 @@ pub fn contains_extern_indicator(&self) -> bool {
             }
     }
 }
+
+impl<'tcx> TyCtxt<'tcx> {
+    /// The codegen attributes of an instance. These are the ones of the function it comes from,
+    /// except that an outlined cold path is always cold and never inlined, whatever the inline and
+    /// optimize hints on its function say. Otherwise, the path would be inlined back into every
+    /// instantiation of the function.
+    pub fn codegen_instance_attrs(self, instance: InstanceKind<'tcx>) -> Cow<'tcx, CodegenFnAttrs> {
+        let attrs = self.codegen_fn_attrs(instance.def_id());
+        match instance {
+            InstanceKind::ColdPathShim(..) => {
+                let mut attrs = attrs.clone();
+                attrs.flags.insert(CodegenFnAttrFlags::COLD);
+                attrs.inline = InlineAttr::Never;
+                attrs.optimize = OptimizeAttr::None;
+                Cow::Owned(attrs)
+            }
+            _ => Cow::Borrowed(attrs),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Registering limits:
 //! * recursion_limit,
-//! * move_size_limit, and
+//! * move_size_limit,
+//! * future_size_limit, and
 //! * type_length_limit
 //!
 //! There are various parts of the compiler that must impose arbitrary limits
@@ -24,7 +25,23 @@ pub fn provide(providers: &mut Providers) {
             tcx.hir().krate_attrs(),
             tcx.sess,
             sym::move_size_limit,
-            tcx.sess.opts.unstable_opts.move_size_limit.unwrap_or(0),
+            tcx.sess
+                .opts
+                .unstable_opts
+                .move_size_limit
+                .or_else(|| lint_config_size_limit(tcx.sess, "large_assignments"))
+                .unwrap_or(0),
+        ),
+        future_size_limit: get_limit(
+            tcx.hir().krate_attrs(),
+            tcx.sess,
+            sym::future_size_limit,
+            tcx.sess
+                .opts
+                .unstable_opts
+                .future_size_limit
+                .or_else(|| lint_config_size_limit(tcx.sess, "large_futures"))
+                .unwrap_or(0),
         ),
         type_length_limit: get_limit(
             tcx.hir().krate_attrs(),
@@ -35,6 +52,13 @@ pub fn provide(providers: &mut Providers) {
     }
 }
 
+/// The `size_limit` option of `lint` in the `--lint-config` file.
+fn lint_config_size_limit(sess: &Session, lint: &str) -> Option<usize> {
+    let limit = sess.opts.lint_config.integer_option(lint, "size_limit")?;
+    // The option was checked to be non-negative when parsing the file.
+    Some(limit.try_into().unwrap_or(usize::MAX))
+}
+
 pub fn get_recursion_limit(krate_attrs: &[Attribute], sess: &Session) -> Limit {
     get_limit(krate_attrs, sess, sym::recursion_limit, 128)
 }
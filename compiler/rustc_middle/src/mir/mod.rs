@@ -444,6 +444,10 @@ pub struct Body<'tcx> {
     /// If `-Cinstrument-coverage` is not active, or if an individual function
     /// is not eligible for coverage, then this should always be `None`.
     pub function_coverage_info: Option<Box<coverage::FunctionCoverageInfo>>,
+
+    /// The cold paths of this body that the `OutlineColdPaths` pass moved to shims. The MIR of
+    /// `InstanceKind::ColdPathShim(def_id, index)` is `outlined_cold_paths[index]`.
+    pub outlined_cold_paths: Vec<Body<'tcx>>,
 }
 
 impl<'tcx> Body<'tcx> {
@@ -487,6 +491,7 @@ pub fn new(
             tainted_by_errors,
             coverage_info_hi: None,
             function_coverage_info: None,
+            outlined_cold_paths: Vec::new(),
         };
         body.is_polymorphic = body.has_non_region_param();
         body
@@ -518,6 +523,7 @@ pub fn new_cfg_only(basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>) ->
             tainted_by_errors: None,
             coverage_info_hi: None,
             function_coverage_info: None,
+            outlined_cold_paths: Vec::new(),
         };
         body.is_polymorphic = body.has_non_region_param();
         body
@@ -419,6 +419,7 @@ fn item_sort_key<'tcx>(tcx: TyCtxt<'tcx>, item: MonoItem<'tcx>) -> ItemSortKey<'
                             | InstanceKind::DropGlue(..)
                             | InstanceKind::CloneShim(..)
                             | InstanceKind::ThreadLocalShim(..)
+                            | InstanceKind::ColdPathShim(..)
                             | InstanceKind::FnPtrAddrShim(..)
                             | InstanceKind::AsyncDropGlueCtorShim(..) => None,
                         }
@@ -344,6 +344,7 @@ fn super_source_scope_data(
                         ty::InstanceKind::ReifyShim(_def_id, _) |
                         ty::InstanceKind::Virtual(_def_id, _) |
                         ty::InstanceKind::ThreadLocalShim(_def_id) |
+                        ty::InstanceKind::ColdPathShim(_def_id, _) |
                         ty::InstanceKind::ClosureOnceShim { call_once: _def_id, track_caller: _ } |
                         ty::InstanceKind::ConstructCoroutineInClosureShim {
                             coroutine_closure_def_id: _def_id,
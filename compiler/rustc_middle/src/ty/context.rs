@@ -2034,6 +2034,10 @@ pub fn move_size_limit(self) -> Limit {
         self.limits(()).move_size_limit
     }
 
+    pub fn future_size_limit(self) -> Limit {
+        self.limits(()).future_size_limit
+    }
+
     pub fn all_traits(self) -> impl Iterator<Item = DefId> + 'tcx {
         iter::once(LOCAL_CRATE)
             .chain(self.crates(()).iter().copied())
@@ -153,6 +153,16 @@ pub enum InstanceKind<'tcx> {
     /// native support.
     ThreadLocalShim(DefId),
 
+    /// A cold path of the generic function `DefId` that ends in a diverging call, e.g. to
+    /// `panic!`, and does not depend on the generic parameters of the function. The
+    /// `OutlineColdPaths` MIR pass moves it to this shim, so it is shared by all of the instances
+    /// of the function.
+    ///
+    /// The `usize` is the index of the path in `outlined_cold_paths` of the optimized MIR of the
+    /// function, which is the MIR of the shim. The shim has no generic arguments, and is cold and
+    /// never inlined, see `TyCtxt::codegen_instance_attrs`.
+    ColdPathShim(DefId, usize),
+
     /// `core::ptr::drop_in_place::<T>`.
     ///
     /// The `DefId` is for `core::ptr::drop_in_place`.
@@ -242,6 +252,7 @@ pub fn def_id(self) -> DefId {
             | InstanceKind::Virtual(def_id, _)
             | InstanceKind::Intrinsic(def_id)
             | InstanceKind::ThreadLocalShim(def_id)
+            | InstanceKind::ColdPathShim(def_id, _)
             | InstanceKind::ClosureOnceShim { call_once: def_id, track_caller: _ }
             | ty::InstanceKind::ConstructCoroutineInClosureShim {
                 coroutine_closure_def_id: def_id,
@@ -270,6 +281,7 @@ pub fn def_id_if_not_guaranteed_local_codegen(self) -> Option<DefId> {
             | InstanceKind::ClosureOnceShim { .. }
             | ty::InstanceKind::ConstructCoroutineInClosureShim { .. }
             | ty::InstanceKind::CoroutineKindShim { .. }
+            | InstanceKind::ColdPathShim(..)
             | InstanceKind::DropGlue(..)
             | InstanceKind::AsyncDropGlueCtorShim(..)
             | InstanceKind::CloneShim(..)
@@ -298,6 +310,8 @@ pub fn requires_inline(&self, tcx: TyCtxt<'tcx>) -> bool {
             ty::InstanceKind::DropGlue(_, Some(_)) => return false,
             ty::InstanceKind::AsyncDropGlueCtorShim(_, Some(_)) => return false,
             ty::InstanceKind::ThreadLocalShim(_) => return false,
+            // The path is outlined so that it is not copied.
+            ty::InstanceKind::ColdPathShim(..) => return false,
             _ => return true,
         };
         matches!(
@@ -344,7 +358,7 @@ pub fn generates_cgu_internal_copy(&self, tcx: TyCtxt<'tcx>) -> bool {
                 .map_or_else(|| adt_def.is_enum(), |did| tcx.cross_crate_inlinable(did))
             });
         }
-        if let ty::InstanceKind::ThreadLocalShim(..) = *self {
+        if let ty::InstanceKind::ThreadLocalShim(..) | ty::InstanceKind::ColdPathShim(..) = *self {
             return false;
         }
         tcx.cross_crate_inlinable(self.def_id())
@@ -370,6 +384,7 @@ pub fn has_polymorphic_mir_body(&self) -> bool {
         match *self {
             InstanceKind::CloneShim(..)
             | InstanceKind::ThreadLocalShim(..)
+            | InstanceKind::ColdPathShim(..)
             | InstanceKind::FnPtrAddrShim(..)
             | InstanceKind::FnPtrShim(..)
             | InstanceKind::DropGlue(_, Some(_))
@@ -446,6 +461,7 @@ pub fn fmt_instance(
         InstanceKind::ReifyShim(_, Some(ReifyReason::FnPtr)) => write!(f, " - shim(reify-fnptr)"),
         InstanceKind::ReifyShim(_, Some(ReifyReason::Vtable)) => write!(f, " - shim(reify-vtable)"),
         InstanceKind::ThreadLocalShim(_) => write!(f, " - shim(tls)"),
+        InstanceKind::ColdPathShim(_, index) => write!(f, " - shim(cold#{index})"),
         InstanceKind::Intrinsic(_) => write!(f, " - intrinsic"),
         InstanceKind::Virtual(_, num) => write!(f, " - virtual#{num}"),
         InstanceKind::FnPtrShim(_, ty) => write!(f, " - shim({ty})"),
@@ -928,10 +944,17 @@ pub fn try_instantiate_mir_and_normalize_erasing_regions<T>(
     }
 
     /// Returns a new `Instance` where generic parameters in `instance.args` are replaced by
-    /// identity parameters if they are determined to be unused in `instance.def`.
+    /// identity parameters if they are determined to be unused in `instance.def`. With
+    /// `-Zpolymorphize=layout`, unused type parameters are replaced by the unit type instead.
     pub fn polymorphize(self, tcx: TyCtxt<'tcx>) -> Self {
         debug!("polymorphize: running polymorphization analysis");
-        if !tcx.sess.opts.unstable_opts.polymorphize {
+        if !tcx.sess.opts.unstable_opts.polymorphize.is_enabled() {
+            return self;
+        }
+
+        // Cold path shims are shared by all the instances of their function, so they have no
+        // generic arguments to polymorphize.
+        if matches!(self.def, InstanceKind::ColdPathShim(..)) || self.args.is_empty() {
             return self;
         }
 
@@ -1025,6 +1048,15 @@ fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
                     ty::GenericArg::from(polymorphized_upvars_ty)
                 },
 
+            // Layout case: If parameter is a type parameter that is unused or does not affect
+            // the layout..
+            ty::GenericParamDefKind::Type { .. } if
+                tcx.sess.opts.unstable_opts.polymorphize.is_layout_enabled() &&
+                unused.is_unused(param.index) =>
+                    // ..then use the unit type, as the pointers to the identity parameter would
+                    // not have a known layout.
+                    tcx.types.unit.into(),
+
             // Simple case: If parameter is a const or type parameter..
             ty::GenericParamDefKind::Const { .. } | ty::GenericParamDefKind::Type { .. } if
                 // ..and is within range and unused..
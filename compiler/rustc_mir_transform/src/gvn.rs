@@ -1510,9 +1510,9 @@ fn visit_statement(&mut self, stmt: &mut Statement<'tcx>, location: Location) {
     }
 }
 
-struct StorageRemover<'tcx> {
-    tcx: TyCtxt<'tcx>,
-    reused_locals: BitSet<Local>,
+pub(crate) struct StorageRemover<'tcx> {
+    pub(crate) tcx: TyCtxt<'tcx>,
+    pub(crate) reused_locals: BitSet<Local>,
 }
 
 impl<'tcx> MutVisitor<'tcx> for StorageRemover<'tcx> {
@@ -337,6 +337,7 @@ fn check_mir_is_available(
             | InstanceKind::DropGlue(..)
             | InstanceKind::CloneShim(..)
             | InstanceKind::ThreadLocalShim(..)
+            | InstanceKind::ColdPathShim(..)
             | InstanceKind::FnPtrAddrShim(..)
             | InstanceKind::AsyncDropGlueCtorShim(..) => return Ok(()),
         }
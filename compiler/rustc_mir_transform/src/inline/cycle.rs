@@ -91,6 +91,7 @@ fn process<'tcx>(
                 | InstanceKind::ConstructCoroutineInClosureShim { .. }
                 | InstanceKind::CoroutineKindShim { .. }
                 | InstanceKind::ThreadLocalShim { .. }
+                | InstanceKind::ColdPathShim(..)
                 | InstanceKind::CloneShim(..) => {}
 
                 // This shim does not call any other functions, thus there can be no recursion.
@@ -83,12 +83,14 @@
 mod known_panics_lint;
 mod large_enums;
 mod lint;
+mod loop_invariant_code_motion;
 mod lower_intrinsics;
 mod lower_slice_len;
 mod match_branches;
 mod mentioned_items;
 mod multiple_return_terminators;
 mod nrvo;
+mod outline_cold_paths;
 mod prettify;
 mod promote_consts;
 mod ref_prop;
@@ -596,6 +598,7 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &dead_store_elimination::DeadStoreElimination::Initial,
             &gvn::GVN,
             &simplify::SimplifyLocals::AfterGVN,
+            &loop_invariant_code_motion::LoopInvariantCodeMotion,
             &dataflow_const_prop::DataflowConstProp,
             &single_use_consts::SingleUseConsts,
             &o1(simplify_branches::SimplifyConstCondition::AfterConstProp),
@@ -605,6 +608,8 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &dest_prop::DestinationPropagation,
             &o1(simplify_branches::SimplifyConstCondition::Final),
             &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
+            // Leaves the outlined blocks unreachable, so it runs before the final cleanup.
+            &outline_cold_paths::OutlineColdPaths,
             &o1(simplify::SimplifyCfg::Final),
             &copy_prop::CopyProp,
             &dead_store_elimination::DeadStoreElimination::Final,
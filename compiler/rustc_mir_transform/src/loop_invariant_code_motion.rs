@@ -0,0 +1,329 @@
+//! Loop-invariant code motion.
+//!
+//! This pass moves the computations that produce the same value on each iteration of a loop to
+//! the block that enters the loop, so they are computed once. For instance, the length of a slice
+//! that is indexed in a loop:
+//!
+//! ```text
+//! bb1: {
+//!     _5 = Len((*_1));
+//!     _6 = Lt(_4, _5);
+//!     assert(move _6, ...) -> [success: bb2, unwind continue];
+//! }
+//! ```
+//!
+//! Only the assignments to SSA locals are hoisted. The SSA analysis guarantees that the hoisted
+//! local is assigned once, and that its assignment dominates all of its uses, which stays true
+//! when the assignment is moved to a block that dominates the loop.
+//!
+//! The loops are the natural loops of the CFG: a header dominates the blocks of the loop, and has
+//! back edges from the latches of the loop. We only hoist to a preheader: the single block that
+//! enters the loop, and that ends with a `Goto` to the header. Inner loops are handled first,
+//! so the computations they hoist can be hoisted out of the enclosing loops as well.
+//!
+//! The hoisted statement is executed even when the loop would not have executed it, so it must
+//! not have undefined behaviour. We only hoist:
+//! - arithmetic that cannot be UB, so not divisions, `Offset` or unchecked operations;
+//! - numeric and pointer-to-pointer casts;
+//! - `Len` of a place behind a pointer, which only reads the pointer metadata;
+//! - reads of invariant places, which are either fields of SSA locals, or fields behind a shared
+//!   reference argument to a `Freeze` type: those are dereferenceable and immutable for the whole
+//!   function.
+//!
+//! The operands must be live at the end of the preheader, which we check with the
+//! `MaybeStorageDead` dataflow analysis.
+
+use rustc_index::bit_set::BitSet;
+use rustc_index::IndexVec;
+use rustc_middle::mir::visit::MutVisitor;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ParamEnv, TyCtxt};
+use rustc_mir_dataflow::impls::MaybeStorageDead;
+use rustc_mir_dataflow::storage::always_storage_live_locals;
+use rustc_mir_dataflow::Analysis;
+use std::borrow::Cow;
+
+use crate::gvn::StorageRemover;
+use crate::ssa::SsaLocals;
+
+pub struct LoopInvariantCodeMotion;
+
+impl<'tcx> MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        if !body.basic_blocks.is_cfg_cyclic() {
+            return;
+        }
+
+        let mut loops = find_loops(body);
+        if loops.is_empty() {
+            return;
+        }
+        // An inner loop has fewer blocks than the loops that contain it.
+        loops.sort_by_key(|lp| lp.blocks.count());
+
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let ssa = SsaLocals::new(tcx, body, param_env);
+        let always_live_locals = always_storage_live_locals(body);
+        let mut maybe_dead = MaybeStorageDead::new(Cow::Owned(always_live_locals))
+            .into_engine(tcx, body)
+            .iterate_to_fixpoint()
+            .into_results_cursor(body);
+
+        let mut hoister = Hoister::new(tcx, param_env, body, &ssa);
+        for lp in &loops {
+            // Hoisting does not move storage statements, so the dataflow state of the original
+            // body is still correct for the locals that were not hoisted.
+            maybe_dead.seek_to_block_end(lp.preheader);
+            hoister.hoist_loop(lp, maybe_dead.get());
+        }
+
+        let Hoister { statements, hoisted, .. } = hoister;
+        if hoisted.is_empty() {
+            return;
+        }
+        debug!(?hoisted);
+
+        let basic_blocks = body.basic_blocks.as_mut_preserves_cfg();
+        let mut old_statements: IndexVec<BasicBlock, Vec<Option<Statement<'tcx>>>> = basic_blocks
+            .iter_mut()
+            .map(|data| std::mem::take(&mut data.statements).into_iter().map(Some).collect())
+            .collect();
+        for (block, locations) in statements.into_iter_enumerated() {
+            basic_blocks[block].statements = locations
+                .into_iter()
+                .map(|loc| old_statements[loc.block][loc.statement_index].take().unwrap())
+                .collect();
+        }
+
+        // The hoisted locals are now live across the loop, and they are reused by each iteration.
+        StorageRemover { tcx, reused_locals: hoisted }.visit_body_preserves_cfg(body);
+    }
+}
+
+struct Loop {
+    header: BasicBlock,
+    /// The only block outside of the loop that jumps to the header.
+    preheader: BasicBlock,
+    /// The blocks of the loop, including the header.
+    blocks: BitSet<BasicBlock>,
+}
+
+/// Finds the natural loops that have a preheader.
+fn find_loops(body: &Body<'_>) -> Vec<Loop> {
+    let basic_blocks = &body.basic_blocks;
+    let dominators = basic_blocks.dominators();
+    let predecessors = basic_blocks.predecessors();
+
+    let mut loops = vec![];
+    for &header in basic_blocks.reverse_postorder() {
+        if basic_blocks[header].is_cleanup {
+            continue;
+        }
+        let mut stack: Vec<BasicBlock> = predecessors[header]
+            .iter()
+            .copied()
+            .filter(|&pred| dominators.is_reachable(pred) && dominators.dominates(header, pred))
+            .collect();
+        if stack.is_empty() {
+            continue;
+        }
+
+        // The loop contains the blocks that reach a latch without going through the header.
+        let mut blocks = BitSet::new_empty(basic_blocks.len());
+        blocks.insert(header);
+        while let Some(block) = stack.pop() {
+            if blocks.insert(block) {
+                stack.extend(
+                    predecessors[block].iter().filter(|&&pred| dominators.is_reachable(pred)),
+                );
+            }
+        }
+
+        let mut entries = predecessors[header]
+            .iter()
+            .copied()
+            .filter(|&pred| dominators.is_reachable(pred) && !blocks.contains(pred));
+        let (Some(preheader), None) = (entries.next(), entries.next()) else { continue };
+        if !matches!(basic_blocks[preheader].terminator().kind, TerminatorKind::Goto { .. }) {
+            continue;
+        }
+        debug!(?header, ?preheader, ?blocks);
+        loops.push(Loop { header, preheader, blocks });
+    }
+    loops
+}
+
+struct Hoister<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    ssa: &'a SsaLocals,
+    /// The block that assigns each SSA local, or `None` for the arguments.
+    defined_in: IndexVec<Local, Option<BasicBlock>>,
+    /// The original locations of the statements of each block, after the hoisting so far.
+    statements: IndexVec<BasicBlock, Vec<Location>>,
+    /// The locals whose assignment was hoisted.
+    hoisted: BitSet<Local>,
+}
+
+impl<'a, 'tcx> Hoister<'a, 'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        body: &'a Body<'tcx>,
+        ssa: &'a SsaLocals,
+    ) -> Self {
+        let defined_in = body
+            .local_decls
+            .indices()
+            .map(|local| match ssa.assignment(local) {
+                Some(DefLocation::Assignment(loc)) => Some(loc.block),
+                Some(DefLocation::CallReturn { call, .. }) => Some(call),
+                Some(DefLocation::Argument) | None => None,
+            })
+            .collect();
+        let statements = body
+            .basic_blocks
+            .iter_enumerated()
+            .map(|(block, data)| {
+                (0..data.statements.len())
+                    .map(|statement_index| Location { block, statement_index })
+                    .collect()
+            })
+            .collect();
+        let hoisted = BitSet::new_empty(body.local_decls.len());
+        Hoister { tcx, param_env, body, ssa, defined_in, statements, hoisted }
+    }
+
+    fn hoist_loop(&mut self, lp: &Loop, maybe_dead: &BitSet<Local>) {
+        let cx = InvariantCx { lp, maybe_dead };
+        // Visit the blocks in reverse postorder, so a statement is hoisted after the statements
+        // that compute its operands.
+        for &block in self.body.basic_blocks.reverse_postorder() {
+            if !lp.blocks.contains(block) {
+                continue;
+            }
+            for loc in std::mem::take(&mut self.statements[block]) {
+                if let Some(local) = self.hoistable_statement(&cx, loc) {
+                    debug!(?lp.header, ?loc, ?local, "hoisting");
+                    self.statements[lp.preheader].push(loc);
+                    self.defined_in[local] = Some(lp.preheader);
+                    self.hoisted.insert(local);
+                } else {
+                    self.statements[block].push(loc);
+                }
+            }
+        }
+    }
+
+    /// Returns the local assigned by the statement at `loc`, if it can be hoisted out of the loop.
+    fn hoistable_statement(&self, cx: &InvariantCx<'_>, loc: Location) -> Option<Local> {
+        let StatementKind::Assign(box (place, ref rvalue)) =
+            self.body.basic_blocks[loc.block].statements[loc.statement_index].kind
+        else {
+            return None;
+        };
+        let local = place.as_local()?;
+        (self.ssa.is_ssa(local) && self.is_invariant_rvalue(cx, rvalue)).then_some(local)
+    }
+
+    fn is_invariant_rvalue(&self, cx: &InvariantCx<'_>, rvalue: &Rvalue<'tcx>) -> bool {
+        match *rvalue {
+            // Copying a local or a constant is as cheap as copying the hoisted value.
+            Rvalue::Use(Operand::Copy(place)) => {
+                !place.projection.is_empty() && self.is_invariant_place(cx, place)
+            }
+            // The length of a slice behind a pointer is in the metadata of the pointer.
+            Rvalue::Len(place) if place.projection[..] == [ProjectionElem::Deref] => {
+                self.is_invariant_local(cx, place.local)
+            }
+            Rvalue::Len(place) | Rvalue::Discriminant(place) => self.is_invariant_place(cx, place),
+            Rvalue::UnaryOp(_, ref operand) => self.is_invariant_operand(cx, operand),
+            Rvalue::BinaryOp(op, box (ref lhs, ref rhs)) => {
+                !matches!(
+                    op,
+                    BinOp::Div
+                        | BinOp::Rem
+                        | BinOp::Offset
+                        | BinOp::AddUnchecked
+                        | BinOp::SubUnchecked
+                        | BinOp::MulUnchecked
+                        | BinOp::ShlUnchecked
+                        | BinOp::ShrUnchecked
+                ) && self.is_invariant_operand(cx, lhs)
+                    && self.is_invariant_operand(cx, rhs)
+            }
+            Rvalue::Cast(
+                CastKind::IntToInt
+                | CastKind::IntToFloat
+                | CastKind::FloatToInt
+                | CastKind::FloatToFloat
+                | CastKind::PtrToPtr,
+                ref operand,
+                _,
+            ) => self.is_invariant_operand(cx, operand),
+            _ => false,
+        }
+    }
+
+    fn is_invariant_operand(&self, cx: &InvariantCx<'_>, operand: &Operand<'tcx>) -> bool {
+        match *operand {
+            Operand::Constant(_) => true,
+            Operand::Copy(place) => self.is_invariant_place(cx, place),
+            Operand::Move(_) => false,
+        }
+    }
+
+    /// Whether `place` has the same value during the whole loop, and can be read before it.
+    fn is_invariant_place(&self, cx: &InvariantCx<'_>, place: Place<'tcx>) -> bool {
+        if !self.is_invariant_local(cx, place.local) {
+            return false;
+        }
+        for (base, elem) in place.iter_projections() {
+            let base_ty = base.ty(self.body, self.tcx).ty;
+            match elem {
+                // Only the references passed as arguments are known to be dereferenceable for
+                // the whole function.
+                ProjectionElem::Deref => {
+                    let is_argument = base.projection.is_empty()
+                        && self.ssa.assignment(base.local) == Some(DefLocation::Argument);
+                    let ty::Ref(_, pointee, Mutability::Not) = *base_ty.kind() else {
+                        return false;
+                    };
+                    if !is_argument || !pointee.is_freeze(self.tcx, self.param_env) {
+                        return false;
+                    }
+                }
+                ProjectionElem::Field(..) if base_ty.is_union() => return false,
+                ProjectionElem::Field(..)
+                | ProjectionElem::OpaqueCast(_)
+                | ProjectionElem::Subtype(_) => {}
+                ProjectionElem::Index(_)
+                | ProjectionElem::ConstantIndex { .. }
+                | ProjectionElem::Subslice { .. }
+                | ProjectionElem::Downcast(..) => return false,
+            }
+        }
+        true
+    }
+
+    /// Whether `local` is assigned once before the loop, and is live at the end of the preheader.
+    fn is_invariant_local(&self, cx: &InvariantCx<'_>, local: Local) -> bool {
+        self.ssa.is_ssa(local)
+            && self.defined_in[local].map_or(true, |block| !cx.lp.blocks.contains(block))
+            && (self.hoisted.contains(local) || !cx.maybe_dead.contains(local))
+    }
+}
+
+/// The loop that is being hoisted out of.
+struct InvariantCx<'a> {
+    lp: &'a Loop,
+    /// The locals that may be dead at the end of the preheader.
+    maybe_dead: &'a BitSet<Local>,
+}
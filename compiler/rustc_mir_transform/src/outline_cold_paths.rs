@@ -0,0 +1,439 @@
+//! Outlining of the cold paths of generic functions.
+//!
+//! A generic function is codegened once for each instantiation, and so are its diverging paths,
+//! even when they do not depend on the generic parameters. For instance, all the instantiations
+//! of this function contain a copy of the code that formats the panic message:
+//!
+//! ```ignore (illustrative)
+//! fn get<T: Copy>(slice: &[T], index: usize) -> T {
+//!     if index >= slice.len() {
+//!         panic!("index {index} is out of bounds");
+//!     }
+//!     slice[index]
+//! }
+//! ```
+//!
+//! This pass moves such paths to an `InstanceKind::ColdPathShim`, which all the instantiations
+//! call, so the path is codegened once per crate. The MIR of the shim is stored in the
+//! `outlined_cold_paths` of the body it was taken from.
+//!
+//! A path is a chain of blocks that ends with a diverging call. Each block of the chain but the
+//! first has a single predecessor, and ends with a `Goto` or a `Call` that does not unwind to a
+//! cleanup block, so the blocks execute one after the other. The path is outlined if none of its
+//! types and constants depend on the generic parameters, once the promoted constants are
+//! evaluated.
+//!
+//! The locals that are only mentioned in the path are moved to the shim. The other locals are
+//! passed to the shim, which is only possible if the path does not modify them, nor borrow them,
+//! since the caller would not see the changes. The path may still use them through a pointer.
+//! We also skip locals that are borrowed anywhere in the body: their value could change during
+//! the path through that borrow.
+//!
+//! The shim has a single source scope, so we lose the debuginfo of the path, and the spans of
+//! the calls are resolved to their caller location beforehand. `#[track_caller]` functions are
+//! not handled, as their caller location is not known to the shim.
+
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_index::bit_set::BitSet;
+use rustc_index::IndexVec;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::interpret::{Pointer, Scalar};
+use rustc_middle::mir::visit::{
+    MutVisitor, NonMutatingUseContext, NonUseContext, PlaceContext, Visitor,
+};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{
+    self, GenericArgs, Instance, InstanceKind, ParamEnv, Ty, TyCtxt, TypeVisitableExt,
+};
+use rustc_mir_dataflow::impls::borrowed_locals;
+use rustc_span::source_map::Spanned;
+use rustc_target::spec::abi::Abi;
+
+use crate::mentioned_items::MentionedItems;
+use crate::required_consts::RequiredConstsVisitor;
+
+pub struct OutlineColdPaths;
+
+impl<'tcx> MirPass<'tcx> for OutlineColdPaths {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        // The path loses its debuginfo, so only do this when optimizing.
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+
+        let InstanceKind::Item(def_id) = body.source.instance else { return };
+        if body.source.promoted.is_some()
+            || !tcx.generics_of(def_id).requires_monomorphization(tcx)
+            || tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::TRACK_CALLER)
+        {
+            return;
+        }
+
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        let predecessors = body.basic_blocks.predecessors().clone();
+        let borrowed = borrowed_locals(body);
+        let mut mentions = LocalUses::new(body.local_decls.len());
+        mentions.visit_body(body);
+
+        // Collect the diverging calls first, as the calls to the shims are diverging too.
+        let diverging: Vec<BasicBlock> = body
+            .basic_blocks
+            .iter_enumerated()
+            .filter(|(_, data)| !data.is_cleanup && is_chain_end(&data.terminator().kind))
+            .map(|(bb, _)| bb)
+            .collect();
+
+        for bb in diverging {
+            let mut region = vec![bb];
+            let mut current = bb;
+            while let &[pred] = &predecessors[current][..] {
+                let pred_data = &body.basic_blocks[pred];
+                if region.contains(&pred)
+                    || pred_data.is_cleanup
+                    || !is_chain_link(&pred_data.terminator().kind)
+                {
+                    break;
+                }
+                region.push(pred);
+                current = pred;
+            }
+            region.reverse();
+
+            // The first blocks may prevent the outlining, for instance because they assign a
+            // local that is used after the path. Try again without them.
+            while !region.is_empty() {
+                if region.len() == 1 && body.basic_blocks[region[0]].statements.is_empty() {
+                    // Only the call is left, there is nothing to share.
+                    break;
+                }
+                if let Some(cold_path) =
+                    ColdPath::new(tcx, param_env, body, &region, &mentions, &borrowed)
+                {
+                    cold_path.outline(tcx, def_id, body, &region);
+                    break;
+                }
+                region.remove(0);
+            }
+        }
+    }
+}
+
+/// The last block of a cold path: a diverging call that does not unwind to a cleanup block.
+fn is_chain_end(kind: &TerminatorKind<'_>) -> bool {
+    match kind {
+        TerminatorKind::Call { target: None, unwind, .. } => {
+            !matches!(unwind, UnwindAction::Cleanup(_))
+        }
+        _ => false,
+    }
+}
+
+/// The other blocks of a cold path, which only have one successor.
+fn is_chain_link(kind: &TerminatorKind<'_>) -> bool {
+    match kind {
+        TerminatorKind::Goto { .. } => true,
+        TerminatorKind::Call { target: Some(_), unwind, .. } => {
+            !matches!(unwind, UnwindAction::Cleanup(_))
+        }
+        _ => false,
+    }
+}
+
+/// Counts the mentions of each local, and records the locals that are used in a way that
+/// prevents passing them to a shim.
+struct LocalUses {
+    mentions: IndexVec<Local, usize>,
+    /// The locals that are used besides their storage markers.
+    used: BitSet<Local>,
+    /// The locals that are modified, borrowed, or only partially read.
+    direct: BitSet<Local>,
+}
+
+impl LocalUses {
+    fn new(locals: usize) -> Self {
+        LocalUses {
+            mentions: IndexVec::from_elem_n(0, locals),
+            used: BitSet::new_empty(locals),
+            direct: BitSet::new_empty(locals),
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for LocalUses {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Whole copies and moves read the value of the local at the start of the path, and uses
+        // through a pointer only read the pointer.
+        let whole_read = place.projection.is_empty()
+            && matches!(
+                context,
+                PlaceContext::NonMutatingUse(
+                    NonMutatingUseContext::Copy | NonMutatingUseContext::Move
+                )
+            );
+        if !whole_read && !place.is_indirect_first_projection() {
+            self.direct.insert(place.local);
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_local(&mut self, local: Local, context: PlaceContext, _: Location) {
+        self.mentions[local] += 1;
+        match context {
+            PlaceContext::NonUse(NonUseContext::StorageDead) => {}
+            PlaceContext::NonUse(NonUseContext::StorageLive) => {
+                self.direct.insert(local);
+            }
+            _ => {
+                self.used.insert(local);
+            }
+        }
+    }
+}
+
+/// A cold path that can be outlined, and the operands to pass to its shim.
+struct ColdPath<'tcx> {
+    shim: Body<'tcx>,
+    args: Vec<Operand<'tcx>>,
+    unwind: UnwindAction,
+}
+
+impl<'tcx> ColdPath<'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        body: &Body<'tcx>,
+        region: &[BasicBlock],
+        mentions: &LocalUses,
+        borrowed: &BitSet<Local>,
+    ) -> Option<Self> {
+        let mut uses = LocalUses::new(body.local_decls.len());
+        for &bb in region {
+            uses.visit_basic_block_data(bb, &body.basic_blocks[bb]);
+        }
+
+        let never = tcx.types.never;
+        let mut local_decls = IndexVec::from_elem_n(LocalDecl::new(never, body.span), 1);
+        let mut map = IndexVec::from_elem(None, &body.local_decls);
+        let mut args = Vec::new();
+        let mut internal = Vec::new();
+        let mut dropped = BitSet::new_empty(body.local_decls.len());
+        for (local, &count) in uses.mentions.iter_enumerated() {
+            if count == 0 {
+                continue;
+            }
+            let decl = &body.local_decls[local];
+            let is_live_in = local.as_usize() <= body.arg_count || mentions.mentions[local] > count;
+            if !is_live_in {
+                internal.push(local);
+            } else if uses.direct.contains(local) || borrowed.contains(local) {
+                return None;
+            } else if !uses.used.contains(local) {
+                // Only the storage of the local ends in the path, we do not need it.
+                dropped.insert(local);
+            } else {
+                if decl.ty.has_param() {
+                    return None;
+                }
+                map[local] = Some(local_decls.push(LocalDecl::new(decl.ty, decl.source_info.span)));
+                let place = Place::from(local);
+                args.push(if decl.ty.is_copy_modulo_regions(tcx, param_env) {
+                    Operand::Copy(place)
+                } else {
+                    Operand::Move(place)
+                });
+            }
+        }
+        let arg_count = args.len();
+        for local in internal {
+            let decl = &body.local_decls[local];
+            if decl.ty.has_param() {
+                return None;
+            }
+            let mut decl = decl.clone();
+            decl.source_info.scope = OUTERMOST_SOURCE_SCOPE;
+            decl.user_ty = None;
+            map[local] = Some(local_decls.push(decl));
+        }
+
+        let mut unwind = UnwindAction::Unreachable;
+        let mut blocks = IndexVec::with_capacity(region.len());
+        for &bb in region {
+            let mut data = body.basic_blocks[bb].clone();
+            data.statements.retain(|statement| match statement.kind {
+                StatementKind::StorageDead(local) => !dropped.contains(local),
+                _ => true,
+            });
+            if data.statements.iter().any(|statement| {
+                matches!(
+                    statement.kind,
+                    StatementKind::Coverage(_) | StatementKind::AscribeUserType(..)
+                )
+            }) {
+                return None;
+            }
+
+            let terminator = data.terminator_mut();
+            if let TerminatorKind::Call { unwind: call_unwind, .. } = terminator.kind {
+                // The shim has no inlined scopes, so resolve the caller location now.
+                terminator.source_info.span =
+                    body.caller_location_span(terminator.source_info, None, tcx, |span| span);
+                if call_unwind == UnwindAction::Continue {
+                    unwind = UnwindAction::Continue;
+                }
+            }
+            // The path is a chain, so each block jumps to the next one.
+            for target in terminator.successors_mut() {
+                if let Some(index) = region.iter().position(|bb| bb == target) {
+                    *target = BasicBlock::from_usize(index);
+                }
+            }
+            blocks.push(data);
+        }
+
+        let mut updater = ShimUpdater { tcx, param_env, map, failed: false };
+        for (bb, data) in blocks.iter_enumerated_mut() {
+            updater.visit_basic_block_data(bb, data);
+        }
+        if updater.failed || blocks.has_param() {
+            return None;
+        }
+
+        let source_scopes = IndexVec::from_elem_n(
+            SourceScopeData {
+                span: body.span,
+                parent_scope: None,
+                inlined: None,
+                inlined_parent_scope: None,
+                local_data: ClearCrossCrate::Clear,
+            },
+            1,
+        );
+        let shim = Body::new(
+            body.source,
+            blocks,
+            source_scopes,
+            local_decls,
+            IndexVec::new(),
+            arg_count,
+            vec![],
+            body.span,
+            None,
+            None,
+        );
+        Some(ColdPath { shim, args, unwind })
+    }
+
+    /// Stores the shim in `body`, and replaces the path with a call to the shim.
+    fn outline(
+        self,
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        body: &mut Body<'tcx>,
+        region: &[BasicBlock],
+    ) {
+        let ColdPath { mut shim, args, unwind } = self;
+        let index = body.outlined_cold_paths.len();
+        let def = InstanceKind::ColdPathShim(def_id, index);
+        debug!(?def, ?region, ?args);
+
+        shim.source = MirSource::from_instance(def);
+        shim.phase = MirPhase::Runtime(RuntimePhase::Optimized);
+        let mut required_consts = Vec::new();
+        RequiredConstsVisitor::new(&mut required_consts).visit_body(&shim);
+        shim.required_consts = required_consts;
+        MentionedItems.run_pass(tcx, &mut shim);
+
+        // The shim does not depend on the generic parameters, so all the instantiations of the
+        // caller share the same instance, which has no generic arguments.
+        let instance = Instance { def, args: GenericArgs::empty() };
+        let sig = tcx.mk_fn_sig(
+            shim.args_iter().map(|local| shim.local_decls[local].ty),
+            tcx.types.never,
+            false,
+            hir::Safety::Safe,
+            Abi::Rust,
+        );
+        let fn_ptr =
+            Scalar::from_pointer(Pointer::from(tcx.reserve_and_set_fn_alloc(instance)), &tcx);
+        body.outlined_cold_paths.push(shim);
+
+        let last = *region.last().unwrap();
+        let source_info = body.basic_blocks[last].terminator().source_info;
+        let TerminatorKind::Call { fn_span, .. } = body.basic_blocks[last].terminator().kind else {
+            bug!("cold path does not end with a call")
+        };
+        let func = Operand::Constant(Box::new(ConstOperand {
+            span: source_info.span,
+            user_ty: None,
+            const_: Const::Val(
+                ConstValue::Scalar(fn_ptr),
+                Ty::new_fn_ptr(tcx, ty::Binder::dummy(sig)),
+            ),
+        }));
+        let destination = body.local_decls.push(LocalDecl::new(tcx.types.never, source_info.span));
+        let args = args.into_iter().map(|node| Spanned { node, span: source_info.span }).collect();
+
+        // The other blocks of the path become unreachable and are removed by `SimplifyCfg`.
+        let entry = &mut body.basic_blocks_mut()[region[0]];
+        entry.statements.clear();
+        entry.terminator = Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Call {
+                func,
+                args,
+                destination: destination.into(),
+                target: None,
+                unwind,
+                call_source: CallSource::Misc,
+                fn_span,
+            },
+        });
+    }
+}
+
+/// Renames the locals of the path to the ones of the shim, moves everything to the outermost
+/// scope, and evaluates the promoted constants that depend on the generic parameters.
+struct ShimUpdater<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    map: IndexVec<Local, Option<Local>>,
+    failed: bool,
+}
+
+impl<'tcx> MutVisitor<'tcx> for ShimUpdater<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        *local = self.map[*local].unwrap();
+    }
+
+    fn visit_source_scope(&mut self, scope: &mut SourceScope) {
+        *scope = OUTERMOST_SOURCE_SCOPE;
+    }
+
+    fn visit_const_operand(&mut self, constant: &mut ConstOperand<'tcx>, _: Location) {
+        if !constant.const_.has_param() {
+            return;
+        }
+        // Promoteds are instantiated with the generic parameters of their parent, but their
+        // value usually does not depend on them.
+        let Const::Unevaluated(uv, ty) = constant.const_ else {
+            self.failed = true;
+            return;
+        };
+        if uv.promoted.is_none() || ty.has_param() {
+            self.failed = true;
+            return;
+        }
+        match constant.const_.eval(self.tcx, self.param_env, constant.span) {
+            Ok(value) => constant.const_ = Const::Val(value, ty),
+            Err(_) => self.failed = true,
+        }
+    }
+}
@@ -112,6 +112,14 @@ pub fn is_ssa(&self, local: Local) -> bool {
         matches!(self.assignments[local], Set1::One(_))
     }
 
+    /// The single assignment of `local`, if it is SSA.
+    pub fn assignment(&self, local: Local) -> Option<DefLocation> {
+        match self.assignments[local] {
+            Set1::One(def) => Some(def),
+            Set1::Empty | Set1::Many => None,
+        }
+    }
+
     /// Return the number of uses if a local that are not "Deref".
     pub fn num_direct_uses(&self, local: Local) -> u32 {
         self.direct_uses[local]
@@ -9,6 +9,30 @@ monomorphize_large_assignments =
     .label = value moved from here
     .note = The current maximum size is {$limit}, but it can be customized with the move_size_limit attribute: `#![move_size_limit = "..."]`
 
+monomorphize_large_future =
+    this future is {$size} bytes large
+    .label = `{$ty}` created here
+    .note = the current maximum size is {$limit}, but it can be customized with the future_size_limit attribute: `#![future_size_limit = "..."]`
+
+monomorphize_large_future_await_point =
+    {$size} {$size ->
+        [one] byte is
+        *[other] bytes are
+    } held across this await point
+
+monomorphize_large_future_captures =
+    {$size} bytes of arguments and captured variables are stored in the future until it completes
+
+monomorphize_large_future_local =
+    {$kind ->
+        [awaitee] the awaited future `{$ty}`
+        [named] `{$name}`
+        *[temporary] a temporary of type `{$ty}`
+    } takes {$size} {$size ->
+        [one] byte
+        *[other] bytes
+    }
+
 monomorphize_no_optimized_mir =
     missing optimized MIR for an item in the crate `{$crate_name}`
     .note = missing optimized MIR for this item (was the crate `{$crate_name}` compiled with `--emit=metadata`?)
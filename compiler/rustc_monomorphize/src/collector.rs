@@ -205,6 +205,7 @@
 //! this is not implemented however: a mono item will be produced
 //! regardless of whether it is actually needed or not.
 
+mod future_size_check;
 mod move_check;
 
 use rustc_data_structures::sync::{par_for_each_in, LRef, MTLock};
@@ -239,6 +240,7 @@
 use tracing::{debug, instrument, trace};
 
 use crate::errors::{self, EncounteredErrorWhileInstantiating, NoOptimizedMir, RecursionLimit};
+use future_size_check::FutureSizeCheckState;
 use move_check::MoveCheckState;
 
 #[derive(PartialEq)]
@@ -257,6 +259,9 @@ pub struct UsageMap<'tcx> {
 
 type MonoItems<'tcx> = Vec<Spanned<MonoItem<'tcx>>>;
 
+/// Maps every polymorphized instance to the instances that were merged into it.
+pub(crate) type PolymorphizedInstances<'tcx> = UnordMap<Instance<'tcx>, UnordSet<Instance<'tcx>>>;
+
 /// The state that is shared across the concurrent threads that are doing collection.
 struct SharedState<'tcx> {
     /// Items that have been or are currently being recursively collected.
@@ -266,6 +271,8 @@ struct SharedState<'tcx> {
     mentioned: MTLock<UnordSet<MonoItem<'tcx>>>,
     /// Which items are being used where, for better errors.
     usage_map: MTLock<UsageMap<'tcx>>,
+    /// The instances merged by polymorphization, only recorded for `-Zprint-mono-items`.
+    polymorphized: MTLock<PolymorphizedInstances<'tcx>>,
 }
 
 /// See module-level docs on some contect for "mentioned" items.
@@ -497,6 +504,10 @@ fn collect_items_rec<'tcx>(
         }
     };
 
+    for item in used_items.iter_mut().chain(mentioned_items.iter_mut()) {
+        polymorphize_item(tcx, state, &mut item.node);
+    }
+
     // Check for PMEs and emit a diagnostic if one happened. To try to show relevant edges of the
     // mono item graph.
     if tcx.dcx().err_count() > error_count
@@ -609,6 +620,7 @@ struct MirUsedCollector<'a, 'tcx> {
     instance: Instance<'tcx>,
     visiting_call_terminator: bool,
     move_check: move_check::MoveCheckState,
+    future_size_check: future_size_check::FutureSizeCheckState,
 }
 
 impl<'a, 'tcx> MirUsedCollector<'a, 'tcx> {
@@ -719,6 +731,11 @@ fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
                     bug!()
                 }
             }
+            mir::Rvalue::Aggregate(ref kind, _) => {
+                if let mir::AggregateKind::Coroutine(def_id, args) = **kind {
+                    self.check_async_block_future_size(def_id, args, location);
+                }
+            }
             mir::Rvalue::ThreadLocalRef(def_id) => {
                 assert!(self.tcx.is_thread_local_static(def_id));
                 let instance = Instance::mono(self.tcx, def_id);
@@ -755,12 +772,15 @@ fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Loc
         };
 
         match terminator.kind {
-            mir::TerminatorKind::Call { ref func, ref args, ref fn_span, .. } => {
+            mir::TerminatorKind::Call {
+                ref func, ref args, ref destination, ref fn_span, ..
+            } => {
                 let callee_ty = func.ty(self.body, tcx);
                 // *Before* monomorphizing, record that we already handled this mention.
                 self.used_mentioned_items.insert(MentionedItem::Fn(callee_ty));
                 let callee_ty = self.monomorphize(callee_ty);
                 self.check_fn_args_move_size(callee_ty, args, *fn_span, location);
+                self.check_call_future_size(destination, *fn_span, location);
                 visit_fn_use(self.tcx, callee_ty, true, source, &mut self.used_items)
             }
             mir::TerminatorKind::Drop { ref place, .. } => {
@@ -921,6 +941,7 @@ fn visit_instance_use<'tcx>(
         | ty::InstanceKind::Item(..)
         | ty::InstanceKind::FnPtrShim(..)
         | ty::InstanceKind::CloneShim(..)
+        | ty::InstanceKind::ColdPathShim(..)
         | ty::InstanceKind::FnPtrAddrShim(..) => {
             output.push(create_fn_mono_item(tcx, instance, source));
         }
@@ -1090,7 +1111,25 @@ fn create_fn_mono_item<'tcx>(
         crate::util::dump_closure_profile(tcx, instance);
     }
 
-    respan(source, MonoItem::Fn(instance.polymorphize(tcx)))
+    respan(source, MonoItem::Fn(instance))
+}
+
+/// Replaces the instance of `item` by its polymorphized version, which is shared by all the
+/// instances that only differ in the generic parameters it does not use.
+fn polymorphize_item<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    state: LRef<'_, SharedState<'tcx>>,
+    item: &mut MonoItem<'tcx>,
+) {
+    if let MonoItem::Fn(instance) = *item {
+        let polymorphized = instance.polymorphize(tcx);
+        if polymorphized != instance {
+            if tcx.sess.opts.unstable_opts.print_mono_items.is_some() {
+                state.polymorphized.lock_mut().entry(polymorphized).or_default().insert(instance);
+            }
+            *item = MonoItem::Fn(polymorphized);
+        }
+    }
 }
 
 /// Creates a `MonoItem` for each method that is referenced by the vtable for
@@ -1218,6 +1257,7 @@ fn collect_items_of_instance<'tcx>(
         instance,
         visiting_call_terminator: false,
         move_check: MoveCheckState::new(),
+        future_size_check: FutureSizeCheckState::new(),
     };
 
     if mode == CollectionMode::UsedItems {
@@ -1350,7 +1390,14 @@ fn collect_roots(tcx: TyCtxt<'_>, mode: MonoItemCollectionStrategy) -> Vec<MonoI
     roots
         .into_iter()
         .filter_map(|Spanned { node: mono_item, .. }| {
-            mono_item.is_instantiable(tcx).then_some(mono_item)
+            if !mono_item.is_instantiable(tcx) {
+                return None;
+            }
+            // The polymorphized instance may not satisfy the predicates of the item anymore.
+            match mono_item {
+                MonoItem::Fn(instance) => Some(MonoItem::Fn(instance.polymorphize(tcx))),
+                _ => Some(mono_item),
+            }
         })
         .collect()
 }
@@ -1570,7 +1617,7 @@ fn create_mono_items_for_default_impls<'tcx>(
 pub(crate) fn collect_crate_mono_items<'tcx>(
     tcx: TyCtxt<'tcx>,
     strategy: MonoItemCollectionStrategy,
-) -> (Vec<MonoItem<'tcx>>, UsageMap<'tcx>) {
+) -> (Vec<MonoItem<'tcx>>, UsageMap<'tcx>, PolymorphizedInstances<'tcx>) {
     let _prof_timer = tcx.prof.generic_activity("monomorphization_collector");
 
     let roots = tcx
@@ -1583,6 +1630,7 @@ pub(crate) fn collect_crate_mono_items<'tcx>(
         visited: MTLock::new(UnordSet::default()),
         mentioned: MTLock::new(UnordSet::default()),
         usage_map: MTLock::new(UsageMap::new()),
+        polymorphized: MTLock::new(UnordMap::default()),
     };
     let recursion_limit = tcx.recursion_limit();
 
@@ -1610,5 +1658,5 @@ pub(crate) fn collect_crate_mono_items<'tcx>(
         state.visited.into_inner().into_sorted(hcx, true)
     });
 
-    (mono_items, state.usage_map.into_inner())
+    (mono_items, state.usage_map.into_inner(), state.polymorphized.into_inner())
 }
@@ -0,0 +1,185 @@
+use std::cmp;
+
+use rustc_middle::ty::{CoroutineArgsExt, GenericArgsRef};
+use rustc_session::lint::builtin::LARGE_FUTURES;
+use rustc_span::symbol::kw;
+use tracing::debug;
+
+use super::*;
+use crate::errors::{
+    LargeFutureAwaitPoint, LargeFutureCaptures, LargeFutureLint, LargeFutureLocal,
+};
+
+/// How many await points the `large_futures` lint points out.
+const REPORTED_AWAIT_POINTS: usize = 3;
+/// How many of the locals held across an await point the `large_futures` lint names.
+const REPORTED_LOCALS: usize = 3;
+
+pub(super) struct FutureSizeCheckState {
+    /// Spans for future size lints already emitted. Helps avoid duplicate lints.
+    future_size_spans: Vec<Span>,
+}
+
+impl FutureSizeCheckState {
+    pub(super) fn new() -> Self {
+        FutureSizeCheckState { future_size_spans: vec![] }
+    }
+}
+
+impl<'a, 'tcx> MirUsedCollector<'a, 'tcx> {
+    /// Checks the size of the future returned by a call to an `async fn` or
+    /// an async closure.
+    pub(super) fn check_call_future_size(
+        &mut self,
+        destination: &mir::Place<'tcx>,
+        fn_span: Span,
+        location: Location,
+    ) {
+        let limit = self.tcx.future_size_limit();
+        if limit.0 == 0 {
+            return;
+        }
+
+        let ty = self.monomorphize(destination.ty(self.body, self.tcx).ty);
+        let ty::Coroutine(def_id, args) = *ty.kind() else {
+            return;
+        };
+        // `async` blocks are checked where they are constructed, see
+        // `check_async_block_future_size`, even if a function returns them.
+        if !self.tcx.coroutine_is_async(def_id) || is_async_block(self.tcx, def_id) {
+            return;
+        }
+        self.check_future_size(limit, def_id, args, fn_span, location);
+    }
+
+    /// Checks the size of the future created by an `async` block.
+    pub(super) fn check_async_block_future_size(
+        &mut self,
+        def_id: DefId,
+        args: GenericArgsRef<'tcx>,
+        location: Location,
+    ) {
+        let limit = self.tcx.future_size_limit();
+        if limit.0 == 0 {
+            return;
+        }
+
+        if !is_async_block(self.tcx, def_id) {
+            return;
+        }
+        let args = self.monomorphize(args);
+        let span = self.body.source_info(location).span;
+        self.check_future_size(limit, def_id, args, span, location);
+    }
+
+    fn check_future_size(
+        &mut self,
+        limit: Limit,
+        def_id: DefId,
+        args: GenericArgsRef<'tcx>,
+        span: Span,
+        location: Location,
+    ) {
+        let tcx = self.tcx;
+        let param_env = ty::ParamEnv::reveal_all();
+        let ty = Ty::new_coroutine(tcx, def_id, args);
+        let Ok(layout) = tcx.layout_of(param_env.and(ty)) else {
+            return;
+        };
+        if layout.size.bytes_usize() <= limit.0 {
+            return;
+        }
+        debug!(?layout);
+
+        for reported_span in &self.future_size_check.future_size_spans {
+            if reported_span.overlaps(span) {
+                return;
+            }
+        }
+        let source_info = self.body.source_info(location);
+        let Some(lint_root) = source_info.scope.lint_root(&self.body.source_scopes) else {
+            // Like for `large_assignments`, we cannot get a `HirId` for code from other crates.
+            return;
+        };
+
+        let size_of =
+            |ty: Ty<'tcx>| tcx.layout_of(param_env.and(ty)).map_or(0, |layout| layout.size.bytes());
+
+        // The upvars of a future are stored in all its variants.
+        let coroutine_args = args.as_coroutine();
+        let captures_size: u64 = coroutine_args.upvar_tys().iter().map(size_of).sum();
+        let captures = (captures_size > 0).then_some(LargeFutureCaptures { size: captures_size });
+
+        // Each suspended variant of the future holds the locals that are live
+        // across the corresponding await point.
+        let mut await_points = Vec::new();
+        if let Some(coroutine_layout) = tcx.coroutine_layout(def_id, coroutine_args.kind_ty()) {
+            let suspended_variants = coroutine_layout
+                .variant_fields
+                .iter_enumerated()
+                .skip(ty::CoroutineArgs::RESERVED_VARIANTS);
+            for (variant, fields) in suspended_variants {
+                let mut locals: Vec<_> = fields
+                    .iter()
+                    .map(|&local| {
+                        let local_ty = tcx.instantiate_and_normalize_erasing_regions(
+                            args,
+                            param_env,
+                            ty::EarlyBinder::bind(coroutine_layout.field_tys[local].ty),
+                        );
+                        (size_of(local_ty), coroutine_layout.field_names[local], local_ty)
+                    })
+                    .collect();
+                let size = locals.iter().map(|&(size, ..)| size).sum();
+                if size == 0 {
+                    continue;
+                }
+                locals.sort_by_key(|&(size, ..)| cmp::Reverse(size));
+                let locals = locals
+                    .into_iter()
+                    .take(REPORTED_LOCALS)
+                    .filter(|&(size, ..)| size > 0)
+                    .map(|(size, name, local_ty)| {
+                        let kind = match name {
+                            Some(sym::__awaitee) => "awaitee",
+                            Some(_) => "named",
+                            None => "temporary",
+                        };
+                        let name = name.unwrap_or(kw::Empty);
+                        LargeFutureLocal { kind, name, ty: local_ty, size }
+                    })
+                    .collect();
+                let span = coroutine_layout.variant_source_info[variant].span;
+                await_points.push(LargeFutureAwaitPoint { span, size, locals });
+            }
+        }
+        // A stable sort, so that await points of the same size are reported in source order.
+        await_points.sort_by_key(|await_point| cmp::Reverse(await_point.size));
+        await_points.truncate(REPORTED_AWAIT_POINTS);
+
+        tcx.emit_node_span_lint(
+            LARGE_FUTURES,
+            lint_root,
+            span,
+            LargeFutureLint {
+                span,
+                ty,
+                size: layout.size.bytes(),
+                limit: limit.0 as u64,
+                captures,
+                await_points,
+            },
+        );
+        self.future_size_check.future_size_spans.push(span);
+    }
+}
+
+fn is_async_block(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    matches!(
+        tcx.coroutine_kind(def_id),
+        Some(hir::CoroutineKind::Desugared(
+            hir::CoroutineDesugaring::Async,
+            hir::CoroutineSource::Block
+        ))
+    )
+}
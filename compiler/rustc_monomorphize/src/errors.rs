@@ -2,7 +2,8 @@
 
 use crate::fluent_generated as fluent;
 use rustc_errors::{Diag, DiagCtxtHandle, Diagnostic, EmissionGuarantee, Level};
-use rustc_macros::{Diagnostic, LintDiagnostic};
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
+use rustc_middle::ty::Ty;
 use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
@@ -59,6 +60,48 @@ pub struct LargeAssignmentsLint {
     pub limit: u64,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(monomorphize_large_future)]
+#[note]
+pub struct LargeFutureLint<'tcx> {
+    #[label]
+    pub span: Span,
+    pub ty: Ty<'tcx>,
+    pub size: u64,
+    pub limit: u64,
+    #[subdiagnostic]
+    pub captures: Option<LargeFutureCaptures>,
+    #[subdiagnostic]
+    pub await_points: Vec<LargeFutureAwaitPoint<'tcx>>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(monomorphize_large_future_captures)]
+pub struct LargeFutureCaptures {
+    pub size: u64,
+}
+
+#[derive(Subdiagnostic)]
+#[note(monomorphize_large_future_await_point)]
+pub struct LargeFutureAwaitPoint<'tcx> {
+    #[primary_span]
+    pub span: Span,
+    pub size: u64,
+    /// The largest locals held across the await point.
+    #[subdiagnostic]
+    pub locals: Vec<LargeFutureLocal<'tcx>>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(monomorphize_large_future_local)]
+pub struct LargeFutureLocal<'tcx> {
+    /// Either `awaitee`, `named` or `temporary`.
+    pub kind: &'static str,
+    pub name: Symbol,
+    pub ty: Ty<'tcx>,
+    pub size: u64,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_symbol_already_defined)]
 pub struct SymbolAlreadyDefined {
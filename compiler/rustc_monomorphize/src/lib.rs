@@ -22,6 +22,7 @@
 mod util;
 
 use collector::should_codegen_locally;
+pub use partitioning::{dump_codegen_stats, CodegenUnitStats};
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
@@ -97,6 +97,7 @@
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
 use rustc_data_structures::sync;
@@ -632,6 +633,7 @@ fn characteristic_def_id_of_mono_item<'tcx>(
                 | ty::InstanceKind::Virtual(..)
                 | ty::InstanceKind::CloneShim(..)
                 | ty::InstanceKind::ThreadLocalShim(..)
+                | ty::InstanceKind::ColdPathShim(..)
                 | ty::InstanceKind::FnPtrAddrShim(..)
                 | ty::InstanceKind::AsyncDropGlueCtorShim(..) => return None,
             };
@@ -658,7 +660,7 @@ fn characteristic_def_id_of_mono_item<'tcx>(
 
                 // When polymorphization is enabled, methods which do not depend on their generic
                 // parameters, but the self-type of their impl block do will fail to normalize.
-                if !tcx.sess.opts.unstable_opts.polymorphize || !instance.has_param() {
+                if !tcx.sess.opts.unstable_opts.polymorphize.is_enabled() || !instance.has_param() {
                     // This is a method within an impl, find out what the self-type is:
                     let impl_self_ty = tcx.instantiate_and_normalize_erasing_regions(
                         instance.args,
@@ -798,6 +800,7 @@ fn mono_item_visibility<'tcx>(
         | InstanceKind::DropGlue(..)
         | InstanceKind::AsyncDropGlueCtorShim(..)
         | InstanceKind::CloneShim(..)
+        | InstanceKind::ColdPathShim(..)
         | InstanceKind::FnPtrAddrShim(..) => return Visibility::Hidden,
     };
 
@@ -1121,7 +1124,8 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
         }
     };
 
-    let (items, usage_map) = collector::collect_crate_mono_items(tcx, collection_strategy);
+    let (items, usage_map, polymorphized) =
+        collector::collect_crate_mono_items(tcx, collection_strategy);
 
     // If there was an error during collection (e.g. from one of the constants we evaluated),
     // then we stop here. This way codegen does not have to worry about failing constants.
@@ -1217,6 +1221,19 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
         for item in item_keys {
             println!("MONO_ITEM {item}");
         }
+
+        // The number of instances that share the code of each polymorphized item.
+        let polymorphized_keys = polymorphized
+            .items()
+            .map(|(&instance, merged)| {
+                let item = with_no_trimmed_paths!(MonoItem::Fn(instance).to_string());
+                format!("{item} @@ {}", merged.len())
+            })
+            .into_sorted_stable_ord();
+
+        for item in polymorphized_keys {
+            println!("POLYMORPHIZED_ITEM {item}");
+        }
     }
 
     (tcx.arena.alloc(mono_items), codegen_units)
@@ -1300,6 +1317,141 @@ struct MonoItem {
     Ok(())
 }
 
+/// The `-Zdump-mono-stats` numbers of a codegen unit, gathered by the codegen
+/// backend right after emitting the unit and before optimizing it.
+pub struct CodegenUnitStats<'tcx> {
+    pub name: Symbol,
+    /// The time spent translating the unit to backend IR.
+    pub codegen_time: Duration,
+    /// The number of backend IR instructions emitted for each function of the
+    /// unit. Empty if the backend can't count them.
+    pub instruction_counts: Vec<(MonoItem<'tcx>, usize)>,
+}
+
+/// Outputs the instructions emitted by the codegen backend per `MonoItem`'s def,
+/// and the time spent on each codegen unit, if `-Zdump-mono-stats` is enabled.
+///
+/// Every emitted copy is instantiated by the crate being compiled, so the file
+/// only covers that crate; the reports of the other crates are in their own files.
+///
+/// Units reused from the incremental cache are not codegened, so they don't
+/// appear in the output.
+pub fn dump_codegen_stats<'tcx>(tcx: TyCtxt<'tcx>, codegen_units: &[CodegenUnitStats<'tcx>]) {
+    if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats {
+        if let Err(err) = write_codegen_stats(tcx, codegen_units, path, tcx.crate_name(LOCAL_CRATE))
+        {
+            tcx.dcx().emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
+        }
+    }
+}
+
+fn write_codegen_stats<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    codegen_units: &[CodegenUnitStats<'tcx>],
+    output_directory: &Option<PathBuf>,
+    crate_name: Symbol,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output_directory = if let Some(ref directory) = output_directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    let format = tcx.sess.opts.unstable_opts.dump_mono_stats_format;
+    let ext = format.extension();
+    let filename = format!("{crate_name}.codegen_items.{ext}");
+    let output_path = output_directory.join(&filename);
+    let file = File::create(&output_path)?;
+    let mut file = BufWriter::new(file);
+
+    // Gather the instruction counts of every emitted copy, grouped by def_id. Unlike
+    // the MIR-based estimates, these are exact for shims as well, so keep them.
+    let mut instructions_per_def_id: FxIndexMap<_, Vec<_>> = Default::default();
+    for cgu in codegen_units {
+        for &(mono_item, instructions) in &cgu.instruction_counts {
+            instructions_per_def_id.entry(mono_item.def_id()).or_default().push(instructions);
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct CodegenItem {
+        name: String,
+        defining_crate: String,
+        instantiation_count: usize,
+        instructions: usize,
+    }
+
+    #[derive(serde::Serialize)]
+    struct CodegenUnitTime {
+        name: String,
+        item_count: usize,
+        instructions: usize,
+        codegen_time_ms: f64,
+    }
+
+    #[derive(serde::Serialize)]
+    struct CodegenStats {
+        items: Vec<CodegenItem>,
+        codegen_units: Vec<CodegenUnitTime>,
+    }
+
+    // Output items sorted by emitted instructions, and codegen units sorted by
+    // codegen time, from heaviest to lightest
+    let mut items: Vec<_> = instructions_per_def_id
+        .into_iter()
+        .map(|(def_id, counts)| CodegenItem {
+            name: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+            defining_crate: tcx.crate_name(def_id.krate).to_string(),
+            instantiation_count: counts.len(),
+            instructions: counts.iter().sum(),
+        })
+        .collect();
+    items.sort_by_key(|item| cmp::Reverse(item.instructions));
+
+    let mut codegen_units: Vec<_> = codegen_units
+        .iter()
+        .map(|cgu| CodegenUnitTime {
+            name: cgu.name.to_string(),
+            item_count: cgu.instruction_counts.len(),
+            instructions: cgu.instruction_counts.iter().map(|&(_, count)| count).sum(),
+            codegen_time_ms: cgu.codegen_time.as_secs_f64() * 1000.0,
+        })
+        .collect();
+    codegen_units.sort_by(|a, b| b.codegen_time_ms.total_cmp(&a.codegen_time_ms));
+
+    match format {
+        DumpMonoStatsFormat::Json => {
+            serde_json::to_writer(file, &CodegenStats { items, codegen_units })?
+        }
+        DumpMonoStatsFormat::Markdown => {
+            writeln!(file, "| Item | Defining Crate | Instantiation count | Instructions |")?;
+            writeln!(file, "| --- | --- | ---: | ---: |")?;
+
+            for CodegenItem { name, defining_crate, instantiation_count, instructions } in items {
+                writeln!(
+                    file,
+                    "| `{name}` | `{defining_crate}` | {instantiation_count} | {instructions} |"
+                )?;
+            }
+
+            writeln!(file)?;
+            writeln!(file, "| Codegen Unit | Item count | Instructions | Codegen Time (ms) |")?;
+            writeln!(file, "| --- | ---: | ---: | ---: |")?;
+
+            for CodegenUnitTime { name, item_count, instructions, codegen_time_ms } in codegen_units
+            {
+                writeln!(
+                    file,
+                    "| `{name}` | {item_count} | {instructions} | {codegen_time_ms:.3} |"
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn provide(providers: &mut Providers) {
     providers.collect_and_partition_mono_items = collect_and_partition_mono_items;
 
@@ -4,18 +4,28 @@
 //! This module implements an analysis of functions, methods and closures to determine which
 //! generic parameters are unused (and eventually, in what ways generic parameters are used - only
 //! for their size, offset of a field, etc.).
-
+//!
+//! With `-Zpolymorphize=layout`, the parameters that are only used in the types of values, and
+//! do not affect their layout, are also considered unused. Those are the parameters of ADTs that
+//! their fields only use through `PhantomData` (or through other such parameters), and the
+//! parameters only used in the pointee types of thin pointers, as `&T` has the same size and ABI
+//! for every sized `T`. The pointee types of the pointers that are dereferenced or offset still
+//! use the parameters of their layout, and the types whose trait implementations are used (in
+//! calls, drops, or vtables) still use all of their parameters.
+
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::{def::DefKind, def_id::DefId, ConstContext};
 use rustc_middle::mir::{
     self,
-    visit::{TyContext, Visitor},
+    visit::{PlaceContext, TyContext, Visitor},
     Local, LocalDecl, Location,
 };
 use rustc_middle::query::Providers;
+use rustc_middle::ty::adjustment::PointerCoercion;
 use rustc_middle::ty::{
     self,
     visit::{TypeSuperVisitable, TypeVisitable, TypeVisitableExt, TypeVisitor},
-    GenericArgsRef, Ty, TyCtxt, UnusedGenericParams,
+    GenericArgsRef, ParamEnv, Ty, TyCtxt, UnusedGenericParams,
 };
 use rustc_span::symbol::sym;
 use tracing::{debug, instrument};
@@ -37,7 +47,7 @@ fn unused_generic_params<'tcx>(
 ) -> UnusedGenericParams {
     assert!(instance.def_id().is_local());
 
-    if !tcx.sess.opts.unstable_opts.polymorphize {
+    if !tcx.sess.opts.unstable_opts.polymorphize.is_enabled() {
         // If polymorphization disabled, then all parameters are used.
         return UnusedGenericParams::new_all_used();
     }
@@ -72,7 +82,15 @@ fn unused_generic_params<'tcx>(
         Some(ConstContext::ConstFn) | None => tcx.optimized_mir(def_id),
         Some(_) => tcx.mir_for_ctfe(def_id),
     };
-    let mut vis = MarkUsedGenericParams { tcx, def_id, unused_parameters: &mut unused_parameters };
+    let mut vis = MarkUsedGenericParams {
+        tcx,
+        def_id,
+        param_env: tcx.param_env(def_id),
+        body,
+        unused_parameters: &mut unused_parameters,
+        layout_only: tcx.sess.opts.unstable_opts.polymorphize.is_layout_enabled(),
+        adt_layout_params: AdtLayoutParams::default(),
+    };
     vis.visit_body(body);
     debug!(?unused_parameters, "(end)");
 
@@ -218,11 +236,131 @@ fn emit_unused_generic_params_error<'tcx>(
     tcx.dcx().emit_err(UnusedGenericParamsHint { span: fn_span, param_spans, param_names });
 }
 
+/// Determines which generic parameters of ADTs do not affect their layout: the ones that their
+/// fields only use in the parameters of `PhantomData`, in the pointee types of thin pointers, or
+/// in the parameters of other ADTs that do not depend on them. The results are memoized per ADT.
+#[derive(Default)]
+struct AdtLayoutParams {
+    cache: FxHashMap<DefId, UnusedGenericParams>,
+    /// The ADTs being visited, whose parameters are all considered used.
+    stack: Vec<DefId>,
+}
+
+impl AdtLayoutParams {
+    fn unused_params<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        adt_def: ty::AdtDef<'tcx>,
+    ) -> UnusedGenericParams {
+        let def_id = adt_def.did();
+        if let Some(&unused_parameters) = self.cache.get(&def_id) {
+            return unused_parameters;
+        }
+        if self.stack.contains(&def_id) {
+            return UnusedGenericParams::new_all_used();
+        }
+
+        // The destructor can observe all the parameters.
+        let unused_parameters = if adt_def.has_dtor(tcx) {
+            UnusedGenericParams::new_all_used()
+        } else {
+            let generics_count: u32 = tcx
+                .generics_of(def_id)
+                .count()
+                .try_into()
+                .expect("more generic parameters than can fit into a `u32`");
+            let mut unused_parameters = UnusedGenericParams::new_all_unused(generics_count);
+            self.stack.push(def_id);
+            let mut vis = MarkLayoutUsedParams {
+                tcx,
+                param_env: tcx.param_env(def_id),
+                unused_parameters: &mut unused_parameters,
+                adt_layout_params: self,
+            };
+            for field in adt_def.all_fields() {
+                tcx.type_of(field.did).instantiate_identity().visit_with(&mut vis);
+            }
+            self.stack.pop();
+            unused_parameters
+        };
+        debug!(?adt_def, ?unused_parameters);
+        self.cache.insert(def_id, unused_parameters);
+        unused_parameters
+    }
+}
+
+/// Returns whether `ty` is a pointer to a sized type, which has the same size and ABI whatever its
+/// pointee type is.
+fn is_thin_pointer<'tcx>(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, ty: Ty<'tcx>) -> bool {
+    match *ty.kind() {
+        ty::Ref(_, pointee, _) | ty::RawPtr(pointee, _) => {
+            !pointee.has_escaping_bound_vars() && pointee.is_sized(tcx, param_env)
+        }
+        _ => false,
+    }
+}
+
+/// Visitor used to aggregate the generic parameters that affect the layout of a type.
+struct MarkLayoutUsedParams<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    unused_parameters: &'a mut UnusedGenericParams,
+    adt_layout_params: &'a mut AdtLayoutParams,
+}
+
+impl<'a, 'tcx> TypeVisitor<TyCtxt<'tcx>> for MarkLayoutUsedParams<'a, 'tcx> {
+    fn visit_const(&mut self, c: ty::Const<'tcx>) {
+        if !c.has_non_region_param() {
+            return;
+        }
+
+        match c.kind() {
+            ty::ConstKind::Param(param) => self.unused_parameters.mark_used(param.index),
+            _ => c.super_visit_with(self),
+        }
+    }
+
+    fn visit_ty(&mut self, ty: Ty<'tcx>) {
+        if !ty.has_non_region_param() {
+            return;
+        }
+
+        match *ty.kind() {
+            ty::Param(param) => self.unused_parameters.mark_used(param.index),
+            ty::Adt(adt_def, args) => {
+                let unused = self.adt_layout_params.unused_params(self.tcx, adt_def);
+                visit_layout_used_args(args, &unused, self);
+            }
+            _ if is_thin_pointer(self.tcx, self.param_env, ty) => {}
+            _ => ty.super_visit_with(self),
+        }
+    }
+}
+
+/// Visit the arguments of an ADT for the parameters that affect its layout.
+fn visit_layout_used_args<'tcx>(
+    args: GenericArgsRef<'tcx>,
+    unused: &UnusedGenericParams,
+    visitor: &mut impl TypeVisitor<TyCtxt<'tcx>, Result = ()>,
+) {
+    for (i, arg) in args.iter().enumerate() {
+        if unused.is_used(i.try_into().unwrap()) {
+            arg.visit_with(visitor);
+        }
+    }
+}
+
 /// Visitor used to aggregate generic parameter uses.
 struct MarkUsedGenericParams<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
     def_id: DefId,
+    param_env: ParamEnv<'tcx>,
+    /// The body being visited, either the item or one of its promoteds.
+    body: &'tcx mir::Body<'tcx>,
     unused_parameters: &'a mut UnusedGenericParams,
+    /// Whether the visited types only matter for their layout, see the module documentation.
+    layout_only: bool,
+    adt_layout_params: AdtLayoutParams,
 }
 
 impl<'a, 'tcx> MarkUsedGenericParams<'a, 'tcx> {
@@ -233,14 +371,31 @@ fn visit_child_body(&mut self, def_id: DefId, args: GenericArgsRef<'tcx>) {
         let instance = ty::InstanceKind::Item(def_id);
         let unused = self.tcx.unused_generic_params(instance);
         debug!(?self.unused_parameters, ?unused);
-        for (i, arg) in args.iter().enumerate() {
-            let i = i.try_into().unwrap();
-            if unused.is_used(i) {
-                arg.visit_with(self);
+        self.with_all_params_used(|this| {
+            for (i, arg) in args.iter().enumerate() {
+                let i = i.try_into().unwrap();
+                if unused.is_used(i) {
+                    arg.visit_with(this);
+                }
             }
-        }
+        });
         debug!(?self.unused_parameters);
     }
+
+    /// Visit the pointee type of `pointer`, whose layout is needed to access it.
+    fn visit_pointee(&mut self, pointer: Ty<'tcx>) {
+        if let Some(pointee) = pointer.builtin_deref(true) {
+            pointee.visit_with(self);
+        }
+    }
+
+    /// Run `f` with all the parameters of the visited types considered used, as something else
+    /// than their layout depends on them.
+    fn with_all_params_used(&mut self, f: impl FnOnce(&mut Self)) {
+        let layout_only = std::mem::replace(&mut self.layout_only, false);
+        f(self);
+        self.layout_only = layout_only;
+    }
 }
 
 impl<'a, 'tcx> Visitor<'tcx> for MarkUsedGenericParams<'a, 'tcx> {
@@ -274,7 +429,9 @@ fn visit_const_operand(&mut self, ct: &mir::ConstOperand<'tcx>, location: Locati
                         // If there is a promoted, don't look at the args - since it will always contain
                         // the generic parameters, instead, traverse the promoted MIR.
                         let promoted = self.tcx.promoted_mir(def);
+                        let parent = std::mem::replace(&mut self.body, &promoted[p]);
                         self.visit_body(&promoted[p]);
+                        self.body = parent;
                     }
                 }
 
@@ -287,6 +444,55 @@ fn visit_const_operand(&mut self, ct: &mir::ConstOperand<'tcx>, location: Locati
     fn visit_ty(&mut self, ty: Ty<'tcx>, _: TyContext) {
         ty.visit_with(self);
     }
+
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        if let mir::Rvalue::Cast(
+            mir::CastKind::PointerCoercion(PointerCoercion::Unsize) | mir::CastKind::DynStar,
+            operand,
+            _,
+        ) = rvalue
+        {
+            // The cast may create the vtable of the source type.
+            let ty = operand.ty(self.body, self.tcx);
+            self.with_all_params_used(|this| ty.visit_with(this));
+        }
+        if let mir::Rvalue::BinaryOp(mir::BinOp::Offset, operands) = rvalue {
+            // The offset is scaled by the size of the pointee.
+            self.visit_pointee(operands.0.ty(self.body, self.tcx));
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_place(&mut self, place: &mir::Place<'tcx>, context: PlaceContext, location: Location) {
+        // Borrowing the pointee is only a pointer copy, other accesses need its layout.
+        let is_reborrow = context.is_borrow() || context.is_address_of();
+        for (i, (base, elem)) in place.iter_projections().enumerate() {
+            let is_last = i + 1 == place.projection.len();
+            if matches!(elem, mir::ProjectionElem::Deref) && !(is_last && is_reborrow) {
+                self.visit_pointee(base.ty(self.body, self.tcx).ty);
+            }
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_statement(&mut self, statement: &mir::Statement<'tcx>, location: Location) {
+        if let mir::StatementKind::Intrinsic(intrinsic) = &statement.kind {
+            if let mir::NonDivergingIntrinsic::CopyNonOverlapping(copy) = &**intrinsic {
+                // The count is scaled by the size of the pointee.
+                self.visit_pointee(copy.src.ty(self.body, self.tcx));
+            }
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Location) {
+        if let mir::TerminatorKind::Drop { place, .. } = terminator.kind {
+            // The drop glue of the type is instantiated with all of its parameters.
+            let ty = place.ty(self.body, self.tcx).ty;
+            self.with_all_params_used(|this| ty.visit_with(this));
+        }
+        self.super_terminator(terminator, location);
+    }
 }
 
 impl<'a, 'tcx> TypeVisitor<TyCtxt<'tcx>> for MarkUsedGenericParams<'a, 'tcx> {
@@ -306,7 +512,7 @@ fn visit_const(&mut self, c: ty::Const<'tcx>) {
             {
                 self.visit_child_body(def, args);
             }
-            _ => c.super_visit_with(self),
+            _ => self.with_all_params_used(|this| c.super_visit_with(this)),
         }
     }
 
@@ -332,6 +538,15 @@ fn visit_ty(&mut self, ty: Ty<'tcx>) {
                 debug!(?param);
                 self.unused_parameters.mark_used(param.index);
             }
+            ty::Adt(adt_def, args) if self.layout_only => {
+                let unused = self.adt_layout_params.unused_params(self.tcx, adt_def);
+                visit_layout_used_args(args, &unused, self);
+            }
+            _ if self.layout_only && is_thin_pointer(self.tcx, self.param_env, ty) => {}
+            // Calls and projections select an implementation for the types of their arguments.
+            ty::FnDef(..) | ty::Alias(..) => {
+                self.with_all_params_used(|this| ty.super_visit_with(this))
+            }
             _ => ty.super_visit_with(self),
         }
     }
@@ -543,6 +543,7 @@ passes_only_has_effect_on =
         [function] functions
         [module] modules
         [implementation_block] implementation blocks
+        [test_function] `#[test]` functions
         *[unspecified] (unspecified--this is a compiler bug)
     }
 
@@ -648,6 +649,21 @@ passes_rustc_std_internal_symbol =
     attribute should be applied to functions or statics
     .label = not a function or static
 
+passes_semver_change = {$severity ->
+        [major] major
+        *[minor] minor
+    } change: {$change}
+
+passes_semver_summary = found {$major ->
+        [0] no major changes
+        [one] 1 major change
+        *[other] {$major} major changes
+    } and {$minor ->
+        [0] no minor changes
+        [one] 1 minor change
+        *[other] {$minor} minor changes
+    } compared to `{$baseline}`
+
 passes_should_be_applied_to_fn =
     attribute should be applied to a function definition
     .label = {$on_crate ->
@@ -223,6 +223,7 @@ fn check_attributes(
                 sym::ignore | sym::should_panic => {
                     self.check_generic_attr(hir_id, attr, target, Target::Fn)
                 }
+                sym::test_timeout => self.check_test_timeout(hir_id, attr, target),
                 sym::automatically_derived => {
                     self.check_generic_attr(hir_id, attr, target, Target::Impl)
                 }
@@ -409,6 +410,24 @@ fn check_generic_attr(
         }
     }
 
+    /// Checks that `#[test_timeout]` is applied to a `#[test]` function. The `#[test]` expansion
+    /// moves it to the generated `#[rustc_test_marker]` const, so it's unused anywhere else.
+    fn check_test_timeout(&self, hir_id: HirId, attr: &Attribute, target: Target) {
+        let is_test_fn = target == Target::Const
+            && self.tcx.hir().attrs(hir_id).iter().any(|a| a.has_name(sym::rustc_test_marker));
+        if !is_test_fn {
+            self.tcx.emit_node_span_lint(
+                UNUSED_ATTRIBUTES,
+                hir_id,
+                attr.span,
+                errors::OnlyHasEffectOn {
+                    attr_name: attr.name_or_empty(),
+                    target_name: "test_function".to_string(),
+                },
+            );
+        }
+    }
+
     /// Checks if `#[naked]` is applied to a function definition.
     fn check_naked(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Target) -> bool {
         match target {
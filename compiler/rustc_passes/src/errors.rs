@@ -1605,6 +1605,23 @@ pub(crate) struct ProcMacroBadSig {
     pub kind: ProcMacroKind,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_semver_change)]
+pub struct SemverChange {
+    #[primary_span]
+    pub span: Option<Span>,
+    pub severity: &'static str,
+    pub change: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_semver_summary)]
+pub struct SemverSummary {
+    pub baseline: Symbol,
+    pub major: usize,
+    pub minor: usize,
+}
+
 #[derive(Diagnostic)]
 #[diag(passes_skipping_const_checks)]
 pub struct SkippingConstChecks {
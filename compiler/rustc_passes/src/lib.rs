@@ -34,6 +34,7 @@
 pub mod loops;
 mod naked_functions;
 mod reachable;
+pub mod semver_check;
 pub mod stability;
 mod upvars;
 mod weak_lang_items;
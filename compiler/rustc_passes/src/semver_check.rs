@@ -0,0 +1,549 @@
+//! Implementation of `-Zsemver-baseline`, which compares the public API of the local crate
+//! against a previous release of it and classifies the differences as major or minor changes.
+//!
+//! The previous release is loaded from its metadata like any other `--extern` crate, so both
+//! APIs are inspected through the same queries. Items are matched by the path under which they
+//! are publicly reachable, and their signatures are compared by printing them, with the paths
+//! into the previous release rewritten to look like paths into the local crate.
+
+use std::{fmt, iter};
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_hir::def::{DefKind, Namespace, Res};
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_middle::ty::print::{with_no_trimmed_paths, with_no_visible_paths};
+use rustc_middle::ty::{AssocItem, AssocKind, GenericParamDefKind, TyCtxt, VariantDef};
+use rustc_span::symbol::{kw, sym, Symbol};
+use rustc_span::Span;
+use rustc_trait_selection::infer::{InferCtxtExt, TyCtxtInferExt};
+
+use crate::errors::{SemverChange, SemverSummary};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    /// Downstream crates may stop compiling.
+    Major,
+    /// Additions to the API, which only break downstream crates through glob imports and
+    /// similar ambiguities.
+    Minor,
+}
+
+#[derive(PartialEq, Eq)]
+enum GenericParamKind {
+    Lifetime,
+    Type,
+    /// A const parameter of the printed type.
+    Const(String),
+}
+
+struct GenericParam {
+    name: Symbol,
+    kind: GenericParamKind,
+    has_default: bool,
+}
+
+impl fmt::Display for GenericParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            GenericParamKind::Lifetime | GenericParamKind::Type => write!(f, "{}", self.name)?,
+            GenericParamKind::Const(ty) => write!(f, "const {}: {ty}", self.name)?,
+        }
+        if self.has_default { write!(f, " = ...") } else { Ok(()) }
+    }
+}
+
+struct Change {
+    severity: Severity,
+    span: Option<Span>,
+    description: String,
+}
+
+pub fn check_crate(tcx: TyCtxt<'_>, baseline: CrateNum) {
+    let mut checker =
+        SemverChecker { tcx, baseline_name: tcx.crate_name(baseline), changes: Vec::new() };
+
+    let old_api = public_api(tcx, baseline);
+    let new_api = public_api(tcx, LOCAL_CRATE);
+    for (key, &old) in &old_api {
+        let path = &key.0;
+        match new_api.get(key) {
+            Some(&new) => checker.compare_items(path, old, new),
+            None => {
+                checker.major(None, format!("{} `{path}` was removed", tcx.def_descr(old)));
+            }
+        }
+    }
+    for (key, &new) in &new_api {
+        if !old_api.contains_key(key) {
+            checker.minor(new, format!("{} `{}` was added", tcx.def_descr(new), key.0));
+        }
+    }
+
+    let dcx = tcx.dcx();
+    let (mut major, mut minor) = (0, 0);
+    for Change { severity, span, description: change } in checker.changes {
+        match severity {
+            Severity::Major => {
+                major += 1;
+                dcx.emit_warn(SemverChange { span, severity: "major", change });
+            }
+            Severity::Minor => {
+                minor += 1;
+                dcx.emit_note(SemverChange { span, severity: "minor", change });
+            }
+        }
+    }
+    dcx.emit_note(SemverSummary { baseline: checker.baseline_name, major, minor });
+}
+
+/// Collects the items of `krate` that are nameable from other crates, keyed by the path and the
+/// namespace under which they are reachable. Items reachable under several paths are collected
+/// once for each path, as removing any of them is a breaking change.
+fn public_api(tcx: TyCtxt<'_>, krate: CrateNum) -> FxIndexMap<(String, Namespace), DefId> {
+    fn collect(
+        tcx: TyCtxt<'_>,
+        module: DefId,
+        prefix: &str,
+        visited: &mut FxHashSet<DefId>,
+        api: &mut FxIndexMap<(String, Namespace), DefId>,
+    ) {
+        let children = match module.as_local() {
+            Some(module) => tcx.module_children_local(module),
+            None => tcx.module_children(module),
+        };
+        for child in children {
+            let Res::Def(kind, def_id) = child.res else { continue };
+            if !child.vis.is_public()
+                || child.ident.name == kw::Underscore
+                || matches!(kind, DefKind::Ctor(..))
+                || tcx.is_doc_hidden(def_id)
+            {
+                continue;
+            }
+            if let Some(local) = def_id.as_local()
+                && !tcx.effective_visibilities(()).is_exported(local)
+            {
+                continue;
+            }
+            let Some(ns) = kind.ns() else { continue };
+
+            let path = if prefix.is_empty() {
+                child.ident.to_string()
+            } else {
+                format!("{prefix}::{}", child.ident)
+            };
+            api.insert((path.clone(), ns), def_id);
+            // Modules re-exported from other crates are not part of this crate's API beyond
+            // their name, their items are checked when comparing the other crate.
+            if kind == DefKind::Mod && def_id.krate == module.krate && visited.insert(def_id) {
+                collect(tcx, def_id, &path, visited, api);
+            }
+        }
+    }
+
+    let mut api = FxIndexMap::default();
+    collect(tcx, krate.as_def_id(), "", &mut FxHashSet::default(), &mut api);
+    api
+}
+
+struct SemverChecker<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    baseline_name: Symbol,
+    changes: Vec<Change>,
+}
+
+impl<'tcx> SemverChecker<'tcx> {
+    fn major(&mut self, span: Option<Span>, description: String) {
+        self.changes.push(Change { severity: Severity::Major, span, description });
+    }
+
+    /// Records a minor change, pointing at `new` if it is defined in the local crate.
+    fn minor(&mut self, new: DefId, description: String) {
+        let span = new.is_local().then(|| self.tcx.def_span(new));
+        self.changes.push(Change { severity: Severity::Minor, span, description });
+    }
+
+    /// Records a major change, pointing at `new` if it is defined in the local crate.
+    fn major_at(&mut self, new: DefId, description: String) {
+        let span = new.is_local().then(|| self.tcx.def_span(new));
+        self.major(span, description);
+    }
+
+    /// Prints `value` with full paths, and with the paths into the baseline crate printed like
+    /// paths into the local crate, so that printed types of both crates can be compared.
+    fn print(&self, value: impl fmt::Display) -> String {
+        let printed = with_no_trimmed_paths!(with_no_visible_paths!(value.to_string()));
+        strip_crate_prefix(&printed, self.baseline_name.as_str())
+    }
+
+    fn compare_items(&mut self, path: &str, old: DefId, new: DefId) {
+        // Both releases re-export the same item of another crate.
+        if old == new {
+            return;
+        }
+        let tcx = self.tcx;
+        let (old_kind, new_kind) = (tcx.def_kind(old), tcx.def_kind(new));
+        if old_kind != new_kind {
+            self.major_at(
+                new,
+                format!(
+                    "`{path}` changed from {} to {}",
+                    tcx.def_kind_descr(old_kind, old),
+                    tcx.def_kind_descr(new_kind, new)
+                ),
+            );
+            return;
+        }
+
+        match new_kind {
+            DefKind::Fn => self.compare_fn_sigs(path, old, new),
+            DefKind::Const | DefKind::Static { .. } | DefKind::TyAlias => {
+                let old_ty = self.print(tcx.type_of(old).instantiate_identity());
+                let new_ty = self.print(tcx.type_of(new).instantiate_identity());
+                if old_ty != new_ty {
+                    self.major_at(
+                        new,
+                        format!("type of `{path}` changed from `{old_ty}` to `{new_ty}`"),
+                    );
+                }
+            }
+            DefKind::Struct | DefKind::Enum | DefKind::Union => self.compare_adts(path, old, new),
+            DefKind::Trait => self.compare_traits(path, old, new),
+            _ => {}
+        }
+
+        if matches!(
+            new_kind,
+            DefKind::Fn
+                | DefKind::Struct
+                | DefKind::Enum
+                | DefKind::Union
+                | DefKind::Trait
+                | DefKind::TyAlias
+        ) {
+            self.compare_generics(path, old, new);
+            self.compare_predicates(path, old, new);
+        }
+    }
+
+    fn compare_fn_sigs(&mut self, path: &str, old: DefId, new: DefId) {
+        let old_sig = self.print(self.tcx.fn_sig(old).instantiate_identity());
+        let new_sig = self.print(self.tcx.fn_sig(new).instantiate_identity());
+        if old_sig != new_sig {
+            self.major_at(
+                new,
+                format!("signature of `{path}` changed from `{old_sig}` to `{new_sig}`"),
+            );
+        }
+    }
+
+    /// The own generic parameters of `def_id` that users can pass arguments for, in order.
+    fn generic_params(&self, def_id: DefId) -> Vec<GenericParam> {
+        self.tcx
+            .generics_of(def_id)
+            .own_params
+            .iter()
+            .filter(|param| param.name != kw::SelfUpper)
+            .filter_map(|param| {
+                let (kind, has_default) = match param.kind {
+                    GenericParamDefKind::Lifetime => (GenericParamKind::Lifetime, false),
+                    GenericParamDefKind::Type { synthetic: true, .. }
+                    | GenericParamDefKind::Const { synthetic: true, .. }
+                    | GenericParamDefKind::Const { is_host_effect: true, .. } => return None,
+                    GenericParamDefKind::Type { has_default, .. } => {
+                        (GenericParamKind::Type, has_default)
+                    }
+                    GenericParamDefKind::Const { has_default, .. } => {
+                        let ty = self.tcx.type_of(param.def_id).instantiate_identity();
+                        (GenericParamKind::Const(self.print(ty)), has_default)
+                    }
+                };
+                Some(GenericParam { name: param.name, kind, has_default })
+            })
+            .collect()
+    }
+
+    /// Compares the generic parameters by position. Their names do not matter to users. Adding
+    /// a default, or a parameter with a default at the end, is a minor change. Any other change
+    /// breaks the code that names the item with explicit arguments.
+    fn compare_generics(&mut self, path: &str, old: DefId, new: DefId) {
+        let old_params = self.generic_params(old);
+        let new_params = self.generic_params(new);
+        let mut severity = None;
+        if new_params.len() < old_params.len() {
+            severity = Some(Severity::Major);
+        }
+        for (old_param, new_param) in iter::zip(&old_params, &new_params) {
+            if old_param.kind != new_param.kind || old_param.has_default && !new_param.has_default
+            {
+                severity = Some(Severity::Major);
+            } else if !old_param.has_default && new_param.has_default {
+                severity = severity.or(Some(Severity::Minor));
+            }
+        }
+        for new_param in new_params.iter().skip(old_params.len()) {
+            if new_param.has_default {
+                severity = severity.or(Some(Severity::Minor));
+            } else {
+                severity = Some(Severity::Major);
+            }
+        }
+
+        let Some(severity) = severity else { return };
+        let print_params = |params: &[GenericParam]| {
+            let params: Vec<_> = params.iter().map(|param| param.to_string()).collect();
+            format!("<{}>", params.join(", "))
+        };
+        let description = format!(
+            "generic parameters of `{path}` changed from `{}` to `{}`",
+            print_params(&old_params),
+            print_params(&new_params)
+        );
+        match severity {
+            Severity::Major => self.major_at(new, description),
+            Severity::Minor => self.minor(new, description),
+        }
+    }
+
+    /// Compares the where clauses and bounds on generic parameters. New bounds break the users
+    /// that do not satisfy them, and removed bounds on traits break the users relying on them.
+    fn compare_predicates(&mut self, path: &str, old: DefId, new: DefId) {
+        let tcx = self.tcx;
+        let predicates = |def_id| -> Vec<String> {
+            tcx.explicit_predicates_of(def_id)
+                .predicates
+                .iter()
+                .map(|(clause, _)| self.print(clause))
+                .collect()
+        };
+        let old_predicates = predicates(old);
+        let new_predicates = predicates(new);
+        for predicate in new_predicates.iter().filter(|p| !old_predicates.contains(p)) {
+            self.major_at(new, format!("`{path}` has a new bound `{predicate}`"));
+        }
+        for predicate in old_predicates.iter().filter(|p| !new_predicates.contains(p)) {
+            let description = format!("`{path}` no longer has the bound `{predicate}`");
+            if tcx.def_kind(new) == DefKind::Trait {
+                self.major_at(new, description);
+            } else {
+                self.minor(new, description);
+            }
+        }
+    }
+
+    fn compare_adts(&mut self, path: &str, old: DefId, new: DefId) {
+        let tcx = self.tcx;
+        let (old_adt, new_adt) = (tcx.adt_def(old), tcx.adt_def(new));
+        if new_adt.is_enum() {
+            if new_adt.is_variant_list_non_exhaustive() && !old_adt.is_variant_list_non_exhaustive()
+            {
+                self.major_at(new, format!("enum `{path}` is now `#[non_exhaustive]`"));
+            }
+            for old_variant in old_adt.variants() {
+                let variant_path = format!("{path}::{}", old_variant.name);
+                match new_adt.variants().iter().find(|v| v.name == old_variant.name) {
+                    Some(new_variant) => {
+                        self.compare_fields(&variant_path, old_variant, new_variant)
+                    }
+                    None => self.major_at(new, format!("variant `{variant_path}` was removed")),
+                }
+            }
+            for new_variant in new_adt.variants() {
+                if old_adt.variants().iter().any(|v| v.name == new_variant.name) {
+                    continue;
+                }
+                // Without `#[non_exhaustive]`, a new variant breaks exhaustive matches.
+                let description = format!("variant `{path}::{}` was added", new_variant.name);
+                if old_adt.is_variant_list_non_exhaustive() {
+                    self.minor(new_variant.def_id, description);
+                } else {
+                    self.major_at(new_variant.def_id, description);
+                }
+            }
+        } else {
+            self.compare_fields(path, old_adt.non_enum_variant(), new_adt.non_enum_variant());
+        }
+
+        self.compare_inherent_items(path, old, new);
+        self.compare_auto_traits(path, old, new);
+    }
+
+    fn compare_fields(&mut self, path: &str, old: &VariantDef, new: &VariantDef) {
+        let tcx = self.tcx;
+        if new.is_field_list_non_exhaustive() && !old.is_field_list_non_exhaustive() {
+            self.major_at(new.def_id, format!("`{path}` is now `#[non_exhaustive]`"));
+        }
+        for old_field in old.fields.iter().filter(|f| f.vis.is_public()) {
+            let field_path = format!("{path}::{}", old_field.name);
+            match new.fields.iter().find(|f| f.name == old_field.name) {
+                Some(new_field) if new_field.vis.is_public() => {
+                    let old_ty = self.print(tcx.type_of(old_field.did).instantiate_identity());
+                    let new_ty = self.print(tcx.type_of(new_field.did).instantiate_identity());
+                    if old_ty != new_ty {
+                        self.major_at(
+                            new_field.did,
+                            format!("type of field `{field_path}` changed from `{old_ty}` to `{new_ty}`"),
+                        );
+                    }
+                }
+                _ => self.major_at(new.def_id, format!("public field `{field_path}` was removed")),
+            }
+        }
+        // If all fields were public, the type could be constructed and matched on exhaustively
+        // by other crates, which any new field breaks.
+        let exhaustive =
+            !old.is_field_list_non_exhaustive() && old.fields.iter().all(|f| f.vis.is_public());
+        for new_field in &new.fields {
+            if old.fields.iter().any(|f| f.name == new_field.name) {
+                continue;
+            }
+            let description = format!("field `{path}::{}` was added", new_field.name);
+            if exhaustive {
+                self.major_at(new_field.did, description);
+            } else if new_field.vis.is_public() {
+                self.minor(new_field.did, description);
+            }
+        }
+    }
+
+    /// Compares the public associated functions and constants of the inherent impls.
+    fn compare_inherent_items(&mut self, path: &str, old: DefId, new: DefId) {
+        let tcx = self.tcx;
+        let items = |def_id| -> Vec<&'tcx AssocItem> {
+            tcx.inherent_impls(def_id)
+                .into_iter()
+                .flatten()
+                .flat_map(|&impl_def_id| tcx.associated_items(impl_def_id).in_definition_order())
+                .filter(|item| {
+                    tcx.visibility(item.def_id).is_public() && !tcx.is_doc_hidden(item.def_id)
+                })
+                .collect()
+        };
+        self.compare_assoc_items(path, &items(old), &items(new), false);
+    }
+
+    fn compare_traits(&mut self, path: &str, old: DefId, new: DefId) {
+        let tcx = self.tcx;
+        if tcx.is_object_safe(old) && !tcx.is_object_safe(new) {
+            self.major_at(new, format!("trait `{path}` is no longer object safe"));
+        }
+        let items = |def_id| -> Vec<&'tcx AssocItem> {
+            tcx.associated_items(def_id)
+                .in_definition_order()
+                .filter(|item| item.opt_rpitit_info.is_none())
+                .collect()
+        };
+        self.compare_assoc_items(path, &items(old), &items(new), true);
+    }
+
+    /// Compares the associated items of a trait or of the inherent impls of a type. New trait
+    /// items without a default break the implementors of the trait.
+    fn compare_assoc_items(
+        &mut self,
+        path: &str,
+        old_items: &[&AssocItem],
+        new_items: &[&AssocItem],
+        is_trait: bool,
+    ) {
+        let tcx = self.tcx;
+        for old_item in old_items {
+            let item_path = format!("{path}::{}", old_item.name);
+            let Some(new_item) =
+                new_items.iter().find(|i| i.name == old_item.name && i.kind == old_item.kind)
+            else {
+                self.major(
+                    None,
+                    format!("{} `{item_path}` was removed", tcx.def_descr(old_item.def_id)),
+                );
+                continue;
+            };
+            match new_item.kind {
+                AssocKind::Fn => self.compare_fn_sigs(&item_path, old_item.def_id, new_item.def_id),
+                AssocKind::Const => {
+                    let old_ty = self.print(tcx.type_of(old_item.def_id).instantiate_identity());
+                    let new_ty = self.print(tcx.type_of(new_item.def_id).instantiate_identity());
+                    if old_ty != new_ty {
+                        self.major_at(
+                            new_item.def_id,
+                            format!("type of `{item_path}` changed from `{old_ty}` to `{new_ty}`"),
+                        );
+                    }
+                }
+                AssocKind::Type => {}
+            }
+            if is_trait
+                && old_item.defaultness(tcx).has_value()
+                && !new_item.defaultness(tcx).has_value()
+            {
+                self.major_at(
+                    new_item.def_id,
+                    format!(
+                        "{} `{item_path}` no longer has a default",
+                        tcx.def_descr(new_item.def_id)
+                    ),
+                );
+            }
+        }
+        for new_item in new_items {
+            if old_items.iter().any(|i| i.name == new_item.name && i.kind == new_item.kind) {
+                continue;
+            }
+            let description =
+                format!("{} `{path}::{}` was added", tcx.def_descr(new_item.def_id), new_item.name);
+            if is_trait && !new_item.defaultness(tcx).has_value() {
+                self.major_at(new_item.def_id, format!("{description} without a default"));
+            } else {
+                self.minor(new_item.def_id, description);
+            }
+        }
+    }
+
+    /// Checks whether the type stopped implementing `Send` or `Sync`, e.g. because it got a
+    /// new field. Generic types are skipped, as whether they implement the auto traits usually
+    /// depends on their arguments.
+    fn compare_auto_traits(&mut self, path: &str, old: DefId, new: DefId) {
+        let tcx = self.tcx;
+        if tcx.generics_of(old).own_requires_monomorphization()
+            || tcx.generics_of(new).own_requires_monomorphization()
+        {
+            return;
+        }
+        let infcx = tcx.infer_ctxt().build();
+        let implements = |trait_def_id, def_id| {
+            let ty = tcx.type_of(def_id).instantiate_identity();
+            infcx
+                .type_implements_trait(trait_def_id, [ty], tcx.param_env(def_id))
+                .must_apply_modulo_regions()
+        };
+        for name in [sym::Send, sym::Sync] {
+            let Some(trait_def_id) = tcx.get_diagnostic_item(name) else { continue };
+            match (implements(trait_def_id, old), implements(trait_def_id, new)) {
+                (true, false) => {
+                    self.major_at(new, format!("`{path}` no longer implements `{name}`"));
+                }
+                (false, true) => self.minor(new, format!("`{path}` now implements `{name}`")),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Removes the `krate::` prefix of the paths in `s` that start with it.
+fn strip_crate_prefix(s: &str, krate: &str) -> String {
+    let prefix = format!("{krate}::");
+    let mut stripped = String::with_capacity(s.len());
+    let mut last = 0;
+    for (pos, _) in s.match_indices(&prefix) {
+        // Only strip whole path segments, not e.g. `other_krate::` or `module::krate::`.
+        let at_path_start = s[..pos]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !(c.is_alphanumeric() || c == '_' || c == ':'));
+        if at_path_start {
+            stripped.push_str(&s[last..pos]);
+            last = pos + prefix.len();
+        }
+    }
+    stripped.push_str(&s[last..]);
+    stripped
+}
@@ -183,6 +183,12 @@ pub(crate) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
     C: QueryCache,
     C::Key: Debug + Clone,
 {
+    tcx.prof.with_summary(|summary| {
+        let mut query_invocation_ids = Vec::new();
+        query_cache.iter(&mut |_, _, i| query_invocation_ids.push(i.into()));
+        summary.map_query_invocations_to_query_name(query_name, query_invocation_ids.into_iter());
+    });
+
     tcx.prof.with_profiler(|profiler| {
         let event_id_builder = profiler.event_id_builder();
 
@@ -246,8 +252,11 @@ pub(crate) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
 /// If we are recording only summary data, the ids will point to
 /// just the query names. If we are recording query keys too, we
 /// allocate the corresponding strings here.
+///
+/// This also attributes the query invocations in the
+/// `-Zself-profile-summary` to query names.
 pub fn alloc_self_profile_query_strings(tcx: TyCtxt<'_>) {
-    if !tcx.prof.enabled() {
+    if !tcx.prof.enabled() && !tcx.prof.summary_enabled() {
         return;
     }
 
@@ -4,6 +4,20 @@
 use rustc_span::Symbol;
 use rustc_target::abi::{Align, Size};
 use std::cmp;
+use std::fmt::Write as _;
+
+/// Which format to use for `-Z print-type-sizes`.
+///
+/// This is separate from `--error-format`, because Cargo and compiletest always pass
+/// `--error-format=json`, which would otherwise change the output of every existing use of
+/// `-Z print-type-sizes`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum PrintTypeSizesFormat {
+    /// Emit human readable text.
+    Text,
+    /// Emit a JSON object per type.
+    Json,
+}
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -37,6 +51,15 @@ fn fmt(&self, w: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+impl std::fmt::Display for SizeKind {
+    fn fmt(&self, w: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SizeKind::Exact => write!(w, "exact"),
+            SizeKind::Min => write!(w, "min"),
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct FieldInfo {
     pub kind: FieldKind,
@@ -59,6 +82,18 @@ pub enum DataTypeKind {
     Coroutine,
 }
 
+impl std::fmt::Display for DataTypeKind {
+    fn fmt(&self, w: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataTypeKind::Struct => write!(w, "struct"),
+            DataTypeKind::Union => write!(w, "union"),
+            DataTypeKind::Enum => write!(w, "enum"),
+            DataTypeKind::Closure => write!(w, "closure"),
+            DataTypeKind::Coroutine => write!(w, "coroutine"),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct TypeSizeInfo {
     pub kind: DataTypeKind,
@@ -134,106 +169,23 @@ pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTabl
         );
     }
 
-    pub fn print_type_sizes(&self) {
+    /// Prints the layout of all types of at least `threshold` bytes.
+    pub fn print_type_sizes(&self, format: PrintTypeSizesFormat, threshold: u64) {
         let type_sizes = self.type_sizes.borrow();
         // We will soon sort, so the initial order does not matter.
         #[allow(rustc::potential_query_instability)]
-        let mut sorted: Vec<_> = type_sizes.iter().collect();
+        let mut sorted: Vec<_> =
+            type_sizes.iter().filter(|info| info.overall_size >= threshold).collect();
 
         // Primary sort: large-to-small.
         // Secondary sort: description (dictionary order)
         sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
 
         for info in sorted {
-            let TypeSizeInfo { type_description, overall_size, align, kind, variants, .. } = info;
-            println!(
-                "print-type-size type: `{type_description}`: {overall_size} bytes, alignment: {align} bytes"
-            );
-            let indent = "    ";
-
-            let discr_size = if let Some(discr_size) = info.opt_discr_size {
-                println!("print-type-size {indent}discriminant: {discr_size} bytes");
-                discr_size
-            } else {
-                0
-            };
-
-            // We start this at discr_size (rather than 0) because
-            // things like C-enums do not have variants but we still
-            // want the max_variant_size at the end of the loop below
-            // to reflect the presence of the discriminant.
-            let mut max_variant_size = discr_size;
-
-            let struct_like = match kind {
-                DataTypeKind::Struct | DataTypeKind::Closure => true,
-                DataTypeKind::Enum | DataTypeKind::Union | DataTypeKind::Coroutine => false,
-            };
-            for (i, variant_info) in variants.into_iter().enumerate() {
-                let VariantInfo { ref name, kind: _, align: _, size, ref fields } = *variant_info;
-                let indent = if !struct_like {
-                    let name = match name.as_ref() {
-                        Some(name) => name.to_string(),
-                        None => i.to_string(),
-                    };
-                    println!(
-                        "print-type-size {indent}variant `{name}`: {diff} bytes",
-                        diff = size - discr_size
-                    );
-                    "        "
-                } else {
-                    assert!(i < 1);
-                    "    "
-                };
-                max_variant_size = cmp::max(max_variant_size, size);
-
-                let mut min_offset = discr_size;
-
-                // We want to print fields by increasing offset. We also want
-                // zero-sized fields before non-zero-sized fields, otherwise
-                // the loop below goes wrong; hence the `f.size` in the sort
-                // key.
-                let mut fields = fields.clone();
-                fields.sort_by_key(|f| (f.offset, f.size));
-
-                for field in fields {
-                    let FieldInfo { kind, ref name, offset, size, align, type_name } = field;
-
-                    if offset > min_offset {
-                        let pad = offset - min_offset;
-                        println!("print-type-size {indent}padding: {pad} bytes");
-                    }
-
-                    if offset < min_offset {
-                        // If this happens it's probably a union.
-                        print!(
-                            "print-type-size {indent}{kind} `.{name}`: {size} bytes, \
-                                  offset: {offset} bytes, \
-                                  alignment: {align} bytes"
-                        );
-                    } else if info.packed || offset == min_offset {
-                        print!("print-type-size {indent}{kind} `.{name}`: {size} bytes");
-                    } else {
-                        // Include field alignment in output only if it caused padding injection
-                        print!(
-                            "print-type-size {indent}{kind} `.{name}`: {size} bytes, \
-                                  alignment: {align} bytes"
-                        );
-                    }
-
-                    if let Some(type_name) = type_name {
-                        println!(", type: {type_name}");
-                    } else {
-                        println!();
-                    }
-
-                    min_offset = offset + size;
-                }
-            }
-
-            match overall_size.checked_sub(max_variant_size) {
-                None => panic!("max_variant_size {max_variant_size} > {overall_size} overall_size"),
-                Some(diff @ 1..) => println!("print-type-size {indent}end padding: {diff} bytes"),
-                Some(0) => {}
+            let layout = TypeLayout::new(info);
+            match format {
+                PrintTypeSizesFormat::Text => layout.print(),
+                PrintTypeSizesFormat::Json => println!("{}", layout.to_json()),
             }
         }
     }
@@ -267,3 +219,220 @@ pub fn print_vtable_sizes(&self, crate_name: Symbol) {
         }
     }
 }
+
+/// The layout of a type as reported by `-Z print-type-sizes`, with the padding between fields
+/// worked out.
+struct TypeLayout<'a> {
+    info: &'a TypeSizeInfo,
+    discr_size: u64,
+    variants: Vec<VariantLayout<'a>>,
+    end_padding: u64,
+}
+
+struct VariantLayout<'a> {
+    info: &'a VariantInfo,
+    /// The name of the variant, or its index if it has none.
+    name: String,
+    /// The size of the variant without the discriminant.
+    size: u64,
+    /// The fields by increasing offset.
+    fields: Vec<FieldLayout>,
+}
+
+struct FieldLayout {
+    info: FieldInfo,
+    /// The padding between the end of the previous field and this field.
+    padding: u64,
+    /// Whether this field starts before the end of the previous field, e.g. in a union.
+    overlaps: bool,
+}
+
+impl<'a> TypeLayout<'a> {
+    fn new(info: &'a TypeSizeInfo) -> Self {
+        let discr_size = info.opt_discr_size.unwrap_or(0);
+
+        // We start this at discr_size (rather than 0) because
+        // things like C-enums do not have variants but we still
+        // want the max_variant_size at the end of the loop below
+        // to reflect the presence of the discriminant.
+        let mut max_variant_size = discr_size;
+
+        let variants = info
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(i, variant)| {
+                max_variant_size = cmp::max(max_variant_size, variant.size);
+
+                // We want the fields by increasing offset. We also want
+                // zero-sized fields before non-zero-sized fields, otherwise
+                // the padding computation goes wrong; hence the `f.size` in
+                // the sort key.
+                let mut fields = variant.fields.clone();
+                fields.sort_by_key(|f| (f.offset, f.size));
+
+                let mut min_offset = discr_size;
+                let fields = fields
+                    .into_iter()
+                    .map(|field| {
+                        let layout = FieldLayout {
+                            info: field,
+                            padding: field.offset.saturating_sub(min_offset),
+                            overlaps: field.offset < min_offset,
+                        };
+                        min_offset = field.offset + field.size;
+                        layout
+                    })
+                    .collect();
+
+                VariantLayout {
+                    info: variant,
+                    name: match variant.name {
+                        Some(name) => name.to_string(),
+                        None => i.to_string(),
+                    },
+                    size: variant.size - discr_size,
+                    fields,
+                }
+            })
+            .collect();
+
+        let overall_size = info.overall_size;
+        let end_padding = overall_size.checked_sub(max_variant_size).unwrap_or_else(|| {
+            panic!("max_variant_size {max_variant_size} > {overall_size} overall_size")
+        });
+
+        TypeLayout { info, discr_size, variants, end_padding }
+    }
+
+    fn print(&self) {
+        let TypeSizeInfo { type_description, overall_size, align, kind, .. } = self.info;
+        println!(
+            "print-type-size type: `{type_description}`: {overall_size} bytes, alignment: {align} bytes"
+        );
+        let indent = "    ";
+
+        if self.info.opt_discr_size.is_some() {
+            println!("print-type-size {indent}discriminant: {} bytes", self.discr_size);
+        }
+
+        let struct_like = match kind {
+            DataTypeKind::Struct | DataTypeKind::Closure => true,
+            DataTypeKind::Enum | DataTypeKind::Union | DataTypeKind::Coroutine => false,
+        };
+        for (i, variant) in self.variants.iter().enumerate() {
+            let indent = if !struct_like {
+                println!(
+                    "print-type-size {indent}variant `{}`: {} bytes",
+                    variant.name, variant.size
+                );
+                "        "
+            } else {
+                assert!(i < 1);
+                "    "
+            };
+
+            for field in &variant.fields {
+                let FieldInfo { kind, ref name, offset, size, align, type_name } = field.info;
+
+                if field.padding > 0 {
+                    println!("print-type-size {indent}padding: {} bytes", field.padding);
+                }
+
+                if field.overlaps {
+                    // If this happens it's probably a union.
+                    print!(
+                        "print-type-size {indent}{kind} `.{name}`: {size} bytes, \
+                              offset: {offset} bytes, \
+                              alignment: {align} bytes"
+                    );
+                } else if self.info.packed || field.padding == 0 {
+                    print!("print-type-size {indent}{kind} `.{name}`: {size} bytes");
+                } else {
+                    // Include field alignment in output only if it caused padding injection
+                    print!(
+                        "print-type-size {indent}{kind} `.{name}`: {size} bytes, \
+                              alignment: {align} bytes"
+                    );
+                }
+
+                if let Some(type_name) = type_name {
+                    println!(", type: {type_name}");
+                } else {
+                    println!();
+                }
+            }
+        }
+
+        if self.end_padding > 0 {
+            println!("print-type-size {indent}end padding: {} bytes", self.end_padding);
+        }
+    }
+
+    /// Renders the layout as a single-line JSON object.
+    fn to_json(&self) -> String {
+        let info = self.info;
+        let mut json = String::new();
+        write!(
+            json,
+            r#"{{"type":{},"kind":"{}","size":{},"align":{},"packed":{},"#,
+            json_string(&info.type_description),
+            info.kind,
+            info.overall_size,
+            info.align,
+            info.packed,
+        )
+        .unwrap();
+        match info.opt_discr_size {
+            Some(discr_size) => write!(json, r#""discriminant_size":{discr_size},"#).unwrap(),
+            None => json.push_str(r#""discriminant_size":null,"#),
+        }
+        json.push_str(r#""variants":["#);
+        for (i, variant) in self.variants.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let VariantInfo { name, kind: size_kind, align, .. } = *variant.info;
+            let name = name.map_or("null".to_string(), |name| json_string(name.as_str()));
+            write!(
+                json,
+                r#"{{"name":{name},"size":{},"size_kind":"{size_kind}","align":{align},"fields":["#,
+                variant.size,
+            )
+            .unwrap();
+            for (i, field) in variant.fields.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                let FieldInfo { kind, name, offset, size, align, type_name } = field.info;
+                // Like in the text output, overlapping fields (e.g. in unions) have no padding.
+                let type_name = type_name.map_or("null".to_string(), |t| json_string(t.as_str()));
+                write!(
+                    json,
+                    r#"{{"kind":"{kind}","name":{},"offset":{offset},"size":{size},"align":{align},"#,
+                    json_string(name.as_str()),
+                )
+                .unwrap();
+                write!(json, r#""padding":{},"type":{type_name}}}"#, field.padding).unwrap();
+            }
+            json.push_str("]}");
+        }
+        write!(json, r#"],"end_padding":{}}}"#, self.end_padding).unwrap();
+        json
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -13,7 +13,9 @@
 use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
 use rustc_data_structures::stable_hasher::{StableOrd, ToStableHashKey};
 use rustc_errors::emitter::HumanReadableErrorType;
-use rustc_errors::{ColorConfig, DiagArgValue, DiagCtxtFlags, IntoDiagArg};
+use rustc_errors::{
+    Applicability, ColorConfig, DiagArgValue, DiagCtxtFlags, IntoDiagArg, LanguageIdentifier,
+};
 use rustc_feature::UnstableFeatures;
 use rustc_macros::{Decodable, Encodable, HashStable_Generic};
 use rustc_span::edition::{Edition, DEFAULT_EDITION, EDITION_NAME_LIST, LATEST_STABLE_EDITION};
@@ -36,9 +38,11 @@
 use tracing::debug;
 
 mod cfg;
+mod lint_config;
 pub mod sigpipe;
 
 pub use cfg::{Cfg, CheckCfg, ExpectedValues};
+pub use lint_config::{path_matches, LintConfig, LintOptionValue};
 
 /// The different settings that the `-C strip` flag can have.
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
@@ -612,6 +616,11 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF 2.1 log, for code scanning services.
+    Sarif {
+        /// Render the log in a human readable way (with indents and newlines).
+        pretty: bool,
+    },
 }
 
 impl Default for ErrorOutputType {
@@ -1126,6 +1135,7 @@ fn default() -> Options {
             debuginfo_compression: DebugInfoCompression::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: LintConfig::default(),
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -1207,6 +1217,7 @@ pub fn dcx_flags(&self, can_emit_warnings: bool) -> DiagCtxtFlags {
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            collect_suggestions: self.apply_suggestions.map(|apply| apply.applicability),
         }
     }
 
@@ -1421,7 +1432,7 @@ pub(crate) fn flagmulti_s(a: S, b: S, c: S) -> R {
         stable(longer(a, b), move |opts| opts.optflagmulti(a, b, c))
     }
 
-    fn opt(a: S, b: S, c: S, d: S) -> R {
+    pub(crate) fn opt(a: S, b: S, c: S, d: S) -> R {
         unstable(longer(a, b), move |opts| opts.optopt(a, b, c, d))
     }
     pub(crate) fn multi(a: S, b: S, c: S, d: S) -> R {
@@ -1545,7 +1556,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|short|sarif|pretty-sarif",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1570,6 +1581,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "FROM=TO",
         ),
         opt::multi("", "env-set", "Inject an environment variable", "VAR=VALUE"),
+        opt::opt("", "lint-config", "Read lint levels and lint options from a file", "PATH"),
+        opt::opt(
+            "",
+            "lang",
+            "Language of the diagnostics, or `auto` for the language of the environment",
+            "LANG|auto",
+        ),
     ]);
     opts
 }
@@ -1606,6 +1624,40 @@ pub fn get_cmd_lint_options(
     (lint_opts, describe_lints, lint_cap)
 }
 
+/// Parses the `--lint-config` flag.
+fn parse_lint_config(early_dcx: &EarlyDiagCtxt, matches: &getopts::Matches) -> LintConfig {
+    let Some(path) = matches.opt_str("lint-config") else {
+        return LintConfig::default();
+    };
+    let src = fs::read_to_string(&path).unwrap_or_else(|e| {
+        early_dcx.early_fatal(format!("failed to read lint configuration file `{path}`: {e}"))
+    });
+    LintConfig::parse(&src).unwrap_or_else(|e| {
+        early_dcx.early_fatal(format!("invalid lint configuration file `{path}`: {e}"))
+    })
+}
+
+/// Parses the `--lang` flag, which sets `-Z translate-lang`. With `auto`, the language of the
+/// environment is used if the sysroot has a translation for it, and English otherwise.
+fn parse_lang(
+    early_dcx: &EarlyDiagCtxt,
+    matches: &getopts::Matches,
+    sysroot_opt: &Option<PathBuf>,
+) -> Option<LanguageIdentifier> {
+    let lang = matches.opt_str("lang")?;
+    if lang != "auto" {
+        return Some(lang.parse().unwrap_or_else(|_| {
+            early_dcx.early_fatal(format!(
+                "`--lang` expects a language identifier or `auto`, found `{lang}`"
+            ))
+        }));
+    }
+    let locale = rustc_errors::locale_from_env()?;
+    let sysroots: Vec<_> =
+        sysroot_opt.iter().cloned().chain(filesearch::sysroot_candidates()).collect();
+    rustc_errors::available_locale(&sysroots, &locale).map(|_| locale)
+}
+
 /// Parses the `--color` flag.
 pub fn parse_color(early_dcx: &EarlyDiagCtxt, matches: &getopts::Matches) -> ColorConfig {
     match matches.opt_str("color").as_deref() {
@@ -1719,14 +1771,16 @@ pub fn parse_error_format(
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
+            Some("sarif") => ErrorOutputType::Sarif { pretty: false },
+            Some("pretty-sarif") => ErrorOutputType::Sarif { pretty: true },
 
             Some(arg) => {
                 early_dcx.abort_if_error_and_set_error_format(ErrorOutputType::HumanReadable(
                     HumanReadableErrorType::Default(color),
                 ));
                 early_dcx.early_fatal(format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{arg}`)"
+                    "argument for `--error-format` must be `human`, `json`, `short`, \
+                     `sarif` or `pretty-sarif` (instead was `{arg}`)"
                 ))
             }
         }
@@ -1790,6 +1844,10 @@ fn check_error_format_stability(
         {
             early_dcx.early_fatal("`--error-format=human-annotate-rs` is unstable");
         }
+        if let ErrorOutputType::Sarif { pretty } = error_format {
+            let format = if pretty { "pretty-sarif" } else { "sarif" };
+            early_dcx.early_fatal(format!("`--error-format={format}` is unstable"));
+        }
     }
 }
 
@@ -2405,9 +2463,18 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
 
     let mut unstable_opts = UnstableOptions::build(early_dcx, matches);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
+    let lint_config = parse_lint_config(early_dcx, matches);
 
     check_error_format_stability(early_dcx, &unstable_opts, error_format);
 
+    // Like the `--json` artifacts, the JSON extern usage report is emitted alongside the JSON
+    // diagnostics, so it needs them to be enabled.
+    if unstable_opts.extern_usage_report == Some(ExternUsageReportFormat::Json)
+        && !matches!(error_format, ErrorOutputType::Json { .. })
+    {
+        early_dcx.early_fatal("`-Zextern-usage-report=json` requires `--error-format=json`");
+    }
+
     let output_types = parse_output_types(early_dcx, &unstable_opts, matches);
 
     let mut cg = CodegenOptions::build(early_dcx, matches);
@@ -2577,6 +2644,9 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
     let cg = cg;
 
     let sysroot_opt = matches.opt_str("sysroot").map(|m| PathBuf::from(&m));
+    if unstable_opts.translate_lang.is_none() {
+        unstable_opts.translate_lang = parse_lang(early_dcx, matches, &sysroot_opt);
+    }
     let target_triple = parse_target_triple(early_dcx, matches);
     let opt_level = parse_opt_level(early_dcx, matches, &cg);
     // The `-g` and `-C debuginfo` flags specify the same setting, so we want to be able
@@ -2662,6 +2732,7 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         debuginfo_compression,
         lint_opts,
         lint_cap,
+        lint_config,
         describe_lints,
         output_types,
         search_paths,
@@ -2963,11 +3034,11 @@ pub(crate) mod dep_tracking {
     use super::{
         BranchProtection, CFGuard, CFProtection, CollapseMacroDebuginfo, CoverageOptions,
         CrateType, DebugInfo, DebugInfoCompression, ErrorOutputType, FunctionReturn,
-        InliningThreshold, InstrumentCoverage, InstrumentXRay, LinkerPluginLto, LocationDetail,
-        LtoCli, NextSolverConfig, OomStrategy, OptLevel, OutFileName, OutputType, OutputTypes,
-        PatchableFunctionEntry, Polonius, RemapPathScopeComponents, ResolveDocLinks,
-        SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion,
-        WasiExecModel,
+        InliningThreshold, InstrumentCoverage, InstrumentXRay, LinkerPluginLto, LintConfig,
+        LocationDetail, LtoCli, NextSolverConfig, OomStrategy, OptLevel, OutFileName, OutputType,
+        OutputTypes, PatchableFunctionEntry, Polonius, Polymorphize, RemapPathScopeComponents,
+        ResolveDocLinks, SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath,
+        SymbolManglingVersion, WasiExecModel,
     };
     use crate::lint;
     use crate::utils::NativeLib;
@@ -3031,6 +3102,7 @@ fn hash(
         String,
         PathBuf,
         lint::Level,
+        LintConfig,
         WasiExecModel,
         u32,
         FramePointer,
@@ -3076,6 +3148,7 @@ fn hash(
         NextSolverConfig,
         PatchableFunctionEntry,
         Polonius,
+        Polymorphize,
         InliningThreshold,
         FunctionReturn,
         WasmCAbi,
@@ -3252,6 +3325,33 @@ pub fn extension(self) -> &'static str {
     }
 }
 
+/// Which format to use for `-Z extern-usage-report`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum ExternUsageReportFormat {
+    /// Emit a note for each `--extern` crate that is not used by code
+    Text,
+    /// Emit a single JSON message with the usage of every `--extern` crate
+    Json,
+}
+
+/// Which format to use for `-Z macro-expansion-report`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum MacroExpansionReportFormat {
+    /// Print tables sorted by expansion time
+    Text,
+    /// Print a single JSON object with the same information
+    Json,
+}
+
+/// Which suggestions to apply with `-Z apply-suggestions`, and how
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ApplySuggestions {
+    /// Apply the suggestions with this or a more certain applicability
+    pub applicability: Applicability,
+    /// Print the changes as a diff instead of writing them to the source files
+    pub dry_run: bool,
+}
+
 /// `-Z patchable-function-entry` representation - how many nops to put before and after function
 /// entry.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
@@ -3308,6 +3408,35 @@ pub fn is_next_enabled(&self) -> bool {
     }
 }
 
+/// `-Zpolymorphize` values, sharing the code of the instances that only differ in some of their
+/// generic parameters.
+#[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
+pub enum Polymorphize {
+    /// The default value: disabled.
+    #[default]
+    Off,
+
+    /// Merge the instances that only differ in generic parameters that are unused.
+    Unused,
+
+    /// Also merge the instances that only differ in generic parameters that do not affect the
+    /// layout of the types they are used in, like the parameter of `PhantomData` or the pointee
+    /// type of a thin pointer.
+    Layout,
+}
+
+impl Polymorphize {
+    /// Returns whether polymorphization is enabled
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Polymorphize::Off)
+    }
+
+    /// Returns whether the parameters that do not affect the layout are considered unused
+    pub fn is_layout_enabled(&self) -> bool {
+        matches!(self, Polymorphize::Layout)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
 pub enum InliningThreshold {
     Always,
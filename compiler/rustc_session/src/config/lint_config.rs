@@ -0,0 +1,355 @@
+//! The lint configuration file passed with `--lint-config`.
+//!
+//! The file uses a small subset of TOML, modelled after the `[lints]` table of Cargo manifests:
+//!
+//! ```toml
+//! [lints.rust]
+//! dead_code = "deny"
+//! large_futures = { level = "warn", size_limit = 4096 }
+//!
+//! [lints.clippy]
+//! enum_glob_use = { level = "deny", priority = 1 }
+//!
+//! [paths]
+//! "crate::generated::*" = "allow"
+//! "crate::ffi" = { non_snake_case = "allow" }
+//! ```
+//!
+//! Every `[lints.<tool>]` table other than `[lints.rust]` sets the levels of the lints of that
+//! tool, e.g. `clippy::enum_glob_use`. They are passed on like `-D clippy::enum_glob_use`.
+
+use std::collections::BTreeMap;
+
+use crate::lint;
+
+#[cfg(test)]
+mod tests;
+
+/// The contents of a `--lint-config` file.
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct LintConfig {
+    /// The crate-wide lint levels, in the order in which they are applied.
+    pub levels: Vec<(String, lint::Level)>,
+    /// The lint levels of the items matching a path pattern, in the order of the file. The
+    /// pattern `crate::a::*` matches `crate::a` and everything in it, `crate::a` only matches
+    /// `crate::a` itself, whose contents inherit its levels as usual. A level for all lints is
+    /// named [`LintConfig::ALL_LINTS`].
+    pub path_levels: Vec<(String, Vec<(String, lint::Level)>)>,
+    /// The options of the lints, keyed by lint name and option name.
+    pub options: BTreeMap<(String, String), LintOptionValue>,
+}
+
+/// The value of a lint option, e.g. the `size_limit` of `large_futures`.
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub enum LintOptionValue {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+/// The options that rustc lints understand. Options of tool lints are not checked, they are
+/// left to the tools.
+const RUSTC_LINT_OPTIONS: &[(&str, &str)] =
+    &[("large_assignments", "size_limit"), ("large_futures", "size_limit")];
+
+impl LintConfig {
+    /// The name of the level of a `[paths]` entry that sets the level of all lints at once, e.g.
+    /// `"crate::generated::*" = "allow"`.
+    pub const ALL_LINTS: &'static str = "*";
+
+    /// Parses the contents of a lint configuration file.
+    pub fn parse(src: &str) -> Result<LintConfig, String> {
+        let mut config = LintConfig::default();
+        // The crate-wide levels with their priority.
+        let mut levels = vec![];
+        let mut table = vec![];
+        for (i, line) in src.lines().enumerate() {
+            let mut parser = Parser { rest: line };
+            let res = parser.line().and_then(|line| match line {
+                Line::Empty => Ok(()),
+                Line::Table(keys) => {
+                    table = keys;
+                    match &table[..] {
+                        [lints, _] if lints == "lints" => Ok(()),
+                        [paths] if paths == "paths" => Ok(()),
+                        _ => Err(format!("unknown table `[{}]`", table.join("."))),
+                    }
+                }
+                Line::KeyValue(key, value) => match &table[..] {
+                    [_, tool] => config.add_lint(tool, &key, value, &mut levels),
+                    [_] => config.add_path(key, value),
+                    _ => Err("expected a `[lints.<tool>]` or `[paths]` table".to_owned()),
+                },
+            });
+            res.map_err(|e| format!("line {}: {e}", i + 1))?;
+        }
+        // Like Cargo, apply the levels with the lowest priority first, so that the levels with a
+        // higher priority override them.
+        levels.sort_by_key(|&(priority, _, _)| priority);
+        config.levels = levels.into_iter().map(|(_, name, level)| (name, level)).collect();
+        Ok(config)
+    }
+
+    fn add_lint(
+        &mut self,
+        tool: &str,
+        key: &str,
+        value: Value,
+        levels: &mut Vec<(i64, String, lint::Level)>,
+    ) -> Result<(), String> {
+        let name = key.replace('-', "_");
+        let name = if tool == "rust" { name } else { format!("{tool}::{name}") };
+        let (level, priority) = match value {
+            Value::String(level) => (parse_level(&level)?, 0),
+            Value::Table(entries) => {
+                let mut level = None;
+                let mut priority = 0;
+                for (option, value) in entries {
+                    match (&option[..], value) {
+                        ("level", Value::String(l)) => level = Some(parse_level(&l)?),
+                        ("priority", Value::Integer(p)) => priority = p,
+                        ("level" | "priority", _) => {
+                            return Err(format!("invalid value for `{option}` of `{name}`"));
+                        }
+                        (_, value) => self.add_option(tool, &name, &option, value)?,
+                    }
+                }
+                (level.ok_or_else(|| format!("missing `level` for `{name}`"))?, priority)
+            }
+            _ => return Err(format!("expected a level or a table for `{name}`")),
+        };
+        levels.push((priority, name, level));
+        Ok(())
+    }
+
+    fn add_option(
+        &mut self,
+        tool: &str,
+        name: &str,
+        option: &str,
+        value: Value,
+    ) -> Result<(), String> {
+        let value = match value {
+            Value::String(s) => LintOptionValue::String(s),
+            Value::Integer(n) => LintOptionValue::Integer(n),
+            Value::Boolean(b) => LintOptionValue::Boolean(b),
+            Value::Table(_) => return Err(format!("invalid value for `{option}` of `{name}`")),
+        };
+        if tool == "rust" {
+            if !RUSTC_LINT_OPTIONS.contains(&(name, option)) {
+                return Err(format!("unknown option `{option}` for `{name}`"));
+            }
+            if !matches!(value, LintOptionValue::Integer(n) if n >= 0) {
+                return Err(format!("`{option}` of `{name}` must be a non-negative integer"));
+            }
+        }
+        self.options.insert((name.to_owned(), option.to_owned()), value);
+        Ok(())
+    }
+
+    fn add_path(&mut self, pattern: String, value: Value) -> Result<(), String> {
+        if pattern != "crate" && !pattern.starts_with("crate::") {
+            return Err(format!("path `{pattern}` must start with `crate`"));
+        }
+        let levels = match value {
+            Value::String(level) => vec![(Self::ALL_LINTS.to_owned(), parse_level(&level)?)],
+            Value::Table(entries) => entries
+                .into_iter()
+                .map(|(name, value)| match value {
+                    Value::String(level) => Ok((name.replace('-', "_"), parse_level(&level)?)),
+                    _ => Err(format!("expected a level for `{name}` in `{pattern}`")),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => return Err(format!("expected a level or a table for `{pattern}`")),
+        };
+        self.path_levels.push((pattern, levels));
+        Ok(())
+    }
+
+    /// Returns the value of `option` of `lint`, e.g. `("large_futures", "size_limit")`. Tool
+    /// lints are named with their tool, e.g. `clippy::too_many_lines`.
+    pub fn option(&self, lint: &str, option: &str) -> Option<&LintOptionValue> {
+        self.options.get(&(lint.to_owned(), option.to_owned()))
+    }
+
+    /// Returns the value of `option` of `lint` if it is an integer.
+    pub fn integer_option(&self, lint: &str, option: &str) -> Option<i64> {
+        match self.option(lint, option)? {
+            &LintOptionValue::Integer(n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the item at `path`, e.g. `crate::a::b`, matches the path `pattern` of a `[paths]`
+/// entry.
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix("::*") {
+        Some(prefix) => {
+            path.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        }
+        None => pattern == path,
+    }
+}
+
+fn parse_level(level: &str) -> Result<lint::Level, String> {
+    lint::Level::from_str(level).ok_or_else(|| {
+        format!("unknown lint level `{level}`, expected `allow`, `warn`, `deny` or `forbid`")
+    })
+}
+
+enum Line {
+    Empty,
+    Table(Vec<String>),
+    KeyValue(String, Value),
+}
+
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    /// An inline table, which may not be nested.
+    Table(Vec<(String, Value)>),
+}
+
+/// A parser for a single line of the file.
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn line(&mut self) -> Result<Line, String> {
+        self.skip_whitespace();
+        let line = if self.rest.is_empty() || self.rest.starts_with('#') {
+            Line::Empty
+        } else if self.eat('[') {
+            let mut keys = vec![self.key()?];
+            while self.eat('.') {
+                keys.push(self.key()?);
+            }
+            self.expect(']')?;
+            Line::Table(keys)
+        } else {
+            let key = self.key()?;
+            self.expect('=')?;
+            Line::KeyValue(key, self.value(true)?)
+        };
+        self.skip_whitespace();
+        if !self.rest.is_empty() && !self.rest.starts_with('#') {
+            return Err(format!("unexpected `{}`", self.rest));
+        }
+        Ok(line)
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        if self.rest.starts_with('"') {
+            return self.string();
+        }
+        let len = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(self.rest.len());
+        if len == 0 {
+            return Err("expected a key".to_owned());
+        }
+        let (key, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Ok(key.to_owned())
+    }
+
+    fn value(&mut self, allow_table: bool) -> Result<Value, String> {
+        self.skip_whitespace();
+        if self.rest.starts_with('"') {
+            return Ok(Value::String(self.string()?));
+        }
+        if allow_table && self.eat('{') {
+            let mut entries = vec![];
+            if !self.eat('}') {
+                loop {
+                    let key = self.key()?;
+                    self.expect('=')?;
+                    entries.push((key, self.value(false)?));
+                    if self.eat('}') {
+                        break;
+                    }
+                    self.expect(',')?;
+                }
+            }
+            return Ok(Value::Table(entries));
+        }
+        let len = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')))
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(len);
+        let value = match word {
+            "true" => Value::Boolean(true),
+            "false" => Value::Boolean(false),
+            _ => match word.replace('_', "").parse() {
+                Ok(n) => Value::Integer(n),
+                Err(_) => {
+                    return Err("expected a string, an integer, a boolean or a table".to_owned());
+                }
+            },
+        };
+        self.rest = rest;
+        Ok(value)
+    }
+
+    /// Parses a basic string, `"..."`, with the escapes of TOML.
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 1..];
+                    return Ok(s);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some(c @ ('u' | 'U')) => {
+                            let len = if c == 'u' { 4 } else { 8 };
+                            let hex: String = chars.by_ref().take(len).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .filter(|_| hex.len() == len)
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("invalid unicode escape `\\{c}{hex}`"))?
+                        }
+                        _ => return Err("invalid escape in string".to_owned()),
+                    };
+                    s.push(escaped);
+                }
+                c => s.push(c),
+            }
+        }
+        Err("unterminated string".to_owned())
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start_matches([' ', '\t']);
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) { Ok(()) } else { Err(format!("expected `{c}`")) }
+    }
+}
@@ -0,0 +1,52 @@
+use super::*;
+use crate::lint::Level;
+
+#[test]
+fn test_lint_config_parsing() {
+    let config = LintConfig::parse(
+        r#"
+# Lints are applied from the lowest to the highest priority.
+[lints.rust]
+dead_code = "deny"
+large-futures = { level = "warn", size_limit = 4_096, priority = -1 }
+
+[lints.clippy]
+enum_glob_use = "forbid" # Tool lints are named with their tool.
+
+[paths]
+"crate::generated::*" = "allow"
+"crate::ffi" = { non_camel_case_types = "allow" }
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.levels,
+        vec![
+            ("large_futures".to_string(), Level::Warn),
+            ("dead_code".to_string(), Level::Deny),
+            ("clippy::enum_glob_use".to_string(), Level::Forbid),
+        ]
+    );
+    assert_eq!(
+        config.path_levels,
+        vec![
+            ("crate::generated::*".to_string(), vec![("*".to_string(), Level::Allow)]),
+            ("crate::ffi".to_string(), vec![("non_camel_case_types".to_string(), Level::Allow)]),
+        ]
+    );
+    assert_eq!(config.integer_option("large_futures", "size_limit"), Some(4096));
+
+    assert!(path_matches("crate::generated::*", "crate::generated"));
+    assert!(path_matches("crate::generated::*", "crate::generated::a::b"));
+    assert!(!path_matches("crate::generated::*", "crate::generated_code"));
+    assert!(path_matches("crate::ffi", "crate::ffi"));
+    assert!(!path_matches("crate::ffi", "crate::ffi::a"));
+
+    assert!(LintConfig::parse("dead_code = \"deny\"").is_err());
+    assert!(LintConfig::parse("[lints.rust]\ndead_code = \"expect\"").is_err());
+    assert!(LintConfig::parse("[lints.rust]\ndead_code = { size_limit = 1 }").is_err());
+    assert!(
+        LintConfig::parse("[lints.rust]\ndead_code = { level = \"deny\", limit = 1 }").is_err()
+    );
+    assert!(LintConfig::parse("[paths]\n\"generated::*\" = \"allow\"").is_err());
+}
@@ -1,3 +1,4 @@
+use crate::code_stats::PrintTypeSizesFormat;
 use crate::config::*;
 use crate::search_paths::SearchPath;
 use crate::utils::NativeLib;
@@ -5,7 +6,7 @@
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::profiling::TimePassesFormat;
 use rustc_data_structures::stable_hasher::Hash64;
-use rustc_errors::ColorConfig;
+use rustc_errors::{Applicability, ColorConfig};
 use rustc_errors::{LanguageIdentifier, TerminalUrl};
 use rustc_feature::UnstableFeatures;
 use rustc_span::edition::Edition;
@@ -140,6 +141,8 @@ pub struct Options {
         debuginfo_compression: DebugInfoCompression [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// The contents of the `--lint-config` file.
+        lint_config: LintConfig [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -376,6 +379,11 @@ mod desc {
     pub const parse_frame_pointer: &str = "one of `true`/`yes`/`on`, `false`/`no`/`off`, or (with -Zunstable-options) `non-leaf` or `always`";
     pub const parse_threads: &str = parse_number;
     pub const parse_time_passes_format: &str = "`text` (default) or `json`";
+    pub const parse_print_type_sizes_format: &str = "`text` (default) or `json`";
+    pub const parse_extern_usage_report: &str = "`text` (default) or `json`";
+    pub const parse_macro_expansion_report: &str = "`text` (default) or `json`";
+    pub const parse_apply_suggestions: &str =
+        "a comma-separated list of `machine-applicable` or `maybe-incorrect`, and `dry-run`";
     pub const parse_passes: &str = "a space-separated list of passes, or `all`";
     pub const parse_panic_strategy: &str = "either `unwind` or `abort`";
     pub const parse_on_broken_pipe: &str = "either `kill`, `error`, or `inherit`";
@@ -431,6 +439,8 @@ mod desc {
     pub const parse_linker_features: &str =
         "a list of enabled (`+` prefix) and disabled (`-` prefix) features: `lld`";
     pub const parse_polonius: &str = "either no value or `legacy` (the default), or `next`";
+    pub const parse_polymorphize: &str =
+        "either a boolean (`yes`, `no`, `on`, `off`, etc), or `layout`";
     pub const parse_stack_protector: &str =
         "one of (`none` (default), `basic`, `strong`, or `all`)";
     pub const parse_branch_protection: &str =
@@ -509,6 +519,21 @@ pub(crate) fn parse_polonius(slot: &mut Polonius, v: Option<&str>) -> bool {
         }
     }
 
+    /// Parses whether polymorphization is enabled, and if so, which parameters it merges.
+    pub(crate) fn parse_polymorphize(slot: &mut Polymorphize, v: Option<&str>) -> bool {
+        let mut bool_arg = None;
+        if parse_opt_bool(&mut bool_arg, v) {
+            *slot = if bool_arg.unwrap() { Polymorphize::Unused } else { Polymorphize::Off };
+            return true;
+        }
+
+        *slot = match v {
+            Some("layout") => Polymorphize::Layout,
+            _ => return false,
+        };
+        true
+    }
+
     /// Use this for any string option that has a static default.
     pub(crate) fn parse_string(slot: &mut String, v: Option<&str>) -> bool {
         match v {
@@ -952,6 +977,80 @@ pub(crate) fn parse_time_passes_format(slot: &mut TimePassesFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_print_type_sizes_format(
+        slot: &mut PrintTypeSizesFormat,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None => true,
+            Some("json") => {
+                *slot = PrintTypeSizesFormat::Json;
+                true
+            }
+            Some("text") => {
+                *slot = PrintTypeSizesFormat::Text;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub(crate) fn parse_extern_usage_report(
+        slot: &mut Option<ExternUsageReportFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None | Some("text") => {
+                *slot = Some(ExternUsageReportFormat::Text);
+                true
+            }
+            Some("json") => {
+                *slot = Some(ExternUsageReportFormat::Json);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub(crate) fn parse_macro_expansion_report(
+        slot: &mut Option<MacroExpansionReportFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None | Some("text") => {
+                *slot = Some(MacroExpansionReportFormat::Text);
+                true
+            }
+            Some("json") => {
+                *slot = Some(MacroExpansionReportFormat::Json);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub(crate) fn parse_apply_suggestions(
+        slot: &mut Option<ApplySuggestions>,
+        v: Option<&str>,
+    ) -> bool {
+        let mut apply_suggestions =
+            ApplySuggestions { applicability: Applicability::MachineApplicable, dry_run: false };
+        for s in v.into_iter().flat_map(|v| v.split(',')) {
+            match s {
+                "machine-applicable" => {
+                    apply_suggestions.applicability = Applicability::MachineApplicable
+                }
+                "maybe-incorrect" => {
+                    apply_suggestions.applicability = Applicability::MaybeIncorrect
+                }
+                "dry-run" => apply_suggestions.dry_run = true,
+                _ => return false,
+            }
+        }
+        *slot = Some(apply_suggestions);
+        true
+    }
+
     pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&str>) -> bool {
         match v {
             None => true,
@@ -1630,6 +1729,10 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "only allow the listed language features to be enabled in code (comma separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<ApplySuggestions> = (None, parse_apply_suggestions, [UNTRACKED],
+        "apply the suggestions of the emitted diagnostics to the source files, or print them as a \
+        diff with `dry-run` (comma separated: `machine-applicable` (default) or `maybe-incorrect`, \
+        and `dry-run`)"),
     assert_incr_state: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "assert that the incremental cache is in given state: \
          either `loaded` or `not-loaded`."),
@@ -1693,7 +1796,7 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "in addition to `.mir` files, create graphviz `.dot` files (default: no)"),
     dump_mono_stats: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
-        "output statistics about monomorphization collection"),
+        "output statistics about monomorphization collection and codegen"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
@@ -1711,6 +1814,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "emit the bc module with thin LTO info (default: yes)"),
     export_executable_symbols: bool = (false, parse_bool, [TRACKED],
         "export symbols from executables, as if they were dynamic libraries"),
+    extern_usage_report: Option<ExternUsageReportFormat> = (None, parse_extern_usage_report, [UNTRACKED],
+        "report how each `--extern` crate is used: not at all, only by tests, only for its \
+        macros, or by code (`text` (default) or `json`)"),
     external_clangrt: bool = (false, parse_bool, [UNTRACKED],
         "rely on user specified linker commands to find clangrt"),
     extra_const_ub_checks: bool = (false, parse_bool, [TRACKED],
@@ -1734,6 +1840,8 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "whether each function should go in its own section"),
     future_incompat_test: bool = (false, parse_bool, [UNTRACKED],
         "forces all lints to be future incompatible, used for internal testing (default: no)"),
+    future_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "the size at which the `large_futures` lint starts to be emitted"),
     graphviz_dark_mode: bool = (false, parse_bool, [UNTRACKED],
         "use dark-themed colors in graphviz output (default: no)"),
     graphviz_font: String = ("Courier, monospace".to_string(), parse_string, [UNTRACKED],
@@ -1814,6 +1922,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         (space separated)"),
     macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
         "show macro backtraces (default: no)"),
+    macro_expansion_report: Option<MacroExpansionReportFormat> = (None, parse_macro_expansion_report, [UNTRACKED],
+        "print the time spent expanding each macro and the number of tokens it produced, \
+        per macro and per invocation (`text` (default) or `json`)"),
     maximal_hir_to_mir_coverage: bool = (false, parse_bool, [TRACKED],
         "save as much information as possible about the correspondence between MIR and HIR \
         as source scopes (default: no)"),
@@ -1894,8 +2005,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         (default: PLT is disabled if full relro is enabled on x86_64)"),
     polonius: Polonius = (Polonius::default(), parse_polonius, [TRACKED],
         "enable polonius-based borrow-checker (default: no)"),
-    polymorphize: bool = (false, parse_bool, [TRACKED],
-          "perform polymorphization analysis"),
+    polymorphize: Polymorphize = (Polymorphize::default(), parse_polymorphize, [TRACKED],
+          "perform polymorphization analysis, `layout` also merges the instances that only differ \
+          in generic parameters that do not affect the layout (default: no)"),
     pre_link_arg: (/* redirected to pre_link_args */) = ((), parse_string_push, [UNTRACKED],
         "a single extra argument to prepend the linker invocation (can be used several times)"),
     pre_link_args: Vec<String> = (Vec::new(), parse_list, [UNTRACKED],
@@ -1917,6 +2029,11 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
          Note that this overwrites the effect `-Clink-dead-code` has on collection!"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered (default: no)"),
+    print_type_sizes_format: PrintTypeSizesFormat = (PrintTypeSizesFormat::Text, parse_print_type_sizes_format, [UNTRACKED],
+        "the format to use for -Z print-type-sizes (`text` or `json`, default: `text`)"),
+    print_type_sizes_threshold: u64 = (0, parse_number, [UNTRACKED],
+        "only print the layout of types of at least this many bytes with -Z print-type-sizes \
+        (default: 0)"),
     print_vtable_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print size comparison between old and new vtable layouts (default: no)"),
     proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
@@ -1968,6 +2085,10 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     self_profile: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
         "run the self profiler and output the raw event data"),
+    self_profile_summary: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write a JSON summary of the time spent in each query and pass to \
+        `<crate>-<pid>.summary.json` in the given directory (default: the working directory)"),
     self_profile_counter: String = ("wall-time".to_string(), parse_string, [UNTRACKED],
         "counter used by the self profiler (default: `wall-time`), one of:
         `wall-time` (monotonic clock, i.e. `std::time::Instant`)
@@ -1980,6 +2101,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    semver_baseline: Option<String> = (None, parse_opt_string, [TRACKED],
+        "compare the public API of the crate against a previous release, passed as \
+        `--extern <name>=<path>`, and report major and minor changes"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     shell_argfiles: bool = (false, parse_bool, [UNTRACKED],
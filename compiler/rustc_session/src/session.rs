@@ -13,7 +13,7 @@
 use rustc_data_structures::flock;
 use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
 use rustc_data_structures::jobserver::{self, Client};
-use rustc_data_structures::profiling::{SelfProfiler, SelfProfilerRef};
+use rustc_data_structures::profiling::{SelfProfileSummary, SelfProfiler, SelfProfilerRef};
 use rustc_data_structures::sync::{
     AtomicU64, DynSend, DynSync, Lock, Lrc, MappedReadGuard, ReadGuard, RwLock,
 };
@@ -21,6 +21,7 @@
 use rustc_errors::emitter::{stderr_destination, DynEmitter, HumanEmitter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{
     codes::*, fallback_fluent_bundle, Diag, DiagCtxt, DiagCtxtHandle, DiagMessage, Diagnostic,
     ErrorGuaranteed, FatalAbort, FluentBundle, LazyFallbackBundle, TerminalUrl,
@@ -126,6 +127,9 @@ pub struct Limits {
     /// The size at which the `large_assignments` lint starts
     /// being emitted.
     pub move_size_limit: Limit,
+    /// The size at which the `large_futures` lint starts
+    /// being emitted.
+    pub future_size_limit: Limit,
     /// The maximum length of types during monomorphization.
     pub type_length_limit: Limit,
 }
@@ -999,6 +1003,22 @@ fn default_emitter(
             .track_diagnostics(track_diagnostics)
             .terminal_url(terminal_url),
         ),
+        config::ErrorOutputType::Sarif { pretty } => Box::new(
+            SarifEmitter::new(
+                Box::new(io::BufWriter::new(io::stderr())),
+                source_map,
+                fallback_bundle,
+                pretty,
+            )
+            .registry(Some(registry))
+            .fluent_bundle(bundle)
+            .crate_name(sopts.crate_name.clone())
+            .tool_version(if sopts.unstable_opts.ui_testing {
+                None
+            } else {
+                option_env!("CFG_VERSION")
+            }),
+        ),
     }
 }
 
@@ -1098,8 +1118,17 @@ pub fn build_session(
     });
     let print_fuel = AtomicU64::new(0);
 
+    let self_profile_summary =
+        if let SwitchWithOptPath::Enabled(ref d) = sopts.unstable_opts.self_profile_summary {
+            let directory = d.as_deref().unwrap_or(std::path::Path::new("."));
+            Some(Arc::new(SelfProfileSummary::new(directory, sopts.crate_name.as_deref())))
+        } else {
+            None
+        };
+
     let prof = SelfProfilerRef::new(
         self_profiler,
+        self_profile_summary,
         sopts.unstable_opts.time_passes.then(|| sopts.unstable_opts.time_passes_format),
     );
 
@@ -1441,6 +1470,15 @@ fn mk_emitter(output: ErrorOutputType) -> Box<DynEmitter> {
             pretty,
             json_rendered,
         )),
+        config::ErrorOutputType::Sarif { pretty } => Box::new(
+            SarifEmitter::new(
+                Box::new(io::BufWriter::new(io::stderr())),
+                Lrc::new(SourceMap::new(FilePathMapping::empty())),
+                fallback_bundle,
+                pretty,
+            )
+            .emit_empty_log(false),
+        ),
     };
     emitter
 }
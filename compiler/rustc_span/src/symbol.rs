@@ -935,6 +935,7 @@
         fused_iterator,
         future,
         future_output,
+        future_size_limit,
         future_trait,
         gdb_script_file,
         ge,
@@ -1080,6 +1081,7 @@
         lang,
         lang_items,
         large_assignments,
+        large_futures,
         lateout,
         lazy_normalization_consts,
         lazy_type_alias,
@@ -1876,6 +1878,7 @@
         test_case,
         test_removed_feature,
         test_runner,
+        test_timeout,
         test_unstable_lint,
         thread,
         thread_local,
@@ -93,6 +93,9 @@ pub(super) fn mangle<'tcx>(
         ty::InstanceKind::CoroutineKindShim { .. } => {
             printer.write_str("{{by-move-body-shim}}").unwrap();
         }
+        ty::InstanceKind::ColdPathShim(_, index) => {
+            write!(printer, "{{{{cold-path-shim#{index}}}}}").unwrap();
+        }
         _ => {}
     }
 
@@ -152,6 +155,10 @@ fn get_symbol_hash<'tcx>(
             // Especially, `VTableShim`s and `ReifyShim`s may overlap with their original
             // instances without this.
             discriminant(&instance.def).hash_stable(hcx, &mut hasher);
+            // The outlined cold paths of a function only differ by their index.
+            if let ty::InstanceKind::ColdPathShim(_, index) = instance.def {
+                index.hash_stable(hcx, &mut hasher);
+            }
         });
 
         // 64 bits should be enough to avoid collisions.
@@ -236,8 +236,11 @@ fn compute_symbol_name<'tcx>(
     // If this is an instance of a generic function, we also hash in
     // the ID of the instantiating crate. This avoids symbol conflicts
     // in case the same instances is emitted in two crates of the same
-    // project.
-    let avoid_cross_crate_conflicts = is_generic(instance, tcx) || is_globally_shared_function;
+    // project. The outlined cold paths of generic functions are emitted
+    // by each crate that instantiates the function as well.
+    let avoid_cross_crate_conflicts = is_generic(instance, tcx)
+        || is_globally_shared_function
+        || matches!(instance.def, ty::InstanceKind::ColdPathShim(..));
 
     let instantiating_crate = avoid_cross_crate_conflicts.then(compute_instantiating_crate);
 
@@ -42,6 +42,7 @@ pub(super) fn mangle<'tcx>(
     };
 
     // Append `::{shim:...#0}` to shims that can coexist with a non-shim instance.
+    let mut shim_disambiguator = 0;
     let shim_kind = match instance.def {
         ty::InstanceKind::ThreadLocalShim(_) => Some("tls"),
         ty::InstanceKind::VTableShim(_) => Some("vtable"),
@@ -58,12 +59,17 @@ pub(super) fn mangle<'tcx>(
             Some("by_ref")
         }
         ty::InstanceKind::CoroutineKindShim { .. } => Some("by_move_body"),
+        ty::InstanceKind::ColdPathShim(_, index) => {
+            shim_disambiguator = index as u64;
+            Some("cold_path")
+        }
 
         _ => None,
     };
 
     if let Some(shim_kind) = shim_kind {
-        cx.path_append_ns(|cx| cx.print_def_path(def_id, args), 'S', 0, shim_kind).unwrap()
+        cx.path_append_ns(|cx| cx.print_def_path(def_id, args), 'S', shim_disambiguator, shim_kind)
+            .unwrap()
     } else {
         cx.print_def_path(def_id, args).unwrap()
     };
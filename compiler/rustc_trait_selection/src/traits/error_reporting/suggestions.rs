@@ -55,6 +55,9 @@
 use itertools::EitherOrBoth;
 use itertools::Itertools;
 
+/// The maximum number of outer futures that `note_await_chain` explains.
+const MAX_AWAIT_CHAIN_NOTES: usize = 8;
+
 #[derive(Debug)]
 pub enum CoroutineInteriorOrUpvar {
     // span of interior type
@@ -63,6 +66,21 @@ pub enum CoroutineInteriorOrUpvar {
     Upvar(Span),
 }
 
+/// Records that `coroutine_did` holds a value of type `held_ty`, unless `coroutine_did` is already
+/// the last coroutine of the chain, as both a coroutine and its witness are part of the chain of
+/// obligations.
+fn push_to_coroutine_chain<'tcx>(
+    coroutine_chain: &mut Vec<(DefId, Ty<'tcx>)>,
+    coroutine_did: DefId,
+    held_ty: Option<Ty<'tcx>>,
+) {
+    if let Some(held_ty) = held_ty
+        && coroutine_chain.last().map_or(true, |&(last_did, _)| last_did != coroutine_did)
+    {
+        coroutine_chain.push((coroutine_did, held_ty));
+    }
+}
+
 // This type provides a uniform interface to retrieve data on coroutines, whether it originated from
 // the local crate being compiled or from a foreign crate.
 #[derive(Debug)]
@@ -2250,12 +2268,19 @@ fn maybe_note_obligation_cause_for_async_await<G: EmissionGuarantee>(
         // the type. The last coroutine (`outer_coroutine` below) has information about where the
         // bound was introduced. At least one coroutine should be present for this diagnostic to be
         // modified.
+        //
+        // Every coroutine in the chain is also recorded in `coroutine_chain`, together with the
+        // type it holds that fails to implement the trait: for B that is the captured type, for
+        // A it is the future returned by B. This lets us point at the await in A that keeps the
+        // future returned by B alive.
         let (mut trait_ref, mut target_ty) = match obligation.predicate.kind().skip_binder() {
             ty::PredicateKind::Clause(ty::ClauseKind::Trait(p)) => (Some(p), Some(p.self_ty())),
             _ => (None, None),
         };
         let mut coroutine = None;
         let mut outer_coroutine = None;
+        let mut coroutine_chain: Vec<(DefId, Ty<'tcx>)> = Vec::new();
+        let mut held_ty = target_ty;
         let mut next_code = Some(obligation.cause.code());
 
         let mut seen_upvar_tys_infer_tuple = false;
@@ -2278,6 +2303,7 @@ fn maybe_note_obligation_cause_for_async_await<G: EmissionGuarantee>(
                         ty::Coroutine(did, ..) | ty::CoroutineWitness(did, _) => {
                             coroutine = coroutine.or(Some(did));
                             outer_coroutine = Some(did);
+                            push_to_coroutine_chain(&mut coroutine_chain, did, held_ty);
                         }
                         ty::Tuple(_) if !seen_upvar_tys_infer_tuple => {
                             // By introducing a tuple of upvar types into the chain of obligations
@@ -2289,8 +2315,9 @@ fn maybe_note_obligation_cause_for_async_await<G: EmissionGuarantee>(
                         _ if coroutine.is_none() => {
                             trait_ref = Some(cause.derived.parent_trait_pred.skip_binder());
                             target_ty = Some(ty);
+                            held_ty = Some(ty);
                         }
-                        _ => {}
+                        _ => held_ty = Some(ty),
                     }
 
                     next_code = Some(&cause.derived.parent_code);
@@ -2307,6 +2334,7 @@ fn maybe_note_obligation_cause_for_async_await<G: EmissionGuarantee>(
                         ty::Coroutine(did, ..) | ty::CoroutineWitness(did, ..) => {
                             coroutine = coroutine.or(Some(did));
                             outer_coroutine = Some(did);
+                            push_to_coroutine_chain(&mut coroutine_chain, did, held_ty);
                         }
                         ty::Tuple(_) if !seen_upvar_tys_infer_tuple => {
                             // By introducing a tuple of upvar types into the chain of obligations
@@ -2318,8 +2346,9 @@ fn maybe_note_obligation_cause_for_async_await<G: EmissionGuarantee>(
                         _ if coroutine.is_none() => {
                             trait_ref = Some(derived_obligation.parent_trait_pred.skip_binder());
                             target_ty = Some(ty);
+                            held_ty = Some(ty);
                         }
-                        _ => {}
+                        _ => held_ty = Some(ty),
                     }
 
                     next_code = Some(&derived_obligation.parent_code);
@@ -2441,6 +2470,7 @@ fn maybe_note_obligation_cause_for_async_await<G: EmissionGuarantee>(
                 interior_or_upvar_span,
                 is_async,
                 outer_coroutine,
+                &coroutine_chain,
                 trait_ref,
                 target_ty,
                 obligation,
@@ -2461,6 +2491,7 @@ fn note_obligation_cause_for_async_await<G: EmissionGuarantee>(
         interior_or_upvar_span: CoroutineInteriorOrUpvar,
         is_async: bool,
         outer_coroutine: Option<DefId>,
+        coroutine_chain: &[(DefId, Ty<'tcx>)],
         trait_pred: ty::TraitPredicate<'tcx>,
         target_ty: Ty<'tcx>,
         obligation: &PredicateObligation<'tcx>,
@@ -2676,6 +2707,11 @@ fn note_obligation_cause_for_async_await<G: EmissionGuarantee>(
             }
         }
 
+        // Explain how the outer futures end up holding the one that was explained above.
+        if is_async {
+            self.note_await_chain(err, coroutine_chain, &trait_explanation);
+        }
+
         // Add a note for the item obligation that remains - normally a note pointing to the
         // bound that introduced the obligation (e.g. `T: Send`).
         debug!(?next_code);
@@ -2690,6 +2726,71 @@ fn note_obligation_cause_for_async_await<G: EmissionGuarantee>(
         );
     }
 
+    /// Adds a note for each of the outer futures in `coroutine_chain`, pointing at the await
+    /// through which it holds the future before it in the chain. The chain stops at the first
+    /// future whose await cannot be found, e.g. because it comes from another crate.
+    ///
+    /// ```text
+    /// note: future is not `Send` as it awaits another future which is not `Send`
+    ///   --> $DIR/await-chain-send.rs:15:5
+    ///    |
+    /// LL |     inner().await;
+    ///    |     ^^^^^^^ await occurs here on type `impl Future<Output = ()>`, which is not `Send`
+    /// ```
+    fn note_await_chain<G: EmissionGuarantee>(
+        &self,
+        err: &mut Diag<'_, G>,
+        coroutine_chain: &[(DefId, Ty<'tcx>)],
+        trait_explanation: &str,
+    ) {
+        let hir = self.tcx.hir();
+        // The first future of the chain is explained by `note_obligation_cause_for_async_await`.
+        for (i, &(coroutine_did, awaited_ty)) in coroutine_chain.iter().enumerate().skip(1) {
+            if i > MAX_AWAIT_CHAIN_NOTES {
+                let remaining = coroutine_chain.len() - i;
+                let s = pluralize!(remaining);
+                err.note(format!("the await chain continues through {remaining} more future{s}"));
+                return;
+            }
+
+            let Some(body) =
+                coroutine_did.as_local().and_then(|def_id| hir.maybe_body_owned_by(def_id))
+            else {
+                return;
+            };
+            let coroutine_did_root = self.tcx.typeck_root_def_id(coroutine_did);
+            let coroutine_data = match &self.typeck_results {
+                Some(t) if t.hir_owner.to_def_id() == coroutine_did_root => CoroutineData(t),
+                _ => CoroutineData(self.tcx.typeck(coroutine_did.expect_local())),
+            };
+            let mut visitor = AwaitsVisitor::default();
+            visitor.visit_body(&body);
+
+            // See `maybe_note_obligation_cause_for_async_await` for why regions are erased here.
+            let awaited_ty_erased = self.tcx.erase_regions(awaited_ty);
+            let ty_matches = |ty| {
+                let ty_erased = self.tcx.instantiate_bound_regions_with_erased(ty);
+                self.tcx.erase_regions(ty_erased) == awaited_ty_erased
+            };
+            let Some(await_span) = coroutine_data.get_from_await_ty(visitor, hir, ty_matches)
+            else {
+                return;
+            };
+
+            let mut span = MultiSpan::from_span(await_span);
+            span.push_span_label(
+                await_span,
+                format!("await occurs here on type `{awaited_ty}`, which {trait_explanation}"),
+            );
+            err.span_note(
+                span,
+                format!(
+                    "future {trait_explanation} as it awaits another future which {trait_explanation}"
+                ),
+            );
+        }
+    }
+
     fn note_obligation_cause_code<G: EmissionGuarantee, T>(
         &self,
         body_id: LocalDefId,
@@ -43,6 +43,18 @@ fn fn_sig_for_fn_abi<'tcx>(
         ));
     }
 
+    // The signature of an outlined cold path is the one of its MIR body.
+    if let InstanceKind::ColdPathShim(def_id, index) = instance.def {
+        let body = &tcx.optimized_mir(def_id).outlined_cold_paths[index];
+        return ty::Binder::dummy(tcx.mk_fn_sig(
+            body.args_iter().map(|local| body.local_decls[local].ty),
+            body.return_ty(),
+            false,
+            hir::Safety::Safe,
+            rustc_target::spec::abi::Abi::Rust,
+        ));
+    }
+
     let ty = instance.ty(tcx, param_env);
     match *ty.kind() {
         ty::FnDef(..) => {
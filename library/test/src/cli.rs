@@ -2,6 +2,7 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
 use super::time::TestTimeOptions;
@@ -25,11 +26,20 @@ pub struct TestOpts {
     pub shuffle_seed: Option<u64>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
+    /// Only run the tests in the given shard, as `(index, count)` where `index`
+    /// starts from 1. See `--shard`.
+    pub shard: Option<(usize, usize)>,
     pub time_options: Option<TestTimeOptions>,
+    /// Time after which a test is stopped and reported as timed out, unless the
+    /// test has a timeout of its own.
+    pub test_timeout: Option<Duration>,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
     pub fail_fast: bool,
+    /// Number of times a failing test is run again before it's reported as
+    /// failed. Tests that pass on a retry are reported as flaky.
+    pub retries: usize,
     pub options: Options,
 }
 
@@ -102,8 +112,9 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP 14 stream",
+            "pretty|terse|json|junit|tap",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -142,6 +153,31 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Stop tests that run for longer than SECS seconds and report them as
+            timed out. Tests run in a subprocess (with panic=abort) are killed;
+            tests run in-process are left running in the background and the
+            test harness exits once the remaining tests are finished.",
+            "SECS",
+        )
+        .optopt(
+            "",
+            "retries",
+            "Run failing tests again up to N times before reporting them as
+            failed. Tests that pass on a retry are reported as flaky. Only
+            static tests (such as `#[test]` functions) can be retried.",
+            "N",
+        )
+        .optopt(
+            "",
+            "shard",
+            "Split the tests into COUNT shards and only run the tests in shard
+            INDEX, counting from 1. Every test is assigned to exactly one shard
+            based on its name.",
+            "INDEX/COUNT",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
@@ -171,6 +207,13 @@ fn usage(binary: &str, options: &getopts::Options) {
 tests in the same order again. Note that --shuffle and --shuffle-seed do not
 affect whether the tests are run in parallel.
 
+The tests can be split across several runs with --shard INDEX/COUNT, which only
+runs the tests in shard INDEX (counting from 1) out of COUNT shards. A test's
+shard only depends on its name, so running each of the shards 1/COUNT up to
+COUNT/COUNT with the same filters runs every test exactly once. The shard is
+chosen independently of --shuffle and --shuffle-seed, and --list only lists
+the tests in the given shard.
+
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or setting RUST_TEST_NOCAPTURE
 environment variable to a value other than "0". Logging is not captured by default.
@@ -259,8 +302,11 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let force_run_in_process = unstable_optflag!(matches, allow_unstable, "force-run-in-process");
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
+    let retries = get_retries(&matches, allow_unstable)?;
+    let shard = get_shard(&matches, allow_unstable)?;
 
     let include_ignored = matches.opt_present("include-ignored");
     let quiet = matches.opt_present("quiet");
@@ -298,9 +344,12 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         shuffle_seed,
         test_threads,
         skip,
+        shard,
         time_options,
+        test_timeout,
         options,
         fail_fast: false,
+        retries,
     };
 
     Ok(test_opts)
@@ -336,6 +385,26 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let test_timeout = match unstable_optopt!(matches, allow_unstable, "test-timeout") {
+        Some(secs_str) => match secs_str.parse::<u64>() {
+            Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+            _ => {
+                return Err(format!(
+                    "argument for --test-timeout must be a positive number of seconds \
+                     (was {secs_str})"
+                ));
+            }
+        },
+        None => None,
+    };
+
+    Ok(test_timeout)
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
     let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
     if !shuffle && allow_unstable {
@@ -375,6 +444,48 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_retries(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<usize> {
+    let retries = match unstable_optopt!(matches, allow_unstable, "retries") {
+        Some(n_str) => match n_str.parse::<usize>() {
+            Ok(n) => n,
+            Err(e) => {
+                return Err(format!(
+                    "argument for --retries must be a number \
+                     (error: {e})"
+                ));
+            }
+        },
+        None => 0,
+    };
+
+    Ok(retries)
+}
+
+fn get_shard(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<(usize, usize)>> {
+    let shard = match unstable_optopt!(matches, allow_unstable, "shard") {
+        Some(shard_str) => {
+            let parsed = shard_str
+                .split_once('/')
+                .and_then(|(index, count)| Some((index.parse().ok()?, count.parse().ok()?)));
+            match parsed {
+                Some((index, count)) if 0 < index && index <= count => Some((index, count)),
+                _ => {
+                    return Err(format!(
+                        "argument for --shard must be of the form INDEX/COUNT, \
+                         where 1 <= INDEX <= COUNT (was {shard_str})"
+                    ));
+                }
+            }
+        }
+        None => None,
+    };
+
+    Ok(shard)
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -414,9 +525,15 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler with -Z unstable-options".into());
+            }
+            OutputFormat::Tap
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit or tap (was \
                  {v})"
             ));
         }
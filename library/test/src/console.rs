@@ -3,14 +3,17 @@
 use std::fs::File;
 use std::io;
 use std::io::prelude::Write;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use super::{
     bench::fmt_bench_samples,
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
     filter_tests,
-    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
+    formatters::{
+        JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TapFormatter,
+        TerseFormatter,
+    },
     helpers::{concurrency::get_concurrency, metrics::MetricMap},
     options::{Options, OutputFormat},
     run_tests, term,
@@ -82,12 +85,20 @@ pub struct ConsoleTestState {
     pub ignored: usize,
     pub filtered_out: usize,
     pub measured: usize,
+    /// Tests that failed at first but passed on a retry. These are not counted
+    /// as passed.
+    pub flaky: usize,
+    pub max_retries: usize,
     pub exec_time: Option<TestSuiteExecTime>,
     pub metrics: MetricMap,
     pub failures: Vec<(TestDesc, Vec<u8>)>,
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub ignores: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
+    /// Flaky tests along with the number of retries they needed to pass.
+    pub flaky_tests: Vec<(TestDesc, usize)>,
+    /// Tests that timed out, also listed in `failures`, along with their timeout.
+    pub timeouts: Vec<(TestDesc, Duration)>,
     pub options: Options,
 }
 
@@ -106,12 +117,16 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             ignored: 0,
             filtered_out: 0,
             measured: 0,
+            flaky: 0,
+            max_retries: opts.retries,
             exec_time: None,
             metrics: MetricMap::new(),
             failures: Vec::new(),
             not_failures: Vec::new(),
             ignores: Vec::new(),
             time_failures: Vec::new(),
+            flaky_tests: Vec::new(),
+            timeouts: Vec::new(),
             options: opts.options,
         })
     }
@@ -154,6 +169,9 @@ pub fn write_log_result(
                     }
                     TestResult::TrBench(ref bs) => fmt_bench_samples(bs),
                     TestResult::TrTimedFail => "failed (time limit exceeded)".to_owned(),
+                    TestResult::TrTimedOut(timeout) => {
+                        format!("failed (timed out after {}s)", timeout.as_secs())
+                    }
                 },
                 name,
             )
@@ -165,7 +183,7 @@ pub fn write_log_result(
     }
 
     fn current_test_count(&self) -> usize {
-        self.passed + self.failed + self.ignored + self.measured
+        self.passed + self.failed + self.ignored + self.measured + self.flaky
     }
 }
 
@@ -177,7 +195,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     };
 
     let mut out: Box<dyn OutputFormatter> = match opts.format {
-        OutputFormat::Pretty | OutputFormat::Junit => {
+        OutputFormat::Pretty | OutputFormat::Junit | OutputFormat::Tap => {
             Box::new(PrettyFormatter::new(output, false, 0, false, None))
         }
         OutputFormat::Terse => Box::new(TerseFormatter::new(output, false, 0, false)),
@@ -216,6 +234,11 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
     let test = completed_test.desc;
     let stdout = completed_test.stdout;
     match completed_test.result {
+        TestResult::TrOk if completed_test.retries > 0 => {
+            st.flaky += 1;
+            st.flaky_tests.push((test.clone(), completed_test.retries));
+            st.not_failures.push((test, stdout));
+        }
         TestResult::TrOk => {
             st.passed += 1;
             st.not_failures.push((test, stdout));
@@ -246,6 +269,11 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
             st.failed += 1;
             st.time_failures.push((test, stdout));
         }
+        TestResult::TrTimedOut(timeout) => {
+            st.failed += 1;
+            st.timeouts.push((test.clone(), timeout));
+            st.failures.push((test, stdout));
+        }
     }
 }
 
@@ -273,9 +301,19 @@ fn on_test_event(
             let stdout = &completed_test.stdout;
 
             st.write_log_result(test, result, exec_time.as_ref())?;
-            out.write_result(test, result, exec_time.as_ref(), stdout, st)?;
+            out.write_result(test, result, exec_time.as_ref(), stdout, completed_test.retries, st)?;
             handle_test_result(st, completed_test);
         }
+        TestEvent::TeRetry(ref completed_test) => {
+            let test = &completed_test.desc;
+            let result = &completed_test.result;
+            let exec_time = &completed_test.exec_time;
+            let stdout = &completed_test.stdout;
+
+            st.write_log_result(test, result, exec_time.as_ref())?;
+            st.write_log(|| format!("retry {} {}\n", completed_test.retries, test.name))?;
+            out.write_retry(test, result, exec_time.as_ref(), stdout, completed_test.retries, st)?;
+        }
     }
 
     Ok(())
@@ -310,6 +348,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
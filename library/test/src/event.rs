@@ -12,6 +12,9 @@ pub struct CompletedTest {
     pub result: TestResult,
     pub exec_time: Option<TestExecTime>,
     pub stdout: Vec<u8>,
+    /// For `TeResult`, the number of times the test was retried before this
+    /// result. For `TeRetry`, the number of the retry that is about to run.
+    pub retries: usize,
 }
 
 impl CompletedTest {
@@ -22,7 +25,7 @@ pub fn new(
         exec_time: Option<TestExecTime>,
         stdout: Vec<u8>,
     ) -> Self {
-        Self { id, desc, result, exec_time, stdout }
+        Self { id, desc, result, exec_time, stdout, retries: 0 }
     }
 }
 
@@ -31,6 +34,8 @@ pub enum TestEvent {
     TeFiltered(usize, Option<u64>),
     TeWait(TestDesc),
     TeResult(CompletedTest),
+    /// A test failed and is going to be run again.
+    TeRetry(CompletedTest),
     TeTimeout(TestDesc),
     TeFilteredOut(usize),
 }
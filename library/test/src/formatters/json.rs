@@ -17,6 +17,11 @@ pub fn new(out: OutputLocation<T>) -> Self {
         Self { out }
     }
 
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
     fn writeln_message(&mut self, s: &str) -> io::Result<()> {
         // self.out will take a lock, but that lock is released when write_all returns. This
         // results in a race condition and json output may not end with a new line. We avoid this
@@ -120,6 +125,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        retries: usize,
         state: &ConsoleTestState,
     ) -> io::Result<()> {
         let display_stdout = state.options.display_output || *result != TestResult::TrOk;
@@ -128,14 +134,31 @@ fn write_result(
         } else {
             None
         };
+        // Only tests that were retried mention it, so that the output of runs without
+        // `--retries` stays the same.
+        let with_retries = |extra: Option<&str>| match (extra, retries) {
+            (extra, 0) => extra.map(str::to_owned),
+            (Some(extra), retries) => Some(format!(r#"{extra}, "retries": {retries}"#)),
+            (None, retries) => Some(format!(r#""retries": {retries}"#)),
+        };
         match *result {
-            TestResult::TrOk => {
-                self.write_event("test", desc.name.as_slice(), "ok", exec_time, stdout, None)
-            }
+            TestResult::TrOk => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "ok",
+                exec_time,
+                stdout,
+                with_retries(None).as_deref(),
+            ),
 
-            TestResult::TrFailed => {
-                self.write_event("test", desc.name.as_slice(), "failed", exec_time, stdout, None)
-            }
+            TestResult::TrFailed => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                with_retries(None).as_deref(),
+            ),
 
             TestResult::TrTimedFail => self.write_event(
                 "test",
@@ -143,7 +166,20 @@ fn write_result(
                 "failed",
                 exec_time,
                 stdout,
-                Some(r#""reason": "time limit exceeded""#),
+                with_retries(Some(r#""reason": "time limit exceeded""#)).as_deref(),
+            ),
+
+            TestResult::TrTimedOut(timeout) => self.write_event(
+                "test",
+                desc.name.as_slice(),
+                "failed",
+                exec_time,
+                stdout,
+                with_retries(Some(&*format!(
+                    r#""reason": "timed out", "timeout": {}"#,
+                    timeout.as_secs()
+                )))
+                .as_deref(),
             ),
 
             TestResult::TrFailedMsg(ref m) => self.write_event(
@@ -152,7 +188,7 @@ fn write_result(
                 "failed",
                 exec_time,
                 stdout,
-                Some(&*format!(r#""message": "{}""#, EscapedString(m))),
+                with_retries(Some(&*format!(r#""message": "{}""#, EscapedString(m)))).as_deref(),
             ),
 
             TestResult::TrIgnored => self.write_event(
@@ -187,6 +223,33 @@ fn write_result(
         }
     }
 
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        attempt: usize,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        let stdout = if display_stdout && !stdout.is_empty() {
+            Some(String::from_utf8_lossy(stdout))
+        } else {
+            None
+        };
+        let extra = match *result {
+            TestResult::TrTimedFail => {
+                format!(r#""attempt": {attempt}, "reason": "time limit exceeded""#)
+            }
+            TestResult::TrFailedMsg(ref m) => {
+                format!(r#""attempt": {attempt}, "message": "{}""#, EscapedString(m))
+            }
+            _ => format!(r#""attempt": {attempt}"#),
+        };
+        self.write_event("test", desc.name.as_slice(), "retry", exec_time, stdout, Some(&extra))
+    }
+
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = EscapedString(desc.name.as_slice());
         let newline = "\n";
@@ -202,6 +265,8 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         let ignored = state.ignored;
         let measured = state.measured;
         let filtered_out = state.filtered_out;
+        let flaky_json =
+            if state.flaky > 0 { format!(r#", "flaky": {}"#, state.flaky) } else { String::new() };
         let exec_time_json = if let Some(ref exec_time) = state.exec_time {
             format!(r#", "exec_time": {}"#, exec_time.0.as_secs_f64())
         } else {
@@ -210,7 +275,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         let newline = "\n";
 
         self.writeln_message(&format!(
-            r#"{{ "type": "suite", "event": "{event}", "passed": {passed}, "failed": {failed}, "ignored": {ignored}, "measured": {measured}, "filtered_out": {filtered_out}{exec_time_json} }}{newline}"#
+            r#"{{ "type": "suite", "event": "{event}", "passed": {passed}, "failed": {failed}, "ignored": {ignored}, "measured": {measured}, "filtered_out": {filtered_out}{flaky_json}{exec_time_json} }}{newline}"#
         ))?;
 
         Ok(state.failed == 0)
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{self, prelude::Write};
 use std::time::Duration;
 
@@ -9,14 +10,23 @@
     types::{TestDesc, TestType},
 };
 
+/// A failed run of a test that was retried afterwards.
+struct FailedAttempt {
+    result: TestResult,
+    duration: Duration,
+    stdout: Vec<u8>,
+}
+
 pub struct JunitFormatter<T> {
     out: OutputLocation<T>,
-    results: Vec<(TestDesc, TestResult, Duration, Vec<u8>)>,
+    results: Vec<(TestDesc, TestResult, Duration, Vec<u8>, Vec<FailedAttempt>)>,
+    /// Failed attempts of tests that are being retried, by test name.
+    failed_attempts: HashMap<String, Vec<FailedAttempt>>,
 }
 
 impl<T: Write> JunitFormatter<T> {
     pub fn new(out: OutputLocation<T>) -> Self {
-        Self { out, results: Vec::new() }
+        Self { out, results: Vec::new(), failed_attempts: HashMap::new() }
     }
 
     fn write_message(&mut self, s: &str) -> io::Result<()> {
@@ -24,6 +34,35 @@ fn write_message(&mut self, s: &str) -> io::Result<()> {
 
         self.out.write_all(s.as_ref())
     }
+
+    /// Writes the earlier failed attempts of a retried test, following the
+    /// conventions of Maven Surefire: `flakyFailure` if the test passed in the
+    /// end, `rerunFailure` if it did not.
+    fn write_failed_attempts(
+        &mut self,
+        element: &str,
+        attempts: &[FailedAttempt],
+    ) -> io::Result<()> {
+        for attempt in attempts {
+            let failure_type = match attempt.result {
+                TestResult::TrTimedFail => "timeout",
+                _ => "assert",
+            };
+            self.write_message(&format!(
+                "<{element} type=\"{failure_type}\" time=\"{}\"",
+                attempt.duration.as_secs_f64()
+            ))?;
+            if attempt.stdout.is_empty() {
+                self.write_message("/>")?;
+            } else {
+                self.write_message("><system-out>")?;
+                self.write_message(&str_to_cdata(&String::from_utf8_lossy(&attempt.stdout)))?;
+                self.write_message("</system-out>")?;
+                self.write_message(&format!("</{element}>"))?;
+            }
+        }
+        Ok(())
+    }
 }
 
 fn str_to_cdata(s: &str) -> String {
@@ -76,15 +115,35 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        _retries: usize,
         _state: &ConsoleTestState,
     ) -> io::Result<()> {
         // Because the testsuite node holds some of the information as attributes, we can't write it
         // until all of the tests have finished. Instead of writing every result as they come in, we add
         // them to a Vec and write them all at once when run is complete.
         let duration = exec_time.map(|t| t.0).unwrap_or_default();
-        self.results.push((desc.clone(), result.clone(), duration, stdout.to_vec()));
+        let attempts = self.failed_attempts.remove(desc.name.as_slice()).unwrap_or_default();
+        self.results.push((desc.clone(), result.clone(), duration, stdout.to_vec(), attempts));
+        Ok(())
+    }
+
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        _attempt: usize,
+        _state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let duration = exec_time.map(|t| t.0).unwrap_or_default();
+        self.failed_attempts
+            .entry(desc.name.as_slice().to_owned())
+            .or_default()
+            .push(FailedAttempt { result: result.clone(), duration, stdout: stdout.to_vec() });
         Ok(())
     }
+
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         self.write_message("<testsuites>")?;
 
@@ -97,7 +156,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
              >",
             state.failed, state.total, state.ignored
         ))?;
-        for (desc, result, duration, stdout) in std::mem::take(&mut self.results) {
+        for (desc, result, duration, stdout, attempts) in std::mem::take(&mut self.results) {
             let (class_name, test_name) = parse_class_name(&desc);
             match result {
                 TestResult::TrIgnored => { /* no-op */ }
@@ -110,6 +169,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
                         duration.as_secs_f64()
                     ))?;
                     self.write_message("<failure type=\"assert\"/>")?;
+                    self.write_failed_attempts("rerunFailure", &attempts)?;
                     if !stdout.is_empty() {
                         self.write_message("<system-out>")?;
                         self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
@@ -127,6 +187,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
                         duration.as_secs_f64()
                     ))?;
                     self.write_message(&format!("<failure message=\"{m}\" type=\"assert\"/>"))?;
+                    self.write_failed_attempts("rerunFailure", &attempts)?;
                     if !stdout.is_empty() {
                         self.write_message("<system-out>")?;
                         self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
@@ -144,6 +205,28 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
                         duration.as_secs_f64()
                     ))?;
                     self.write_message("<failure type=\"timeout\"/>")?;
+                    self.write_failed_attempts("rerunFailure", &attempts)?;
+                    self.write_message("</testcase>")?;
+                }
+
+                TestResult::TrTimedOut(timeout) => {
+                    self.write_message(&format!(
+                        "<testcase classname=\"{}\" \
+                         name=\"{}\" time=\"{}\">",
+                        class_name,
+                        test_name,
+                        duration.as_secs_f64()
+                    ))?;
+                    self.write_message(&format!(
+                        "<failure message=\"timed out after {}s\" type=\"timeout\"/>",
+                        timeout.as_secs()
+                    ))?;
+                    self.write_failed_attempts("rerunFailure", &attempts)?;
+                    if !stdout.is_empty() {
+                        self.write_message("<system-out>")?;
+                        self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
+                        self.write_message("</system-out>")?;
+                    }
                     self.write_message("</testcase>")?;
                 }
 
@@ -163,12 +246,17 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
                         test_name,
                         duration.as_secs_f64()
                     ))?;
-                    if stdout.is_empty() || !state.options.display_output {
+                    let display_stdout = !stdout.is_empty() && state.options.display_output;
+                    if attempts.is_empty() && !display_stdout {
                         self.write_message("/>")?;
                     } else {
-                        self.write_message("><system-out>")?;
-                        self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
-                        self.write_message("</system-out>")?;
+                        self.write_message(">")?;
+                        self.write_failed_attempts("flakyFailure", &attempts)?;
+                        if display_stdout {
+                            self.write_message("<system-out>")?;
+                            self.write_message(&str_to_cdata(&String::from_utf8_lossy(&stdout)))?;
+                            self.write_message("</system-out>")?;
+                        }
                         self.write_message("</testcase>")?;
                     }
                 }
@@ -10,11 +10,13 @@
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
 pub(crate) use self::json::JsonFormatter;
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
@@ -31,6 +33,16 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        retries: usize,
+        state: &ConsoleTestState,
+    ) -> io::Result<()>;
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        attempt: usize,
         state: &ConsoleTestState,
     ) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
@@ -44,3 +56,15 @@ pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &Test
     }
     writeln!(test_output, "---- {test_name} stderr ----").unwrap();
 }
+
+/// Lists the tests that only passed after being retried, for the human-readable formatters.
+pub(crate) fn flaky_tests_summary(state: &ConsoleTestState) -> String {
+    let mut flaky = state.flaky_tests.iter().collect::<Vec<_>>();
+    flaky.sort_by(|(a, _), (b, _)| a.name.as_slice().cmp(b.name.as_slice()));
+    let mut summary = String::from("\nflaky tests:\n");
+    for (desc, retries) in flaky {
+        let noun = if *retries != 1 { "retries" } else { "retry" };
+        summary.push_str(&format!("    {} (passed after {retries} {noun})\n", desc.name));
+    }
+    summary
+}
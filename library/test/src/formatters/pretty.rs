@@ -1,6 +1,6 @@
-use std::{io, io::prelude::Write};
+use std::{io, io::prelude::Write, time::Duration};
 
-use super::OutputFormatter;
+use super::{flaky_tests_summary, OutputFormatter};
 use crate::{
     bench::fmt_bench_samples,
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
@@ -57,6 +57,22 @@ pub fn write_time_failed(&mut self) -> io::Result<()> {
         self.write_short_result("FAILED (time limit exceeded)", term::color::RED)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_short_result("FAILED (timed out)", term::color::RED)
+    }
+
+    pub fn write_retry_failed(&mut self, attempt: usize, max_retries: usize) -> io::Result<()> {
+        self.write_short_result(
+            &format!("FAILED, retrying ({attempt}/{max_retries})"),
+            term::color::YELLOW,
+        )
+    }
+
+    pub fn write_flaky(&mut self, retries: usize) -> io::Result<()> {
+        let noun = if retries != 1 { "retries" } else { "retry" };
+        self.write_short_result(&format!("ok (flaky, {retries} {noun})"), term::color::YELLOW)
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
@@ -126,6 +142,7 @@ fn write_time(
     fn write_results(
         &mut self,
         inputs: &Vec<(TestDesc, Vec<u8>)>,
+        timeouts: &[(TestDesc, Duration)],
         results_type: &str,
     ) -> io::Result<()> {
         let results_out_str = format!("\n{results_type}:\n");
@@ -142,6 +159,14 @@ fn write_results(
                 stdouts.push_str(&output);
                 stdouts.push('\n');
             }
+            // The note is not part of the captured output, so it's written after it.
+            if let Some((_, timeout)) = timeouts.iter().find(|(desc, _)| desc.name == f.name) {
+                stdouts.push_str(&format!(
+                    "note: test {} timed out after {}s\n",
+                    f.name,
+                    timeout.as_secs()
+                ));
+            }
         }
         if !stdouts.is_empty() {
             self.write_plain("\n")?;
@@ -157,15 +182,15 @@ fn write_results(
     }
 
     pub fn write_successes(&mut self, state: &ConsoleTestState) -> io::Result<()> {
-        self.write_results(&state.not_failures, "successes")
+        self.write_results(&state.not_failures, &[], "successes")
     }
 
     pub fn write_failures(&mut self, state: &ConsoleTestState) -> io::Result<()> {
-        self.write_results(&state.failures, "failures")
+        self.write_results(&state.failures, &state.timeouts, "failures")
     }
 
     pub fn write_time_failures(&mut self, state: &ConsoleTestState) -> io::Result<()> {
-        self.write_results(&state.time_failures, "failures (time limit exceeded)")
+        self.write_results(&state.time_failures, &[], "failures (time limit exceeded)")
     }
 
     fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -236,6 +261,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         _: &[u8],
+        retries: usize,
         _: &ConsoleTestState,
     ) -> io::Result<()> {
         if self.is_multithreaded {
@@ -243,6 +269,7 @@ fn write_result(
         }
 
         match *result {
+            TestResult::TrOk if retries > 0 => self.write_flaky(retries)?,
             TestResult::TrOk => self.write_ok()?,
             TestResult::TrFailed | TestResult::TrFailedMsg(_) => self.write_failed()?,
             TestResult::TrIgnored => self.write_ignored(desc.ignore_message)?,
@@ -251,8 +278,27 @@ fn write_result(
                 self.write_plain(format!(": {}", fmt_bench_samples(bs)))?;
             }
             TestResult::TrTimedFail => self.write_time_failed()?,
+            TestResult::TrTimedOut(_) => self.write_timed_out()?,
+        }
+
+        self.write_time(desc, exec_time)?;
+        self.write_plain("\n")
+    }
+
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        _: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        _: &[u8],
+        attempt: usize,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        if self.is_multithreaded {
+            self.write_test_name(desc)?;
         }
 
+        self.write_retry_failed(attempt, state.max_retries)?;
         self.write_time(desc, exec_time)?;
         self.write_plain("\n")
     }
@@ -280,6 +326,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             }
         }
 
+        if !state.flaky_tests.is_empty() {
+            self.write_plain(flaky_tests_summary(state))?;
+        }
+
         self.write_plain("\ntest result: ")?;
 
         if success {
@@ -296,6 +346,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
 
         self.write_plain(s)?;
 
+        if state.flaky > 0 {
+            self.write_plain(format!("; {} flaky", state.flaky))?;
+        }
+
         if let Some(ref exec_time) = state.exec_time {
             let time_str = format!("; finished in {exec_time}");
             self.write_plain(time_str)?;
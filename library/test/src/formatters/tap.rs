@@ -0,0 +1,233 @@
+use std::{io, io::prelude::Write};
+
+use super::OutputFormatter;
+use crate::{
+    console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Writes the results in the [TAP 14](https://testanything.org/tap-version-14-specification.html)
+/// format.
+///
+/// Test points are numbered in the order in which the tests finish. The failure
+/// message, execution time and captured output go into the YAML diagnostics block
+/// after the test point.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 0 }
+    }
+
+    fn writeln_message(&mut self, s: &str) -> io::Result<()> {
+        self.out.write_all(s.as_bytes())?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_test_point(
+        &mut self,
+        ok: bool,
+        desc: &TestDesc,
+        directive: Option<&str>,
+        diagnostics: &[(&str, String)],
+    ) -> io::Result<()> {
+        self.test_number += 1;
+        let status = if ok { "ok" } else { "not ok" };
+        let directive =
+            if let Some(directive) = directive { format!(" # {directive}") } else { String::new() };
+        self.writeln_message(&format!(
+            "{status} {} - {}{directive}",
+            self.test_number,
+            EscapedDescription(desc.name.as_slice())
+        ))?;
+
+        if diagnostics.is_empty() {
+            return Ok(());
+        }
+        self.writeln_message("  ---")?;
+        for (key, value) in diagnostics {
+            self.writeln_message(&format!("  {key}: {value}"))?;
+        }
+        self.writeln_message("  ...")
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.writeln_message("TAP version 14")?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.writeln_message(&format!("# shuffle seed: {shuffle_seed}"))?;
+        }
+        self.writeln_message(&format!("1..{test_count}"))
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // Test points are only written once the test has finished.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.writeln_message(&format!(
+            "# test {} has been running for over {} seconds",
+            desc.name,
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        retries: usize,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let mut diagnostics = Vec::new();
+        match *result {
+            TestResult::TrFailed => {
+                if let Some(message) = panic_message(stdout) {
+                    diagnostics.push(("message", yaml_string(&message)));
+                }
+            }
+            TestResult::TrFailedMsg(ref m) => diagnostics.push(("message", yaml_string(m))),
+            TestResult::TrTimedFail => {
+                diagnostics.push(("message", yaml_string("time limit exceeded")))
+            }
+            TestResult::TrTimedOut(timeout) => {
+                let message = format!("timed out after {}s", timeout.as_secs());
+                diagnostics.push(("message", yaml_string(&message)))
+            }
+            TestResult::TrBench(ref bs) => {
+                let median = bs.ns_iter_summ.median;
+                let deviation = bs.ns_iter_summ.max - bs.ns_iter_summ.min;
+                diagnostics.push(("median_ns", median.to_string()));
+                diagnostics.push(("deviation_ns", deviation.to_string()));
+                if bs.mb_s != 0 {
+                    diagnostics.push(("mib_per_second", bs.mb_s.to_string()));
+                }
+            }
+            TestResult::TrOk | TestResult::TrIgnored => {}
+        }
+        if let Some(exec_time) = exec_time {
+            diagnostics.push(("duration_ms", (exec_time.0.as_secs_f64() * 1000.0).to_string()));
+        }
+        if retries > 0 {
+            diagnostics.push(("retries", retries.to_string()));
+        }
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        if display_stdout && !stdout.is_empty() {
+            diagnostics.push(("output", yaml_string(&String::from_utf8_lossy(stdout))));
+        }
+
+        match *result {
+            TestResult::TrOk | TestResult::TrBench(_) => {
+                self.write_test_point(true, desc, None, &diagnostics)
+            }
+            TestResult::TrIgnored => {
+                let directive = match desc.ignore_message {
+                    Some(message) => format!("SKIP {}", EscapedDescription(message)),
+                    None => String::from("SKIP"),
+                };
+                self.write_test_point(true, desc, Some(&directive), &diagnostics)
+            }
+            TestResult::TrFailed
+            | TestResult::TrFailedMsg(_)
+            | TestResult::TrTimedFail
+            | TestResult::TrTimedOut(_) => self.write_test_point(false, desc, None, &diagnostics),
+        }
+    }
+
+    fn write_retry(
+        &mut self,
+        desc: &TestDesc,
+        _result: &TestResult,
+        _exec_time: Option<&time::TestExecTime>,
+        _stdout: &[u8],
+        attempt: usize,
+        _state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // TAP has no notion of retries, the number of retries ends up in the
+        // diagnostics of the final test point.
+        self.writeln_message(&format!("# test {} failed, retrying (attempt {attempt})", desc.name))
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let mut summary = format!(
+            "# {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+        );
+        if state.flaky > 0 {
+            summary.push_str(&format!("; {} flaky", state.flaky));
+        }
+        if let Some(ref exec_time) = state.exec_time {
+            summary.push_str(&format!("; finished in {exec_time}"));
+        }
+        self.writeln_message(&summary)?;
+
+        Ok(state.failed == 0)
+    }
+}
+
+/// Extracts the panic message from the captured output of a test that panicked,
+/// i.e. the lines following the `thread '...' panicked at ...` header up to the
+/// backtrace note, if any.
+fn panic_message(stdout: &[u8]) -> Option<String> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let mut lines = stdout.lines();
+    lines.find(|line| line.starts_with("thread '") && line.contains(" panicked at "))?;
+    let message: Vec<&str> = lines.take_while(|line| !line.starts_with("note: ")).collect();
+    if message.is_empty() { None } else { Some(message.join("\n")) }
+}
+
+/// Formats `s` as a YAML scalar, using a literal block for multi-line strings.
+fn yaml_string(s: &str) -> String {
+    if s.contains('\n') {
+        let mut block = String::from("|-");
+        for line in s.lines() {
+            block.push('\n');
+            if !line.is_empty() {
+                block.push_str("    ");
+                block.push_str(line);
+            }
+        }
+        block
+    } else {
+        format!("'{}'", s.replace('\'', "''"))
+    }
+}
+
+/// A formatting utility used to escape the characters that have a special
+/// meaning in the description of a TAP test point.
+struct EscapedDescription<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> std::fmt::Display for EscapedDescription<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        for c in self.0.as_ref().chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '#' => f.write_str("\\#")?,
+                '\n' => f.write_str(" ")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
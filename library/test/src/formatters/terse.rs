@@ -1,6 +1,6 @@
 use std::{io, io::prelude::Write};
 
-use super::OutputFormatter;
+use super::{flaky_tests_summary, OutputFormatter};
 use crate::{
     bench::fmt_bench_samples,
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
@@ -69,6 +69,17 @@ pub fn write_ignored(&mut self) -> io::Result<()> {
         self.write_short_result("i", term::color::YELLOW)
     }
 
+    pub fn write_retry_failed(&mut self) -> io::Result<()> {
+        // A retry isn't a result of its own, so it doesn't count towards the progress.
+        self.write_pretty("r", term::color::YELLOW)?;
+        self.test_column += 1;
+        if self.test_column % QUIET_MODE_MAX_COLUMN == QUIET_MODE_MAX_COLUMN - 1 {
+            self.write_progress()?;
+        }
+
+        Ok(())
+    }
+
     pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
@@ -161,6 +172,15 @@ pub fn write_failures(&mut self, state: &ConsoleTestState) -> io::Result<()> {
                 fail_out.push_str(&output);
                 fail_out.push('\n');
             }
+            // The note is not part of the captured output, so it's written after it.
+            if let Some((_, timeout)) = state.timeouts.iter().find(|(desc, _)| desc.name == f.name)
+            {
+                fail_out.push_str(&format!(
+                    "note: test {} timed out after {}s\n",
+                    f.name,
+                    timeout.as_secs()
+                ));
+            }
         }
         if !fail_out.is_empty() {
             self.write_plain("\n")?;
@@ -229,13 +249,15 @@ fn write_result(
         result: &TestResult,
         _: Option<&time::TestExecTime>,
         _: &[u8],
+        _: usize,
         _: &ConsoleTestState,
     ) -> io::Result<()> {
         match *result {
             TestResult::TrOk => self.write_ok(),
-            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
-                self.write_failed(desc.name.as_slice())
-            }
+            TestResult::TrFailed
+            | TestResult::TrFailedMsg(_)
+            | TestResult::TrTimedFail
+            | TestResult::TrTimedOut(_) => self.write_failed(desc.name.as_slice()),
             TestResult::TrIgnored => self.write_ignored(),
             TestResult::TrBench(ref bs) => {
                 if self.is_multithreaded {
@@ -247,6 +269,18 @@ fn write_result(
         }
     }
 
+    fn write_retry(
+        &mut self,
+        _: &TestDesc,
+        _: &TestResult,
+        _: Option<&time::TestExecTime>,
+        _: &[u8],
+        _: usize,
+        _: &ConsoleTestState,
+    ) -> io::Result<()> {
+        self.write_retry_failed()
+    }
+
     fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
         self.write_plain(format!(
             "test {} has been running for over {} seconds\n",
@@ -264,6 +298,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_failures(state)?;
         }
 
+        if !state.flaky_tests.is_empty() {
+            self.write_plain(flaky_tests_summary(state))?;
+        }
+
         self.write_plain("\ntest result: ")?;
 
         if success {
@@ -280,6 +318,10 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
 
         self.write_plain(s)?;
 
+        if state.flaky > 0 {
+            self.write_plain(format!("; {} flaky", state.flaky))?;
+        }
+
         if let Some(ref exec_time) = state.exec_time {
             let time_str = format!("; finished in {exec_time}");
             self.write_plain(time_str)?;
@@ -3,4 +3,5 @@
 
 pub mod concurrency;
 pub mod metrics;
+pub mod shard;
 pub mod shuffle;
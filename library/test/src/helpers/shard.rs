@@ -0,0 +1,23 @@
+//! Helpers for splitting the test suite into shards with `--shard`.
+
+use crate::types::TestName;
+
+/// Returns whether the test called `name` belongs to the shard `index`
+/// (starting from 1) out of `count` shards.
+///
+/// Only the name of the test is taken into account, so the assignment doesn't
+/// depend on the other tests in the suite, on their order, or on the shuffle
+/// seed, and every test ends up in exactly one shard.
+pub fn is_test_in_shard(name: &TestName, index: usize, count: usize) -> bool {
+    debug_assert!(0 < index && index <= count);
+    stable_hash(name.as_slice().as_bytes()) % count as u64 == (index - 1) as u64
+}
+
+// The sharding must agree between test binaries built by different compilers
+// for different targets, so neither `DefaultHasher` nor `usize` arithmetic can
+// be used here. This is 64-bit FNV-1a.
+fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
@@ -85,6 +85,7 @@ pub mod test {
 use core::any::Any;
 use event::{CompletedTest, TestEvent};
 use helpers::concurrency::get_concurrency;
+use helpers::shard::is_test_in_shard;
 use helpers::shuffle::{get_shuffle_seed, shuffle_tests};
 use options::RunStrategy;
 use test_result::*;
@@ -221,6 +222,17 @@ fn make_owned_test(test: &&TestDescAndFn) -> TestDescAndFn {
     }
 }
 
+/// Copies a static test so that it can be run again. Returns `None` for dynamic
+/// tests and benchmarks, which cannot be copied.
+fn clone_static_test(test: &TestDescAndFn) -> Option<TestDescAndFn> {
+    let testfn = match test.testfn {
+        StaticTestFn(f) => StaticTestFn(f),
+        StaticBenchAsTestFn(f) => StaticBenchAsTestFn(f),
+        _ => return None,
+    };
+    Some(TestDescAndFn { desc: test.desc.clone(), testfn })
+}
+
 /// Invoked when unit tests terminate. Returns `Result::Err` if the test is
 /// considered a failure. By default, invokes `report()` and checks for a `0`
 /// result.
@@ -290,12 +302,26 @@ fn join(self, completed_test: &mut CompletedTest) {
     // Use a deterministic hasher
     type TestMap = HashMap<TestId, RunningTest, BuildHasherDefault<DefaultHasher>>;
 
+    // Copies of the tests that can be run again if they fail, along with the
+    // number of times they have been retried so far.
+    type RetryMap = HashMap<TestId, (TestDescAndFn, usize), BuildHasherDefault<DefaultHasher>>;
+
     struct TimeoutEntry {
         id: TestId,
         desc: TestDesc,
         timeout: Instant,
     }
 
+    // Tests run in-process can't be stopped once they exceed their timeout. They are
+    // reported as timed out and left running in the background, and whatever they
+    // report once they finish is ignored.
+    struct DeadlineEntry {
+        id: TestId,
+        desc: TestDesc,
+        timeout: Duration,
+        deadline: Instant,
+    }
+
     let tests_len = tests.len();
 
     let mut filtered = FilteredTests { tests: Vec::new(), benches: Vec::new(), next_id: 0 };
@@ -348,6 +374,43 @@ struct TimeoutEntry {
 
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
+    let mut retry_map: RetryMap = HashMap::default();
+    let mut deadlines: Vec<DeadlineEntry> = Vec::new();
+
+    fn record_retryable_test(
+        opts: &TestOpts,
+        retry_map: &mut RetryMap,
+        id: TestId,
+        test: &TestDescAndFn,
+    ) {
+        if opts.retries > 0 && !retry_map.contains_key(&id) {
+            if let Some(copy) = clone_static_test(test) {
+                retry_map.insert(id, (copy, 0));
+            }
+        }
+    }
+
+    // If the test failed and has retries left, returns a fresh copy of it to run again and
+    // records the number of the upcoming retry. Otherwise records how many times the test
+    // was retried before reaching its final result.
+    fn retry_failed_test(
+        opts: &TestOpts,
+        retry_map: &mut RetryMap,
+        completed_test: &mut CompletedTest,
+    ) -> Option<TestDescAndFn> {
+        // Tests that timed out aren't retried: when run in-process, the first attempt
+        // would still be running alongside the retry.
+        let failed = matches!(completed_test.result, TrFailed | TrFailedMsg(_) | TrTimedFail);
+        let (test, retries) = retry_map.get_mut(&completed_test.id)?;
+        if failed && *retries < opts.retries {
+            *retries += 1;
+            completed_test.retries = *retries;
+            return clone_static_test(test);
+        }
+        completed_test.retries = *retries;
+        retry_map.remove(&completed_test.id);
+        None
+    }
 
     fn get_timed_out_tests(
         running_tests: &TestMap,
@@ -374,19 +437,83 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
         })
     }
 
+    fn get_deadline(
+        opts: &TestOpts,
+        run_strategy: RunStrategy,
+        id: TestId,
+        desc: &TestDesc,
+    ) -> Option<DeadlineEntry> {
+        // Subprocesses are killed by `spawn_test_subprocess` once they time out.
+        if !matches!(run_strategy, RunStrategy::InProcess) {
+            return None;
+        }
+        let timeout = time::get_test_timeout(desc, opts.test_timeout)?;
+        Some(DeadlineEntry { id, desc: desc.clone(), timeout, deadline: Instant::now() + timeout })
+    }
+
+    fn get_expired_tests(
+        running_tests: &mut TestMap,
+        deadlines: &mut Vec<DeadlineEntry>,
+    ) -> Vec<DeadlineEntry> {
+        let now = Instant::now();
+        let (expired, unexpired) =
+            std::mem::take(deadlines).into_iter().partition(|entry| entry.deadline <= now);
+        *deadlines = unexpired;
+        // Dropping the running test detaches its thread.
+        expired.into_iter().filter(|entry| running_tests.remove(&entry.id).is_some()).collect()
+    }
+
+    fn calc_deadline_timeout(deadlines: &[DeadlineEntry]) -> Option<Duration> {
+        let next_deadline = deadlines.iter().map(|entry| entry.deadline).min()?;
+        Some(next_deadline.saturating_duration_since(Instant::now()))
+    }
+
+    fn timed_out_test(opts: &TestOpts, entry: DeadlineEntry) -> CompletedTest {
+        let DeadlineEntry { id, desc, timeout, .. } = entry;
+        let exec_time = opts.time_options.map(|_| TestExecTime(timeout));
+        CompletedTest::new(id, desc, TrTimedOut(timeout), exec_time, Vec::new())
+    }
+
     if concurrency == 1 {
         while !remaining.is_empty() {
             let (id, test) = remaining.pop_front().unwrap();
+            record_retryable_test(opts, &mut retry_map, id, &test);
+            let deadline = get_deadline(opts, run_strategy, id, &test.desc);
             let event = TestEvent::TeWait(test.desc.clone());
             notify_about_test_event(event)?;
             let join_handle = run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
             // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
-            RunningTest { join_handle }.join(&mut completed_test);
+            let mut completed_test = loop {
+                let res = match deadline {
+                    Some(ref entry) => {
+                        rx.recv_timeout(entry.deadline.saturating_duration_since(Instant::now()))
+                    }
+                    None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match res {
+                    Ok(mut completed_test) if completed_test.id == id => {
+                        RunningTest { join_handle }.join(&mut completed_test);
+                        break completed_test;
+                    }
+                    // A late result of a test that timed out before.
+                    Ok(_) => {}
+                    Err(RecvTimeoutError::Timeout) => {
+                        break timed_out_test(opts, deadline.unwrap());
+                    }
+                    Err(e @ RecvTimeoutError::Disconnected) => panic!("{e}"),
+                }
+            };
+
+            if let Some(test) = retry_failed_test(opts, &mut retry_map, &mut completed_test) {
+                let event = TestEvent::TeRetry(completed_test);
+                notify_about_test_event(event)?;
+                remaining.push_front((id, test));
+                continue;
+            }
 
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
-                TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
+                TrFailed | TrFailedMsg(_) | TrTimedFail | TrTimedOut(_) => opts.fail_fast,
             };
 
             let event = TestEvent::TeResult(completed_test);
@@ -400,8 +527,10 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
         while pending > 0 || !remaining.is_empty() {
             while pending < concurrency && !remaining.is_empty() {
                 let (id, test) = remaining.pop_front().unwrap();
+                record_retryable_test(opts, &mut retry_map, id, &test);
                 let timeout = time::get_default_test_timeout();
                 let desc = test.desc.clone();
+                deadlines.extend(get_deadline(opts, run_strategy, id, &desc));
 
                 let event = TestEvent::TeWait(desc.clone());
                 notify_about_test_event(event)?; //here no pad
@@ -413,16 +542,22 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             }
 
             let mut res;
+            let mut expired = Vec::new();
             loop {
-                if let Some(timeout) = calc_timeout(&timeout_queue) {
+                let timeout = calc_timeout(&timeout_queue)
+                    .into_iter()
+                    .chain(calc_deadline_timeout(&deadlines))
+                    .min();
+                if let Some(timeout) = timeout {
                     res = rx.recv_timeout(timeout);
                     for test in get_timed_out_tests(&running_tests, &mut timeout_queue) {
                         let event = TestEvent::TeTimeout(test);
                         notify_about_test_event(event)?;
                     }
+                    expired = get_expired_tests(&mut running_tests, &mut deadlines);
 
                     match res {
-                        Err(RecvTimeoutError::Timeout) => {
+                        Err(RecvTimeoutError::Timeout) if expired.is_empty() => {
                             // Result is not yet ready, continue waiting.
                         }
                         _ => {
@@ -436,18 +571,49 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
                 }
             }
 
-            let mut completed_test = res.unwrap();
-            let running_test = running_tests.remove(&completed_test.id).unwrap();
+            for entry in expired {
+                pending -= 1;
+                let event = TestEvent::TeResult(timed_out_test(opts, entry));
+                notify_about_test_event(event)?;
+
+                if opts.fail_fast {
+                    // Prevent remaining test threads from panicking
+                    std::mem::forget(rx);
+                    return Ok(());
+                }
+            }
+
+            let mut completed_test = match res {
+                Ok(completed_test) => completed_test,
+                // Only timed out tests were reported.
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(e @ RecvTimeoutError::Disconnected) => panic!("{e}"),
+            };
+            let Some(running_test) = running_tests.remove(&completed_test.id) else {
+                // A late result of a test that timed out before.
+                continue;
+            };
+            deadlines.retain(|entry| entry.id != completed_test.id);
             running_test.join(&mut completed_test);
+            pending -= 1;
+
+            if let Some(test) = retry_failed_test(opts, &mut retry_map, &mut completed_test) {
+                let id = completed_test.id;
+                // The retry gets a timeout of its own.
+                timeout_queue.retain(|entry| entry.id != id);
+                let event = TestEvent::TeRetry(completed_test);
+                notify_about_test_event(event)?;
+                remaining.push_front((id, test));
+                continue;
+            }
 
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
-                TrFailed | TrFailedMsg(_) | TrTimedFail => opts.fail_fast,
+                TrFailed | TrFailedMsg(_) | TrTimedFail | TrTimedOut(_) => opts.fail_fast,
             };
 
             let event = TestEvent::TeResult(completed_test);
             notify_about_test_event(event)?;
-            pending -= 1;
 
             if fail_fast {
                 // Prevent remaining test threads from panicking
@@ -463,14 +629,20 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             let event = TestEvent::TeWait(b.desc.clone());
             notify_about_test_event(event)?;
             let join_handle = run_test(opts, false, id, b, run_strategy, tx.clone());
-            // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
+            // Wait for the test to complete, skipping late results of tests that timed out.
+            let mut completed_test = loop {
+                let completed_test = rx.recv().unwrap();
+                if completed_test.id == id {
+                    break completed_test;
+                }
+            };
             RunningTest { join_handle }.join(&mut completed_test);
 
             let event = TestEvent::TeResult(completed_test);
             notify_about_test_event(event)?;
         }
     }
+
     Ok(())
 }
 
@@ -512,6 +684,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         RunIgnored::No => {}
     }
 
+    // Only keep the tests in the requested shard
+    if let Some((index, count)) = opts.shard {
+        filtered.retain(|test| is_test_in_shard(&test.desc.name, index, count));
+    }
+
     filtered
 }
 
@@ -564,6 +741,7 @@ pub fn run_test(
             let nocapture = opts.nocapture;
             let time_options = opts.time_options;
             let bench_benchmarks = opts.bench_benchmarks;
+            let timeout = time::get_test_timeout(&desc, opts.test_timeout);
 
             let runtest = move || match strategy {
                 RunStrategy::InProcess => run_test_in_process(
@@ -583,6 +761,7 @@ pub fn run_test(
                     monitor_ch,
                     time_options,
                     bench_benchmarks,
+                    timeout,
                 ),
             };
 
@@ -659,7 +838,8 @@ fn run_test_in_process(
     };
     let stdout = data.lock().unwrap_or_else(|e| e.into_inner()).to_vec();
     let message = CompletedTest::new(id, desc, test_result, exec_time, stdout);
-    monitor_ch.send(message).unwrap();
+    // The receiver is gone if the test timed out and the run finished without it.
+    let _ = monitor_ch.send(message);
 }
 
 fn fold_err<T, E>(
@@ -683,6 +863,7 @@ fn spawn_test_subprocess(
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
     bench_benchmarks: bool,
+    timeout: Option<Duration>,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
@@ -696,10 +877,17 @@ fn spawn_test_subprocess(
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
+        } else {
+            command.stdout(process::Stdio::piped());
+            command.stderr(process::Stdio::piped());
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let output = match timeout {
+            Some(timeout) => output_with_timeout(&mut command, timeout),
+            None => command.output().map(|output| (output, false)),
+        };
+        let (output, timed_out) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -716,7 +904,10 @@ fn spawn_test_subprocess(
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
 
-        let result = get_result_from_exit_code(&desc, status, &time_opts, &exec_time);
+        let result = match timeout {
+            Some(timeout) if timed_out => TrTimedOut(timeout),
+            _ => get_result_from_exit_code(&desc, status, &time_opts, &exec_time),
+        };
         (result, test_output, exec_time)
     })();
 
@@ -724,6 +915,58 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
+/// Like `Command::output`, but kills the child process if it's still running
+/// after `timeout`. Also returns whether the child process was killed.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+) -> io::Result<(process::Output, bool)> {
+    type Reader = thread::JoinHandle<io::Result<Vec<u8>>>;
+
+    // Read the output on separate threads so that the child process can't get
+    // stuck on a full pipe while we're waiting for it.
+    fn read_to_end<R: io::Read + Send + 'static>(pipe: Option<R>) -> Option<Reader> {
+        pipe.map(|mut pipe| {
+            thread::spawn(move || {
+                let mut output = Vec::new();
+                pipe.read_to_end(&mut output).map(|_| output)
+            })
+        })
+    }
+
+    fn join(reader: Option<Reader>) -> io::Result<Vec<u8>> {
+        match reader {
+            Some(reader) => reader.join().unwrap(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut child = command.spawn()?;
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            timed_out = true;
+            // The child process may have exited in the meantime, in which case
+            // there's nothing left to kill.
+            let _ = child.kill();
+            break child.wait()?;
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(10)));
+    };
+
+    let stdout = join(stdout)?;
+    let stderr = join(stderr)?;
+    Ok((process::Output { status, stdout, stderr }, timed_out))
+}
+
 fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -> ! {
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicHookInfo<'_>>| {
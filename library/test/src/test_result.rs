@@ -1,5 +1,6 @@
 use std::any::Any;
 use std::process::ExitStatus;
+use std::time::Duration;
 
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
@@ -29,6 +30,8 @@ pub enum TestResult {
     TrIgnored,
     TrBench(BenchSamples),
     TrTimedFail,
+    /// The test didn't finish within its timeout, see `--test-timeout`.
+    TrTimedOut(Duration),
 }
 
 /// Creates a `TestResult` depending on the raw result of test execution
@@ -2,7 +2,7 @@
 
 use crate::{
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{JsonFormatter, OutputFormatter, PrettyFormatter},
     test::{
         parse_opts,
         MetricMap,
@@ -12,6 +12,7 @@
     },
     time::{TestTimeOptions, TimeThreshold},
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 impl TestOpts {
     fn new() -> TestOpts {
@@ -32,9 +33,12 @@ fn new() -> TestOpts {
             shuffle_seed: None,
             test_threads: None,
             skip: vec![],
+            shard: None,
             time_options: None,
+            test_timeout: None,
             options: Options::new(),
             fail_fast: false,
+            retries: 0,
         }
     }
 }
@@ -55,6 +59,7 @@ fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move || Ok(()))),
         },
@@ -72,6 +77,7 @@ fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move || Ok(()))),
         },
@@ -97,6 +103,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -125,6 +132,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -156,6 +164,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -187,6 +196,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -223,6 +233,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -263,6 +274,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -297,6 +309,7 @@ fn f() -> Result<(), String> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -330,6 +343,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -372,6 +386,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -416,6 +431,7 @@ fn typed_test_desc(test_type: TestType) -> TestDesc {
         compile_fail: false,
         no_run: false,
         test_type,
+        timeout: None,
     }
 }
 
@@ -478,6 +494,62 @@ fn parse_include_ignored_flag() {
     assert_eq!(opts.run_ignored, RunIgnored::Yes);
 }
 
+#[test]
+fn parse_test_timeout_option() {
+    let parse = |secs: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "--test-timeout".to_string(),
+            secs.to_string(),
+            "-Zunstable-options".to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| opts.test_timeout)
+    };
+    assert_eq!(parse("30"), Ok(Some(Duration::from_secs(30))));
+    assert!(parse("0").is_err());
+    assert!(parse("1.5").is_err());
+
+    let args = vec!["progname".to_string(), "--test-timeout".to_string(), "30".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_retries_option() {
+    let args = vec![
+        "progname".to_string(),
+        "--retries".to_string(),
+        "3".to_string(),
+        "-Zunstable-options".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.retries, 3);
+
+    let args = vec!["progname".to_string(), "--retries".to_string(), "3".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_shard_option() {
+    let parse = |shard: &str| {
+        let args = vec![
+            "progname".to_string(),
+            "--shard".to_string(),
+            shard.to_string(),
+            "-Zunstable-options".to_string(),
+        ];
+        parse_opts(&args).unwrap().map(|opts| opts.shard)
+    };
+    assert_eq!(parse("1/1"), Ok(Some((1, 1))));
+    assert_eq!(parse("2/3"), Ok(Some((2, 3))));
+    assert!(parse("0/3").is_err());
+    assert!(parse("4/3").is_err());
+    assert!(parse("1").is_err());
+    assert!(parse("a/b").is_err());
+
+    let args = vec!["progname".to_string(), "--shard".to_string(), "1/2".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
 #[test]
 pub fn filter_for_ignored_option() {
     // When we run ignored tests the test filter should filter out all the
@@ -533,6 +605,7 @@ pub fn exclude_should_panic_option() {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(move || Ok(()))),
     });
@@ -562,6 +635,7 @@ fn tests() -> Vec<TestDescAndFn> {
                     compile_fail: false,
                     no_run: false,
                     test_type: TestType::Unknown,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move || Ok(()))),
             })
@@ -658,6 +732,7 @@ fn testfn() -> Result<(), String> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(testfn)),
         };
@@ -666,6 +741,51 @@ fn testfn() -> Result<(), String> {
     tests
 }
 
+#[test]
+pub fn shard_tests() {
+    let names = |tests: Vec<TestDescAndFn>| {
+        tests.into_iter().map(|test| test.desc.name.to_string()).collect::<Vec<_>>()
+    };
+
+    let mut opts = TestOpts::new();
+    let mut sharded = Vec::new();
+    for index in 1..=3 {
+        opts.shard = Some((index, 3));
+        sharded.extend(names(filter_tests(&opts, sample_tests())));
+    }
+    sharded.sort();
+
+    // Every test ends up in exactly one shard.
+    let mut all = names(sample_tests());
+    all.sort();
+    assert_eq!(sharded, all);
+}
+
+#[test]
+pub fn shard_depends_only_on_test_name() {
+    let mut opts = TestOpts::new();
+    opts.shard = Some((2, 3));
+    let shard = filter_tests(&opts, sample_tests());
+
+    // Filtering out other tests doesn't move tests between shards.
+    opts.skip = vec!["sha1".to_string()];
+    let filtered_shard = filter_tests(&opts, sample_tests());
+    assert!(filtered_shard.iter().all(|a| shard.iter().any(|b| a.desc.name == b.desc.name)));
+
+    // Neither does the order of the input tests.
+    opts.skip = vec![];
+    let mut shuffled =
+        sample_tests().into_iter().enumerate().map(|(i, e)| (TestId(i), e)).collect::<Vec<_>>();
+    helpers::shuffle::shuffle_tests(1, shuffled.as_mut_slice());
+    let shuffled_shard = filter_tests(&opts, shuffled.into_iter().map(|(_, e)| e).collect());
+    let mut names = shard.iter().map(|t| t.desc.name.as_slice()).collect::<Vec<_>>();
+    let mut shuffled_names =
+        shuffled_shard.iter().map(|t| t.desc.name.as_slice()).collect::<Vec<_>>();
+    names.sort();
+    shuffled_names.sort();
+    assert_eq!(names, shuffled_names);
+}
+
 #[test]
 pub fn shuffle_tests() {
     let mut opts = TestOpts::new();
@@ -792,6 +912,7 @@ fn f(_: &mut Bencher) -> Result<(), String> {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
     crate::bench::benchmark(TestId(0), desc, tx, true, f);
@@ -820,6 +941,7 @@ fn f(b: &mut Bencher) -> Result<(), String> {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
     crate::bench::benchmark(TestId(0), desc, tx, true, f);
@@ -841,6 +963,7 @@ fn should_sort_failures_before_printing_them() {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
     let test_b = TestDesc {
@@ -856,6 +979,7 @@ fn should_sort_failures_before_printing_them() {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
     let mut out = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 10, false, None);
@@ -875,6 +999,10 @@ fn should_sort_failures_before_printing_them() {
         not_failures: Vec::new(),
         ignores: Vec::new(),
         time_failures: Vec::new(),
+        flaky: 0,
+        flaky_tests: Vec::new(),
+        timeouts: Vec::new(),
+        max_retries: 0,
     };
 
     out.write_failures(&st).unwrap();
@@ -908,6 +1036,7 @@ fn f(_: &mut Bencher) -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynBenchFn(Box::new(f)),
     };
@@ -922,3 +1051,225 @@ fn f(_: &mut Bencher) -> Result<(), String> {
     let result = rx.recv().unwrap().result;
     assert_eq!(result, TrFailed);
 }
+
+fn retried_test_events(testfn: fn() -> Result<(), String>, retries: usize) -> Vec<TestEvent> {
+    let desc = TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName("whatever"),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+            timeout: None,
+        },
+        testfn: StaticTestFn(testfn),
+    };
+    let mut events = Vec::new();
+    let notify = |event: TestEvent| {
+        events.push(event);
+        Ok(())
+    };
+    let opts = TestOpts { run_tests: true, retries, ..TestOpts::new() };
+    run_tests(&opts, vec![desc], notify).unwrap();
+    events
+}
+
+#[test]
+fn test_retries_report_flaky_test() {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    fn f() -> Result<(), String> {
+        if RUNS.fetch_add(1, Ordering::SeqCst) < 2 { Err("not yet".into()) } else { Ok(()) }
+    }
+
+    let events = retried_test_events(f, 3);
+    let attempts: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            TestEvent::TeRetry(test) => Some(test.retries),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(attempts, [1, 2]);
+    let Some(TestEvent::TeResult(result)) = events.last() else { panic!("no test result") };
+    assert_eq!(result.result, TrOk);
+    assert_eq!(result.retries, 2);
+}
+
+#[test]
+fn test_retries_report_hard_failure() {
+    fn f() -> Result<(), String> {
+        Err("always".into())
+    }
+
+    let events = retried_test_events(f, 2);
+    let retries = events.iter().filter(|event| matches!(event, TestEvent::TeRetry(_))).count();
+    assert_eq!(retries, 2);
+    let Some(TestEvent::TeResult(result)) = events.last() else { panic!("no test result") };
+    assert_eq!(result.result, TrFailed);
+    assert_eq!(result.retries, 2);
+}
+
+#[test]
+fn test_no_retries_by_default() {
+    fn f() -> Result<(), String> {
+        Err("always".into())
+    }
+
+    let events = retried_test_events(f, 0);
+    assert!(!events.iter().any(|event| matches!(event, TestEvent::TeRetry(_))));
+    let Some(TestEvent::TeResult(result)) = events.last() else { panic!("no test result") };
+    assert_eq!(result.retries, 0);
+}
+
+fn formatter_test_desc() -> TestDesc {
+    TestDesc {
+        name: StaticTestName("flaky"),
+        ignore: false,
+        ignore_message: None,
+        source_file: "",
+        start_line: 0,
+        start_col: 0,
+        end_line: 0,
+        end_col: 0,
+        should_panic: ShouldPanic::No,
+        compile_fail: false,
+        no_run: false,
+        test_type: TestType::Unknown,
+        timeout: None,
+    }
+}
+
+fn raw_output(out: &OutputLocation<Vec<u8>>) -> String {
+    match out {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(m).into_owned(),
+        OutputLocation::Pretty(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_pretty_formatter_reports_retries_and_flaky_tests() {
+    let desc = formatter_test_desc();
+    let opts = TestOpts { retries: 2, ..TestOpts::new() };
+    let mut st = console::ConsoleTestState::new(&opts).unwrap();
+    let mut out = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 5, false, None);
+
+    out.write_test_start(&desc).unwrap();
+    out.write_retry(&desc, &TrFailed, None, b"", 1, &st).unwrap();
+    out.write_result(&desc, &TrOk, None, b"", 1, &st).unwrap();
+    st.total = 1;
+    st.passed = 1;
+    st.flaky = 1;
+    st.flaky_tests.push((desc, 1));
+    out.write_run_finish(&st).unwrap();
+
+    let s = raw_output(out.output_location());
+    assert!(s.contains("test flaky ... FAILED, retrying (1/2)\n"), "{s}");
+    assert!(s.contains("ok (flaky, 1 retry)\n"), "{s}");
+    assert!(s.contains("\nflaky tests:\n    flaky (passed after 1 retry)\n"), "{s}");
+    assert!(s.contains("; 1 flaky"), "{s}");
+}
+
+#[test]
+fn test_json_formatter_retry_output() {
+    let desc = formatter_test_desc();
+    let opts = TestOpts { retries: 2, ..TestOpts::new() };
+    let st = console::ConsoleTestState::new(&opts).unwrap();
+    let mut out = JsonFormatter::new(OutputLocation::Raw(Vec::new()));
+
+    out.write_retry(&desc, &TrFailedMsg("boom".into()), None, b"failing output", 1, &st).unwrap();
+    out.write_result(&desc, &TrOk, None, b"passing output", 1, &st).unwrap();
+
+    let s = raw_output(out.output_location());
+    let lines: Vec<_> = s.lines().collect();
+    assert_eq!(lines.len(), 2, "{s}");
+    assert!(lines[0].contains(r#""event": "retry""#), "{s}");
+    assert!(lines[0].contains(r#""attempt": 1, "message": "boom""#), "{s}");
+    // Output of failed attempts is always shown, like that of failed tests.
+    assert!(lines[0].contains(r#""stdout": "failing output""#), "{s}");
+    assert!(lines[1].contains(r#""event": "ok""#), "{s}");
+    assert!(lines[1].contains(r#""retries": 1"#), "{s}");
+    assert!(!lines[1].contains("stdout"), "{s}");
+}
+
+#[test]
+fn test_json_formatter_retry_output_with_display_output() {
+    let desc = formatter_test_desc();
+    let mut opts = TestOpts { retries: 2, ..TestOpts::new() };
+    opts.options.display_output = true;
+    let st = console::ConsoleTestState::new(&opts).unwrap();
+    let mut out = JsonFormatter::new(OutputLocation::Raw(Vec::new()));
+
+    out.write_retry(&desc, &TrFailed, None, b"", 1, &st).unwrap();
+    out.write_result(&desc, &TrOk, None, b"passing output", 1, &st).unwrap();
+
+    let s = raw_output(out.output_location());
+    let lines: Vec<_> = s.lines().collect();
+    assert_eq!(lines.len(), 2, "{s}");
+    // Empty output is left out.
+    assert!(!lines[0].contains("stdout"), "{s}");
+    assert!(lines[1].contains(r#""stdout": "passing output""#), "{s}");
+}
+
+#[test]
+fn test_in_process_timeout() {
+    // Outlives its timeout of 1s, and the run, but not by much: the test threads are
+    // left running once they time out.
+    fn hang() -> Result<(), String> {
+        thread::sleep(Duration::from_secs(3));
+        Ok(())
+    }
+    fn quick() -> Result<(), String> {
+        Ok(())
+    }
+    let test = |name, testfn, timeout| TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+            timeout,
+        },
+        testfn: StaticTestFn(testfn),
+    };
+
+    for test_threads in [1, 2] {
+        let tests = vec![test("hang", hang, Some(1)), test("quick", quick, None)];
+        let opts = TestOpts {
+            run_tests: true,
+            test_threads: Some(test_threads),
+            test_timeout: Some(Duration::from_secs(60)),
+            ..TestOpts::new()
+        };
+        let mut results = Vec::new();
+        let notify = |event: TestEvent| {
+            if let TestEvent::TeResult(completed_test) = event {
+                results.push((completed_test.desc.name.to_string(), completed_test.result));
+            }
+            Ok(())
+        };
+        run_tests(&opts, tests, notify).unwrap();
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            results,
+            [
+                ("hang".to_string(), TestResult::TrTimedOut(Duration::from_secs(1))),
+                ("quick".to_string(), TestResult::TrOk),
+            ]
+        );
+    }
+}
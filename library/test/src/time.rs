@@ -61,6 +61,12 @@ pub fn get_default_test_timeout() -> Instant {
     Instant::now() + Duration::from_secs(TEST_WARN_TIMEOUT_S)
 }
 
+/// Returns how long the test may run before it's stopped and reported as timed
+/// out: either its own timeout, or `default_timeout` as given with `--test-timeout`.
+pub fn get_test_timeout(desc: &TestDesc, default_timeout: Option<Duration>) -> Option<Duration> {
+    desc.timeout.map(Duration::from_secs).or(default_timeout)
+}
+
 /// The measured execution time of a unit test.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestExecTime(pub Duration);
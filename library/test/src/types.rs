@@ -208,6 +208,9 @@ pub struct TestDesc {
     pub compile_fail: bool,
     pub no_run: bool,
     pub test_type: TestType,
+    /// Time limit for the test in seconds, which takes precedence over the one
+    /// given with `--test-timeout`.
+    pub timeout: Option<u64>,
 }
 
 impl TestDesc {
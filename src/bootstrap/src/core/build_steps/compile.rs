@@ -1881,6 +1881,11 @@ fn run(self, builder: &Builder<'_>) -> Compiler {
         let compiler = builder.rustc(target_compiler);
         builder.copy_link(&rustc, &compiler);
 
+        // Link the translations of the diagnostics into place, so that `--lang` can find them.
+        let locale_dir = sysroot.join("share/locale");
+        t!(fs::create_dir_all(&locale_dir));
+        builder.cp_link_r(&builder.src.join("src/etc/locale"), &locale_dir);
+
         target_compiler
     }
 }
@@ -473,6 +473,10 @@ fn prepare_image(builder: &Builder<'_>, compiler: Compiler, image: &Path) {
                 }
             }
 
+            // Translations of the diagnostics
+            t!(fs::create_dir_all(image.join("share/locale")));
+            builder.cp_link_r(&src.join("share/locale"), &image.join("share/locale"));
+
             // Man pages
             t!(fs::create_dir_all(image.join("share/man/man1")));
             let man_src = builder.src.join("src/doc/man");
@@ -0,0 +1,278 @@
+borrowck_assign_due_to_use_closure =
+    la asignación se produce por el uso en la clausura
+
+borrowck_assign_due_to_use_coroutine =
+    la asignación se produce por el uso en la corrutina
+
+borrowck_assign_part_due_to_use_closure =
+    la asignación a una parte se produce por el uso en la clausura
+
+borrowck_assign_part_due_to_use_coroutine =
+    la asignación a una parte se produce por el uso en la corrutina
+
+borrowck_borrow_due_to_use_closure =
+    el préstamo se produce por el uso en la clausura
+
+borrowck_borrow_due_to_use_coroutine =
+    el préstamo se produce por el uso en la corrutina
+
+borrowck_calling_operator_moves =
+    llamar a este operador mueve el valor
+
+borrowck_calling_operator_moves_lhs =
+    llamar a este operador mueve el lado izquierdo
+
+borrowck_cannot_move_when_borrowed =
+    no se puede mover {$place ->
+        [value] el valor
+        *[other] {$place}
+    } porque está prestado
+    .label = el préstamo de {$borrow_place ->
+        [value] el valor
+        *[other] {$borrow_place}
+    } se produce aquí
+    .move_label = el movimiento de {$value_place ->
+        [value] el valor
+        *[other] {$value_place}
+    } se produce aquí
+
+borrowck_capture_immute =
+    la captura es inmutable por el uso aquí
+
+borrowck_capture_move =
+    la captura se mueve por el uso aquí
+
+borrowck_capture_mut =
+    la captura es mutable por el uso aquí
+
+borrowck_closure_inferred_mut = se infiere que es una clausura `FnMut`
+
+borrowck_closure_invoked_twice =
+    la clausura no se puede invocar más de una vez porque mueve la variable `{$place_name}` fuera de su entorno
+
+borrowck_closure_moved_twice =
+    la clausura no se puede mover más de una vez, ya que no es `Copy` porque mueve la variable `{$place_name}` fuera de su entorno
+
+borrowck_consider_borrow_type_contents =
+    ayuda: considera llamar a `.as_ref()` o `.as_mut()` para tomar prestado el contenido del tipo
+
+borrowck_could_not_normalize =
+    no se pudo normalizar `{$value}`
+
+borrowck_could_not_prove =
+    no se pudo demostrar `{$predicate}`
+
+borrowck_func_take_self_moved_place =
+    `{$func}` toma posesión del receptor `self`, lo que mueve {$place_name}
+
+borrowck_generic_does_not_live_long_enough =
+    `{$kind}` no vive lo suficiente
+
+borrowck_higher_ranked_lifetime_error =
+    error de tiempo de vida de rango superior
+
+borrowck_higher_ranked_subtype_error =
+    error de subtipo de rango superior
+
+borrowck_lifetime_constraints_error =
+    puede que el tiempo de vida no dure lo suficiente
+
+borrowck_move_out_place_here =
+    {$place} se mueve aquí
+
+borrowck_move_unsized =
+    no se puede mover un valor del tipo `{$ty}`
+    .label = el tamaño de `{$ty}` no se puede determinar de forma estática
+
+borrowck_moved_a_fn_once_in_call =
+    este valor implementa `FnOnce`, lo que hace que se mueva al llamarlo
+
+borrowck_moved_a_fn_once_in_call_call =
+    las clausuras `FnOnce` solo se pueden llamar una vez
+
+borrowck_moved_a_fn_once_in_call_def =
+    `{$ty}` pasa a ser una clausura `FnOnce` aquí
+
+borrowck_moved_due_to_await =
+    {$place_name} {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por este {$is_loop_message ->
+        [true] await, en una iteración anterior del bucle
+        *[false] await
+    }
+
+borrowck_moved_due_to_call =
+    {$place_name} {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por esta {$is_loop_message ->
+        [true] llamada, en una iteración anterior del bucle
+        *[false] llamada
+    }
+
+borrowck_moved_due_to_implicit_into_iter_call =
+    {$place_name} {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por esta llamada implícita a {$is_loop_message ->
+        [true] `.into_iter()`, en una iteración anterior del bucle
+        *[false] `.into_iter()`
+    }
+
+borrowck_moved_due_to_method_call =
+    {$place_name} {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por esta llamada a un {$is_loop_message ->
+        [true] método, en una iteración anterior del bucle
+        *[false] método
+    }
+
+borrowck_moved_due_to_usage_in_operator =
+    {$place_name} {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por el uso en el {$is_loop_message ->
+        [true] operador, en una iteración anterior del bucle
+        *[false] operador
+    }
+
+borrowck_opaque_type_lifetime_mismatch =
+    tipo opaco usado dos veces con tiempos de vida distintos
+    .label = el tiempo de vida `{$arg}` se usa aquí
+    .prev_lifetime_label = el tiempo de vida `{$prev}` se usó antes aquí
+    .note = si todos los parámetros genéricos que no son tiempos de vida son iguales, pero los parámetros de tiempo de vida difieren, no es posible distinguir los tipos opacos
+
+borrowck_opaque_type_non_generic_param =
+    se esperaba un parámetro {$kind} genérico, se encontró `{$ty}`
+    .label = {STREQ($ty, "'static") ->
+        [true] no se puede usar el tiempo de vida static; usa un tiempo de vida ligado o elimina el parámetro de tiempo de vida del tipo opaco
+        *[other] este parámetro genérico se debe usar con un parámetro {$kind} genérico
+    }
+
+borrowck_partial_var_move_by_use_in_closure =
+    la variable {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por el uso en la clausura
+
+borrowck_partial_var_move_by_use_in_coroutine =
+    la variable {$is_partial ->
+        [true] se mueve en parte
+        *[false] se mueve
+    } por el uso en la corrutina
+
+borrowck_returned_async_block_escaped =
+    devuelve un bloque `async` que contiene una referencia a una variable capturada, que luego escapa del cuerpo de la clausura
+
+borrowck_returned_closure_escaped =
+    devuelve una clausura que contiene una referencia a una variable capturada, que luego escapa del cuerpo de la clausura
+
+borrowck_returned_lifetime_short =
+    {$category_desc}requiere que `{$free_region_name}` dure más que `{$outlived_fr_name}`
+
+borrowck_returned_lifetime_wrong =
+    {$mir_def_name} debía devolver datos con el tiempo de vida `{$outlived_fr_name}`, pero devuelve datos con el tiempo de vida `{$fr_name}`
+
+borrowck_returned_ref_escaped =
+    devuelve una referencia a una variable capturada que escapa del cuerpo de la clausura
+
+borrowck_simd_intrinsic_arg_const =
+    el {$arg ->
+        [1] 1.º
+        [2] 2.º
+        [3] 3.º
+        *[other] {$arg}.º
+    } argumento de `{$intrinsic}` debe ser un elemento `const`
+
+borrowck_suggest_create_freash_reborrow =
+    considera volver a tomar prestado el `Pin` en lugar de moverlo
+
+borrowck_suggest_iterate_over_slice =
+    considera iterar sobre un slice del contenido de `{$ty}` para evitar moverlo al bucle `for`
+
+borrowck_ty_no_impl_copy =
+    {$is_partial_move ->
+        [true] el movimiento parcial
+        *[false] el movimiento
+    } se produce porque {$place} tiene el tipo `{$ty}`, que no implementa el trait `Copy`
+
+borrowck_use_due_to_use_closure =
+    el uso se produce por el uso en la clausura
+
+borrowck_use_due_to_use_coroutine =
+    el uso se produce por el uso en la corrutina
+
+borrowck_used_impl_require_static =
+    el `impl` usado tiene un requisito `'static`
+
+borrowck_value_capture_here =
+    valor capturado {$is_within ->
+        [true] aquí por la corrutina
+        *[false] aquí
+    }
+
+borrowck_value_moved_here =
+    valor {$is_partial ->
+        [true] movido en parte
+        *[false] movido
+    } {$is_move_msg ->
+        [true] a la clausura aquí
+        *[false] aquí
+    }{$is_loop_message ->
+        [true] , en una iteración anterior del bucle
+        *[false] {""}
+    }
+
+borrowck_var_borrow_by_use_in_closure =
+    el préstamo se produce por el uso en la clausura
+
+borrowck_var_borrow_by_use_in_coroutine =
+    el préstamo se produce por el uso en la corrutina
+
+borrowck_var_borrow_by_use_place_in_closure =
+    {$is_single_var ->
+        *[true] el préstamo se produce
+        [false] los préstamos se producen
+    } por el uso de {$place} en la clausura
+
+borrowck_var_borrow_by_use_place_in_coroutine =
+    {$is_single_var ->
+        *[true] el préstamo se produce
+        [false] los préstamos se producen
+    } por el uso de {$place} en la corrutina
+
+borrowck_var_cannot_escape_closure =
+    la variable capturada no puede escapar del cuerpo de la clausura `FnMut`
+    .note = las clausuras `FnMut` solo tienen acceso a sus variables capturadas mientras se ejecutan...
+    .cannot_escape = ...por lo tanto, no pueden permitir que escapen referencias a las variables capturadas
+
+borrowck_var_does_not_need_mut =
+    la variable no necesita ser mutable
+    .suggestion = elimina este `mut`
+
+borrowck_var_first_borrow_by_use_place_in_closure =
+    el primer préstamo se produce por el uso de {$place} en la clausura
+
+borrowck_var_first_borrow_by_use_place_in_coroutine =
+    el primer préstamo se produce por el uso de {$place} en la corrutina
+
+borrowck_var_here_captured = variable capturada aquí
+
+borrowck_var_here_defined = variable definida aquí
+
+borrowck_var_move_by_use_in_closure =
+    el movimiento se produce por el uso en la clausura
+
+borrowck_var_move_by_use_in_coroutine =
+    el movimiento se produce por el uso en la corrutina
+
+borrowck_var_mutable_borrow_by_use_place_in_closure =
+    el préstamo mutable se produce por el uso de {$place} en la clausura
+
+borrowck_var_second_borrow_by_use_place_in_closure =
+    el segundo préstamo se produce por el uso de {$place} en la clausura
+
+borrowck_var_second_borrow_by_use_place_in_coroutine =
+    el segundo préstamo se produce por el uso de {$place} en la corrutina
@@ -0,0 +1,187 @@
+hir_typeck_add_missing_parentheses_in_range = debes rodear el rango con paréntesis para llamar a su función `{$func_name}`
+
+hir_typeck_add_return_type_add = intenta añadir un tipo de retorno
+
+hir_typeck_add_return_type_missing_here = puede que falte un tipo de retorno aquí
+
+hir_typeck_address_of_temporary_taken = no se puede tomar la dirección de un valor temporal
+    .label = valor temporal
+
+hir_typeck_arg_mismatch_indeterminate = se detectó que el tipo de un argumento no coincide, pero rustc no pudo determinar dónde
+    .note = agradeceríamos un informe de error: https://github.com/rust-lang/rust/issues/new
+
+hir_typeck_candidate_trait_note = `{$trait_name}` define un elemento `{$item_name}`{$action_or_ty ->
+    [NONE] {""}
+    [implement] , quizás necesites implementarlo
+    *[other] , quizás necesites restringir el parámetro de tipo `{$action_or_ty}` con él
+}
+
+hir_typeck_cannot_cast_to_bool = no se puede convertir `{$expr_ty}` en `bool`
+    .suggestion = compara con cero en su lugar
+    .help = compara con cero en su lugar
+    .label = conversión no admitida
+
+hir_typeck_cast_enum_drop = no se puede convertir el enum `{$expr_ty}` en el entero `{$cast_ty}` porque implementa `Drop`
+
+hir_typeck_cast_thin_pointer_to_fat_pointer = no se puede convertir el puntero estrecho `{$expr_ty}` en el puntero ancho `{$cast_ty}`
+    .teach_help = Los punteros estrechos son punteros "simples": son solo una referencia a una
+        dirección de memoria.
+
+        Los punteros anchos son punteros que hacen referencia a "tipos de tamaño dinámico"
+        (también llamados DST). Los DST no tienen un tamaño conocido de forma estática, así que
+        solo pueden existir detrás de algún tipo de puntero que contenga información adicional.
+        Los slices y los objetos trait son DST. En el caso de los slices, la información adicional
+        que guarda el puntero ancho es su tamaño.
+
+        Para corregir este error, no intentes convertir directamente entre punteros estrechos y
+        punteros anchos.
+
+        Para más información sobre las conversiones, consulta la referencia:
+        https://doc.rust-lang.org/reference/expressions/operator-expr.html#type-cast-expressions
+
+hir_typeck_cast_unknown_pointer = no se puede convertir {$to ->
+    [true] a
+    *[false] desde
+    } un puntero de un tipo desconocido
+    .label_to = se necesita más información de tipos
+    .note = la información de tipos dada aquí no basta para comprobar si la conversión del puntero es válida
+    .label_from = la información de tipos dada aquí no basta para comprobar si la conversión del puntero es válida
+
+hir_typeck_const_select_must_be_const = este argumento debe ser una `const fn`
+    .help = consulta la documentación de `const_eval_select` para más información
+
+hir_typeck_const_select_must_be_fn = este argumento debe ser un elemento función
+    .note = se esperaba un elemento función, se encontró {$ty}
+    .help = consulta la documentación de `const_eval_select` para más información
+
+hir_typeck_convert_to_str = intenta convertir el tipo pasado en un `&str`
+
+hir_typeck_convert_using_method = intenta usar `{$sugg}` para convertir `{$found}` en `{$expected}`
+
+hir_typeck_ctor_is_private = el constructor del struct tupla `{$def}` es privado
+
+hir_typeck_dependency_on_unit_never_type_fallback = esta función depende de que el tipo por defecto de never sea `()`
+    .note = en la edición 2024, el requisito `{$obligation}` fallará
+    .help = especifica los tipos de forma explícita
+
+hir_typeck_deref_is_empty = esta expresión hace `Deref` a `{$deref_ty}`, que implementa `is_empty`
+
+hir_typeck_expected_default_return_type = se esperaba `()` por el tipo de retorno por defecto
+
+hir_typeck_expected_return_type = se esperaba `{$expected}` por el tipo de retorno
+
+hir_typeck_explicit_destructor = uso explícito del método destructor
+    .label = no se permiten llamadas explícitas al destructor
+    .suggestion = considera usar la función `drop`
+
+hir_typeck_field_multiply_specified_in_initializer =
+    el campo `{$ident}` se especifica más de una vez
+    .label = se usa más de una vez
+    .previous_use_label = primer uso de `{$ident}`
+
+hir_typeck_fru_expr = esta expresión no termina en una coma...
+hir_typeck_fru_expr2 = ... así que se interpreta como una expresión de rango `..`, en lugar de la sintaxis de actualización funcional de registros
+hir_typeck_fru_note = puede que esta expresión se haya interpretado mal como una expresión de rango `..`
+hir_typeck_fru_suggestion =
+    para asignar los campos restantes{$expr ->
+        [NONE]{""}
+        *[other] {" "}a partir de `{$expr}`
+    }, separa el último campo con nombre con una coma
+
+hir_typeck_functional_record_update_on_non_struct =
+    la sintaxis de actualización funcional de registros requiere un struct
+
+hir_typeck_help_set_edition_cargo = establece `edition = "{$edition}"` en `Cargo.toml`
+hir_typeck_help_set_edition_standalone = pasa `--edition {$edition}` a `rustc`
+
+hir_typeck_int_to_fat = no se puede convertir `{$expr_ty}` en un puntero que {$known_wide ->
+    [true] es
+    *[false] puede ser
+    } ancho
+hir_typeck_int_to_fat_label = crear un `{$cast_ty}` requiere tanto una dirección como {$metadata}
+hir_typeck_int_to_fat_label_nightly = considera convertir esta expresión en `*const ()` y luego usar `core::ptr::from_raw_parts`
+
+hir_typeck_invalid_callee = se esperaba una función, se encontró {$ty}
+
+hir_typeck_lossy_provenance_int2ptr =
+    la procedencia estricta no permite convertir el entero `{$expr_ty}` en el puntero `{$cast_ty}`
+    .suggestion = usa `.with_addr()` para ajustar un puntero válido de la misma asignación a esta dirección
+    .help = si no puedes cumplir con la procedencia estricta y no tienes un puntero con la procedencia correcta, puedes usar `std::ptr::with_exposed_provenance()` en su lugar
+
+hir_typeck_lossy_provenance_ptr2int =
+    con la procedencia estricta se considera mal estilo convertir el puntero `{$expr_ty}` en el entero `{$cast_ty}`
+    .suggestion = usa `.addr()` para obtener la dirección de un puntero
+    .help = si no puedes cumplir con la procedencia estricta y necesitas exponer la procedencia del puntero, puedes usar `.expose_provenance()` en su lugar
+
+hir_typeck_missing_parentheses_in_range = no se puede llamar al método `{$method_name}` en el tipo `{$ty_str}`
+
+hir_typeck_never_type_fallback_flowing_into_unsafe_call = el tipo por defecto de never afecta a esta llamada a una función `unsafe`
+    .help = especifica el tipo de forma explícita
+hir_typeck_never_type_fallback_flowing_into_unsafe_deref = el tipo por defecto de never afecta a esta desreferencia de un puntero sin procesar
+    .help = especifica el tipo de forma explícita
+hir_typeck_never_type_fallback_flowing_into_unsafe_method = el tipo por defecto de never afecta a esta llamada a un método `unsafe`
+    .help = especifica el tipo de forma explícita
+hir_typeck_never_type_fallback_flowing_into_unsafe_path = el tipo por defecto de never afecta a esta función `unsafe`
+    .help = especifica el tipo de forma explícita
+hir_typeck_never_type_fallback_flowing_into_unsafe_union_field = el tipo por defecto de never afecta a este acceso a una union
+    .help = especifica el tipo de forma explícita
+
+hir_typeck_no_associated_item = no se encontró ningún {$item_kind} llamado `{$item_name}` para {$ty_prefix} `{$ty_str}`{$trait_missing_method ->
+    [true] {""}
+    *[other] {" "}en el ámbito actual
+}
+
+hir_typeck_note_caller_chooses_ty_for_ty_param = quien llama elige un tipo para `{$ty_param_name}`, que puede ser distinto de `{$found_ty}`
+
+hir_typeck_note_edition_guide = para más información sobre las ediciones, lee https://doc.rust-lang.org/edition-guide
+
+hir_typeck_option_result_asref = usa `{$def_path}::as_ref` para convertir `{$expected_ty}` en `{$expr_ty}`
+hir_typeck_option_result_cloned = usa `{$def_path}::cloned` para clonar el valor dentro del `{$def_path}`
+hir_typeck_option_result_copied = usa `{$def_path}::copied` para copiar el valor dentro del `{$def_path}`
+
+hir_typeck_remove_semi_for_coerce = quizás quisiste devolver la expresión `match`
+hir_typeck_remove_semi_for_coerce_expr = esto podría devolverse de forma implícita, pero es una sentencia, no una expresión final
+hir_typeck_remove_semi_for_coerce_ret = los brazos del `match` pueden ajustarse a este tipo de retorno
+hir_typeck_remove_semi_for_coerce_semi = el `match` es una sentencia por este punto y coma, considera eliminarlo
+hir_typeck_remove_semi_for_coerce_suggestion = elimina este punto y coma
+
+hir_typeck_return_stmt_outside_of_fn_body =
+    sentencia {$statement_kind} fuera del cuerpo de una función
+    .encl_body_label = el {$statement_kind} forma parte de este cuerpo...
+    .encl_fn_label = ...no del cuerpo de la función que lo contiene
+
+hir_typeck_rpit_box_return_expr = si cambias el tipo de retorno para que espere objetos trait, mete en una caja las expresiones devueltas
+
+hir_typeck_rpit_change_return_type = podrías cambiar el tipo de retorno para que sea un objeto trait en una caja
+
+hir_typeck_rustcall_incorrect_args =
+    las funciones con la ABI "rust-call" deben tomar un único argumento de tipo tupla que no sea self
+
+hir_typeck_self_ctor_from_outer_item = no se puede hacer referencia al constructor `Self` desde un elemento externo
+    .label = el elemento interno no hereda los genéricos de este impl, así que no es válido hacer referencia a `Self`
+    .suggestion = sustituye `Self` por el tipo concreto
+
+hir_typeck_struct_expr_non_exhaustive =
+    no se puede crear un {$what} no exhaustivo con una expresión de struct
+
+hir_typeck_suggest_boxing_note = para más información sobre la diferencia entre la pila y el montículo, lee https://doc.rust-lang.org/book/ch15-01-box.html, https://doc.rust-lang.org/rust-by-example/std/box.html y https://doc.rust-lang.org/std/boxed/index.html
+
+hir_typeck_suggest_boxing_when_appropriate = guarda esto en el montículo llamando a `Box::new`
+
+hir_typeck_suggest_ptr_null_mut = considera usar `core::ptr::null_mut` en su lugar
+
+hir_typeck_trivial_cast = conversión {$numeric ->
+    [true] numérica trivial
+    *[false] trivial
+    }: `{$expr_ty}` como `{$cast_ty}`
+    .help = la conversión puede sustituirse por una coerción; puede que esto requiera una variable temporal
+
+hir_typeck_union_pat_dotdot = no se puede usar `..` en los patrones de union
+
+hir_typeck_union_pat_multiple_fields = los patrones de union deben tener exactamente un campo
+
+hir_typeck_use_is_empty =
+    considera usar el método `is_empty` de `{$expr_ty}` para determinar si contiene algo
+
+hir_typeck_yield_expr_outside_of_coroutine =
+    expresión yield fuera de un literal de corrutina
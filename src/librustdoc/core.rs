@@ -3,6 +3,7 @@
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::emitter::{stderr_destination, DynEmitter, HumanEmitter};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{codes::*, DiagCtxtHandle, ErrorGuaranteed, TerminalUrl};
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::Res;
@@ -126,8 +127,8 @@ pub(crate) fn as_local_hir_id(tcx: TyCtxt<'_>, item_id: ItemId) -> Option<HirId>
 
 /// Creates a new `DiagCtxt` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the `DiagCtxt`.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the `DiagCtxt`.
 pub(crate) fn new_dcx(
     error_format: ErrorOutputType,
     source_map: Option<Lrc<source_map::SourceMap>>,
@@ -169,6 +170,17 @@ pub(crate) fn new_dcx(
                 .terminal_url(TerminalUrl::No),
             )
         }
+        ErrorOutputType::Sarif { pretty } => {
+            let source_map = source_map.unwrap_or_else(|| {
+                Lrc::new(source_map::SourceMap::new(source_map::FilePathMapping::empty()))
+            });
+            Box::new(SarifEmitter::new(
+                Box::new(io::BufWriter::new(io::stderr())),
+                source_map,
+                fallback_bundle,
+                pretty,
+            ))
+        }
     };
 
     rustc_errors::DiagCtxt::new(emitter).with_flags(unstable_opts.dcx_flags(true))
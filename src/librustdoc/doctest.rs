@@ -717,6 +717,7 @@ fn add_test(&mut self, test: ScrapedDoctest) {
                 compile_fail: test.langstr.compile_fail,
                 no_run: test.no_run(&rustdoc_options),
                 test_type: test::TestType::DocTest,
+                timeout: None,
             },
             testfn: test::DynTestFn(Box::new(move || {
                 doctest_run_fn(rustdoc_test_options, opts, test, rustdoc_options, unused_externs)
@@ -443,7 +443,7 @@ fn opts() -> Vec<RustcOptGroup> {
                 "",
                 "error-format",
                 "How errors and other messages are produced",
-                "human|json|short",
+                "human|json|short|sarif|pretty-sarif",
             )
         }),
         stable("diagnostic-width", |o| {
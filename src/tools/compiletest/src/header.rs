@@ -1308,6 +1308,7 @@ macro_rules! decision {
         compile_fail: false,
         no_run: false,
         test_type: test::TestType::Unknown,
+        timeout: None,
     }
 }
 
@@ -545,11 +545,14 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         shuffle_seed: None,
         test_threads: None,
         skip: config.skip.clone(),
+        shard: None,
         list: false,
         options: test::Options::new(),
         time_options: None,
+        test_timeout: None,
         force_run_in_process: false,
         fail_fast: std::env::var_os("RUSTC_TEST_FAIL_FAST").is_some(),
+        retries: 0,
     }
 }
 
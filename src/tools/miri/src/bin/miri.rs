@@ -44,7 +44,10 @@
 use rustc_session::search_paths::PathKind;
 use rustc_session::{CtfeBacktrace, EarlyDiagCtxt};
 
-use miri::{BacktraceStyle, BorrowTrackerMethod, ProvenanceMode, RetagFields};
+use miri::{
+    BacktraceStyle, BorrowTrackerMethod, ProvenanceMode, RetagFields, ScheduleTrace,
+    ScheduleTraceMode,
+};
 
 struct MiriCompilerCalls {
     miri_config: miri::MiriConfig,
@@ -407,6 +410,8 @@ fn main() {
     let mut after_dashdash = false;
     // If user has explicitly enabled/disabled isolation
     let mut isolation_enabled: Option<bool> = None;
+    // Where to write the recorded (or minimized) schedule trace to
+    let mut record_schedule: Option<PathBuf> = None;
 
     // Note that we require values to be given with `=`, not with a space.
     // This matches how rustc parses `-Z`.
@@ -503,6 +508,27 @@ fn main() {
                 show_error!("-Zmiri-seed must be an integer that fits into u64")
             });
             miri_config.seed = Some(seed);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record-schedule=") {
+            if record_schedule.is_some() {
+                show_error!("Cannot record more than one schedule trace!");
+            }
+            record_schedule = Some(PathBuf::from(param));
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay-schedule=") {
+            if !matches!(miri_config.schedule_trace, ScheduleTraceMode::Off) {
+                show_error!("Cannot replay or minimize more than one schedule trace!");
+            }
+            let trace = ScheduleTrace::read(param.as_ref()).unwrap_or_else(|err| {
+                show_error!("-Zmiri-replay-schedule could not read `{param}`: {err}")
+            });
+            miri_config.schedule_trace = ScheduleTraceMode::Replay(trace);
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-minimize-schedule=") {
+            if !matches!(miri_config.schedule_trace, ScheduleTraceMode::Off) {
+                show_error!("Cannot replay or minimize more than one schedule trace!");
+            }
+            let trace = ScheduleTrace::read(param.as_ref()).unwrap_or_else(|err| {
+                show_error!("-Zmiri-minimize-schedule could not read `{param}`: {err}")
+            });
+            miri_config.schedule_trace = ScheduleTraceMode::Minimize(PathBuf::from(param), trace);
         } else if let Some(_param) = arg.strip_prefix("-Zmiri-env-exclude=") {
             show_error!(
                 "`-Zmiri-env-exclude` has been removed; unset env vars before starting Miri instead"
@@ -609,6 +635,15 @@ fn main() {
             rustc_args.push(arg);
         }
     }
+    // `-Zmiri-record-schedule` records a new trace, or says where to write the minimized trace to
+    if let Some(path) = record_schedule {
+        miri_config.schedule_trace = match miri_config.schedule_trace {
+            ScheduleTraceMode::Off => ScheduleTraceMode::Record(path),
+            ScheduleTraceMode::Minimize(_, trace) => ScheduleTraceMode::Minimize(path, trace),
+            ScheduleTraceMode::Record(_) | ScheduleTraceMode::Replay(_) =>
+                show_error!("-Zmiri-record-schedule cannot be used with -Zmiri-replay-schedule"),
+        };
+    }
     // `-Zmiri-unique-is-unique` should only be used with `-Zmiri-tree-borrows`
     if miri_config.unique_is_unique
         && !matches!(miri_config.borrow_tracker, Some(BorrowTrackerMethod::TreeBorrows))
@@ -1,6 +1,7 @@
 pub mod data_race;
 pub mod init_once;
 mod range_object_map;
+pub mod replay;
 pub mod sync;
 pub mod thread;
 mod vector_clock;
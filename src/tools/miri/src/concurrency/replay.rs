@@ -0,0 +1,404 @@
+//! Recording and replaying the non-deterministic choices that Miri makes.
+//!
+//! Which bugs a run of the interpreted program hits depends on when threads get preempted, which
+//! store a weak memory load reads from, and all other randomness drawn from the machine's RNG.
+//! With `-Zmiri-record-schedule`, all of these choices are written to a trace file, which
+//! `-Zmiri-replay-schedule` then forces to be made the same way again, independent of
+//! `-Zmiri-seed` and `-Zmiri-preemption-rate`. Every kind of choice is replayed from its own
+//! stream, and preemptions are identified by the thread and how far that thread has got, so
+//! changing the code that one thread runs only shifts the later preemptions of that thread.
+//! Changes that affect how many weak memory loads or random draws happen before a choice do shift
+//! that choice.
+//!
+//! The trace file has one choice per line:
+//! - `preempt <thread> <n>`: the thread is preempted at its `n`-th preemption point (counting
+//!   from 0), i.e. at the end of the `n`-th basic block it executes.
+//! - `store <i>`: a weak memory load reads from the `i`-th store it may read from, counting
+//!   from the latest store.
+//! - `u32 <n>`, `u64 <n>`, `bytes <hex>`: random numbers and bytes drawn from the RNG.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::{Rng, RngCore, SeedableRng};
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+
+use crate::ThreadId;
+
+/// What to do with schedule traces.
+#[derive(Clone, Debug, Default)]
+pub enum ScheduleTraceMode {
+    /// Make all choices with the seeded RNG.
+    #[default]
+    Off,
+    /// Make all choices with the seeded RNG, and write them to this file when the program ends.
+    Record(PathBuf),
+    /// Make the choices from this trace.
+    Replay(ScheduleTrace),
+    /// Shrink this trace to as few preemptions as possible while the program still fails the
+    /// same way, write the result to this file, and then replay it.
+    Minimize(PathBuf, ScheduleTrace),
+}
+
+/// A random value drawn from the RNG.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RandomDraw {
+    U32(u32),
+    U64(u64),
+    Bytes(Vec<u8>),
+}
+
+/// The non-deterministic choices made during a run of the interpreted program. Each kind of
+/// choice is stored separately, so that the choices of one kind still line up with the program
+/// if the choices of another kind change.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScheduleTrace {
+    /// The preemptions in the order they happened, as the thread that was preempted and the
+    /// preemption point of that thread at which it was preempted.
+    preemptions: Vec<(u32, u64)>,
+    /// For each weak memory load, which of the stores it may read from it read from.
+    stores: VecDeque<usize>,
+    random: VecDeque<RandomDraw>,
+}
+
+impl ScheduleTrace {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        Self::parse(&contents)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.serialize())
+    }
+
+    fn parse(contents: &str) -> Result<Self, String> {
+        let mut trace = ScheduleTrace::default();
+        for (line_number, line) in contents.lines().enumerate() {
+            let invalid = || format!("line {} is not a valid choice: `{line}`", line_number + 1);
+            let Some((kind, value)) = line.split_once(' ') else {
+                return Err(invalid());
+            };
+            match kind {
+                "preempt" => {
+                    let (thread, point) = value.split_once(' ').ok_or_else(invalid)?;
+                    let thread = thread.parse().map_err(|_| invalid())?;
+                    let point = point.parse().map_err(|_| invalid())?;
+                    trace.preemptions.push((thread, point));
+                }
+                "store" => trace.stores.push_back(value.parse().map_err(|_| invalid())?),
+                "u32" => {
+                    let value = value.parse().map_err(|_| invalid())?;
+                    trace.random.push_back(RandomDraw::U32(value));
+                }
+                "u64" => {
+                    let value = value.parse().map_err(|_| invalid())?;
+                    trace.random.push_back(RandomDraw::U64(value));
+                }
+                "bytes" => {
+                    if !value.bytes().all(|b| b.is_ascii_hexdigit()) || value.len() % 2 != 0 {
+                        return Err(invalid());
+                    }
+                    let bytes = value
+                        .as_bytes()
+                        .chunks(2)
+                        .map(|digits| {
+                            // The digits are ASCII, so they are valid UTF-8.
+                            u8::from_str_radix(std::str::from_utf8(digits).unwrap(), 16).unwrap()
+                        })
+                        .collect();
+                    trace.random.push_back(RandomDraw::Bytes(bytes));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(trace)
+    }
+
+    fn serialize(&self) -> String {
+        let mut contents = String::new();
+        for (thread, point) in &self.preemptions {
+            writeln!(contents, "preempt {thread} {point}").unwrap();
+        }
+        for store in &self.stores {
+            writeln!(contents, "store {store}").unwrap();
+        }
+        for draw in &self.random {
+            match draw {
+                RandomDraw::U32(value) => writeln!(contents, "u32 {value}").unwrap(),
+                RandomDraw::U64(value) => writeln!(contents, "u64 {value}").unwrap(),
+                RandomDraw::Bytes(bytes) => {
+                    contents.push_str("bytes ");
+                    for byte in bytes {
+                        write!(contents, "{byte:02x}").unwrap();
+                    }
+                    contents.push('\n');
+                }
+            }
+        }
+        contents
+    }
+
+    /// The number of preemptions in this trace.
+    pub fn preemption_count(&self) -> usize {
+        self.preemptions.len()
+    }
+
+    /// Shrinks this trace to as few preemptions as possible such that `still_fails` holds for
+    /// it, assuming that `still_fails` holds for this trace. The other choices are kept as they
+    /// are, since they only take effect as long as the program still asks for them.
+    pub fn minimize(self, mut still_fails: impl FnMut(&ScheduleTrace) -> bool) -> Self {
+        // Try removing ever smaller chunks of preemptions, keeping every removal after which the
+        // program still fails the same way.
+        let mut trace = self;
+        let mut chunk_size = trace.preemption_count().div_ceil(2);
+        while chunk_size > 0 {
+            let mut start = 0;
+            while start < trace.preemption_count() {
+                let end = (start + chunk_size).min(trace.preemption_count());
+                let mut candidate = trace.clone();
+                candidate.preemptions.drain(start..end);
+                if still_fails(&candidate) {
+                    trace = candidate;
+                } else {
+                    start = end;
+                }
+            }
+            chunk_size /= 2;
+        }
+        trace
+    }
+}
+
+#[derive(Debug)]
+enum TraceState {
+    Off,
+    Record(ScheduleTrace),
+    /// The choices that have not been replayed yet. Once a stream runs out, or does not match
+    /// what the program asks for, the seeded RNG takes over. Preemptions are looked up by
+    /// thread and preemption point instead.
+    Replay {
+        trace: ScheduleTrace,
+        preemptions: FxHashSet<(u32, u64)>,
+    },
+}
+
+/// The RNG that Miri uses for all non-deterministic choices. Depending on the
+/// `ScheduleTraceMode`, it records the choices it makes or replays them from a trace.
+#[derive(Debug)]
+pub struct TracedRng {
+    rng: StdRng,
+    state: TraceState,
+    /// How many preemption points each thread has passed so far.
+    preemption_points: FxHashMap<u32, u64>,
+}
+
+impl TracedRng {
+    pub fn new(seed: u64, mode: &ScheduleTraceMode) -> Self {
+        let state = match mode {
+            ScheduleTraceMode::Off => TraceState::Off,
+            ScheduleTraceMode::Record(_) => TraceState::Record(ScheduleTrace::default()),
+            ScheduleTraceMode::Replay(trace) | ScheduleTraceMode::Minimize(_, trace) =>
+                TraceState::Replay {
+                    trace: trace.clone(),
+                    preemptions: trace.preemptions.iter().copied().collect(),
+                },
+        };
+        TracedRng { rng: StdRng::seed_from_u64(seed), state, preemption_points: Default::default() }
+    }
+
+    /// Returns the choices recorded so far, if recording.
+    pub fn recorded_trace(&self) -> Option<&ScheduleTrace> {
+        match &self.state {
+            TraceState::Record(trace) => Some(trace),
+            _ => None,
+        }
+    }
+
+    /// Decides whether to preempt `thread`, which is the active thread and has reached a
+    /// preemption point, where `rate` is the probability of doing so.
+    pub fn preempt(&mut self, thread: ThreadId, rate: f64) -> bool {
+        let thread = thread.to_u32();
+        let points = self.preemption_points.entry(thread).or_insert(0);
+        let point = *points;
+        *points += 1;
+        match &mut self.state {
+            TraceState::Off => self.rng.gen_bool(rate),
+            TraceState::Record(trace) => {
+                let preempt = self.rng.gen_bool(rate);
+                if preempt {
+                    trace.preemptions.push((thread, point));
+                }
+                preempt
+            }
+            TraceState::Replay { preemptions, .. } => preemptions.contains(&(thread, point)),
+        }
+    }
+
+    /// Chooses which store a weak memory load reads from. `candidates` are the stores it may read
+    /// from, starting with the latest one.
+    pub fn choose_store<T>(&mut self, candidates: impl Iterator<Item = T>) -> Option<T> {
+        match &mut self.state {
+            TraceState::Off => candidates.choose(&mut self.rng),
+            TraceState::Record(trace) => {
+                let (index, candidate) = candidates.enumerate().choose(&mut self.rng)?;
+                trace.stores.push_back(index);
+                Some(candidate)
+            }
+            TraceState::Replay { trace, .. } => {
+                let mut candidates: Vec<T> = candidates.collect();
+                // Without a (matching) recorded choice, we read from the latest store.
+                let index = trace.stores.pop_front().filter(|&index| index < candidates.len());
+                if candidates.is_empty() {
+                    None
+                } else {
+                    Some(candidates.swap_remove(index.unwrap_or(0)))
+                }
+            }
+        }
+    }
+}
+
+impl RngCore for TracedRng {
+    fn next_u32(&mut self) -> u32 {
+        match &mut self.state {
+            TraceState::Off => self.rng.next_u32(),
+            TraceState::Record(trace) => {
+                let value = self.rng.next_u32();
+                trace.random.push_back(RandomDraw::U32(value));
+                value
+            }
+            TraceState::Replay { trace, .. } =>
+                match trace.random.pop_front() {
+                    Some(RandomDraw::U32(value)) => value,
+                    _ => {
+                        // The program no longer draws what was recorded.
+                        trace.random.clear();
+                        self.rng.next_u32()
+                    }
+                },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match &mut self.state {
+            TraceState::Off => self.rng.next_u64(),
+            TraceState::Record(trace) => {
+                let value = self.rng.next_u64();
+                trace.random.push_back(RandomDraw::U64(value));
+                value
+            }
+            TraceState::Replay { trace, .. } =>
+                match trace.random.pop_front() {
+                    Some(RandomDraw::U64(value)) => value,
+                    _ => {
+                        trace.random.clear();
+                        self.rng.next_u64()
+                    }
+                },
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match &mut self.state {
+            TraceState::Off => self.rng.fill_bytes(dest),
+            TraceState::Record(trace) => {
+                self.rng.fill_bytes(dest);
+                trace.random.push_back(RandomDraw::Bytes(dest.to_vec()));
+            }
+            TraceState::Replay { trace, .. } =>
+                match trace.random.pop_front() {
+                    Some(RandomDraw::Bytes(bytes)) if bytes.len() == dest.len() =>
+                        dest.copy_from_slice(&bytes),
+                    _ => {
+                        trace.random.clear();
+                        self.rng.fill_bytes(dest);
+                    }
+                },
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Makes the same kinds of choices as a run of a program with two threads would.
+    fn run(rng: &mut TracedRng, main_steps: u64) -> Vec<String> {
+        let mut choices = Vec::new();
+        for step in 0..main_steps {
+            choices.push(format!("main {step}: {}", rng.preempt(ThreadId::MAIN_THREAD, 0.5)));
+        }
+        for step in 0..20 {
+            choices.push(format!("thread {step}: {}", rng.preempt(ThreadId::from(1), 0.5)));
+        }
+        choices.push(format!("store {:?}", rng.choose_store(0..5)));
+        choices.push(format!("u32 {}", rng.next_u32()));
+        let mut bytes = [0; 6];
+        rng.fill_bytes(&mut bytes);
+        choices.push(format!("bytes {bytes:?}"));
+        choices
+    }
+
+    #[test]
+    fn replay_makes_the_recorded_choices() {
+        let mut rng = TracedRng::new(1, &ScheduleTraceMode::Record(PathBuf::new()));
+        let recorded = run(&mut rng, 20);
+        let trace = ScheduleTrace::parse(&rng.recorded_trace().unwrap().serialize()).unwrap();
+        assert_eq!(&trace, rng.recorded_trace().unwrap());
+        assert!(trace.preemption_count() > 0);
+
+        // The seed and the preemption rate do not matter when replaying.
+        let mut rng = TracedRng::new(2, &ScheduleTraceMode::Replay(trace));
+        assert_eq!(run(&mut rng, 20), recorded);
+    }
+
+    #[test]
+    fn preemptions_are_per_thread() {
+        let trace = ScheduleTrace::parse("preempt 1 3\npreempt 0 2\n").unwrap();
+        for main_steps in [0, 3, 10] {
+            let mut rng = TracedRng::new(0, &ScheduleTraceMode::Replay(trace.clone()));
+            let choices = run(&mut rng, main_steps);
+            // Running more steps on the main thread does not move the preemption of thread 1.
+            assert!(choices.contains(&"thread 3: true".to_string()));
+            let expected = if main_steps > 2 { 2 } else { 1 };
+            assert_eq!(choices.iter().filter(|choice| choice.ends_with("true")).count(), expected);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_lines() {
+        assert!(ScheduleTrace::parse("preempt 1\n").is_err());
+        assert!(ScheduleTrace::parse("store x\n").is_err());
+        assert!(ScheduleTrace::parse("bytes abc\n").is_err());
+        assert!(ScheduleTrace::parse("bytes aéa\n").is_err());
+        assert!(ScheduleTrace::parse("bytes +f\n").is_err());
+        assert!(ScheduleTrace::parse("yield 1 2\n").is_err());
+    }
+
+    #[test]
+    fn minimize_keeps_the_needed_preemptions() {
+        let trace = ScheduleTrace::parse(
+            "preempt 0 1\npreempt 1 5\npreempt 0 7\npreempt 1 9\npreempt 1 12\nu32 4\n",
+        )
+        .unwrap();
+        let mut runs = 0;
+        let minimized = trace.minimize(|trace| {
+            runs += 1;
+            trace.preemptions.contains(&(0, 1)) && trace.preemptions.contains(&(1, 9))
+        });
+        assert_eq!(minimized.preemptions, [(0, 1), (1, 9)]);
+        assert_eq!(minimized.random, [RandomDraw::U32(4)]);
+        assert!(runs < 10);
+    }
+}
@@ -150,6 +150,33 @@ struct FutexWaiter {
     bitset: u32,
 }
 
+declare_id!(KeyedEventId);
+
+/// The keyed event state. Keyed events are a rendezvous: a thread waiting for a key blocks
+/// until another thread releases that key, and a thread releasing a key blocks until another
+/// thread waits for it.
+#[derive(Default, Debug)]
+struct KeyedEvent {
+    /// The threads that are blocked on this keyed event.
+    waiters: VecDeque<KeyedEventWaiter>,
+    /// The clocks of the threads that were unblocked by the other side of the rendezvous,
+    /// which they acquire once they are scheduled again.
+    handoff_clocks: FxHashMap<ThreadId, VClock>,
+}
+
+/// A thread blocked on a keyed event.
+#[derive(Debug)]
+struct KeyedEventWaiter {
+    /// The thread that is blocked on this keyed event.
+    thread: ThreadId,
+    /// The key the thread is waiting for or releasing.
+    key: u64,
+    /// Whether the thread is releasing the key, as opposed to waiting for it.
+    releasing: bool,
+    /// The clock of the thread at the time it blocked.
+    clock: VClock,
+}
+
 /// The state of all synchronization objects.
 #[derive(Default, Debug)]
 pub struct SynchronizationObjects {
@@ -157,6 +184,7 @@ pub struct SynchronizationObjects {
     rwlocks: IndexVec<RwLockId, RwLock>,
     condvars: IndexVec<CondvarId, Condvar>,
     futexes: FxHashMap<u64, Futex>,
+    keyed_events: IndexVec<KeyedEventId, KeyedEvent>,
     pub(super) init_onces: IndexVec<InitOnceId, InitOnce>,
 }
 
@@ -771,4 +799,89 @@ fn futex_wake(&mut self, addr: u64, bitset: u32) -> InterpResult<'tcx, bool> {
         this.unblock_thread(waiter.thread, BlockReason::Futex { addr })?;
         Ok(true)
     }
+
+    /// Creates a new keyed event.
+    fn keyed_event_create(&mut self) -> KeyedEventId {
+        let this = self.eval_context_mut();
+        this.machine.sync.keyed_events.push(Default::default())
+    }
+
+    #[inline]
+    fn keyed_event_exists(&self, id: KeyedEventId) -> bool {
+        let this = self.eval_context_ref();
+        this.machine.sync.keyed_events.get(id).is_some()
+    }
+
+    /// Wait for (if `releasing` is `false`) or release (if `releasing` is `true`) `key` on the
+    /// keyed event. If a thread is already blocked on the other side of the rendezvous for the
+    /// same key, it is unblocked and `retval_succ` is written to `dest` right away. Otherwise the
+    /// current thread blocks until another thread shows up, or until the timeout, in which case
+    /// `retval_timeout` is written to `dest`.
+    fn keyed_event_rendezvous(
+        &mut self,
+        id: KeyedEventId,
+        key: u64,
+        releasing: bool,
+        timeout: Option<(TimeoutClock, TimeoutAnchor, Duration)>,
+        retval_succ: Scalar,
+        retval_timeout: Scalar,
+        dest: MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let thread = this.active_thread();
+        let keyed_event = &mut this.machine.sync.keyed_events[id];
+
+        if let Some(i) =
+            keyed_event.waiters.iter().position(|w| w.key == key && w.releasing != releasing)
+        {
+            // Someone is already waiting for us. Both sides of the rendezvous happen-before the
+            // other side returns.
+            let waiter = keyed_event.waiters.remove(i).unwrap();
+            if let Some(data_race) = &this.machine.data_race {
+                data_race.acquire_clock(&waiter.clock, &this.machine.threads);
+                let clock = data_race.release_clock(&this.machine.threads).clone();
+                keyed_event.handoff_clocks.insert(waiter.thread, clock);
+            }
+            this.unblock_thread(waiter.thread, BlockReason::KeyedEvent(id))?;
+            this.write_scalar(retval_succ, &dest)?;
+            return Ok(());
+        }
+
+        let clock = match &this.machine.data_race {
+            Some(data_race) => data_race.release_clock(&this.machine.threads).clone(),
+            None => VClock::default(),
+        };
+        keyed_event.waiters.push_back(KeyedEventWaiter { thread, key, releasing, clock });
+        this.block_thread(
+            BlockReason::KeyedEvent(id),
+            timeout,
+            callback!(
+                @capture<'tcx> {
+                    id: KeyedEventId,
+                    retval_succ: Scalar,
+                    retval_timeout: Scalar,
+                    dest: MPlaceTy<'tcx>,
+                }
+                @unblock = |this| {
+                    // Acquire the clock of the thread that unblocked us.
+                    let thread = this.active_thread();
+                    let clock = this.machine.sync.keyed_events[id].handoff_clocks.remove(&thread);
+                    if let (Some(data_race), Some(clock)) = (&this.machine.data_race, clock) {
+                        data_race.acquire_clock(&clock, &this.machine.threads);
+                    }
+                    this.write_scalar(retval_succ, &dest)?;
+                    Ok(())
+                }
+                @timeout = |this| {
+                    // Remove the waiter from the keyed event.
+                    let thread = this.active_thread();
+                    let keyed_event = &mut this.machine.sync.keyed_events[id];
+                    keyed_event.waiters.retain(|waiter| waiter.thread != thread);
+                    this.write_scalar(retval_timeout, &dest)?;
+                    Ok(())
+                }
+            ),
+        );
+        Ok(())
+    }
 }
@@ -172,6 +172,10 @@ pub enum BlockReason {
     Futex { addr: u64 },
     /// Blocked on an InitOnce.
     InitOnce(InitOnceId),
+    /// Blocked on a keyed event.
+    KeyedEvent(KeyedEventId),
+    /// Blocked until a file description becomes ready.
+    Fd,
 }
 
 /// The state of a thread.
@@ -1170,10 +1174,9 @@ fn yield_active_thread(&mut self) {
 
     #[inline]
     fn maybe_preempt_active_thread(&mut self) {
-        use rand::Rng as _;
-
         let this = self.eval_context_mut();
-        if this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate) {
+        let thread = this.active_thread();
+        if this.machine.rng.get_mut().preempt(thread, this.machine.preemption_rate) {
             this.yield_active_thread();
         }
     }
@@ -280,7 +280,7 @@ fn buffered_read(
         global: &DataRaceState,
         thread_mgr: &ThreadManager<'_>,
         is_seqcst: bool,
-        rng: &mut (impl rand::Rng + ?Sized),
+        rng: &mut TracedRng,
         validate: impl FnOnce() -> InterpResult<'tcx>,
     ) -> InterpResult<'tcx, (Scalar, LoadRecency)> {
         // Having a live borrow to store_buffer while calling validate_atomic_load is fine
@@ -291,7 +291,7 @@ fn buffered_read(
             // as the race detector will update it
             let (.., clocks) = global.active_thread_state(thread_mgr);
             // Load from a valid entry in the store buffer
-            self.fetch_store(is_seqcst, &clocks, &mut *rng)
+            self.fetch_store(is_seqcst, &clocks, rng)
         };
 
         // Unlike in buffered_atomic_write, thread clock updates have to be done
@@ -320,13 +320,12 @@ fn buffered_write(
 
     #[allow(clippy::if_same_then_else, clippy::needless_bool)]
     /// Selects a valid store element in the buffer.
-    fn fetch_store<R: rand::Rng + ?Sized>(
+    fn fetch_store(
         &self,
         is_seqcst: bool,
         clocks: &ThreadClockSet,
-        rng: &mut R,
+        rng: &mut TracedRng,
     ) -> (&StoreElement, LoadRecency) {
-        use rand::seq::IteratorRandom;
         let mut found_sc = false;
         // FIXME: we want an inclusive take_while (stops after a false predicate, but
         // includes the element that gave the false), but such function doesn't yet
@@ -397,7 +396,7 @@ fn fetch_store<R: rand::Rng + ?Sized>(
                 }
             });
 
-        let chosen = candidates.choose(rng).expect("store buffer cannot be empty");
+        let chosen = rng.choose_store(candidates).expect("store buffer cannot be empty");
         if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
             (chosen, LoadRecency::Latest)
         } else {
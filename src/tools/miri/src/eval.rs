@@ -2,6 +2,7 @@
 
 use std::ffi::{OsStr, OsString};
 use std::iter;
+use std::mem;
 use std::panic::{self, AssertUnwindSafe};
 use std::path::PathBuf;
 use std::task::Poll;
@@ -17,6 +18,7 @@
     layout::{LayoutCx, LayoutOf},
     Ty, TyCtxt,
 };
+use rustc_span::Span;
 use rustc_target::spec::abi::Abi;
 
 use rustc_session::config::EntryFnType;
@@ -156,6 +158,9 @@ pub struct MiriConfig {
     pub address_reuse_rate: f64,
     /// Probability for address reuse across threads.
     pub address_reuse_cross_thread_rate: f64,
+    /// Whether to record the non-deterministic choices made during execution, or to replay
+    /// previously recorded ones.
+    pub schedule_trace: ScheduleTraceMode,
 }
 
 impl Default for MiriConfig {
@@ -195,6 +200,7 @@ fn default() -> MiriConfig {
             collect_leak_backtraces: true,
             address_reuse_rate: 0.5,
             address_reuse_cross_thread_rate: 0.1,
+            schedule_trace: ScheduleTraceMode::Off,
         }
     }
 }
@@ -417,6 +423,81 @@ pub fn create_ecx<'tcx>(
     Ok(ecx)
 }
 
+/// How a run of the interpreted program failed, as far as `minimize_schedule` cares. Together
+/// with the span of the active thread at the time of the failure, this tells apart different
+/// failures of the same kind, e.g. two different data races.
+#[derive(PartialEq)]
+enum FailureKind<'tcx> {
+    /// The program exited with a non-zero exit code, e.g. because it panicked.
+    Exit(i64),
+    Termination(mem::Discriminant<TerminationInfo>),
+    Interp(mem::Discriminant<InterpError<'tcx>>),
+}
+
+/// Returns how and where the run of the interpreted program in `ecx` that ended with `err`
+/// failed, or `None` if it exited successfully.
+fn failure<'tcx>(
+    ecx: &MiriInterpCx<'tcx>,
+    err: &InterpErrorInfo<'tcx>,
+) -> Option<(FailureKind<'tcx>, Span)> {
+    let kind = match err.kind() {
+        InterpError::MachineStop(info) => {
+            let info = info.downcast_ref::<TerminationInfo>().expect("invalid MachineStop payload");
+            match info {
+                TerminationInfo::Exit { code: 0, .. } => None,
+                TerminationInfo::Exit { code, .. } => Some(FailureKind::Exit(*code)),
+                info => Some(FailureKind::Termination(mem::discriminant(info))),
+            }
+        }
+        kind => Some(FailureKind::Interp(mem::discriminant(kind))),
+    };
+    kind.map(|kind| (kind, ecx.cur_span()))
+}
+
+/// Shrinks `trace` to as few preemptions as possible such that replaying it still makes the
+/// program fail the same way as replaying the original trace. This runs the program many times,
+/// with its output muted and its errors not reported.
+fn minimize_schedule<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: EntryFnType,
+    config: &MiriConfig,
+    trace: ScheduleTrace,
+) -> ScheduleTrace {
+    let run = |trace: &ScheduleTrace| {
+        let mut config = config.clone();
+        config.schedule_trace = ScheduleTraceMode::Replay(trace.clone());
+        config.mute_stdout_stderr = true;
+        let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
+            Ok(v) => v,
+            Err(err) => {
+                let (kind, backtrace) = err.into_parts();
+                backtrace.print_backtrace();
+                panic!("Miri initialization error: {kind:?}")
+            }
+        };
+        match ecx.run_threads() {
+            Err(err) => failure(&ecx, &err),
+            // `Ok` can never happen
+            Ok(never) => match never {},
+        }
+    };
+
+    let Some(failure) = run(&trace) else {
+        tcx.dcx().fatal(
+            "the program does not fail when replaying the schedule trace, \
+            so there is nothing to minimize",
+        );
+    };
+    let preemptions = trace.preemption_count();
+    let trace = trace.minimize(|candidate| run(candidate).as_ref() == Some(&failure));
+    tcx.dcx().note(format!(
+        "kept {} of the {preemptions} preemptions in the schedule trace",
+        trace.preemption_count()
+    ));
+    trace
+}
+
 /// Evaluates the entry function specified by `entry_id`.
 /// Returns `Some(return_code)` if program executed completed.
 /// Returns `None` if an evaluation error occurred.
@@ -425,11 +506,20 @@ pub fn eval_entry<'tcx>(
     tcx: TyCtxt<'tcx>,
     entry_id: DefId,
     entry_type: EntryFnType,
-    config: MiriConfig,
+    mut config: MiriConfig,
 ) -> Option<i64> {
     // Copy setting before we move `config`.
     let ignore_leaks = config.ignore_leaks;
 
+    if let ScheduleTraceMode::Minimize(path, trace) = &config.schedule_trace {
+        let trace = minimize_schedule(tcx, entry_id, entry_type, &config, trace.clone());
+        if let Err(err) = trace.write(path) {
+            tcx.dcx().fatal(format!("failed to write schedule trace to {}: {err}", path.display()));
+        }
+        // Show the failure that the minimized trace reproduces.
+        config.schedule_trace = ScheduleTraceMode::Replay(trace);
+    }
+
     let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
         Ok(v) => v,
         Err(err) => {
@@ -452,6 +542,13 @@ pub fn eval_entry<'tcx>(
         Ok(never) => match never {},
     };
 
+    if let ScheduleTraceMode::Record(path) = &config.schedule_trace {
+        let trace = ecx.machine.rng.get_mut().recorded_trace().unwrap();
+        if let Err(err) = trace.write(path) {
+            tcx.dcx().fatal(format!("failed to write schedule trace to {}: {err}", path.display()));
+        }
+    }
+
     // Machine cleanup. Only do this if all threads have terminated; threads that are still running
     // might cause Stacked Borrows errors (https://github.com/rust-lang/miri/issues/2396).
     if ecx.have_all_terminated() {
@@ -89,10 +89,16 @@ pub enum AccessKind {
 const WINDOWS_IO_ERROR_TABLE: &[(&str, std::io::ErrorKind)] = {
     use std::io::ErrorKind::*;
     // FIXME: this is still incomplete.
+    // `io_error_to_errnum` uses the first code listed for a kind.
     &[
         ("ERROR_ACCESS_DENIED", PermissionDenied),
+        ("ERROR_ALREADY_EXISTS", AlreadyExists),
+        ("ERROR_DIRECTORY", NotADirectory),
+        ("ERROR_DIR_NOT_EMPTY", DirectoryNotEmpty),
+        ("ERROR_FILE_EXISTS", AlreadyExists),
         ("ERROR_FILE_NOT_FOUND", NotFound),
         ("ERROR_INVALID_PARAMETER", InvalidInput),
+        ("ERROR_PATH_NOT_FOUND", NotFound),
     ]
 };
 
@@ -7,9 +7,7 @@
 use std::path::Path;
 use std::process;
 
-use rand::rngs::StdRng;
 use rand::Rng;
-use rand::SeedableRng;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 #[allow(unused)]
@@ -465,6 +463,8 @@ pub struct MiriMachine<'tcx> {
     pub(crate) fds: shims::FdTable,
     /// The table of directory descriptors.
     pub(crate) dirs: shims::DirTable,
+    /// The network that sockets use when isolation is enabled.
+    pub(crate) loopback: shims::LoopbackNetwork,
 
     /// This machine's monotone clock.
     pub(crate) clock: Clock,
@@ -507,7 +507,7 @@ pub struct MiriMachine<'tcx> {
 
     /// The random number generator used for resolving non-determinism.
     /// Needs to be queried by ptr_to_int, hence needs interior mutability.
-    pub(crate) rng: RefCell<StdRng>,
+    pub(crate) rng: RefCell<TracedRng>,
 
     /// The allocation IDs to report when they are being allocated
     /// (helps for debugging memory leaks and use after free bugs).
@@ -598,7 +598,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             let path = Path::new(out).join(filename);
             measureme::Profiler::new(path).expect("Couldn't create `measureme` profiler")
         });
-        let rng = StdRng::seed_from_u64(config.seed.unwrap_or(0));
+        let rng = TracedRng::new(config.seed.unwrap_or(0), &config.schedule_trace);
         let borrow_tracker = config.borrow_tracker.map(|bt| bt.instantiate_global_state(config));
         let data_race = config.data_race_detector.then(|| data_race::GlobalState::new(config));
         // Determine page size, stack address, and stack size.
@@ -643,6 +643,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             validate: config.validate,
             fds: shims::FdTable::new(config.mute_stdout_stderr),
             dirs: Default::default(),
+            loopback: Default::default(),
             layouts,
             threads: ThreadManager::default(),
             sync: SynchronizationObjects::default(),
@@ -778,6 +779,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             data_race,
             alloc_addresses,
             fds,
+            loopback: _,
             tcx: _,
             isolated_op: _,
             validate: _,
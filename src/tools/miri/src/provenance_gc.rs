@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use either::Either;
 
 use rustc_data_structures::fx::FxHashSet;
@@ -20,7 +22,7 @@ fn visit_provenance(&self, _visit: &mut VisitWith<'_>) {}
         )+
     }
 }
-no_provenance!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize ThreadId);
+no_provenance!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize ThreadId Duration);
 
 impl<T: VisitProvenance> VisitProvenance for Option<T> {
     fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
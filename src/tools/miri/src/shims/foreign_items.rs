@@ -98,7 +98,8 @@ fn is_dyn_sym(&self, name: &str) -> bool {
         match this.tcx.sess.target.os.as_ref() {
             os if this.target_os_is_unix() => shims::unix::foreign_items::is_dyn_sym(name, os),
             "wasi" => shims::wasi::foreign_items::is_dyn_sym(name),
-            "windows" => shims::windows::foreign_items::is_dyn_sym(name),
+            "windows" =>
+                shims::windows::foreign_items::is_dyn_sym(name, &this.tcx.sess.target.vendor),
             _ => false,
         }
     }
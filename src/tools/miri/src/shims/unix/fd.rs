@@ -5,13 +5,19 @@
 use std::cell::{Ref, RefCell, RefMut};
 use std::collections::BTreeMap;
 use std::io::{self, ErrorKind, IsTerminal, Read, SeekFrom, Write};
+use std::mem;
 use std::rc::Rc;
+use std::time::Duration;
 
 use rustc_target::abi::Size;
 
 use crate::shims::unix::*;
 use crate::*;
 
+/// How often a thread that waits for an external file description checks whether it has become
+/// ready, see `FileDescription::is_external`.
+const EXTERNAL_FD_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
 /// Represents an open file descriptor.
 pub trait FileDescription: std::fmt::Debug + Any {
     fn name(&self) -> &'static str;
@@ -58,6 +64,44 @@ fn is_tty(&self, _communicate_allowed: bool) -> bool {
         // so we use a default impl here.
         false
     }
+
+    /// Returns whether reading from or writing to this file description would currently block,
+    /// as reported by `poll`.
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        throw_unsup_format!("cannot poll {}", self.name());
+    }
+
+    /// Switches this file description to non-blocking mode, or back to blocking mode.
+    fn set_nonblocking<'tcx>(&mut self, _nonblocking: bool) -> InterpResult<'tcx, io::Result<()>> {
+        throw_unsup_format!("cannot change whether {} is non-blocking", self.name());
+    }
+
+    /// Whether a `read` or `write` that fails with `EWOULDBLOCK` should instead wait until this
+    /// file description becomes ready.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+
+    /// Whether this file description can become ready without the interpreted program doing
+    /// anything, e.g. because it is backed by the host. Nobody wakes up the threads waiting for
+    /// such a file description, so they check regularly whether it has become ready.
+    fn is_external(&self) -> bool {
+        false
+    }
+}
+
+/// Which operations on a file description would currently not block.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Readiness {
+    /// Whether there is data to read, or reading returns end-of-file.
+    pub readable: bool,
+    /// Whether there is space to write data to.
+    pub writable: bool,
+    /// Whether the other end of a connection has been closed.
+    pub hangup: bool,
 }
 
 impl dyn FileDescription {
@@ -93,6 +137,14 @@ fn read<'tcx>(
     fn is_tty(&self, communicate_allowed: bool) -> bool {
         communicate_allowed && self.is_terminal()
     }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        // We do not know whether the host has input for us, so we let `read` block if needed.
+        Ok(Ok(Readiness { readable: true, ..Readiness::default() }))
+    }
 }
 
 impl FileDescription for io::Stdout {
@@ -121,6 +173,13 @@ fn write<'tcx>(
     fn is_tty(&self, communicate_allowed: bool) -> bool {
         communicate_allowed && self.is_terminal()
     }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        Ok(Ok(Readiness { writable: true, ..Readiness::default() }))
+    }
 }
 
 impl FileDescription for io::Stderr {
@@ -142,6 +201,13 @@ fn write<'tcx>(
     fn is_tty(&self, communicate_allowed: bool) -> bool {
         communicate_allowed && self.is_terminal()
     }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        Ok(Ok(Readiness { writable: true, ..Readiness::default() }))
+    }
 }
 
 /// Like /dev/null
@@ -162,6 +228,13 @@ fn write<'tcx>(
         // We just don't write anything, but report to the user that we did.
         Ok(Ok(bytes.len()))
     }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        Ok(Ok(Readiness { writable: true, ..Readiness::default() }))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -194,6 +267,8 @@ pub fn close<'ctx>(self, communicate_allowed: bool) -> InterpResult<'ctx, io::Re
 #[derive(Debug)]
 pub struct FdTable {
     pub fds: BTreeMap<i32, FileDescriptor>,
+    /// The threads that are blocked until some file description becomes ready.
+    waiters: Vec<ThreadId>,
 }
 
 impl VisitProvenance for FdTable {
@@ -213,7 +288,7 @@ pub(crate) fn new(mute_stdout_stderr: bool) -> FdTable {
             fds.insert(1i32, FileDescriptor::new(io::stdout()));
             fds.insert(2i32, FileDescriptor::new(io::stderr()));
         }
-        FdTable { fds }
+        FdTable { fds, waiters: Vec::new() }
     }
 
     pub fn insert_fd(&mut self, file_handle: FileDescriptor) -> i32 {
@@ -271,8 +346,80 @@ pub fn is_fd(&self, fd: i32) -> bool {
     }
 }
 
+/// The callback of a thread that waits for file descriptions to become ready, see
+/// `block_on_fds`.
+struct FdWaiter<'tcx> {
+    /// When the thread stops waiting, measured from the epoch of the monotone clock.
+    deadline: Option<Duration>,
+    callback: Box<dyn UnblockCallback<'tcx> + 'tcx>,
+}
+
+impl VisitProvenance for FdWaiter<'_> {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        self.callback.visit_provenance(visit);
+    }
+}
+
+impl<'tcx> UnblockCallback<'tcx> for FdWaiter<'tcx> {
+    fn unblock(self: Box<Self>, ecx: &mut MiriInterpCx<'tcx>) -> InterpResult<'tcx> {
+        self.callback.unblock(ecx)
+    }
+
+    fn timeout(self: Box<Self>, ecx: &mut MiriInterpCx<'tcx>) -> InterpResult<'tcx> {
+        let thread = ecx.active_thread();
+        ecx.machine.fds.waiters.retain(|&waiter| waiter != thread);
+        let now = ecx.machine.clock.now().duration_since(ecx.machine.clock.epoch());
+        if self.deadline.is_some_and(|deadline| now >= deadline) {
+            self.callback.timeout(ecx)
+        } else {
+            // We only woke up to check on an external file description.
+            self.callback.unblock(ecx)
+        }
+    }
+}
+
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
 pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Blocks the active thread until some file description may have become ready, and then runs
+    /// `callback.unblock` so that the thread can check whether it can make progress. If `deadline`
+    /// passes first, `callback.timeout` runs instead. `deadline` is measured from the epoch of
+    /// the monotone clock. `external` says whether the thread waits for an external file
+    /// description, see `FileDescription::is_external`.
+    fn block_on_fds(
+        &mut self,
+        deadline: Option<Duration>,
+        external: bool,
+        callback: impl UnblockCallback<'tcx> + 'tcx,
+    ) {
+        let this = self.eval_context_mut();
+
+        let thread = this.active_thread();
+        this.machine.fds.waiters.push(thread);
+        let now = this.machine.clock.now().duration_since(this.machine.clock.epoch());
+        let poll_at = external.then(|| now.saturating_add(EXTERNAL_FD_POLL_INTERVAL));
+        let timeout = match (deadline, poll_at) {
+            (Some(deadline), Some(poll_at)) => Some(deadline.min(poll_at)),
+            (deadline, poll_at) => deadline.or(poll_at),
+        };
+        this.block_thread(
+            BlockReason::Fd,
+            timeout.map(|timeout| (TimeoutClock::Monotonic, TimeoutAnchor::Absolute, timeout)),
+            FdWaiter { deadline, callback: Box::new(callback) },
+        );
+    }
+
+    /// Wakes up all threads that wait for a file description to become ready. This has to be
+    /// called whenever a file description may have become ready.
+    fn wake_fd_waiters(&mut self) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        // Threads that still cannot make progress add themselves again.
+        for thread in mem::take(&mut this.machine.fds.waiters) {
+            this.unblock_thread(thread, BlockReason::Fd)?;
+        }
+        Ok(())
+    }
+
     fn dup(&mut self, old_fd: i32) -> InterpResult<'tcx, i32> {
         let this = self.eval_context_mut();
 
@@ -355,6 +502,46 @@ fn fcntl(&mut self, args: &[OpTy<'tcx>]) -> InterpResult<'tcx, i32> {
         }
     }
 
+    fn ioctl(&mut self, args: &[OpTy<'tcx>]) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        if args.len() < 2 {
+            throw_ub_format!(
+                "incorrect number of arguments for ioctl: got {}, expected at least 2",
+                args.len()
+            );
+        }
+        let fd = this.read_scalar(&args[0])?.to_i32()?;
+        // The type of `request` differs between targets.
+        let request = this.read_scalar(&args[1])?.to_uint(args[1].layout.size)?;
+        let fioclex = this.eval_libc("FIOCLEX");
+        let fionbio = this.eval_libc("FIONBIO");
+
+        if request == fioclex.to_uint(fioclex.size())? {
+            // As with `fcntl`, we always assume the FD_CLOEXEC flag is set, so we only need to
+            // check that the file is open.
+            if this.machine.fds.is_fd(fd) { Ok(0) } else { this.fd_not_found() }
+        } else if request == fionbio.to_uint(fionbio.size())? {
+            if args.len() < 3 {
+                throw_ub_format!(
+                    "incorrect number of arguments for ioctl with request=`FIONBIO`: got {}, expected at least 3",
+                    args.len()
+                );
+            }
+            let nonblocking = this.deref_pointer_as(&args[2], this.machine.layouts.i32)?;
+            let nonblocking = this.read_scalar(&nonblocking)?.to_i32()? != 0;
+
+            let Some(file_descriptor) = this.machine.fds.dup(fd) else {
+                return this.fd_not_found();
+            };
+            let result = file_descriptor.borrow_mut().set_nonblocking(nonblocking)?;
+            drop(file_descriptor);
+            this.try_unwrap_io_result(result.map(|()| 0))
+        } else {
+            throw_unsup_format!("the {:#x} request is not supported for `ioctl`", request);
+        }
+    }
+
     fn close(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
@@ -364,6 +551,8 @@ fn close(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
             return Ok(Scalar::from_i32(this.fd_not_found()?));
         };
         let result = file_descriptor.close(this.machine.communicate())?;
+        // Closing may have hung up the other end of a pipe or socket.
+        this.wake_fd_waiters()?;
         // return `0` if close is successful
         let result = result.map(|()| 0i32);
         Ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
@@ -380,7 +569,15 @@ fn fd_not_found<T: From<i32>>(&mut self) -> InterpResult<'tcx, T> {
         Ok((-1).into())
     }
 
-    fn read(&mut self, fd: i32, buf: Pointer, count: u64) -> InterpResult<'tcx, i64> {
+    /// Reads from `fd` and writes the result to `dest`. If `fd` is blocking and there is nothing
+    /// to read yet, this blocks the active thread until there is.
+    fn read(
+        &mut self,
+        fd: i32,
+        buf: Pointer,
+        count: u64,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
         // Isolation check is done via `FileDescriptor` trait.
@@ -400,7 +597,8 @@ fn read(&mut self, fd: i32, buf: Pointer, count: u64) -> InterpResult<'tcx, i64>
         // We temporarily dup the FD to be able to retain mutable access to `this`.
         let Some(file_descriptor) = this.machine.fds.dup(fd) else {
             trace!("read: FD not found");
-            return this.fd_not_found();
+            let result = this.fd_not_found::<i64>()?;
+            return this.write_int(result, dest);
         };
 
         trace!("read: FD mapped to {:?}", file_descriptor);
@@ -414,22 +612,56 @@ fn read(&mut self, fd: i32, buf: Pointer, count: u64) -> InterpResult<'tcx, i64>
             .borrow_mut()
             .read(communicate, &mut bytes, this)?
             .map(|c| i64::try_from(c).unwrap());
+        let (is_blocking, is_external) = {
+            let file_description = file_descriptor.borrow();
+            (file_description.is_blocking(), file_description.is_external())
+        };
         drop(file_descriptor);
 
         match result {
             Ok(read_bytes) => {
                 // If reading to `bytes` did not fail, we write those bytes to the buffer.
                 this.write_bytes_ptr(buf, bytes)?;
-                Ok(read_bytes)
+                this.write_int(read_bytes, dest)?;
+                // Reading may have made space for writers.
+                if read_bytes > 0 {
+                    this.wake_fd_waiters()?;
+                }
+                Ok(())
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock && is_blocking => {
+                let dest = dest.clone();
+                this.block_on_fds(
+                    None,
+                    is_external,
+                    callback!(
+                        @capture<'tcx> {
+                            fd: i32,
+                            buf: Pointer,
+                            count: u64,
+                            dest: MPlaceTy<'tcx>,
+                        }
+                        @unblock = |this| { this.read(fd, buf, count, &dest) }
+                    ),
+                );
+                Ok(())
             }
             Err(e) => {
                 this.set_last_error_from_io_error(e)?;
-                Ok(-1)
+                this.write_int(-1, dest)
             }
         }
     }
 
-    fn write(&mut self, fd: i32, buf: Pointer, count: u64) -> InterpResult<'tcx, i64> {
+    /// Writes to `fd` and writes the result to `dest`. If `fd` is blocking and there is no space
+    /// to write to yet, this blocks the active thread until there is.
+    fn write(
+        &mut self,
+        fd: i32,
+        buf: Pointer,
+        count: u64,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
         // Isolation check is done via `FileDescriptor` trait.
@@ -447,15 +679,50 @@ fn write(&mut self, fd: i32, buf: Pointer, count: u64) -> InterpResult<'tcx, i64
         let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(count))?.to_owned();
         // We temporarily dup the FD to be able to retain mutable access to `this`.
         let Some(file_descriptor) = this.machine.fds.dup(fd) else {
-            return this.fd_not_found();
+            let result = this.fd_not_found::<i64>()?;
+            return this.write_int(result, dest);
         };
 
         let result = file_descriptor
             .borrow_mut()
             .write(communicate, &bytes, this)?
             .map(|c| i64::try_from(c).unwrap());
+        let (is_blocking, is_external) = {
+            let file_description = file_descriptor.borrow();
+            (file_description.is_blocking(), file_description.is_external())
+        };
         drop(file_descriptor);
 
-        this.try_unwrap_io_result(result)
+        match result {
+            Ok(written_bytes) => {
+                this.write_int(written_bytes, dest)?;
+                // Writing may have given readers something to read.
+                if written_bytes > 0 {
+                    this.wake_fd_waiters()?;
+                }
+                Ok(())
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock && is_blocking => {
+                let dest = dest.clone();
+                this.block_on_fds(
+                    None,
+                    is_external,
+                    callback!(
+                        @capture<'tcx> {
+                            fd: i32,
+                            buf: Pointer,
+                            count: u64,
+                            dest: MPlaceTy<'tcx>,
+                        }
+                        @unblock = |this| { this.write(fd, buf, count, &dest) }
+                    ),
+                );
+                Ok(())
+            }
+            Err(e) => {
+                this.set_last_error_from_io_error(e)?;
+                this.write_int(-1, dest)
+            }
+        }
     }
 }
@@ -90,8 +90,7 @@ fn emulate_foreign_item_inner(
                 let fd = this.read_scalar(fd)?.to_i32()?;
                 let buf = this.read_pointer(buf)?;
                 let count = this.read_target_usize(count)?;
-                let result = this.read(fd, buf, count)?;
-                this.write_scalar(Scalar::from_target_isize(result, this), dest)?;
+                this.read(fd, buf, count, dest)?;
             }
             "write" => {
                 let [fd, buf, n] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -99,9 +98,7 @@ fn emulate_foreign_item_inner(
                 let buf = this.read_pointer(buf)?;
                 let count = this.read_target_usize(n)?;
                 trace!("Called write({:?}, {:?}, {:?})", fd, buf, count);
-                let result = this.write(fd, buf, count)?;
-                // Now, `result` is the value we return back to the program.
-                this.write_scalar(Scalar::from_target_isize(result, this), dest)?;
+                this.write(fd, buf, count, dest)?;
             }
             "close" => {
                 let [fd] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -115,6 +112,13 @@ fn emulate_foreign_item_inner(
                 let result = this.fcntl(args)?;
                 this.write_scalar(Scalar::from_i32(result), dest)?;
             }
+            "ioctl" => {
+                // `ioctl` is variadic. The argument count is checked based on the request
+                // in `this.ioctl()`, so we do not use `check_shim` here.
+                this.check_abi_and_shim_symbol_clash(abi, Abi::C { unwind: false }, link_name)?;
+                let result = this.ioctl(args)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
             "dup" => {
                 let [old_fd] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
                 let old_fd = this.read_scalar(old_fd)?.to_i32()?;
@@ -247,6 +251,107 @@ fn emulate_foreign_item_inner(
                 let result = this.socketpair(domain, type_, protocol, sv)?;
                 this.write_scalar(result, dest)?;
             }
+            "socket" => {
+                let [domain, type_, protocol] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(result, dest)?;
+            }
+            "bind" => {
+                let [socket, address, address_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.bind(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "listen" => {
+                let [socket, backlog] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.listen(socket, backlog)?;
+                this.write_scalar(result, dest)?;
+            }
+            "accept" => {
+                let [socket, address, address_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.accept4(socket, address, address_len, None, dest)?;
+            }
+            "accept4" => {
+                let [socket, address, address_len, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.accept4(socket, address, address_len, Some(flags), dest)?;
+            }
+            "connect" => {
+                let [socket, address, address_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.connect(socket, address, address_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "send" => {
+                let [socket, buf, len, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.sendto(socket, buf, len, flags, None, link_name.as_str(), dest)?;
+            }
+            "sendto" => {
+                let [socket, buf, len, flags, dest_addr, dest_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let dest_addr = Some((dest_addr, dest_len));
+                this.sendto(socket, buf, len, flags, dest_addr, link_name.as_str(), dest)?;
+            }
+            "recv" => {
+                let [socket, buf, len, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(socket, buf, len, flags, None, link_name.as_str(), dest)?;
+            }
+            "recvfrom" => {
+                let [socket, buf, len, flags, src_addr, src_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let src_addr = Some((src_addr, src_len));
+                this.recvfrom(socket, buf, len, flags, src_addr, link_name.as_str(), dest)?;
+            }
+            "getsockname" => {
+                let [socket, address, address_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockname(socket, address, address_len, /* peer */ false)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getpeername" => {
+                let [socket, address, address_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockname(socket, address, address_len, /* peer */ true)?;
+                this.write_scalar(result, dest)?;
+            }
+            "setsockopt" => {
+                let [socket, level, option_name, option_value, option_len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result =
+                    this.setsockopt(socket, level, option_name, option_value, option_len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "poll" => {
+                let [fds, nfds, timeout] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.poll(fds, nfds, timeout, dest)?;
+            }
+            "ppoll" => {
+                let [fds, nfds, timeout, sigmask] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.ppoll(fds, nfds, timeout, sigmask, dest)?;
+            }
+            "select" => {
+                let [nfds, readfds, writefds, exceptfds, timeout] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.select(nfds, readfds, writefds, exceptfds, timeout, dest)?;
+            }
+            "pipe" => {
+                let [pipefd] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.pipe2(pipefd, /* flags */ None)?;
+                this.write_scalar(result, dest)?;
+            }
+            "pipe2" => {
+                let [pipefd, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.pipe2(pipefd, Some(flags))?;
+                this.write_scalar(result, dest)?;
+            }
 
             // Time
             "gettimeofday" => {
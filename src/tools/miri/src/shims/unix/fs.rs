@@ -18,10 +18,11 @@
 
 use self::fd::FileDescriptor;
 
+/// A file opened on the host. This is also used for Windows file handles.
 #[derive(Debug)]
-struct FileHandle {
-    file: File,
-    writable: bool,
+pub struct FileHandle {
+    pub file: File,
+    pub writable: bool,
 }
 
 impl FileDescription for FileHandle {
@@ -81,6 +82,14 @@ fn close<'tcx>(
         }
     }
 
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        // Regular files are always ready.
+        Ok(Ok(Readiness { readable: true, writable: true, hangup: false }))
+    }
+
     fn is_tty(&self, communicate_allowed: bool) -> bool {
         communicate_allowed && self.file.is_terminal()
     }
@@ -57,12 +57,7 @@ fn read<'tcx>(
         };
         // Block when counter == 0.
         if self.counter == 0 {
-            if self.is_nonblock {
-                return Ok(Err(Error::from(ErrorKind::WouldBlock)));
-            } else {
-                //FIXME: blocking is not supported
-                throw_unsup_format!("eventfd: blocking is unsupported");
-            }
+            return Ok(Err(Error::from(ErrorKind::WouldBlock)));
         } else {
             // Synchronize with all prior `write` calls to this FD.
             ecx.acquire_clock(&self.clock);
@@ -118,16 +113,32 @@ fn write<'tcx>(
                 self.counter = new_count;
             }
             None | Some(u64::MAX) => {
-                if self.is_nonblock {
-                    return Ok(Err(Error::from(ErrorKind::WouldBlock)));
-                } else {
-                    //FIXME: blocking is not supported
-                    throw_unsup_format!("eventfd: blocking is unsupported");
-                }
+                return Ok(Err(Error::from(ErrorKind::WouldBlock)));
             }
         };
         Ok(Ok(U64_ARRAY_SIZE))
     }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        // Any write of at least 1 fits if the counter is below the maximum.
+        Ok(Ok(Readiness {
+            readable: self.counter > 0,
+            writable: self.counter < MAX_COUNTER,
+            hangup: false,
+        }))
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.is_nonblock
+    }
+
+    fn set_nonblocking<'tcx>(&mut self, nonblocking: bool) -> InterpResult<'tcx, io::Result<()>> {
+        self.is_nonblock = nonblocking;
+        Ok(Ok(()))
+    }
 }
 
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
@@ -4,6 +4,8 @@
 mod fd;
 mod fs;
 mod mem;
+mod pipe;
+mod poll;
 mod socket;
 mod sync;
 mod thread;
@@ -15,13 +17,16 @@
 mod solarish;
 
 pub use env::UnixEnvVars;
-pub use fd::{FdTable, FileDescription};
-pub use fs::DirTable;
+pub use fd::{FdTable, FileDescription, FileDescriptor, Readiness};
+pub use fs::{DirTable, FileHandle};
+pub use socket::LoopbackNetwork;
 // All the Unix-specific extension traits
 pub use env::EvalContextExt as _;
 pub use fd::EvalContextExt as _;
 pub use fs::EvalContextExt as _;
 pub use mem::EvalContextExt as _;
+pub use pipe::EvalContextExt as _;
+pub use poll::EvalContextExt as _;
 pub use socket::EvalContextExt as _;
 pub use sync::EvalContextExt as _;
 pub use thread::EvalContextExt as _;
@@ -0,0 +1,232 @@
+//! Anonymous pipes, as created by `pipe` and `pipe2`.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::io::{Error, ErrorKind, Read};
+use std::rc::{Rc, Weak};
+
+use crate::shims::unix::*;
+use crate::{concurrency::VClock, *};
+
+use self::fd::FileDescriptor;
+
+/// The capacity of a pipe in bytes. This is the default on Linux.
+const PIPE_CAPACITY: usize = 65536;
+
+#[derive(Debug)]
+struct PipeBuffer {
+    buf: VecDeque<u8>,
+    clock: VClock,
+    /// Whether the write end of the pipe is still open. Once it is closed, reading from an empty
+    /// pipe indicates EOF instead of blocking.
+    has_writer: bool,
+}
+
+/// The read end of a pipe.
+#[derive(Debug)]
+struct PipeReader {
+    buf: Rc<RefCell<PipeBuffer>>,
+    is_nonblock: bool,
+}
+
+/// The write end of a pipe.
+#[derive(Debug)]
+struct PipeWriter {
+    // By making the link to the buffer weak, a `write` can detect when the read end is gone, and
+    // trigger EPIPE as appropriate.
+    buf: Weak<RefCell<PipeBuffer>>,
+    is_nonblock: bool,
+}
+
+impl FileDescription for PipeReader {
+    fn name(&self) -> &'static str {
+        "pipe"
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        Ok(Ok(()))
+    }
+
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let mut buf = self.buf.borrow_mut();
+
+        // Always succeed on read size 0.
+        if bytes.is_empty() {
+            return Ok(Ok(0));
+        }
+
+        if buf.buf.is_empty() {
+            if !buf.has_writer {
+                // 0 bytes successfully read indicates end-of-file.
+                return Ok(Ok(0));
+            }
+            // If the pipe is blocking, `read` waits for a writer instead.
+            return Ok(Err(Error::from(ErrorKind::WouldBlock)));
+        }
+
+        // Synchronize with all previous writes to this pipe.
+        // FIXME: this over-synchronizes; a more precise approach would be to
+        // only sync with the writes whose data we will read.
+        ecx.acquire_clock(&buf.clock);
+        // Conveniently, `read` exists on `VecDeque` and has exactly the desired behavior.
+        Ok(Ok(buf.buf.read(bytes).unwrap()))
+    }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        let buf = self.buf.borrow();
+        Ok(Ok(Readiness {
+            readable: !buf.buf.is_empty() || !buf.has_writer,
+            writable: false,
+            hangup: !buf.has_writer,
+        }))
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.is_nonblock
+    }
+
+    fn set_nonblocking<'tcx>(&mut self, nonblocking: bool) -> InterpResult<'tcx, io::Result<()>> {
+        self.is_nonblock = nonblocking;
+        Ok(Ok(()))
+    }
+}
+
+impl FileDescription for PipeWriter {
+    fn name(&self) -> &'static str {
+        "pipe"
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        // If the upgrade fails, there is no need to update as the read end has been dropped.
+        if let Some(buf) = self.buf.upgrade() {
+            buf.borrow_mut().has_writer = false;
+        }
+        Ok(Ok(()))
+    }
+
+    fn write<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &[u8],
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        // Always succeed on write size 0.
+        if bytes.is_empty() {
+            return Ok(Ok(0));
+        }
+
+        let Some(buf) = self.buf.upgrade() else {
+            // The read end has been closed.
+            return Ok(Err(Error::from(ErrorKind::BrokenPipe)));
+        };
+        let mut buf = buf.borrow_mut();
+        let available_space = PIPE_CAPACITY.strict_sub(buf.buf.len());
+        if available_space == 0 {
+            // If the pipe is blocking, `write` waits for a reader instead.
+            return Ok(Err(Error::from(ErrorKind::WouldBlock)));
+        }
+        // Remember this clock so `read` can synchronize with us.
+        if let Some(clock) = &ecx.release_clock() {
+            buf.clock.join(clock);
+        }
+        // Do full write / partial write based on the space available.
+        let write_size = bytes.len().min(available_space);
+        buf.buf.extend(&bytes[..write_size]);
+        Ok(Ok(write_size))
+    }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        let readiness = match self.buf.upgrade() {
+            Some(buf) =>
+                Readiness {
+                    readable: false,
+                    writable: buf.borrow().buf.len() < PIPE_CAPACITY,
+                    hangup: false,
+                },
+            // Writing does not block if the read end is gone, it fails with EPIPE instead.
+            None => Readiness { readable: false, writable: true, hangup: true },
+        };
+        Ok(Ok(readiness))
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.is_nonblock
+    }
+
+    fn set_nonblocking<'tcx>(&mut self, nonblocking: bool) -> InterpResult<'tcx, io::Result<()>> {
+        self.is_nonblock = nonblocking;
+        Ok(Ok(()))
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Implements `pipe2`, and `pipe` if `flags` is `None`.
+    /// For more information on the arguments see the pipe manpage:
+    /// <https://man7.org/linux/man-pages/man2/pipe.2.html>
+    fn pipe2(
+        &mut self,
+        pipefd: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let pipefd = this.deref_pointer_as(pipefd, this.machine.layouts.i32)?;
+        let mut flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        let mut is_nonblock = false;
+        // Parse and remove the flags that we support. If flags != 0 after removing,
+        // unsupported flags are used.
+        let o_nonblock = this.eval_libc_i32("O_NONBLOCK");
+        if flags & o_nonblock == o_nonblock {
+            is_nonblock = true;
+            flags &= !o_nonblock;
+        }
+        // cloexec is ignored because Miri does not support exec.
+        flags &= !this.eval_libc_i32("O_CLOEXEC");
+        if flags != 0 {
+            throw_unsup_format!(
+                "pipe2: flags {flags:#x} are unsupported, only O_NONBLOCK and O_CLOEXEC are allowed"
+            );
+        }
+
+        let buf = Rc::new(RefCell::new(PipeBuffer {
+            buf: VecDeque::new(),
+            clock: VClock::default(),
+            has_writer: true,
+        }));
+        let writer = PipeWriter { buf: Rc::downgrade(&buf), is_nonblock };
+        let reader = PipeReader { buf, is_nonblock };
+
+        let fds = &mut this.machine.fds;
+        let read_fd = fds.insert_fd(FileDescriptor::new(reader));
+        let write_fd = fds.insert_fd(FileDescriptor::new(writer));
+
+        this.write_scalar(Scalar::from_i32(read_fd), &pipefd)?;
+        let write_fd_place = pipefd.offset(pipefd.layout.size, pipefd.layout, this)?;
+        this.write_scalar(Scalar::from_i32(write_fd), &write_fd_place)?;
+
+        Ok(Scalar::from_i32(0))
+    }
+}
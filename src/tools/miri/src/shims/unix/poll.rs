@@ -0,0 +1,347 @@
+//! Waiting for file descriptors to become ready with `poll`, `ppoll` and `select`.
+
+use std::time::Duration;
+
+use crate::shims::unix::*;
+use crate::*;
+
+impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns how much time has passed since the epoch of the monotone clock, which is what the
+    /// deadlines of `block_on_fds` are measured from.
+    fn time_since_epoch(&self) -> Duration {
+        let this = self.eval_context_ref();
+        this.machine.clock.now().duration_since(this.machine.clock.epoch())
+    }
+
+    /// Turns a timeout that starts now into a deadline for `block_on_fds`. A timeout of `None`
+    /// means waiting forever.
+    fn timeout_to_deadline(&self, timeout: Option<Duration>) -> Option<Duration> {
+        let this = self.eval_context_ref();
+        timeout.map(|timeout| this.time_since_epoch().saturating_add(timeout))
+    }
+
+    fn poll_impl(
+        &mut self,
+        fds: Pointer,
+        nfds: u64,
+        deadline: Option<Duration>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let pollfd = this.libc_ty_layout("pollfd");
+        let pollin = this.eval_libc("POLLIN").to_i16()?;
+        let pollout = this.eval_libc("POLLOUT").to_i16()?;
+        let pollerr = this.eval_libc("POLLERR").to_i16()?;
+        let pollhup = this.eval_libc("POLLHUP").to_i16()?;
+        let pollnval = this.eval_libc("POLLNVAL").to_i16()?;
+        let communicate = this.machine.communicate();
+
+        let mut ready = 0i32;
+        let mut external = false;
+        for i in 0..nfds {
+            let entry = this.ptr_to_mplace(fds.wrapping_offset(pollfd.size * i, this), pollfd);
+            let fd = this.project_field_named(&entry, "fd")?;
+            let fd = this.read_scalar(&fd)?.to_i32()?;
+            let events = this.project_field_named(&entry, "events")?;
+            let events = this.read_scalar(&events)?.to_i16()?;
+
+            let revents = if fd < 0 {
+                // Negative file descriptors are ignored.
+                0
+            } else if let Some(file_descriptor) = this.machine.fds.dup(fd) {
+                let mut file_description = file_descriptor.borrow_mut();
+                external |= file_description.is_external();
+                match file_description.readiness(communicate)? {
+                    Ok(readiness) => {
+                        let mut revents = 0;
+                        if readiness.readable {
+                            revents |= events & pollin;
+                        }
+                        if readiness.writable {
+                            revents |= events & pollout;
+                        }
+                        // POLLHUP is reported even if it was not requested.
+                        if readiness.hangup {
+                            revents |= pollhup;
+                        }
+                        revents
+                    }
+                    Err(_) => pollerr,
+                }
+            } else {
+                pollnval
+            };
+            this.write_int_fields_named(&[("revents", revents.into())], &entry)?;
+            if revents != 0 {
+                ready += 1;
+            }
+        }
+
+        if ready > 0 || deadline.is_some_and(|deadline| this.time_since_epoch() >= deadline) {
+            return this.write_int(ready, dest);
+        }
+        let dest = dest.clone();
+        this.block_on_fds(
+            deadline,
+            external,
+            callback!(
+                @capture<'tcx> {
+                    fds: Pointer,
+                    nfds: u64,
+                    deadline: Option<Duration>,
+                    dest: MPlaceTy<'tcx>,
+                }
+                @unblock = |this| { this.poll_impl(fds, nfds, deadline, &dest) }
+                @timeout = |this| {
+                    // Nothing became ready, so all `revents` are still 0.
+                    this.write_int(0, &dest)
+                }
+            ),
+        );
+        Ok(())
+    }
+
+    /// Reads which of the file descriptors below `nfds` are in the `fd_set` that `set` points to.
+    /// Returns `None` if `set` is null.
+    fn read_fd_set(&self, set: Pointer, nfds: u64) -> InterpResult<'tcx, Option<Vec<bool>>> {
+        let this = self.eval_context_ref();
+
+        if this.ptr_is_null(set)? {
+            return Ok(None);
+        }
+        let set = this.ptr_to_mplace(set, this.libc_ty_layout("fd_set"));
+        // All targets store the set as an array of words, with the bit for `fd` at position
+        // `fd % bits_per_word` of word `fd / bits_per_word`.
+        let words = this.project_field(&set, 0)?;
+        let word_bits = words.layout.field(this, 0).size.bits();
+        let mut fds = Vec::new();
+        for fd in 0..nfds {
+            let word = this.project_index(&words, fd / word_bits)?;
+            let bits = this.read_scalar(&word)?.to_uint(word.layout.size)?;
+            fds.push(bits & (1 << (fd % word_bits)) != 0);
+        }
+        Ok(Some(fds))
+    }
+
+    /// Writes `fds` back to the `fd_set` that `set` points to. Does nothing if `set` is null.
+    fn write_fd_set(&mut self, set: Pointer, fds: &[bool]) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if this.ptr_is_null(set)? {
+            return Ok(());
+        }
+        let set = this.ptr_to_mplace(set, this.libc_ty_layout("fd_set"));
+        let words = this.project_field(&set, 0)?;
+        let word_bits = words.layout.field(this, 0).size.bits();
+        for (fd, &is_set) in (0u64..).zip(fds) {
+            let word = this.project_index(&words, fd / word_bits)?;
+            let bit = 1 << (fd % word_bits);
+            let bits = this.read_scalar(&word)?.to_uint(word.layout.size)?;
+            let bits = if is_set { bits | bit } else { bits & !bit };
+            this.write_scalar(Scalar::from_uint(bits, word.layout.size), &word)?;
+        }
+        Ok(())
+    }
+
+    fn select_impl(
+        &mut self,
+        nfds: u64,
+        readfds: Pointer,
+        writefds: Pointer,
+        exceptfds: Pointer,
+        deadline: Option<Duration>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let communicate = this.machine.communicate();
+        let nfds_usize = usize::try_from(nfds).unwrap();
+        let wants_read =
+            this.read_fd_set(readfds, nfds)?.unwrap_or_else(|| vec![false; nfds_usize]);
+        let wants_write =
+            this.read_fd_set(writefds, nfds)?.unwrap_or_else(|| vec![false; nfds_usize]);
+        let wants_except =
+            this.read_fd_set(exceptfds, nfds)?.unwrap_or_else(|| vec![false; nfds_usize]);
+
+        let mut readable = vec![false; nfds_usize];
+        let mut writable = vec![false; nfds_usize];
+        let mut external = false;
+        for fd in 0..nfds_usize {
+            if !(wants_read[fd] || wants_write[fd] || wants_except[fd]) {
+                continue;
+            }
+            let Some(file_descriptor) = this.machine.fds.dup(i32::try_from(fd).unwrap()) else {
+                let result = this.fd_not_found::<i32>()?;
+                return this.write_int(result, dest);
+            };
+            let mut file_description = file_descriptor.borrow_mut();
+            external |= file_description.is_external();
+            let readiness = match file_description.readiness(communicate)? {
+                Ok(readiness) => readiness,
+                // Errors are reported by the `read` or `write` that follows, so they make the
+                // file descriptor ready for both.
+                Err(_) => Readiness { readable: true, writable: true, hangup: false },
+            };
+            // A hangup makes `read` return end-of-file, so it does not block.
+            readable[fd] = wants_read[fd] && (readiness.readable || readiness.hangup);
+            writable[fd] = wants_write[fd] && readiness.writable;
+        }
+
+        let ready = readable.iter().chain(&writable).filter(|&&ready| ready).count();
+        if ready > 0 || deadline.is_some_and(|deadline| this.time_since_epoch() >= deadline) {
+            this.write_fd_set(readfds, &readable)?;
+            this.write_fd_set(writefds, &writable)?;
+            // There is no out-of-band data, which is the only exceptional condition.
+            this.write_fd_set(exceptfds, &vec![false; nfds_usize])?;
+            return this.write_int(u64::try_from(ready).unwrap(), dest);
+        }
+        let dest = dest.clone();
+        this.block_on_fds(
+            deadline,
+            external,
+            callback!(
+                @capture<'tcx> {
+                    nfds: u64,
+                    readfds: Pointer,
+                    writefds: Pointer,
+                    exceptfds: Pointer,
+                    deadline: Option<Duration>,
+                    dest: MPlaceTy<'tcx>,
+                }
+                @unblock = |this| {
+                    this.select_impl(nfds, readfds, writefds, exceptfds, deadline, &dest)
+                }
+                @timeout = |this| {
+                    // Nothing became ready, so all sets are cleared.
+                    let empty = vec![false; usize::try_from(nfds).unwrap()];
+                    this.write_fd_set(readfds, &empty)?;
+                    this.write_fd_set(writefds, &empty)?;
+                    this.write_fd_set(exceptfds, &empty)?;
+                    this.write_int(0, &dest)
+                }
+            ),
+        );
+        Ok(())
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// For more information on the arguments see the poll manpage:
+    /// <https://linux.die.net/man/2/poll>
+    fn poll(
+        &mut self,
+        fds: &OpTy<'tcx>,
+        nfds: &OpTy<'tcx>,
+        timeout: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fds = this.read_pointer(fds)?;
+        // The type of `nfds` differs between targets.
+        let nfds = this.read_scalar(nfds)?.to_uint(nfds.layout.size)?;
+        let timeout = this.read_scalar(timeout)?.to_i32()?;
+        // A negative timeout means that `poll` waits forever.
+        let timeout = u64::try_from(timeout).ok().map(Duration::from_millis);
+
+        let deadline = this.timeout_to_deadline(timeout);
+        this.poll_impl(fds, u64::try_from(nfds).unwrap(), deadline, dest)
+    }
+
+    /// For more information on the arguments see the ppoll manpage:
+    /// <https://man7.org/linux/man-pages/man2/ppoll.2.html>
+    fn ppoll(
+        &mut self,
+        fds: &OpTy<'tcx>,
+        nfds: &OpTy<'tcx>,
+        timeout: &OpTy<'tcx>,
+        sigmask: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fds = this.read_pointer(fds)?;
+        let nfds = this.read_scalar(nfds)?.to_uint(nfds.layout.size)?;
+        let timeout = this.read_pointer(timeout)?;
+        let sigmask = this.read_pointer(sigmask)?;
+
+        // Miri does not support signals, so there is nothing to mask.
+        if !this.ptr_is_null(sigmask)? {
+            throw_unsup_format!("ppoll: only a null `sigmask` is supported");
+        }
+        // A null timeout means that `ppoll` waits forever.
+        let timeout = if this.ptr_is_null(timeout)? {
+            None
+        } else {
+            let timeout = this.ptr_to_mplace(timeout, this.libc_ty_layout("timespec"));
+            match this.read_timespec(&timeout)? {
+                Some(timeout) => Some(timeout),
+                None => {
+                    let einval = this.eval_libc("EINVAL");
+                    this.set_last_error(einval)?;
+                    return this.write_int(-1, dest);
+                }
+            }
+        };
+
+        let deadline = this.timeout_to_deadline(timeout);
+        this.poll_impl(fds, u64::try_from(nfds).unwrap(), deadline, dest)
+    }
+
+    /// For more information on the arguments see the select manpage:
+    /// <https://man7.org/linux/man-pages/man2/select.2.html>
+    fn select(
+        &mut self,
+        nfds: &OpTy<'tcx>,
+        readfds: &OpTy<'tcx>,
+        writefds: &OpTy<'tcx>,
+        exceptfds: &OpTy<'tcx>,
+        timeout: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let nfds = this.read_scalar(nfds)?.to_i32()?;
+        let readfds = this.read_pointer(readfds)?;
+        let writefds = this.read_pointer(writefds)?;
+        let exceptfds = this.read_pointer(exceptfds)?;
+        let timeout = this.read_pointer(timeout)?;
+
+        // `fd_set` has one bit for each file descriptor below `FD_SETSIZE`.
+        let fd_setsize = this.libc_ty_layout("fd_set").size.bits();
+        let Some(nfds) = u64::try_from(nfds).ok().filter(|&nfds| nfds <= fd_setsize) else {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return this.write_int(-1, dest);
+        };
+        // A null timeout means that `select` waits forever.
+        let timeout = if this.ptr_is_null(timeout)? {
+            None
+        } else {
+            let timeout = this.ptr_to_mplace(timeout, this.libc_ty_layout("timeval"));
+            let seconds = this.project_field_named(&timeout, "tv_sec")?;
+            let seconds = this.read_scalar(&seconds)?.to_int(seconds.layout.size)?;
+            let microseconds = this.project_field_named(&timeout, "tv_usec")?;
+            let microseconds = this.read_scalar(&microseconds)?.to_int(microseconds.layout.size)?;
+            let timeout: Option<Duration> = try {
+                let seconds = u64::try_from(seconds).ok()?;
+                let microseconds = u32::try_from(microseconds).ok().filter(|&us| us < 1_000_000)?;
+                Duration::new(seconds, microseconds * 1000)
+            };
+            match timeout {
+                Some(timeout) => Some(timeout),
+                None => {
+                    let einval = this.eval_libc("EINVAL");
+                    this.set_last_error(einval)?;
+                    return this.write_int(-1, dest);
+                }
+            }
+        };
+
+        let deadline = this.timeout_to_deadline(timeout);
+        this.select_impl(nfds, readfds, writefds, exceptfds, deadline, dest)
+    }
+}
@@ -1,9 +1,16 @@
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::io;
-use std::io::{Error, ErrorKind, Read};
+use std::io::{Error, ErrorKind, Read, Write};
+use std::iter;
+use std::net::{
+    IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, TcpListener, TcpStream,
+    UdpSocket,
+};
 use std::rc::{Rc, Weak};
 
+use rustc_target::abi::Size;
+
 use crate::shims::unix::*;
 use crate::{concurrency::VClock, *};
 
@@ -14,6 +21,10 @@
 /// be configured in the real system.
 const MAX_SOCKETPAIR_BUFFER_CAPACITY: usize = 212992;
 
+/// The first port that is handed out to sockets bound to port 0 on the loopback network. This
+/// is where the dynamic port range suggested by IANA starts.
+const FIRST_EPHEMERAL_PORT: u16 = 49152;
+
 /// Pair of connected sockets.
 #[derive(Debug)]
 struct SocketPair {
@@ -71,18 +82,13 @@ fn read<'tcx>(
                 // 0 bytes successfully read indicates end-of-file.
                 return Ok(Ok(0));
             } else {
-                if self.is_nonblock {
-                    // Non-blocking socketpair with writer and empty buffer.
-                    // https://linux.die.net/man/2/read
-                    // EAGAIN or EWOULDBLOCK can be returned for socket,
-                    // POSIX.1-2001 allows either error to be returned for this case.
-                    // Since there is no ErrorKind for EAGAIN, WouldBlock is used.
-                    return Ok(Err(Error::from(ErrorKind::WouldBlock)));
-                } else {
-                    // Blocking socketpair with writer and empty buffer.
-                    // FIXME: blocking is currently not supported
-                    throw_unsup_format!("socketpair read: blocking isn't supported yet");
-                }
+                // Socketpair with writer and empty buffer.
+                // https://linux.die.net/man/2/read
+                // EAGAIN or EWOULDBLOCK can be returned for socket,
+                // POSIX.1-2001 allows either error to be returned for this case.
+                // Since there is no ErrorKind for EAGAIN, WouldBlock is used.
+                // If the socketpair is blocking, `read` waits for a writer instead.
+                return Ok(Err(Error::from(ErrorKind::WouldBlock)));
             }
         }
 
@@ -118,13 +124,8 @@ fn write<'tcx>(
         let data_size = writebuf.buf.len();
         let available_space = MAX_SOCKETPAIR_BUFFER_CAPACITY.strict_sub(data_size);
         if available_space == 0 {
-            if self.is_nonblock {
-                // Non-blocking socketpair with a full buffer.
-                return Ok(Err(Error::from(ErrorKind::WouldBlock)));
-            } else {
-                // Blocking socketpair with a full buffer.
-                throw_unsup_format!("socketpair write: blocking isn't supported yet");
-            }
+            // Socketpair with a full buffer. If it is blocking, `write` waits for a reader.
+            return Ok(Err(Error::from(ErrorKind::WouldBlock)));
         }
         // Remember this clock so `read` can synchronize with us.
         if let Some(clock) = &ecx.release_clock() {
@@ -135,6 +136,947 @@ fn write<'tcx>(
         writebuf.buf.extend(&bytes[..actual_write_size]);
         return Ok(Ok(actual_write_size));
     }
+
+    fn readiness<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        let readbuf = self.readbuf.borrow();
+        let writable = match self.writebuf.upgrade() {
+            Some(writebuf) => writebuf.borrow().buf.len() < MAX_SOCKETPAIR_BUFFER_CAPACITY,
+            // Writing does not block if all read ends are gone, it fails with EPIPE instead.
+            None => true,
+        };
+        Ok(Ok(Readiness {
+            readable: !readbuf.buf.is_empty() || !readbuf.buf_has_writer,
+            writable,
+            hangup: !readbuf.buf_has_writer,
+        }))
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.is_nonblock
+    }
+
+    fn set_nonblocking<'tcx>(&mut self, nonblocking: bool) -> InterpResult<'tcx, io::Result<()>> {
+        self.is_nonblock = nonblocking;
+        Ok(Ok(()))
+    }
+}
+
+impl SocketPair {
+    /// Creates two sockets that are connected to each other.
+    fn new_pair(is_nonblock: bool) -> (SocketPair, SocketPair) {
+        let buffer1 = Rc::new(RefCell::new(Buffer {
+            buf: VecDeque::new(),
+            clock: VClock::default(),
+            buf_has_writer: true,
+        }));
+
+        let buffer2 = Rc::new(RefCell::new(Buffer {
+            buf: VecDeque::new(),
+            clock: VClock::default(),
+            buf_has_writer: true,
+        }));
+
+        let socketpair_0 = SocketPair {
+            writebuf: Rc::downgrade(&buffer1),
+            readbuf: Rc::clone(&buffer2),
+            is_nonblock,
+        };
+
+        let socketpair_1 = SocketPair {
+            writebuf: Rc::downgrade(&buffer2),
+            readbuf: Rc::clone(&buffer1),
+            is_nonblock,
+        };
+
+        (socketpair_0, socketpair_1)
+    }
+}
+
+/// The in-interpreter network that sockets use when isolation is enabled. All addresses on it
+/// refer to the same host, so sockets are told apart by their port only.
+#[derive(Debug, Default)]
+pub struct LoopbackNetwork {
+    tcp_ports: BTreeMap<u16, Weak<RefCell<LoopbackPort>>>,
+    udp_ports: BTreeMap<u16, Weak<RefCell<LoopbackPort>>>,
+}
+
+impl LoopbackNetwork {
+    fn ports(&mut self, kind: SocketKind) -> &mut BTreeMap<u16, Weak<RefCell<LoopbackPort>>> {
+        match kind {
+            SocketKind::Stream => &mut self.tcp_ports,
+            SocketKind::Datagram => &mut self.udp_ports,
+        }
+    }
+
+    /// Reserves `port`, or an unused ephemeral port if `port` is 0. The port stays reserved
+    /// until the returned state is dropped. Returns `None` if the port is already in use.
+    fn bind(&mut self, kind: SocketKind, port: u16) -> Option<(u16, Rc<RefCell<LoopbackPort>>)> {
+        let ports = self.ports(kind);
+        let in_use = |port: &u16| ports.get(port).is_some_and(|state| state.strong_count() > 0);
+        let port = if port == 0 {
+            (FIRST_EPHEMERAL_PORT..=u16::MAX).find(|port| !in_use(port))?
+        } else if in_use(&port) {
+            return None;
+        } else {
+            port
+        };
+        let state = Rc::new(RefCell::new(LoopbackPort::default()));
+        ports.insert(port, Rc::downgrade(&state));
+        Some((port, state))
+    }
+
+    fn lookup(&mut self, kind: SocketKind, port: u16) -> Option<Rc<RefCell<LoopbackPort>>> {
+        self.ports(kind).get(&port)?.upgrade()
+    }
+}
+
+/// The state of a port on the loopback network.
+#[derive(Debug, Default)]
+struct LoopbackPort {
+    /// The connections to a listening TCP socket that have not been accepted yet, or `None` if
+    /// the socket is not listening.
+    backlog: Option<VecDeque<LoopbackConnection>>,
+    /// The datagrams sent to a UDP socket that have not been received yet.
+    datagrams: VecDeque<LoopbackDatagram>,
+}
+
+/// One end of a TCP connection on the loopback network.
+#[derive(Debug)]
+struct LoopbackConnection {
+    /// The data sent in both directions. This is always non-blocking; whether the socket blocks
+    /// is decided by the `InetSocket` that owns this connection.
+    stream: SocketPair,
+    local_addr: SocketAddr,
+    peer_addr: SocketAddr,
+    /// Keeps the local port reserved while the connection is open. Accepted connections use the
+    /// port of their listener, so they do not reserve anything.
+    _port: Option<Rc<RefCell<LoopbackPort>>>,
+}
+
+#[derive(Debug)]
+struct LoopbackDatagram {
+    from: SocketAddr,
+    data: Vec<u8>,
+    clock: VClock,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SocketKind {
+    /// A TCP socket.
+    Stream,
+    /// A UDP socket.
+    Datagram,
+}
+
+/// An IPv4 or IPv6 socket created by `socket`.
+///
+/// With isolation disabled, these sockets use the host's network stack. With isolation enabled,
+/// they can only reach each other through the machine's [`LoopbackNetwork`].
+#[derive(Debug)]
+struct InetSocket {
+    kind: SocketKind,
+    is_ipv6: bool,
+    is_nonblock: bool,
+    /// Whether this socket uses the host's network stack. Host sockets are always non-blocking
+    /// on the host, whether they block is decided by `is_nonblock`.
+    is_host: bool,
+    state: SocketState,
+}
+
+#[derive(Debug)]
+enum SocketState {
+    /// The socket is neither listening nor connected. `local_addr` is the address it was bound
+    /// to, if any, and `port` keeps that port reserved on the loopback network.
+    Unconnected { local_addr: Option<SocketAddr>, port: Option<Rc<RefCell<LoopbackPort>>> },
+    /// A listening TCP socket on the host. `pending` is a connection that `poll` already
+    /// accepted to find out whether the socket is readable.
+    HostListener { listener: TcpListener, pending: Option<(TcpStream, SocketAddr)> },
+    HostStream(TcpStream),
+    HostDatagram(UdpSocket),
+    LoopbackListener { local_addr: SocketAddr, port: Rc<RefCell<LoopbackPort>> },
+    LoopbackStream(LoopbackConnection),
+    LoopbackDatagram {
+        local_addr: SocketAddr,
+        peer_addr: Option<SocketAddr>,
+        port: Rc<RefCell<LoopbackPort>>,
+    },
+}
+
+/// Returns the loopback address if `ip` is unspecified, and `ip` otherwise.
+fn loopback_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ip => ip,
+    }
+}
+
+impl InetSocket {
+    fn new(kind: SocketKind, is_ipv6: bool, is_nonblock: bool, is_host: bool) -> Self {
+        InetSocket {
+            kind,
+            is_ipv6,
+            is_nonblock,
+            is_host,
+            state: SocketState::Unconnected { local_addr: None, port: None },
+        }
+    }
+
+    fn unspecified_addr(&self) -> SocketAddr {
+        let ip = if self.is_ipv6 {
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+        } else {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        };
+        SocketAddr::new(ip, 0)
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        match &self.state {
+            SocketState::Unconnected { local_addr, .. } =>
+                Ok(local_addr.unwrap_or_else(|| self.unspecified_addr())),
+            SocketState::HostListener { listener, .. } => listener.local_addr(),
+            SocketState::HostStream(stream) => stream.local_addr(),
+            SocketState::HostDatagram(socket) => socket.local_addr(),
+            SocketState::LoopbackListener { local_addr, .. }
+            | SocketState::LoopbackDatagram { local_addr, .. } => Ok(*local_addr),
+            SocketState::LoopbackStream(connection) => Ok(connection.local_addr),
+        }
+    }
+
+    fn peer_addr(&self) -> io::Result<SocketAddr> {
+        match &self.state {
+            SocketState::HostStream(stream) => stream.peer_addr(),
+            SocketState::HostDatagram(socket) => socket.peer_addr(),
+            SocketState::LoopbackStream(connection) => Ok(connection.peer_addr),
+            SocketState::LoopbackDatagram { peer_addr: Some(peer_addr), .. } => Ok(*peer_addr),
+            _ => Err(ErrorKind::NotConnected.into()),
+        }
+    }
+
+    fn bind(&mut self, addr: SocketAddr, network: &mut LoopbackNetwork) -> io::Result<()> {
+        if !matches!(self.state, SocketState::Unconnected { local_addr: None, .. })
+            || addr.is_ipv6() != self.is_ipv6
+        {
+            return Err(ErrorKind::InvalidInput.into());
+        }
+        if !self.is_host && !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            return Err(ErrorKind::AddrNotAvailable.into());
+        }
+        self.state = match (self.kind, self.is_host) {
+            // FIXME: `TcpListener` and `TcpStream` are bound when they start listening or
+            // connecting, so the address is only used once this socket listens.
+            (SocketKind::Stream, true) =>
+                SocketState::Unconnected { local_addr: Some(addr), port: None },
+            (SocketKind::Datagram, true) => {
+                let socket = UdpSocket::bind(addr)?;
+                socket.set_nonblocking(true)?;
+                SocketState::HostDatagram(socket)
+            }
+            (kind, false) => {
+                let Some((port_number, port)) = network.bind(kind, addr.port()) else {
+                    return Err(ErrorKind::AddrInUse.into());
+                };
+                let local_addr = SocketAddr::new(addr.ip(), port_number);
+                match kind {
+                    SocketKind::Stream =>
+                        SocketState::Unconnected { local_addr: Some(local_addr), port: Some(port) },
+                    SocketKind::Datagram =>
+                        SocketState::LoopbackDatagram { local_addr, peer_addr: None, port },
+                }
+            }
+        };
+        Ok(())
+    }
+
+    /// Binds an unbound UDP socket to an ephemeral port, which happens when it first sends or
+    /// connects.
+    fn autobind(&mut self, network: &mut LoopbackNetwork) -> io::Result<()> {
+        if matches!(self.state, SocketState::Unconnected { local_addr: None, .. }) {
+            self.bind(self.unspecified_addr(), network)?;
+        }
+        Ok(())
+    }
+
+    fn listen(&mut self, network: &mut LoopbackNetwork) -> io::Result<()> {
+        let unspecified_addr = self.unspecified_addr();
+        let state = match &mut self.state {
+            SocketState::Unconnected { local_addr, port } => {
+                let local_addr = local_addr.unwrap_or(unspecified_addr);
+                if self.is_host {
+                    let listener = TcpListener::bind(local_addr)?;
+                    listener.set_nonblocking(true)?;
+                    SocketState::HostListener { listener, pending: None }
+                } else {
+                    let (local_addr, port) = match port.take() {
+                        Some(port) => (local_addr, port),
+                        None => {
+                            let Some((port_number, port)) = network.bind(SocketKind::Stream, 0)
+                            else {
+                                return Err(ErrorKind::AddrInUse.into());
+                            };
+                            (SocketAddr::new(local_addr.ip(), port_number), port)
+                        }
+                    };
+                    port.borrow_mut().backlog = Some(VecDeque::new());
+                    SocketState::LoopbackListener { local_addr, port }
+                }
+            }
+            SocketState::HostListener { .. } | SocketState::LoopbackListener { .. } =>
+                return Ok(()),
+            _ => return Err(ErrorKind::InvalidInput.into()),
+        };
+        self.state = state;
+        Ok(())
+    }
+
+    /// Accepts a connection without blocking, and returns the connected socket and the address
+    /// of its peer.
+    fn accept(&mut self, is_nonblock: bool) -> io::Result<(InetSocket, SocketAddr)> {
+        let (state, peer_addr) = match &mut self.state {
+            SocketState::HostListener { listener, pending } => {
+                let (stream, peer_addr) = match pending.take() {
+                    Some(connection) => connection,
+                    None => listener.accept()?,
+                };
+                stream.set_nonblocking(true)?;
+                (SocketState::HostStream(stream), peer_addr)
+            }
+            SocketState::LoopbackListener { port, .. } => {
+                let mut port = port.borrow_mut();
+                let connection =
+                    port.backlog.as_mut().unwrap().pop_front().ok_or(ErrorKind::WouldBlock)?;
+                let peer_addr = connection.peer_addr;
+                (SocketState::LoopbackStream(connection), peer_addr)
+            }
+            _ => return Err(ErrorKind::InvalidInput.into()),
+        };
+        let socket = InetSocket {
+            kind: SocketKind::Stream,
+            is_ipv6: self.is_ipv6,
+            is_nonblock,
+            is_host: self.is_host,
+            state,
+        };
+        Ok((socket, peer_addr))
+    }
+
+    fn connect(&mut self, addr: SocketAddr, network: &mut LoopbackNetwork) -> io::Result<()> {
+        if self.kind == SocketKind::Datagram {
+            self.autobind(network)?;
+            match &mut self.state {
+                SocketState::HostDatagram(socket) => socket.connect(addr)?,
+                SocketState::LoopbackDatagram { peer_addr, .. } => *peer_addr = Some(addr),
+                _ => unreachable!(),
+            }
+            return Ok(());
+        }
+
+        let SocketState::Unconnected { local_addr, port } = &mut self.state else {
+            return Err(ErrorKind::InvalidInput.into());
+        };
+        let state = if self.is_host {
+            // FIXME: the address this socket was bound to is ignored.
+            let stream = TcpStream::connect(addr)?;
+            stream.set_nonblocking(true)?;
+            SocketState::HostStream(stream)
+        } else {
+            let Some(listener) = network.lookup(SocketKind::Stream, addr.port()) else {
+                return Err(ErrorKind::ConnectionRefused.into());
+            };
+            let mut listener = listener.borrow_mut();
+            let Some(backlog) = &mut listener.backlog else {
+                return Err(ErrorKind::ConnectionRefused.into());
+            };
+            let (local_addr, port) = match port.take() {
+                Some(port) => (local_addr.unwrap(), port),
+                None => {
+                    let Some((port_number, port)) = network.bind(SocketKind::Stream, 0) else {
+                        return Err(ErrorKind::AddrInUse.into());
+                    };
+                    (SocketAddr::new(addr.ip(), port_number), port)
+                }
+            };
+            let local_addr = SocketAddr::new(loopback_ip(local_addr.ip()), local_addr.port());
+            let peer_addr = SocketAddr::new(loopback_ip(addr.ip()), addr.port());
+            let (client, server) = SocketPair::new_pair(/* is_nonblock */ true);
+            backlog.push_back(LoopbackConnection {
+                stream: server,
+                local_addr: peer_addr,
+                peer_addr: local_addr,
+                _port: None,
+            });
+            SocketState::LoopbackStream(LoopbackConnection {
+                stream: client,
+                local_addr,
+                peer_addr,
+                _port: Some(port),
+            })
+        };
+        self.state = state;
+        Ok(())
+    }
+
+    /// Receives data without blocking, and returns how many bytes were received and, for UDP
+    /// sockets, where they were sent from.
+    fn recv<'tcx>(
+        &mut self,
+        bytes: &mut [u8],
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<(usize, Option<SocketAddr>)>> {
+        let result = match &mut self.state {
+            SocketState::HostStream(stream) => stream.read(bytes).map(|read| (read, None)),
+            SocketState::HostDatagram(socket) =>
+                socket.recv_from(bytes).map(|(read, from)| (read, Some(from))),
+            SocketState::LoopbackStream(connection) => {
+                let result = connection.stream.read(/* communicate_allowed */ false, bytes, ecx)?;
+                result.map(|read| (read, None))
+            }
+            SocketState::LoopbackDatagram { peer_addr, port, .. } => {
+                let mut port = port.borrow_mut();
+                // A connected socket only receives datagrams from its peer.
+                if let Some(peer_addr) = peer_addr {
+                    port.datagrams.retain(|datagram| datagram.from == *peer_addr);
+                }
+                match port.datagrams.pop_front() {
+                    Some(datagram) => {
+                        ecx.acquire_clock(&datagram.clock);
+                        // Whatever does not fit into the buffer is discarded.
+                        let read = bytes.len().min(datagram.data.len());
+                        bytes[..read].copy_from_slice(&datagram.data[..read]);
+                        Ok((read, Some(datagram.from)))
+                    }
+                    None => Err(ErrorKind::WouldBlock.into()),
+                }
+            }
+            _ => Err(ErrorKind::NotConnected.into()),
+        };
+        Ok(result)
+    }
+
+    /// Sends data without blocking, and returns how many bytes were sent. `to` is the
+    /// destination of a UDP datagram, if it differs from the peer the socket is connected to.
+    fn send<'tcx>(
+        &mut self,
+        bytes: &[u8],
+        to: Option<SocketAddr>,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        if self.kind == SocketKind::Datagram {
+            if let Err(err) = self.autobind(&mut ecx.machine.loopback) {
+                return Ok(Err(err));
+            }
+        }
+        let result = match &mut self.state {
+            SocketState::HostStream(stream) => stream.write(bytes),
+            SocketState::HostDatagram(socket) =>
+                match to {
+                    Some(to) => socket.send_to(bytes, to),
+                    None => socket.send(bytes),
+                },
+            SocketState::LoopbackStream(connection) =>
+                connection.stream.write(/* communicate_allowed */ false, bytes, ecx)?,
+            SocketState::LoopbackDatagram { local_addr, peer_addr, .. } => {
+                let Some(to) = to.or(*peer_addr) else {
+                    return Ok(Err(ErrorKind::NotConnected.into()));
+                };
+                // Like on a real network, datagrams that nobody receives get lost.
+                if let Some(port) = ecx.machine.loopback.lookup(SocketKind::Datagram, to.port()) {
+                    let clock = ecx.release_clock().map(|clock| clock.clone()).unwrap_or_default();
+                    port.borrow_mut().datagrams.push_back(LoopbackDatagram {
+                        from: SocketAddr::new(loopback_ip(local_addr.ip()), local_addr.port()),
+                        data: bytes.to_vec(),
+                        clock,
+                    });
+                }
+                Ok(bytes.len())
+            }
+            _ => Err(ErrorKind::NotConnected.into()),
+        };
+        Ok(result)
+    }
+}
+
+impl FileDescription for InetSocket {
+    fn name(&self) -> &'static str {
+        "socket"
+    }
+
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        Ok(self.recv(bytes, ecx)?.map(|(read, _from)| read))
+    }
+
+    fn write<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &[u8],
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        self.send(bytes, None, ecx)
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        match self.state {
+            SocketState::LoopbackStream(connection) =>
+                Box::new(connection.stream).close(communicate_allowed),
+            SocketState::LoopbackListener { port, .. } => {
+                // Connections that were never accepted are closed along with the listener.
+                let backlog = port.borrow_mut().backlog.take().unwrap();
+                for connection in backlog {
+                    Box::new(connection.stream).close(communicate_allowed)?.ok();
+                }
+                Ok(Ok(()))
+            }
+            // Everything else is closed by dropping it.
+            _ => Ok(Ok(())),
+        }
+    }
+
+    fn readiness<'tcx>(
+        &mut self,
+        communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<Readiness>> {
+        let is_datagram = self.kind == SocketKind::Datagram;
+        let result = match &mut self.state {
+            // Like Linux, report that an unconnected TCP socket is hung up.
+            SocketState::Unconnected { .. } =>
+                Ok(Readiness { readable: false, writable: is_datagram, hangup: !is_datagram }),
+            SocketState::HostListener { listener, pending } => {
+                if pending.is_none() {
+                    match listener.accept() {
+                        Ok(connection) => *pending = Some(connection),
+                        Err(err) if err.kind() == ErrorKind::WouldBlock => {}
+                        Err(err) => return Ok(Err(err)),
+                    }
+                }
+                Ok(Readiness { readable: pending.is_some(), ..Readiness::default() })
+            }
+            SocketState::HostStream(stream) =>
+                match stream.peek(&mut [0]) {
+                    Ok(read) =>
+                        Ok(Readiness { readable: true, writable: true, hangup: read == 0 }),
+                    Err(err) if err.kind() == ErrorKind::WouldBlock =>
+                        Ok(Readiness { writable: true, ..Readiness::default() }),
+                    Err(err) => Err(err),
+                },
+            SocketState::HostDatagram(socket) =>
+                match socket.peek_from(&mut [0]) {
+                    Ok(_) => Ok(Readiness { readable: true, writable: true, hangup: false }),
+                    Err(err) if err.kind() == ErrorKind::WouldBlock =>
+                        Ok(Readiness { writable: true, ..Readiness::default() }),
+                    Err(err) => Err(err),
+                },
+            SocketState::LoopbackListener { port, .. } => {
+                let readable = !port.borrow().backlog.as_ref().unwrap().is_empty();
+                Ok(Readiness { readable, ..Readiness::default() })
+            }
+            SocketState::LoopbackStream(connection) =>
+                return connection.stream.readiness(communicate_allowed),
+            SocketState::LoopbackDatagram { peer_addr, port, .. } => {
+                let readable = port.borrow().datagrams.iter().any(|datagram| {
+                    peer_addr.map_or(true, |peer_addr| datagram.from == peer_addr)
+                });
+                Ok(Readiness { readable, writable: true, hangup: false })
+            }
+        };
+        Ok(result)
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.is_nonblock
+    }
+
+    fn is_external(&self) -> bool {
+        self.is_host
+    }
+
+    fn set_nonblocking<'tcx>(&mut self, nonblocking: bool) -> InterpResult<'tcx, io::Result<()>> {
+        self.is_nonblock = nonblocking;
+        Ok(Ok(()))
+    }
+}
+
+/// A socket operation that a thread is blocked on, see `block_on_socket`.
+#[derive(Debug)]
+enum BlockedSocketOp {
+    /// `nonblock_connection` is whether the accepted connection is non-blocking.
+    Accept { fd: i32, addr: Pointer, addr_len: Pointer, nonblock_connection: bool },
+    Recv { fd: i32, buf: Pointer, len: u64, addr: Pointer, addr_len: Pointer },
+    Send { fd: i32, buf: Pointer, len: u64, to: Option<SocketAddr> },
+}
+
+impl VisitProvenance for BlockedSocketOp {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        match self {
+            BlockedSocketOp::Accept { addr, addr_len, .. } => {
+                addr.visit_provenance(visit);
+                addr_len.visit_provenance(visit);
+            }
+            BlockedSocketOp::Recv { buf, addr, addr_len, .. } => {
+                buf.visit_provenance(visit);
+                addr.visit_provenance(visit);
+                addr_len.visit_provenance(visit);
+            }
+            BlockedSocketOp::Send { buf, .. } => buf.visit_provenance(visit),
+        }
+    }
+}
+
+impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns `fd` if it is an IPv4 or IPv6 socket. Otherwise, sets the last error and returns
+    /// `None`.
+    fn inet_socket(&mut self, fd: i32) -> InterpResult<'tcx, Option<FileDescriptor>> {
+        let this = self.eval_context_mut();
+
+        let Some(file_descriptor) = this.machine.fds.dup(fd) else {
+            this.fd_not_found::<i32>()?;
+            return Ok(None);
+        };
+        if file_descriptor.borrow().downcast_ref::<InetSocket>().is_none() {
+            let enotsock = this.eval_libc("ENOTSOCK");
+            this.set_last_error(enotsock)?;
+            return Ok(None);
+        }
+        Ok(Some(file_descriptor))
+    }
+
+    /// Reads the `sockaddr_in` or `sockaddr_in6` that `addr` points to. `addr_len` is the size of
+    /// the buffer behind `addr`.
+    fn read_socket_addr(
+        &self,
+        addr: Pointer,
+        addr_len: u32,
+    ) -> InterpResult<'tcx, io::Result<SocketAddr>> {
+        let this = self.eval_context_ref();
+
+        let sockaddr_in = this.libc_ty_layout("sockaddr_in");
+        let sockaddr_in6 = this.libc_ty_layout("sockaddr_in6");
+        if u64::from(addr_len) < sockaddr_in.size.bytes() {
+            return Ok(Err(ErrorKind::InvalidInput.into()));
+        }
+        // Ports and addresses are stored in network byte order, so we read them as bytes.
+        let read_bytes = |place: &MPlaceTy<'tcx>, field: &str, len: u64| {
+            let field = this.project_field_named(place, field)?;
+            let bytes = this.read_bytes_ptr_strip_provenance(field.ptr(), Size::from_bytes(len))?;
+            InterpResult::Ok(bytes.to_vec())
+        };
+
+        let place = this.ptr_to_mplace(addr, sockaddr_in);
+        let family = this.project_field_named(&place, "sin_family")?;
+        let family = this.read_scalar(&family)?.to_uint(family.layout.size)?;
+        let family = i32::try_from(family).unwrap();
+        let addr = if family == this.eval_libc_i32("AF_INET") {
+            let port = read_bytes(&place, "sin_port", 2)?.try_into().unwrap();
+            let ip = <[u8; 4]>::try_from(read_bytes(&place, "sin_addr", 4)?).unwrap();
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(ip), u16::from_be_bytes(port)))
+        } else if family == this.eval_libc_i32("AF_INET6") {
+            if u64::from(addr_len) < sockaddr_in6.size.bytes() {
+                return Ok(Err(ErrorKind::InvalidInput.into()));
+            }
+            let place = this.ptr_to_mplace(addr, sockaddr_in6);
+            let port = read_bytes(&place, "sin6_port", 2)?.try_into().unwrap();
+            let ip = <[u8; 16]>::try_from(read_bytes(&place, "sin6_addr", 16)?).unwrap();
+            let flowinfo = this.project_field_named(&place, "sin6_flowinfo")?;
+            let flowinfo = this.read_scalar(&flowinfo)?.to_u32()?;
+            let scope_id = this.project_field_named(&place, "sin6_scope_id")?;
+            let scope_id = this.read_scalar(&scope_id)?.to_u32()?;
+            SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(ip),
+                u16::from_be_bytes(port),
+                flowinfo,
+                scope_id,
+            ))
+        } else {
+            throw_unsup_format!(
+                "socket address family {family:#x} is unsupported, only AF_INET and AF_INET6 are allowed"
+            );
+        };
+        Ok(Ok(addr))
+    }
+
+    /// Writes `addr` to the buffer that `buf` points to and its size to `*buf_len`, like
+    /// `accept`, `recvfrom` and `getsockname` do. Does nothing if `buf` is null.
+    fn write_socket_addr(
+        &mut self,
+        addr: SocketAddr,
+        buf: Pointer,
+        buf_len: Pointer,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if this.ptr_is_null(buf)? {
+            return Ok(());
+        }
+        let buf_len = this.ptr_to_mplace(buf_len, this.libc_ty_layout("socklen_t"));
+        let layout =
+            this.libc_ty_layout(if addr.is_ipv4() { "sockaddr_in" } else { "sockaddr_in6" });
+        // FIXME: real systems truncate the address if the buffer is too small.
+        if u64::from(this.read_scalar(&buf_len)?.to_u32()?) < layout.size.bytes() {
+            throw_unsup_format!("socket addresses can only be written to buffers that fit them");
+        }
+
+        let place = this.ptr_to_mplace(buf, layout);
+        this.write_bytes_ptr(place.ptr(), iter::repeat(0u8).take(layout.size.bytes_usize()))?;
+        // Some targets store the length of the address in the address itself.
+        let (prefix, family) = match addr {
+            SocketAddr::V4(_) => ("sin", this.eval_libc_i32("AF_INET")),
+            SocketAddr::V6(_) => ("sin6", this.eval_libc_i32("AF_INET6")),
+        };
+        let family_field = format!("{prefix}_family");
+        this.write_int_fields_named(&[(family_field.as_str(), family.into())], &place)?;
+        let len_field = format!("{prefix}_len");
+        if this.projectable_has_field(&place, &len_field) {
+            let len = layout.size.bytes().into();
+            this.write_int_fields_named(&[(len_field.as_str(), len)], &place)?;
+        }
+        // Ports and addresses are stored in network byte order, so we write them as bytes.
+        let port = this.project_field_named(&place, &format!("{prefix}_port"))?;
+        this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+        match addr {
+            SocketAddr::V4(addr) => {
+                let ip = this.project_field_named(&place, "sin_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+            }
+            SocketAddr::V6(addr) => {
+                let ip = this.project_field_named(&place, "sin6_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+                this.write_int_fields_named(
+                    &[
+                        ("sin6_flowinfo", addr.flowinfo().into()),
+                        ("sin6_scope_id", addr.scope_id().into()),
+                    ],
+                    &place,
+                )?;
+            }
+        }
+        this.write_int(layout.size.bytes(), &buf_len)
+    }
+
+    /// Checks that a socket can reach `addr`. Sockets that do not use the host's network stack
+    /// can only reach the loopback network. Sets the last error if `addr` cannot be reached.
+    fn check_socket_addr_reachable(
+        &mut self,
+        socket_is_host: bool,
+        addr: SocketAddr,
+        op_name: &str,
+    ) -> InterpResult<'tcx, bool> {
+        let this = self.eval_context_mut();
+
+        if socket_is_host || addr.ip().is_loopback() || addr.ip().is_unspecified() {
+            return Ok(true);
+        }
+        // Sockets only avoid the host's network stack if isolation is enabled.
+        let IsolatedOp::Reject(reject_with) = this.machine.isolated_op else {
+            unreachable!("socket does not use the host's network stack with isolation disabled")
+        };
+        this.reject_in_isolation(op_name, reject_with)?;
+        this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+        Ok(false)
+    }
+
+    /// Parses the `flags` of `send`, `recv`, `sendto` and `recvfrom`, and returns whether
+    /// `MSG_DONTWAIT` is set.
+    fn parse_msg_flags(&self, flags: i32, link_name: &str) -> InterpResult<'tcx, bool> {
+        let this = self.eval_context_ref();
+
+        let mut remaining = flags;
+        let msg_dontwait = this.eval_libc_i32("MSG_DONTWAIT");
+        let is_dontwait = flags & msg_dontwait == msg_dontwait;
+        remaining &= !msg_dontwait;
+        // Miri does not raise SIGPIPE anyway.
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+            remaining &= !this.eval_libc_i32("MSG_NOSIGNAL");
+        }
+        if remaining != 0 {
+            throw_unsup_format!(
+                "{link_name}: flags {flags:#x} are unsupported, only MSG_DONTWAIT and MSG_NOSIGNAL are allowed"
+            );
+        }
+        Ok(is_dontwait)
+    }
+
+    /// Blocks the active thread until `op` may be able to make progress, and retries it then.
+    /// `is_host` is whether the socket uses the host's network stack.
+    fn block_on_socket(&mut self, op: BlockedSocketOp, is_host: bool, dest: &MPlaceTy<'tcx>) {
+        let this = self.eval_context_mut();
+
+        let dest = dest.clone();
+        this.block_on_fds(
+            None,
+            is_host,
+            callback!(
+                @capture<'tcx> {
+                    op: BlockedSocketOp,
+                    dest: MPlaceTy<'tcx>,
+                }
+                @unblock = |this| { this.retry_socket_op(op, &dest) }
+            ),
+        );
+    }
+
+    fn retry_socket_op(
+        &mut self,
+        op: BlockedSocketOp,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        match op {
+            BlockedSocketOp::Accept { fd, addr, addr_len, nonblock_connection } =>
+                this.accept_impl(fd, addr, addr_len, nonblock_connection, dest),
+            BlockedSocketOp::Recv { fd, buf, len, addr, addr_len } =>
+                this.recv_impl(fd, buf, len, /* dontwait */ false, addr, addr_len, dest),
+            BlockedSocketOp::Send { fd, buf, len, to } =>
+                this.send_impl(fd, buf, len, /* dontwait */ false, to, dest),
+        }
+    }
+
+    fn accept_impl(
+        &mut self,
+        fd: i32,
+        addr: Pointer,
+        addr_len: Pointer,
+        nonblock_connection: bool,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return this.write_int(-1, dest);
+        };
+        let mut file_description = file_descriptor.borrow_mut();
+        let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+        let (is_nonblock, is_host) = (socket.is_nonblock, socket.is_host);
+        let result = socket.accept(nonblock_connection);
+        drop(file_description);
+
+        match result {
+            Ok((connection, peer_addr)) => {
+                this.write_socket_addr(peer_addr, addr, addr_len)?;
+                let fd = this.machine.fds.insert_fd(FileDescriptor::new(connection));
+                this.write_int(fd, dest)
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock && !is_nonblock => {
+                let op = BlockedSocketOp::Accept { fd, addr, addr_len, nonblock_connection };
+                this.block_on_socket(op, is_host, dest);
+                Ok(())
+            }
+            Err(err) => {
+                this.set_last_error_from_io_error(err)?;
+                this.write_int(-1, dest)
+            }
+        }
+    }
+
+    fn recv_impl(
+        &mut self,
+        fd: i32,
+        buf: Pointer,
+        len: u64,
+        dontwait: bool,
+        addr: Pointer,
+        addr_len: Pointer,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+        // We cap the number of read bytes to the largest value that we are able to fit in both
+        // the host's and target's `isize`.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return this.write_int(-1, dest);
+        };
+        let mut file_description = file_descriptor.borrow_mut();
+        let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+        let (is_nonblock, is_host) = (socket.is_nonblock || dontwait, socket.is_host);
+        let mut bytes = vec![0; usize::try_from(len).unwrap()];
+        let result = socket.recv(&mut bytes, this)?;
+        drop(file_description);
+
+        match result {
+            Ok((read, from)) => {
+                this.write_bytes_ptr(buf, bytes[..read].iter().copied())?;
+                if let Some(from) = from {
+                    this.write_socket_addr(from, addr, addr_len)?;
+                }
+                this.write_int(u64::try_from(read).unwrap(), dest)?;
+                // Receiving may have made space for senders.
+                this.wake_fd_waiters()
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock && !is_nonblock => {
+                let op = BlockedSocketOp::Recv { fd, buf, len, addr, addr_len };
+                this.block_on_socket(op, is_host, dest);
+                Ok(())
+            }
+            Err(err) => {
+                this.set_last_error_from_io_error(err)?;
+                this.write_int(-1, dest)
+            }
+        }
+    }
+
+    fn send_impl(
+        &mut self,
+        fd: i32,
+        buf: Pointer,
+        len: u64,
+        dontwait: bool,
+        to: Option<SocketAddr>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+        // We cap the number of written bytes to the largest value that we are able to fit in both
+        // the host's and target's `isize`.
+        let len = len
+            .min(u64::try_from(this.target_isize_max()).unwrap())
+            .min(u64::try_from(isize::MAX).unwrap());
+        let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(len))?.to_owned();
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return this.write_int(-1, dest);
+        };
+        let mut file_description = file_descriptor.borrow_mut();
+        let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+        let (is_nonblock, is_host) = (socket.is_nonblock || dontwait, socket.is_host);
+        let result = socket.send(&bytes, to, this)?;
+        drop(file_description);
+
+        match result {
+            Ok(written) => {
+                this.write_int(u64::try_from(written).unwrap(), dest)?;
+                // Sending may have given receivers something to receive.
+                this.wake_fd_waiters()
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock && !is_nonblock => {
+                this.block_on_socket(BlockedSocketOp::Send { fd, buf, len, to }, is_host, dest);
+                Ok(())
+            }
+            Err(err) => {
+                this.set_last_error_from_io_error(err)?;
+                this.write_int(-1, dest)
+            }
+        }
+    }
 }
 
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
@@ -196,29 +1138,7 @@ fn socketpair(
             );
         }
 
-        let buffer1 = Rc::new(RefCell::new(Buffer {
-            buf: VecDeque::new(),
-            clock: VClock::default(),
-            buf_has_writer: true,
-        }));
-
-        let buffer2 = Rc::new(RefCell::new(Buffer {
-            buf: VecDeque::new(),
-            clock: VClock::default(),
-            buf_has_writer: true,
-        }));
-
-        let socketpair_0 = SocketPair {
-            writebuf: Rc::downgrade(&buffer1),
-            readbuf: Rc::clone(&buffer2),
-            is_nonblock: is_sock_nonblock,
-        };
-
-        let socketpair_1 = SocketPair {
-            writebuf: Rc::downgrade(&buffer2),
-            readbuf: Rc::clone(&buffer1),
-            is_nonblock: is_sock_nonblock,
-        };
+        let (socketpair_0, socketpair_1) = SocketPair::new_pair(is_sock_nonblock);
 
         let fds = &mut this.machine.fds;
         let sv0 = fds.insert_fd(FileDescriptor::new(socketpair_0));
@@ -231,4 +1151,354 @@ fn socketpair(
 
         Ok(Scalar::from_i32(0))
     }
+
+    /// For more information on the arguments see the socket manpage:
+    /// <https://linux.die.net/man/2/socket>
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx>,
+        type_: &OpTy<'tcx>,
+        protocol: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut type_ = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let mut is_sock_nonblock = false;
+
+        // SOCK_NONBLOCK and SOCK_CLOEXEC do not exist on macOS.
+        if this.tcx.sess.target.os != "macos" {
+            if type_ & this.eval_libc_i32("SOCK_NONBLOCK") == this.eval_libc_i32("SOCK_NONBLOCK") {
+                is_sock_nonblock = true;
+                type_ &= !(this.eval_libc_i32("SOCK_NONBLOCK"));
+            }
+            if type_ & this.eval_libc_i32("SOCK_CLOEXEC") == this.eval_libc_i32("SOCK_CLOEXEC") {
+                type_ &= !(this.eval_libc_i32("SOCK_CLOEXEC"));
+            }
+        }
+
+        let is_ipv6 = if domain == this.eval_libc_i32("AF_INET") {
+            false
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            true
+        } else {
+            throw_unsup_format!(
+                "socket: domain {:#x} is unsupported, only AF_INET and AF_INET6 are allowed",
+                domain
+            );
+        };
+        let kind = if type_ == this.eval_libc_i32("SOCK_STREAM")
+            && (protocol == 0 || protocol == this.eval_libc_i32("IPPROTO_TCP"))
+        {
+            SocketKind::Stream
+        } else if type_ == this.eval_libc_i32("SOCK_DGRAM")
+            && (protocol == 0 || protocol == this.eval_libc_i32("IPPROTO_UDP"))
+        {
+            SocketKind::Datagram
+        } else {
+            throw_unsup_format!(
+                "socket: type {:#x} with protocol {protocol} is unsupported, only TCP and UDP \
+                 sockets are allowed",
+                type_
+            );
+        };
+
+        let socket = InetSocket::new(kind, is_ipv6, is_sock_nonblock, this.machine.communicate());
+        let fd = this.machine.fds.insert_fd(FileDescriptor::new(socket));
+        Ok(Scalar::from_i32(fd))
+    }
+
+    fn bind(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_scalar(address_len)?.to_u32()?;
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return Ok(Scalar::from_i32(-1));
+        };
+        let result = match this.read_socket_addr(address, address_len)? {
+            Ok(addr) => {
+                let mut file_description = file_descriptor.borrow_mut();
+                let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+                socket.bind(addr, &mut this.machine.loopback)
+            }
+            Err(err) => Err(err),
+        };
+        Ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|()| 0))?))
+    }
+
+    fn listen(&mut self, socket: &OpTy<'tcx>, backlog: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        // The backlog is only a hint, so we ignore it.
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return Ok(Scalar::from_i32(-1));
+        };
+        let mut file_description = file_descriptor.borrow_mut();
+        let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+        if socket.kind == SocketKind::Datagram {
+            let eopnotsupp = this.eval_libc("EOPNOTSUPP");
+            this.set_last_error(eopnotsupp)?;
+            return Ok(Scalar::from_i32(-1));
+        }
+        let result = socket.listen(&mut this.machine.loopback);
+        drop(file_description);
+        Ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|()| 0))?))
+    }
+
+    /// Implements `accept4`, and `accept` if `flags` is `None`.
+    fn accept4(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+        let flags = match flags {
+            Some(flags) => this.read_scalar(flags)?.to_i32()?,
+            None => 0,
+        };
+
+        // Parse and remove the flags that we support. If flags != 0 after removing,
+        // unsupported flags are used.
+        let mut remaining = flags;
+        let mut is_sock_nonblock = false;
+        if flags != 0 {
+            if flags & this.eval_libc_i32("SOCK_NONBLOCK") == this.eval_libc_i32("SOCK_NONBLOCK") {
+                is_sock_nonblock = true;
+                remaining &= !(this.eval_libc_i32("SOCK_NONBLOCK"));
+            }
+            remaining &= !(this.eval_libc_i32("SOCK_CLOEXEC"));
+        }
+        if remaining != 0 {
+            throw_unsup_format!(
+                "accept4: flags {:#x} are unsupported, only SOCK_CLOEXEC and SOCK_NONBLOCK are allowed",
+                flags
+            );
+        }
+
+        this.accept_impl(fd, address, address_len, is_sock_nonblock, dest)
+    }
+
+    fn connect(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_scalar(address_len)?.to_u32()?;
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return Ok(Scalar::from_i32(-1));
+        };
+        let addr = match this.read_socket_addr(address, address_len)? {
+            Ok(addr) => addr,
+            Err(err) => {
+                this.set_last_error_from_io_error(err)?;
+                return Ok(Scalar::from_i32(-1));
+            }
+        };
+        let mut file_description = file_descriptor.borrow_mut();
+        let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+        if socket.kind == SocketKind::Stream
+            && !matches!(socket.state, SocketState::Unconnected { .. })
+        {
+            let eisconn = this.eval_libc("EISCONN");
+            this.set_last_error(eisconn)?;
+            return Ok(Scalar::from_i32(-1));
+        }
+        if !this.check_socket_addr_reachable(socket.is_host, addr, "`connect`")? {
+            return Ok(Scalar::from_i32(-1));
+        }
+        let result = socket.connect(addr, &mut this.machine.loopback);
+        drop(file_description);
+        if result.is_ok() {
+            // A listener on the loopback network may have a new connection to accept.
+            this.wake_fd_waiters()?;
+        }
+        Ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|()| 0))?))
+    }
+
+    /// Implements `sendto`, and `send` if `dest_addr` is `None`.
+    fn sendto(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        dest_addr: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        link_name: &str,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+        let dontwait = this.parse_msg_flags(flags, link_name)?;
+
+        let mut to = None;
+        if let Some((addr, addr_len)) = dest_addr {
+            let addr = this.read_pointer(addr)?;
+            let addr_len = this.read_scalar(addr_len)?.to_u32()?;
+            // A null address means that the datagram goes to the peer, like with `send`.
+            if !this.ptr_is_null(addr)? {
+                let addr = match this.read_socket_addr(addr, addr_len)? {
+                    Ok(addr) => addr,
+                    Err(err) => {
+                        this.set_last_error_from_io_error(err)?;
+                        return this.write_int(-1, dest);
+                    }
+                };
+                let Some(file_descriptor) = this.inet_socket(fd)? else {
+                    return this.write_int(-1, dest);
+                };
+                let is_host =
+                    file_descriptor.borrow().downcast_ref::<InetSocket>().unwrap().is_host;
+                if !this.check_socket_addr_reachable(is_host, addr, link_name)? {
+                    return this.write_int(-1, dest);
+                }
+                to = Some(addr);
+            }
+        }
+
+        this.send_impl(fd, buf, len, dontwait, to, dest)
+    }
+
+    /// Implements `recvfrom`, and `recv` if `src_addr` is `None`.
+    fn recvfrom(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        src_addr: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        link_name: &str,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+        let dontwait = this.parse_msg_flags(flags, link_name)?;
+        let (addr, addr_len) = match src_addr {
+            Some((addr, addr_len)) => (this.read_pointer(addr)?, this.read_pointer(addr_len)?),
+            None => (Pointer::null(), Pointer::null()),
+        };
+
+        this.recv_impl(fd, buf, len, dontwait, addr, addr_len, dest)
+    }
+
+    /// Implements `getpeername` if `peer` is true, and `getsockname` otherwise.
+    fn getsockname(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        address: &OpTy<'tcx>,
+        address_len: &OpTy<'tcx>,
+        peer: bool,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let address = this.read_pointer(address)?;
+        let address_len = this.read_pointer(address_len)?;
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return Ok(Scalar::from_i32(-1));
+        };
+        let file_description = file_descriptor.borrow();
+        let socket = file_description.downcast_ref::<InetSocket>().unwrap();
+        let result = if peer { socket.peer_addr() } else { socket.local_addr() };
+        drop(file_description);
+
+        match result {
+            Ok(addr) => {
+                this.write_socket_addr(addr, address, address_len)?;
+                Ok(Scalar::from_i32(0))
+            }
+            Err(err) => {
+                this.set_last_error_from_io_error(err)?;
+                Ok(Scalar::from_i32(-1))
+            }
+        }
+    }
+
+    fn setsockopt(
+        &mut self,
+        socket: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        option_name: &OpTy<'tcx>,
+        option_value: &OpTy<'tcx>,
+        option_len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(socket)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let option_name = this.read_scalar(option_name)?.to_i32()?;
+        let option_value = this.read_pointer(option_value)?;
+        let option_len = this.read_scalar(option_len)?.to_u32()?;
+
+        let Some(file_descriptor) = this.inet_socket(fd)? else {
+            return Ok(Scalar::from_i32(-1));
+        };
+        let mut file_description = file_descriptor.borrow_mut();
+        let socket = file_description.downcast_mut::<InetSocket>().unwrap();
+
+        let result = if level == this.eval_libc_i32("SOL_SOCKET")
+            && (option_name == this.eval_libc_i32("SO_REUSEADDR")
+                || (this.tcx.sess.target.os == "macos"
+                    && option_name == this.eval_libc_i32("SO_NOSIGPIPE")))
+        {
+            // Addresses can always be reused, and Miri does not raise SIGPIPE anyway.
+            Ok(())
+        } else if level == this.eval_libc_i32("IPPROTO_TCP")
+            && option_name == this.eval_libc_i32("TCP_NODELAY")
+        {
+            if u64::from(option_len) < this.machine.layouts.i32.size.bytes() {
+                Err(ErrorKind::InvalidInput.into())
+            } else {
+                let nodelay = this.ptr_to_mplace(option_value, this.machine.layouts.i32);
+                let nodelay = this.read_scalar(&nodelay)?.to_i32()? != 0;
+                match &socket.state {
+                    SocketState::HostStream(stream) => stream.set_nodelay(nodelay),
+                    // Nothing gets delayed on the loopback network.
+                    _ => Ok(()),
+                }
+            }
+        } else {
+            throw_unsup_format!(
+                "setsockopt: option {:#x} at level {:#x} is unsupported",
+                option_name,
+                level
+            );
+        };
+        drop(file_description);
+        Ok(Scalar::from_i32(this.try_unwrap_io_result(result.map(|()| 0))?))
+    }
 }
@@ -13,12 +13,18 @@
 use crate::*;
 use shims::windows::handle::{Handle, PseudoHandle};
 
-pub fn is_dyn_sym(name: &str) -> bool {
+pub fn is_dyn_sym(name: &str, vendor: &str) -> bool {
     // std does dynamic detection for these symbols
-    matches!(
-        name,
-        "SetThreadDescription" | "GetThreadDescription" | "WaitOnAddress" | "WakeByAddressSingle"
-    )
+    match name {
+        "SetThreadDescription"
+        | "GetThreadDescription"
+        | "NtCreateKeyedEvent"
+        | "NtWaitForKeyedEvent"
+        | "NtReleaseKeyedEvent" => true,
+        // Pretend to be Windows 7 on the win7 targets, so that std falls back to keyed events.
+        "WaitOnAddress" | "WakeByAddressSingle" => vendor != "win7",
+        _ => false,
+    }
 }
 
 #[cfg(windows)]
@@ -163,7 +169,8 @@ fn emulate_foreign_item_inner(
                     byte_offset,
                     _key,
                 ] = this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
-                let handle = this.read_target_isize(handle)?;
+                let handle_op = handle;
+                let handle = this.read_target_isize(handle_op)?;
                 let buf = this.read_pointer(buf)?;
                 let n = this.read_scalar(n)?.to_u32()?;
                 let byte_offset = this.read_target_usize(byte_offset)?; // is actually a pointer
@@ -176,7 +183,7 @@ fn emulate_foreign_item_inner(
                     );
                 }
 
-                let written = if handle == -11 || handle == -12 {
+                let (written, status) = if handle == -11 || handle == -12 {
                     // stdout/stderr
                     use io::Write;
 
@@ -190,11 +197,16 @@ fn emulate_foreign_item_inner(
                         io::stderr().write(buf_cont)
                     };
                     // We write at most `n` bytes, which is a `u32`, so we cannot have written more than that.
-                    res.ok().map(|n| u32::try_from(n).unwrap())
+                    match res {
+                        Ok(n) => (Some(u32::try_from(n).unwrap()), 0),
+                        // For the error code we arbitrarily pick 0xC0000185, STATUS_IO_DEVICE_ERROR.
+                        Err(_) => (None, 0xC0000185u32),
+                    }
                 } else {
-                    throw_unsup_format!(
-                        "on Windows, writing to anything except stdout/stderr is not supported"
-                    )
+                    match this.write_to_file_handle(handle_op, buf, n, "NtWriteFile")? {
+                        Ok(n) => (Some(n), 0),
+                        Err(e) => (None, this.io_error_to_ntstatus(e)?),
+                    }
                 };
                 // We have to put the result into io_status_block.
                 if let Some(n) = written {
@@ -206,11 +218,177 @@ fn emulate_foreign_item_inner(
                     )?;
                 }
                 // Return whether this was a success. >= 0 is success.
-                // For the error code we arbitrarily pick 0xC0000185, STATUS_IO_DEVICE_ERROR.
-                this.write_scalar(
-                    Scalar::from_u32(if written.is_some() { 0 } else { 0xC0000185u32 }),
-                    dest,
+                this.write_scalar(Scalar::from_u32(status), dest)?;
+            }
+            "NtReadFile" => {
+                let [
+                    handle,
+                    _event,
+                    _apc_routine,
+                    _apc_context,
+                    io_status_block,
+                    buf,
+                    n,
+                    byte_offset,
+                    _key,
+                ] = this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let buf = this.read_pointer(buf)?;
+                let n = this.read_scalar(n)?.to_u32()?;
+                let byte_offset = this.read_target_usize(byte_offset)?; // is actually a pointer
+                let io_status_block = this
+                    .deref_pointer_as(io_status_block, this.windows_ty_layout("IO_STATUS_BLOCK"))?;
+
+                if byte_offset != 0 {
+                    throw_unsup_format!(
+                        "`NtReadFile` `ByteOffset` parameter is non-null, which is unsupported"
+                    );
+                }
+
+                // Reaching the end of the file is reported as a successful read of 0 bytes, which
+                // is what `ReadFile` does as well.
+                let status = match this.read_from_file_handle(handle, buf, n, "NtReadFile")? {
+                    Ok(read) => {
+                        let io_status_information =
+                            this.project_field_named(&io_status_block, "Information")?;
+                        this.write_scalar(
+                            Scalar::from_target_usize(read.into(), this),
+                            &io_status_information,
+                        )?;
+                        0
+                    }
+                    Err(e) => this.io_error_to_ntstatus(e)?,
+                };
+                this.write_scalar(Scalar::from_u32(status), dest)?;
+            }
+            "RtlNtStatusToDosError" => {
+                let [status] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let status = this.read_scalar(status)?.to_u32()?;
+                let error = match status {
+                    0 => this.eval_windows_u32("c", "ERROR_SUCCESS"),
+                    // STATUS_IO_DEVICE_ERROR, which `NtWriteFile` reports for stdout/stderr.
+                    0xC0000185 => this.eval_windows_u32("c", "ERROR_IO_DEVICE"),
+                    // `FACILITY_NTWIN32` wraps a Win32 error code, see `io_error_to_ntstatus`.
+                    _ if status & 0xFFFF_0000 == 0xC007_0000 => status & 0xFFFF,
+                    // This is what Windows returns for statuses it cannot map.
+                    _ => this.eval_windows_u32("c", "ERROR_MR_MID_NOT_FOUND"),
+                };
+                this.write_scalar(Scalar::from_u32(error), dest)?;
+            }
+            "CreateFileW" => {
+                let [
+                    file_name,
+                    desired_access,
+                    share_mode,
+                    security_attributes,
+                    creation_disposition,
+                    flags_and_attributes,
+                    template_file,
+                ] = this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let handle = this.CreateFileW(
+                    file_name,
+                    desired_access,
+                    share_mode,
+                    security_attributes,
+                    creation_disposition,
+                    flags_and_attributes,
+                    template_file,
+                )?;
+                this.write_scalar(handle, dest)?;
+            }
+            "ReadFile" => {
+                let [file, buffer, number_of_bytes_to_read, number_of_bytes_read, overlapped] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.ReadFile(
+                    file,
+                    buffer,
+                    number_of_bytes_to_read,
+                    number_of_bytes_read,
+                    overlapped,
+                )?;
+                this.write_scalar(result, dest)?;
+            }
+            "GetFileInformationByHandle" => {
+                let [file, file_information] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.GetFileInformationByHandle(file, file_information)?;
+                this.write_scalar(result, dest)?;
+            }
+            "GetFileInformationByHandleEx" => {
+                let [file, file_information_class, file_information, buffer_size] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.GetFileInformationByHandleEx(
+                    file,
+                    file_information_class,
+                    file_information,
+                    buffer_size,
                 )?;
+                this.write_scalar(result, dest)?;
+            }
+            "SetFileInformationByHandle" => {
+                let [file, file_information_class, file_information, buffer_size] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.SetFileInformationByHandle(
+                    file,
+                    file_information_class,
+                    file_information,
+                    buffer_size,
+                )?;
+                this.write_scalar(result, dest)?;
+            }
+            "SetFilePointerEx" => {
+                let [file, distance_to_move, new_file_pointer, move_method] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result =
+                    this.SetFilePointerEx(file, distance_to_move, new_file_pointer, move_method)?;
+                this.write_scalar(result, dest)?;
+            }
+            "FlushFileBuffers" => {
+                let [file] = this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.FlushFileBuffers(file)?;
+                this.write_scalar(result, dest)?;
+            }
+            "FindFirstFileW" => {
+                let [file_name, find_file_data] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let handle = this.FindFirstFileW(file_name, find_file_data)?;
+                this.write_scalar(handle, dest)?;
+            }
+            "FindNextFileW" => {
+                let [find_file, find_file_data] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.FindNextFileW(find_file, find_file_data)?;
+                this.write_scalar(result, dest)?;
+            }
+            "FindClose" => {
+                let [find_file] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.FindClose(find_file)?;
+                this.write_scalar(result, dest)?;
+            }
+            "DeleteFileW" => {
+                let [file_name] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.DeleteFileW(file_name)?;
+                this.write_scalar(result, dest)?;
+            }
+            "CreateDirectoryW" => {
+                let [path_name, security_attributes] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.CreateDirectoryW(path_name, security_attributes)?;
+                this.write_scalar(result, dest)?;
+            }
+            "RemoveDirectoryW" => {
+                let [path_name] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.RemoveDirectoryW(path_name)?;
+                this.write_scalar(result, dest)?;
+            }
+            "MoveFileExW" => {
+                let [existing_file_name, new_file_name, flags] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let result = this.MoveFileExW(existing_file_name, new_file_name, flags)?;
+                this.write_scalar(result, dest)?;
             }
             "GetFullPathNameW" => {
                 let [filename, size, buffer, filepart] =
@@ -454,6 +632,22 @@ fn emulate_foreign_item_inner(
 
                 this.WakeByAddressAll(ptr_op)?;
             }
+            "NtCreateKeyedEvent" => {
+                let [handle, access, object_attributes, flags] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                let status = this.NtCreateKeyedEvent(handle, access, object_attributes, flags)?;
+                this.write_scalar(status, dest)?;
+            }
+            "NtWaitForKeyedEvent" => {
+                let [handle, key, alertable, timeout] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                this.NtWaitForKeyedEvent(handle, key, alertable, timeout, dest)?;
+            }
+            "NtReleaseKeyedEvent" => {
+                let [handle, key, alertable, timeout] =
+                    this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
+                this.NtReleaseKeyedEvent(handle, key, alertable, timeout, dest)?;
+            }
 
             // Dynamic symbol loading
             "GetProcAddress" => {
@@ -463,7 +657,7 @@ fn emulate_foreign_item_inner(
                 this.read_target_isize(hModule)?;
                 let name = this.read_c_str(this.read_pointer(lpProcName)?)?;
                 if let Ok(name) = str::from_utf8(name)
-                    && is_dyn_sym(name)
+                    && is_dyn_sym(name, &this.tcx.sess.target.vendor)
                 {
                     let ptr = this.fn_ptr(FnVal::Other(DynSym::from_str(name)));
                     this.write_pointer(ptr, dest)?;
@@ -625,9 +819,9 @@ fn emulate_foreign_item_inner(
                 let [handle] =
                     this.check_shim(abi, Abi::System { unwind: false }, link_name, args)?;
 
-                this.CloseHandle(handle)?;
+                let result = this.CloseHandle(handle)?;
 
-                this.write_scalar(Scalar::from_u32(1), dest)?;
+                this.write_scalar(result, dest)?;
             }
             "GetModuleFileNameW" => {
                 let [handle, filename, size] =
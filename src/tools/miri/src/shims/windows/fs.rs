@@ -0,0 +1,964 @@
+//! File and file system access on Windows.
+//!
+//! Files are stored in the machine's file descriptor table, just like on Unix targets, and the
+//! `HANDLE`s we give out to the program refer to the index in that table.
+
+use std::ffi::OsStr;
+use std::fs::{
+    create_dir, read_dir, remove_dir, remove_file, rename, Metadata, OpenOptions, ReadDir,
+};
+use std::io::{self, ErrorKind, SeekFrom};
+use std::path::Path;
+use std::time::SystemTime;
+
+use rustc_target::abi::Size;
+
+use crate::shims::unix::{FileDescription, FileDescriptor, FileHandle};
+use crate::*;
+use shims::time::system_time_to_duration;
+use shims::windows::handle::Handle;
+
+/// A directory search started by `FindFirstFileW`.
+#[derive(Debug)]
+struct DirectorySearch {
+    /// The remaining entries of the directory. This is `None` if the search was for a single
+    /// file, in which case there are no further entries.
+    read_dir: Option<ReadDir>,
+}
+
+impl FileDescription for DirectorySearch {
+    fn name(&self) -> &'static str {
+        "directory search"
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        Ok(Ok(()))
+    }
+}
+
+/// The metadata of a file, in the form used by the various Windows file information structs.
+struct FileMetadata {
+    attributes: u32,
+    size: u64,
+    is_dir: bool,
+    /// The file times, in 100ns intervals since the Windows epoch (`FILETIME`).
+    created: u64,
+    accessed: u64,
+    modified: u64,
+}
+
+impl FileMetadata {
+    fn from_meta<'tcx>(ecx: &MiriInterpCx<'tcx>, metadata: &Metadata) -> InterpResult<'tcx, Self> {
+        let mut attributes = 0;
+        if metadata.is_dir() {
+            attributes |= ecx.eval_windows_u32("c", "FILE_ATTRIBUTE_DIRECTORY");
+        }
+        if metadata.permissions().readonly() {
+            attributes |= ecx.eval_windows_u32("c", "FILE_ATTRIBUTE_READONLY");
+        }
+        if attributes == 0 {
+            // `FILE_ATTRIBUTE_NORMAL` is only valid when used alone.
+            attributes = ecx.eval_windows_u32("c", "FILE_ATTRIBUTE_NORMAL");
+        }
+
+        // FIXME: Provide the volume serial number, file index and number of links using platform
+        // specific methods.
+        Ok(FileMetadata {
+            attributes,
+            size: metadata.len(),
+            is_dir: metadata.is_dir(),
+            created: system_time_to_filetime(ecx, metadata.created())?,
+            accessed: system_time_to_filetime(ecx, metadata.accessed())?,
+            modified: system_time_to_filetime(ecx, metadata.modified())?,
+        })
+    }
+}
+
+/// Converts `time` into the number of 100ns intervals since the Windows epoch. Returns 0 (which
+/// Windows uses for "unknown") if `time` is an error.
+#[allow(non_snake_case, clippy::arithmetic_side_effects)]
+fn system_time_to_filetime<'tcx>(
+    ecx: &MiriInterpCx<'tcx>,
+    time: io::Result<SystemTime>,
+) -> InterpResult<'tcx, u64> {
+    let Ok(time) = time else {
+        return Ok(0);
+    };
+
+    let NANOS_PER_SEC = ecx.eval_windows_u64("time", "NANOS_PER_SEC");
+    let INTERVALS_PER_SEC = ecx.eval_windows_u64("time", "INTERVALS_PER_SEC");
+    let INTERVALS_TO_UNIX_EPOCH = ecx.eval_windows_u64("time", "INTERVALS_TO_UNIX_EPOCH");
+    let NANOS_PER_INTERVAL = NANOS_PER_SEC / INTERVALS_PER_SEC;
+
+    let duration = system_time_to_duration(&time)?;
+    let intervals =
+        duration.as_nanos() / u128::from(NANOS_PER_INTERVAL) + u128::from(INTERVALS_TO_UNIX_EPOCH);
+    u64::try_from(intervals).map_err(|_| {
+        err_unsup_format!(
+            "file times more than 2^64 Windows ticks after the Windows epoch are not supported"
+        )
+        .into()
+    })
+}
+
+impl<'tcx> EvalContextExtPrivate<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPrivate<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns the index in the file descriptor table of the file handle `handle_op`, and aborts
+    /// if it is not a valid file handle.
+    fn file_handle_fd(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        function_name: &str,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let handle = this.read_scalar(handle_op)?;
+        match Handle::from_scalar(handle, this)? {
+            Some(Handle::File(fd)) if this.machine.fds.is_fd(fd) => Ok(fd),
+            _ => this.invalid_handle(function_name)?,
+        }
+    }
+
+    /// Gets the metadata of the file behind `fd`. Returns `None` and sets the last error if the
+    /// metadata could not be obtained.
+    fn file_handle_metadata(
+        &mut self,
+        fd: i32,
+        function_name: &str,
+    ) -> InterpResult<'tcx, Option<FileMetadata>> {
+        let this = self.eval_context_mut();
+
+        let file_descriptor = this.machine.fds.get(fd).unwrap();
+        let Some(file_handle) = file_descriptor.downcast_ref::<FileHandle>() else {
+            throw_unsup_format!("`{function_name}` is only supported on file handles");
+        };
+        let metadata = file_handle.file.metadata();
+        drop(file_descriptor);
+
+        match metadata {
+            Ok(metadata) => Ok(Some(FileMetadata::from_meta(this, &metadata)?)),
+            Err(e) => {
+                this.set_last_error_from_io_error(e)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn write_filetime(&mut self, filetime: u64, dest: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let low = filetime & 0xFFFF_FFFF;
+        let high = filetime.checked_shr(32).unwrap();
+        this.write_int_fields_named(
+            &[("dwLowDateTime", low.into()), ("dwHighDateTime", high.into())],
+            dest,
+        )
+    }
+
+    /// Fills in the `WIN32_FIND_DATAW` struct behind `find_data_op` with the given file name and
+    /// metadata.
+    fn write_find_data(
+        &mut self,
+        file_name: &OsStr,
+        metadata: &Metadata,
+        find_data_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let find_data =
+            this.deref_pointer_as(find_data_op, this.windows_ty_layout("WIN32_FIND_DATAW"))?;
+        let metadata = FileMetadata::from_meta(this, metadata)?;
+
+        this.write_int_fields_named(
+            &[
+                ("dwFileAttributes", metadata.attributes.into()),
+                ("nFileSizeHigh", metadata.size.checked_shr(32).unwrap().into()),
+                ("nFileSizeLow", (metadata.size & 0xFFFF_FFFF).into()),
+                ("dwReserved0", 0),
+                ("dwReserved1", 0),
+            ],
+            &find_data,
+        )?;
+        this.write_filetime(
+            metadata.created,
+            &this.project_field_named(&find_data, "ftCreationTime")?,
+        )?;
+        this.write_filetime(
+            metadata.accessed,
+            &this.project_field_named(&find_data, "ftLastAccessTime")?,
+        )?;
+        this.write_filetime(
+            metadata.modified,
+            &this.project_field_named(&find_data, "ftLastWriteTime")?,
+        )?;
+
+        let name_field = this.project_field_named(&find_data, "cFileName")?;
+        let name_len = name_field.layout.size.bytes().strict_div(2);
+        let (written, _) = this.write_os_str_to_wide_str(file_name, name_field.ptr(), name_len)?;
+        if !written {
+            throw_unsup_format!("file names longer than `MAX_PATH` are not supported");
+        }
+        // We never provide 8.3 file names.
+        let alternate_name_field = this.project_field_named(&find_data, "cAlternateFileName")?;
+        this.write_os_str_to_wide_str(OsStr::new(""), alternate_name_field.ptr(), 1)?;
+
+        Ok(())
+    }
+
+    /// Stores the new directory search in the file descriptor table and returns its handle.
+    fn insert_directory_search(&mut self, read_dir: Option<ReadDir>) -> Scalar {
+        let this = self.eval_context_mut();
+
+        let fd = this.machine.fds.insert_fd(FileDescriptor::new(DirectorySearch { read_dir }));
+        Handle::File(fd).to_scalar(this)
+    }
+
+    /// Returns `TRUE` if `result` is `Ok`, and otherwise sets the last error and returns `FALSE`.
+    fn io_result_to_bool(&mut self, result: io::Result<()>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        match result {
+            Ok(()) => Ok(Scalar::from_i32(1)),
+            Err(e) => {
+                this.set_last_error_from_io_error(e)?;
+                Ok(Scalar::from_i32(0))
+            }
+        }
+    }
+
+    fn invalid_handle_value(&self) -> Scalar {
+        Scalar::from_target_isize(-1, self.eval_context_ref())
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+#[allow(non_snake_case)]
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    #[allow(clippy::too_many_arguments)]
+    fn CreateFileW(
+        &mut self,
+        file_name_op: &OpTy<'tcx>,
+        desired_access_op: &OpTy<'tcx>,
+        share_mode_op: &OpTy<'tcx>,
+        security_attributes_op: &OpTy<'tcx>,
+        creation_disposition_op: &OpTy<'tcx>,
+        flags_and_attributes_op: &OpTy<'tcx>,
+        template_file_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let file_name = this.read_path_from_wide_str(this.read_pointer(file_name_op)?)?;
+        let desired_access = this.read_scalar(desired_access_op)?.to_u32()?;
+        // The share mode only restricts what *other* handles can do with the file, and Miri
+        // cannot enforce that for other processes on the host anyway.
+        let _share_mode = this.read_scalar(share_mode_op)?.to_u32()?;
+        let security_attributes = this.read_pointer(security_attributes_op)?;
+        let creation_disposition = this.read_scalar(creation_disposition_op)?.to_u32()?;
+        let flags_and_attributes = this.read_scalar(flags_and_attributes_op)?.to_u32()?;
+        let template_file = this.read_target_isize(template_file_op)?;
+
+        if !this.ptr_is_null(security_attributes)? {
+            throw_unsup_format!("`CreateFileW`: non-null `lpSecurityAttributes` is not supported");
+        }
+        if template_file != 0 {
+            throw_unsup_format!("`CreateFileW`: non-null `hTemplateFile` is not supported");
+        }
+
+        let mut options = OpenOptions::new();
+
+        let generic_read = this.eval_windows_u32("c", "GENERIC_READ");
+        let generic_write = this.eval_windows_u32("c", "GENERIC_WRITE");
+        let file_read_data = this.eval_windows_u32("c", "FILE_READ_DATA");
+        let file_write_data = this.eval_windows_u32("c", "FILE_WRITE_DATA");
+        let file_append_data = this.eval_windows_u32("c", "FILE_APPEND_DATA");
+        // Other access rights, e.g. to the file attributes, are granted implicitly.
+        let read = desired_access & (generic_read | file_read_data) != 0;
+        let write = desired_access & (generic_write | file_write_data) != 0;
+        let append = !write && desired_access & file_append_data != 0;
+        // Windows allows opening a file without any access rights to query its metadata. The
+        // closest we can do on the host is to open it for reading.
+        options.read(read || !(write || append)).write(write).append(append);
+
+        let create_new = this.eval_windows_u32("c", "CREATE_NEW");
+        let create_always = this.eval_windows_u32("c", "CREATE_ALWAYS");
+        let open_existing = this.eval_windows_u32("c", "OPEN_EXISTING");
+        let open_always = this.eval_windows_u32("c", "OPEN_ALWAYS");
+        let truncate_existing = this.eval_windows_u32("c", "TRUNCATE_EXISTING");
+        // `CREATE_ALWAYS` and `OPEN_ALWAYS` report whether the file already existed via the last
+        // error, even if they succeed.
+        let mut report_existing = false;
+        if creation_disposition == create_new {
+            options.create_new(true);
+        } else if creation_disposition == create_always {
+            options.create(true).truncate(true);
+            report_existing = true;
+        } else if creation_disposition == open_always {
+            options.create(true);
+            report_existing = true;
+        } else if creation_disposition == truncate_existing {
+            options.truncate(true);
+        } else if creation_disposition != open_existing {
+            throw_unsup_format!(
+                "`CreateFileW`: unsupported creation disposition {creation_disposition:#x}"
+            );
+        }
+
+        let backup_semantics = this.eval_windows_u32("c", "FILE_FLAG_BACKUP_SEMANTICS");
+        let open_reparse_point = this.eval_windows_u32("c", "FILE_FLAG_OPEN_REPARSE_POINT");
+        let attribute_normal = this.eval_windows_u32("c", "FILE_ATTRIBUTE_NORMAL");
+        // The security quality of service flags only matter for named pipes.
+        let sqos_flags = this.eval_windows_u32("c", "SECURITY_VALID_SQOS_FLAGS");
+        let unsupported_flags = flags_and_attributes
+            & !(backup_semantics | open_reparse_point | attribute_normal | sqos_flags);
+        if unsupported_flags != 0 {
+            throw_unsup_format!(
+                "`CreateFileW`: unsupported flags and attributes {unsupported_flags:#x}"
+            );
+        }
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+            this.reject_in_isolation("`CreateFileW`", reject_with)?;
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(this.invalid_handle_value());
+        }
+
+        if flags_and_attributes & open_reparse_point != 0 && file_name.is_symlink() {
+            throw_unsup_format!(
+                "`CreateFileW`: opening symbolic links themselves is not supported"
+            );
+        }
+        // Directories can only be opened with `FILE_FLAG_BACKUP_SEMANTICS`.
+        if flags_and_attributes & backup_semantics == 0 && file_name.is_dir() {
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(this.invalid_handle_value());
+        }
+        #[cfg(windows)]
+        {
+            // Forward the flag so that directories can also be opened on Windows hosts.
+            use std::os::windows::fs::OpenOptionsExt;
+            options.custom_flags(flags_and_attributes & backup_semantics);
+        }
+
+        let existed = report_existing && file_name.exists();
+        match options.open(&file_name) {
+            Ok(file) => {
+                let fd = this
+                    .machine
+                    .fds
+                    .insert_fd(FileDescriptor::new(FileHandle { file, writable: write || append }));
+                if report_existing {
+                    let last_error = if existed {
+                        this.eval_windows("c", "ERROR_ALREADY_EXISTS")
+                    } else {
+                        this.eval_windows("c", "ERROR_SUCCESS")
+                    };
+                    this.set_last_error(last_error)?;
+                }
+                Ok(Handle::File(fd).to_scalar(this))
+            }
+            Err(e) => {
+                this.set_last_error_from_io_error(e)?;
+                Ok(this.invalid_handle_value())
+            }
+        }
+    }
+
+    /// Reads up to `len` bytes from the file behind `handle_op` into `buf`.
+    fn read_from_file_handle(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        buf: Pointer,
+        len: u32,
+        function_name: &str,
+    ) -> InterpResult<'tcx, io::Result<u32>> {
+        let this = self.eval_context_mut();
+
+        // Isolation check is done via `FileDescriptor` trait.
+
+        let fd = this.file_handle_fd(handle_op, function_name)?;
+        let len = u64::from(len);
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+        let communicate = this.machine.communicate();
+
+        // We temporarily dup the FD to be able to retain mutable access to `this`.
+        let file_descriptor = this.machine.fds.dup(fd).unwrap();
+        let mut bytes = vec![0; usize::try_from(len).unwrap()];
+        let result = file_descriptor.borrow_mut().read(communicate, &mut bytes, this)?;
+        drop(file_descriptor);
+
+        match result {
+            Ok(read) => {
+                bytes.truncate(read);
+                this.write_bytes_ptr(buf, bytes)?;
+                // We read at most `len` bytes, which is a `u32`.
+                Ok(Ok(u32::try_from(read).unwrap()))
+            }
+            Err(e) => Ok(Err(e)),
+        }
+    }
+
+    /// Writes `len` bytes from `buf` into the file behind `handle_op`.
+    fn write_to_file_handle(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        buf: Pointer,
+        len: u32,
+        function_name: &str,
+    ) -> InterpResult<'tcx, io::Result<u32>> {
+        let this = self.eval_context_mut();
+
+        // Isolation check is done via `FileDescriptor` trait.
+
+        let fd = this.file_handle_fd(handle_op, function_name)?;
+        // The host reports writing to a read-only file as a bad file descriptor, Windows reports
+        // it as access denied.
+        let read_only = this
+            .machine
+            .fds
+            .get(fd)
+            .unwrap()
+            .downcast_ref::<FileHandle>()
+            .is_some_and(|file_handle| !file_handle.writable);
+        if read_only {
+            return Ok(Err(ErrorKind::PermissionDenied.into()));
+        }
+        let communicate = this.machine.communicate();
+        let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(len))?.to_owned();
+
+        // We temporarily dup the FD to be able to retain mutable access to `this`.
+        let file_descriptor = this.machine.fds.dup(fd).unwrap();
+        let result = file_descriptor.borrow_mut().write(communicate, &bytes, this)?;
+        drop(file_descriptor);
+
+        // We wrote at most `len` bytes, which is a `u32`.
+        Ok(result.map(|written| u32::try_from(written).unwrap()))
+    }
+
+    /// Converts an I/O error into an `NTSTATUS` of the `FACILITY_NTWIN32` facility, which is how
+    /// Windows wraps Win32 error codes. `RtlNtStatusToDosError` turns it back into the error code.
+    fn io_error_to_ntstatus(&self, err: io::Error) -> InterpResult<'tcx, u32> {
+        let this = self.eval_context_ref();
+
+        let error_code = this.io_error_to_errnum(err)?.to_u32()?;
+        Ok(0xC007_0000 | error_code)
+    }
+
+    fn ReadFile(
+        &mut self,
+        file_op: &OpTy<'tcx>,
+        buffer_op: &OpTy<'tcx>,
+        number_of_bytes_to_read_op: &OpTy<'tcx>,
+        number_of_bytes_read_op: &OpTy<'tcx>,
+        overlapped_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let buffer = this.read_pointer(buffer_op)?;
+        let number_of_bytes_to_read = this.read_scalar(number_of_bytes_to_read_op)?.to_u32()?;
+        let number_of_bytes_read = this.read_pointer(number_of_bytes_read_op)?;
+        let overlapped = this.read_pointer(overlapped_op)?;
+
+        if !this.ptr_is_null(overlapped)? {
+            throw_unsup_format!("`ReadFile`: non-null `lpOverlapped` is not supported");
+        }
+
+        let result =
+            this.read_from_file_handle(file_op, buffer, number_of_bytes_to_read, "ReadFile")?;
+        match result {
+            Ok(read) => {
+                if !this.ptr_is_null(number_of_bytes_read)? {
+                    let number_of_bytes_read =
+                        this.ptr_to_mplace(number_of_bytes_read, this.machine.layouts.u32);
+                    this.write_scalar(Scalar::from_u32(read), &number_of_bytes_read)?;
+                }
+                Ok(Scalar::from_i32(1))
+            }
+            Err(e) => {
+                this.set_last_error_from_io_error(e)?;
+                Ok(Scalar::from_i32(0))
+            }
+        }
+    }
+
+    fn GetFileInformationByHandle(
+        &mut self,
+        file_op: &OpTy<'tcx>,
+        file_information_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.file_handle_fd(file_op, "GetFileInformationByHandle")?;
+        let file_information = this.deref_pointer_as(
+            file_information_op,
+            this.windows_ty_layout("BY_HANDLE_FILE_INFORMATION"),
+        )?;
+
+        let Some(metadata) = this.file_handle_metadata(fd, "GetFileInformationByHandle")? else {
+            return Ok(Scalar::from_i32(0));
+        };
+
+        this.write_int_fields_named(
+            &[
+                ("dwFileAttributes", metadata.attributes.into()),
+                ("dwVolumeSerialNumber", 0),
+                ("nFileSizeHigh", metadata.size.checked_shr(32).unwrap().into()),
+                ("nFileSizeLow", (metadata.size & 0xFFFF_FFFF).into()),
+                ("nNumberOfLinks", 1),
+                ("nFileIndexHigh", 0),
+                ("nFileIndexLow", 0),
+            ],
+            &file_information,
+        )?;
+        this.write_filetime(
+            metadata.created,
+            &this.project_field_named(&file_information, "ftCreationTime")?,
+        )?;
+        this.write_filetime(
+            metadata.accessed,
+            &this.project_field_named(&file_information, "ftLastAccessTime")?,
+        )?;
+        this.write_filetime(
+            metadata.modified,
+            &this.project_field_named(&file_information, "ftLastWriteTime")?,
+        )?;
+
+        Ok(Scalar::from_i32(1))
+    }
+
+    fn GetFileInformationByHandleEx(
+        &mut self,
+        file_op: &OpTy<'tcx>,
+        file_information_class_op: &OpTy<'tcx>,
+        file_information_op: &OpTy<'tcx>,
+        buffer_size_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.file_handle_fd(file_op, "GetFileInformationByHandleEx")?;
+        let class = this.read_scalar(file_information_class_op)?.to_i32()?;
+        let buffer_size = this.read_scalar(buffer_size_op)?.to_u32()?;
+
+        let basic_info = this.eval_windows("c", "FileBasicInfo").to_i32()?;
+        let standard_info = this.eval_windows("c", "FileStandardInfo").to_i32()?;
+        let attribute_tag_info = this.eval_windows("c", "FileAttributeTagInfo").to_i32()?;
+        let struct_name = if class == basic_info {
+            "FILE_BASIC_INFO"
+        } else if class == standard_info {
+            "FILE_STANDARD_INFO"
+        } else if class == attribute_tag_info {
+            "FILE_ATTRIBUTE_TAG_INFO"
+        } else {
+            throw_unsup_format!(
+                "`GetFileInformationByHandleEx`: unsupported information class {class}"
+            );
+        };
+        let file_information =
+            this.deref_pointer_as(file_information_op, this.windows_ty_layout(struct_name))?;
+        if u64::from(buffer_size) < file_information.layout.size.bytes() {
+            this.set_last_error_from_io_error(ErrorKind::InvalidInput.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+
+        let Some(metadata) = this.file_handle_metadata(fd, "GetFileInformationByHandleEx")? else {
+            return Ok(Scalar::from_i32(0));
+        };
+
+        if class == basic_info {
+            this.write_int_fields_named(
+                &[
+                    ("CreationTime", metadata.created.into()),
+                    ("LastAccessTime", metadata.accessed.into()),
+                    ("LastWriteTime", metadata.modified.into()),
+                    // We do not track metadata changes separately.
+                    ("ChangeTime", metadata.modified.into()),
+                    ("FileAttributes", metadata.attributes.into()),
+                ],
+                &file_information,
+            )?;
+        } else if class == standard_info {
+            this.write_int_fields_named(
+                &[
+                    ("AllocationSize", metadata.size.into()),
+                    ("EndOfFile", metadata.size.into()),
+                    ("NumberOfLinks", 1),
+                    ("DeletePending", 0),
+                    ("Directory", metadata.is_dir.into()),
+                ],
+                &file_information,
+            )?;
+        } else {
+            this.write_int_fields_named(
+                &[("FileAttributes", metadata.attributes.into()), ("ReparseTag", 0)],
+                &file_information,
+            )?;
+        }
+
+        Ok(Scalar::from_i32(1))
+    }
+
+    fn SetFileInformationByHandle(
+        &mut self,
+        file_op: &OpTy<'tcx>,
+        file_information_class_op: &OpTy<'tcx>,
+        file_information_op: &OpTy<'tcx>,
+        buffer_size_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.file_handle_fd(file_op, "SetFileInformationByHandle")?;
+        let class = this.read_scalar(file_information_class_op)?.to_i32()?;
+        let buffer_size = this.read_scalar(buffer_size_op)?.to_u32()?;
+
+        if class != this.eval_windows("c", "FileEndOfFileInfo").to_i32()? {
+            throw_unsup_format!(
+                "`SetFileInformationByHandle`: unsupported information class {class}"
+            );
+        }
+        let file_information = this.deref_pointer_as(
+            file_information_op,
+            this.windows_ty_layout("FILE_END_OF_FILE_INFO"),
+        )?;
+        if u64::from(buffer_size) < file_information.layout.size.bytes() {
+            this.set_last_error_from_io_error(ErrorKind::InvalidInput.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+        let end_of_file = this
+            .read_scalar(&this.project_field_named(&file_information, "EndOfFile")?)?
+            .to_i64()?;
+        let Ok(end_of_file) = u64::try_from(end_of_file) else {
+            this.set_last_error_from_io_error(ErrorKind::InvalidInput.into())?;
+            return Ok(Scalar::from_i32(0));
+        };
+
+        let file_descriptor = this.machine.fds.get(fd).unwrap();
+        let Some(file_handle) = file_descriptor.downcast_ref::<FileHandle>() else {
+            throw_unsup_format!("`SetFileInformationByHandle` is only supported on file handles");
+        };
+        let result = if file_handle.writable {
+            file_handle.file.set_len(end_of_file)
+        } else {
+            Err(ErrorKind::PermissionDenied.into())
+        };
+        drop(file_descriptor);
+
+        this.io_result_to_bool(result)
+    }
+
+    fn SetFilePointerEx(
+        &mut self,
+        file_op: &OpTy<'tcx>,
+        distance_to_move_op: &OpTy<'tcx>,
+        new_file_pointer_op: &OpTy<'tcx>,
+        move_method_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        // Isolation check is done via `FileDescriptor` trait.
+
+        let fd = this.file_handle_fd(file_op, "SetFilePointerEx")?;
+        let distance_to_move = this.read_scalar(distance_to_move_op)?.to_i64()?;
+        let new_file_pointer = this.read_pointer(new_file_pointer_op)?;
+        let move_method = this.read_scalar(move_method_op)?.to_u32()?;
+
+        let seek_from = if move_method == this.eval_windows_u32("c", "FILE_BEGIN") {
+            let Ok(offset) = u64::try_from(distance_to_move) else {
+                let negative_seek = this.eval_windows("c", "ERROR_NEGATIVE_SEEK");
+                this.set_last_error(negative_seek)?;
+                return Ok(Scalar::from_i32(0));
+            };
+            SeekFrom::Start(offset)
+        } else if move_method == this.eval_windows_u32("c", "FILE_CURRENT") {
+            SeekFrom::Current(distance_to_move)
+        } else if move_method == this.eval_windows_u32("c", "FILE_END") {
+            SeekFrom::End(distance_to_move)
+        } else {
+            this.set_last_error_from_io_error(ErrorKind::InvalidInput.into())?;
+            return Ok(Scalar::from_i32(0));
+        };
+
+        let communicate = this.machine.communicate();
+        let mut file_descriptor = this.machine.fds.get_mut(fd).unwrap();
+        let result = file_descriptor.seek(communicate, seek_from)?;
+        drop(file_descriptor);
+
+        match result {
+            Ok(offset) => {
+                if !this.ptr_is_null(new_file_pointer)? {
+                    let new_file_pointer =
+                        this.ptr_to_mplace(new_file_pointer, this.machine.layouts.i64);
+                    this.write_scalar(
+                        Scalar::from_i64(i64::try_from(offset).unwrap()),
+                        &new_file_pointer,
+                    )?;
+                }
+                Ok(Scalar::from_i32(1))
+            }
+            Err(e) => {
+                this.set_last_error_from_io_error(e)?;
+                Ok(Scalar::from_i32(0))
+            }
+        }
+    }
+
+    fn FlushFileBuffers(&mut self, file_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.file_handle_fd(file_op, "FlushFileBuffers")?;
+
+        let file_descriptor = this.machine.fds.get(fd).unwrap();
+        let Some(file_handle) = file_descriptor.downcast_ref::<FileHandle>() else {
+            throw_unsup_format!("`FlushFileBuffers` is only supported on file handles");
+        };
+        // Windows requires the handle to have write access.
+        let result = if file_handle.writable {
+            file_handle.file.sync_all()
+        } else {
+            Err(ErrorKind::PermissionDenied.into())
+        };
+        drop(file_descriptor);
+
+        this.io_result_to_bool(result)
+    }
+
+    /// Closes a file handle or a directory search handle.
+    fn close_file_handle(&mut self, fd: i32, function_name: &str) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let Some(file_descriptor) = this.machine.fds.remove(fd) else {
+            this.invalid_handle(function_name)?
+        };
+        let result = file_descriptor.close(this.machine.communicate())?;
+
+        this.io_result_to_bool(result)
+    }
+
+    fn FindFirstFileW(
+        &mut self,
+        file_name_op: &OpTy<'tcx>,
+        find_file_data_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let file_name = this.read_path_from_wide_str(this.read_pointer(file_name_op)?)?;
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+            this.reject_in_isolation("`FindFirstFileW`", reject_with)?;
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(this.invalid_handle_value());
+        }
+
+        let Some(pattern) = file_name.file_name() else {
+            throw_unsup_format!(
+                "`FindFirstFileW`: searching for `{}` is not supported",
+                file_name.display()
+            );
+        };
+
+        if pattern == OsStr::new("*") {
+            // List all entries of the directory. We do not report the `.` and `..` entries,
+            // callers have to skip them anyway.
+            let directory = file_name.parent().unwrap_or(Path::new(""));
+            let mut read_dir = match read_dir(directory) {
+                Ok(read_dir) => read_dir,
+                Err(e) => {
+                    this.set_last_error_from_io_error(e)?;
+                    return Ok(this.invalid_handle_value());
+                }
+            };
+            match read_dir.next() {
+                Some(Ok(entry)) => {
+                    let metadata = match entry.metadata() {
+                        Ok(metadata) => metadata,
+                        Err(e) => {
+                            this.set_last_error_from_io_error(e)?;
+                            return Ok(this.invalid_handle_value());
+                        }
+                    };
+                    this.write_find_data(&entry.file_name(), &metadata, find_file_data_op)?;
+                    Ok(this.insert_directory_search(Some(read_dir)))
+                }
+                Some(Err(e)) => {
+                    this.set_last_error_from_io_error(e)?;
+                    Ok(this.invalid_handle_value())
+                }
+                None => {
+                    // No matching files were found.
+                    let file_not_found = this.eval_windows("c", "ERROR_FILE_NOT_FOUND");
+                    this.set_last_error(file_not_found)?;
+                    Ok(this.invalid_handle_value())
+                }
+            }
+        } else if pattern.as_encoded_bytes().iter().any(|&c| c == b'*' || c == b'?') {
+            throw_unsup_format!(
+                "`FindFirstFileW`: wildcards other than a sole `*` are not supported"
+            );
+        } else {
+            // Without wildcards, this finds at most the file itself.
+            match std::fs::metadata(&file_name) {
+                Ok(metadata) => {
+                    this.write_find_data(pattern, &metadata, find_file_data_op)?;
+                    Ok(this.insert_directory_search(None))
+                }
+                Err(e) => {
+                    this.set_last_error_from_io_error(e)?;
+                    Ok(this.invalid_handle_value())
+                }
+            }
+        }
+    }
+
+    fn FindNextFileW(
+        &mut self,
+        find_file_op: &OpTy<'tcx>,
+        find_file_data_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.file_handle_fd(find_file_op, "FindNextFileW")?;
+
+        let mut file_descriptor = this.machine.fds.get_mut(fd).unwrap();
+        let next = file_descriptor
+            .downcast_mut::<DirectorySearch>()
+            .map(|search| search.read_dir.as_mut().and_then(|read_dir| read_dir.next()));
+        drop(file_descriptor);
+        let Some(next) = next else { this.invalid_handle("FindNextFileW")? };
+
+        let result = next.map(|entry| -> io::Result<_> {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            Ok((entry.file_name(), metadata))
+        });
+        match result {
+            Some(Ok((file_name, metadata))) => {
+                this.write_find_data(&file_name, &metadata, find_file_data_op)?;
+                Ok(Scalar::from_i32(1))
+            }
+            Some(Err(e)) => {
+                this.set_last_error_from_io_error(e)?;
+                Ok(Scalar::from_i32(0))
+            }
+            None => {
+                let no_more_files = this.eval_windows("c", "ERROR_NO_MORE_FILES");
+                this.set_last_error(no_more_files)?;
+                Ok(Scalar::from_i32(0))
+            }
+        }
+    }
+
+    fn FindClose(&mut self, find_file_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.file_handle_fd(find_file_op, "FindClose")?;
+        if this.machine.fds.get(fd).unwrap().downcast_ref::<DirectorySearch>().is_none() {
+            this.invalid_handle("FindClose")?;
+        }
+
+        this.close_file_handle(fd, "FindClose")
+    }
+
+    fn DeleteFileW(&mut self, file_name_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let file_name = this.read_path_from_wide_str(this.read_pointer(file_name_op)?)?;
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+            this.reject_in_isolation("`DeleteFileW`", reject_with)?;
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+
+        // Windows reports an attempt to delete a directory as a file as access denied.
+        let result = remove_file(file_name).map_err(|e| {
+            if e.kind() == ErrorKind::IsADirectory { ErrorKind::PermissionDenied.into() } else { e }
+        });
+        this.io_result_to_bool(result)
+    }
+
+    fn CreateDirectoryW(
+        &mut self,
+        path_name_op: &OpTy<'tcx>,
+        security_attributes_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let path_name = this.read_path_from_wide_str(this.read_pointer(path_name_op)?)?;
+        let security_attributes = this.read_pointer(security_attributes_op)?;
+
+        if !this.ptr_is_null(security_attributes)? {
+            throw_unsup_format!(
+                "`CreateDirectoryW`: non-null `lpSecurityAttributes` is not supported"
+            );
+        }
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+            this.reject_in_isolation("`CreateDirectoryW`", reject_with)?;
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+
+        let result = create_dir(path_name);
+        this.io_result_to_bool(result)
+    }
+
+    fn RemoveDirectoryW(&mut self, path_name_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let path_name = this.read_path_from_wide_str(this.read_pointer(path_name_op)?)?;
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+            this.reject_in_isolation("`RemoveDirectoryW`", reject_with)?;
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+
+        let result = remove_dir(path_name);
+        this.io_result_to_bool(result)
+    }
+
+    fn MoveFileExW(
+        &mut self,
+        existing_file_name_op: &OpTy<'tcx>,
+        new_file_name_op: &OpTy<'tcx>,
+        flags_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let existing_file_name =
+            this.read_path_from_wide_str(this.read_pointer(existing_file_name_op)?)?;
+        let new_file_name = this.read_path_from_wide_str(this.read_pointer(new_file_name_op)?)?;
+        let flags = this.read_scalar(flags_op)?.to_u32()?;
+
+        let replace_existing = this.eval_windows_u32("c", "MOVEFILE_REPLACE_EXISTING");
+        if flags & !replace_existing != 0 {
+            throw_unsup_format!(
+                "`MoveFileExW`: unsupported flags {:#x}",
+                flags & !replace_existing
+            );
+        }
+
+        // Reject if isolation is enabled.
+        if let IsolatedOp::Reject(reject_with) = this.machine.isolated_op {
+            this.reject_in_isolation("`MoveFileExW`", reject_with)?;
+            this.set_last_error_from_io_error(ErrorKind::PermissionDenied.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+
+        // The host `rename` always replaces the destination.
+        if flags & replace_existing == 0 && new_file_name.exists() {
+            this.set_last_error_from_io_error(ErrorKind::AlreadyExists.into())?;
+            return Ok(Scalar::from_i32(0));
+        }
+
+        let result = rename(existing_file_name, new_file_name);
+        this.io_result_to_bool(result)
+    }
+}
@@ -1,6 +1,8 @@
 use rustc_target::abi::HasDataLayout;
 use std::mem::variant_count;
 
+use crate::concurrency::sync::SyncId;
+use crate::shims::windows::fs::EvalContextExt as _;
 use crate::*;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -14,6 +16,10 @@ pub enum Handle {
     Null,
     Pseudo(PseudoHandle),
     Thread(ThreadId),
+    /// A file or directory search, indexing into the machine's file descriptor table.
+    File(i32),
+    /// A keyed event created by `NtCreateKeyedEvent`.
+    KeyedEvent(KeyedEventId),
 }
 
 impl PseudoHandle {
@@ -37,12 +43,16 @@ impl Handle {
     const NULL_DISCRIMINANT: u32 = 0;
     const PSEUDO_DISCRIMINANT: u32 = 1;
     const THREAD_DISCRIMINANT: u32 = 2;
+    const FILE_DISCRIMINANT: u32 = 3;
+    const KEYED_EVENT_DISCRIMINANT: u32 = 4;
 
     fn discriminant(self) -> u32 {
         match self {
             Self::Null => Self::NULL_DISCRIMINANT,
             Self::Pseudo(_) => Self::PSEUDO_DISCRIMINANT,
             Self::Thread(_) => Self::THREAD_DISCRIMINANT,
+            Self::File(_) => Self::FILE_DISCRIMINANT,
+            Self::KeyedEvent(_) => Self::KEYED_EVENT_DISCRIMINANT,
         }
     }
 
@@ -51,6 +61,9 @@ fn data(self) -> u32 {
             Self::Null => 0,
             Self::Pseudo(pseudo_handle) => pseudo_handle.value(),
             Self::Thread(thread) => thread.to_u32(),
+            // File descriptors are never negative.
+            Self::File(fd) => u32::try_from(fd).unwrap(),
+            Self::KeyedEvent(keyed_event) => keyed_event.to_u32(),
         }
     }
 
@@ -96,6 +109,9 @@ fn new(discriminant: u32, data: u32) -> Option<Self> {
             Self::NULL_DISCRIMINANT if data == 0 => Some(Self::Null),
             Self::PSEUDO_DISCRIMINANT => Some(Self::Pseudo(PseudoHandle::from_value(data)?)),
             Self::THREAD_DISCRIMINANT => Some(Self::Thread(data.into())),
+            Self::FILE_DISCRIMINANT => Some(Self::File(i32::try_from(data).ok()?)),
+            Self::KEYED_EVENT_DISCRIMINANT if data != 0 =>
+                Some(Self::KeyedEvent(KeyedEventId::from_u32(data))),
             _ => None,
         }
     }
@@ -155,7 +171,7 @@ fn invalid_handle(&mut self, function_name: &str) -> InterpResult<'tcx, !> {
         )))
     }
 
-    fn CloseHandle(&mut self, handle_op: &OpTy<'tcx>) -> InterpResult<'tcx> {
+    fn CloseHandle(&mut self, handle_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
         let handle = this.read_scalar(handle_op)?;
@@ -163,9 +179,12 @@ fn CloseHandle(&mut self, handle_op: &OpTy<'tcx>) -> InterpResult<'tcx> {
         match Handle::from_scalar(handle, this)? {
             Some(Handle::Thread(thread)) =>
                 this.detach_thread(thread, /*allow_terminated_joined*/ true)?,
+            Some(Handle::File(fd)) => return this.close_file_handle(fd, "CloseHandle"),
+            // Keyed events are never destroyed, as other handles to them might still be in use.
+            Some(Handle::KeyedEvent(keyed_event)) if this.keyed_event_exists(keyed_event) => {}
             _ => this.invalid_handle("CloseHandle")?,
         }
 
-        Ok(())
+        Ok(Scalar::from_i32(1))
     }
 }
@@ -1,6 +1,7 @@
 pub mod foreign_items;
 
 mod env;
+mod fs;
 mod handle;
 mod sync;
 mod thread;
@@ -8,6 +9,7 @@
 pub use env::WindowsEnvVars;
 // All the Windows-specific extension traits
 pub use env::EvalContextExt as _;
+pub use fs::EvalContextExt as _;
 pub use handle::EvalContextExt as _;
 pub use sync::EvalContextExt as _;
 pub use thread::EvalContextExt as _;
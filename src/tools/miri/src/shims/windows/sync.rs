@@ -4,6 +4,7 @@
 
 use crate::concurrency::init_once::InitOnceStatus;
 use crate::*;
+use shims::windows::handle::{EvalContextExt as _, Handle};
 
 impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
 trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
@@ -39,6 +40,58 @@ fn init_once_try_begin(
             InitOnceStatus::Begun => false,
         })
     }
+
+    /// Shared implementation of `NtWaitForKeyedEvent` and `NtReleaseKeyedEvent`.
+    fn keyed_event_wait_or_release(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        key_op: &OpTy<'tcx>,
+        alertable_op: &OpTy<'tcx>,
+        timeout_op: &OpTy<'tcx>,
+        releasing: bool,
+        function_name: &str,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let handle = this.read_scalar(handle_op)?;
+        let key = this.read_pointer(key_op)?.addr().bytes();
+        let alertable = this.read_scalar(alertable_op)?.to_u8()?;
+        let timeout = this.read_pointer(timeout_op)?;
+
+        let id = match Handle::from_scalar(handle, this)? {
+            Some(Handle::KeyedEvent(id)) if this.keyed_event_exists(id) => id,
+            _ => this.invalid_handle(function_name)?,
+        };
+        if alertable != 0 {
+            throw_unsup_format!("alertable waits in `{function_name}` are not supported");
+        }
+
+        // The timeout is given in 100ns intervals. Negative values are relative to the current
+        // time, positive values are absolute system times.
+        let timeout = if this.ptr_is_null(timeout)? {
+            None
+        } else {
+            let timeout_place = this.ptr_to_mplace(timeout, this.machine.layouts.i64);
+            let timeout = this.read_scalar(&timeout_place)?.to_i64()?;
+            if timeout > 0 {
+                throw_unsup_format!("absolute timeouts in `{function_name}` are not supported");
+            }
+            let duration = Duration::from_nanos(timeout.unsigned_abs().saturating_mul(100));
+            Some((TimeoutClock::Monotonic, TimeoutAnchor::Relative, duration))
+        };
+
+        this.keyed_event_rendezvous(
+            id,
+            key,
+            releasing,
+            timeout,
+            this.eval_windows("c", "STATUS_SUCCESS"),
+            // STATUS_TIMEOUT, which is not defined by std.
+            Scalar::from_u32(0x102),
+            dest.clone(),
+        )
+    }
 }
 
 impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
@@ -216,4 +269,72 @@ fn WakeByAddressAll(&mut self, ptr_op: &OpTy<'tcx>) -> InterpResult<'tcx> {
 
         Ok(())
     }
+
+    fn NtCreateKeyedEvent(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        access_op: &OpTy<'tcx>,
+        object_attributes_op: &OpTy<'tcx>,
+        flags_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let handle_place = this.deref_pointer(handle_op)?;
+        // The access rights are not checked by the other keyed event functions, so we ignore them.
+        let _access = this.read_scalar(access_op)?.to_u32()?;
+        let object_attributes = this.read_pointer(object_attributes_op)?;
+        let flags = this.read_scalar(flags_op)?.to_u32()?;
+
+        if !this.ptr_is_null(object_attributes)? {
+            throw_unsup_format!("non-null `ObjectAttributes` in `NtCreateKeyedEvent`");
+        }
+        if flags != 0 {
+            throw_unsup_format!("unsupported `Flags` {flags} in `NtCreateKeyedEvent`");
+        }
+
+        let id = this.keyed_event_create();
+        this.write_scalar(Handle::KeyedEvent(id).to_scalar(this), &handle_place)?;
+
+        Ok(this.eval_windows("c", "STATUS_SUCCESS"))
+    }
+
+    fn NtWaitForKeyedEvent(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        key_op: &OpTy<'tcx>,
+        alertable_op: &OpTy<'tcx>,
+        timeout_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.keyed_event_wait_or_release(
+            handle_op,
+            key_op,
+            alertable_op,
+            timeout_op,
+            /* releasing */ false,
+            "NtWaitForKeyedEvent",
+            dest,
+        )
+    }
+
+    fn NtReleaseKeyedEvent(
+        &mut self,
+        handle_op: &OpTy<'tcx>,
+        key_op: &OpTy<'tcx>,
+        alertable_op: &OpTy<'tcx>,
+        timeout_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.keyed_event_wait_or_release(
+            handle_op,
+            key_op,
+            alertable_op,
+            timeout_op,
+            /* releasing */ true,
+            "NtReleaseKeyedEvent",
+            dest,
+        )
+    }
 }
@@ -2,7 +2,7 @@
 fn main() {
     // eventfd write will block when EFD_NONBLOCK flag is clear
     // and the addition caused counter to exceed u64::MAX - 1.
-    // This will pass when blocking is implemented.
+    // Nobody else can read the counter, so this blocks forever.
     let flags = libc::EFD_CLOEXEC;
     let fd = unsafe { libc::eventfd(0, flags) };
     // Write u64 - 1.
@@ -16,6 +16,6 @@ fn main() {
     sized_8_data = 1_u64.to_ne_bytes();
     // Write 1 to the counter.
     let _res: i64 = unsafe {
-        libc::write(fd, sized_8_data.as_ptr() as *const libc::c_void, 8).try_into().unwrap() //~ERROR: blocking is unsupported
+        libc::write(fd, sized_8_data.as_ptr() as *const libc::c_void, 8).try_into().unwrap() //~ERROR: deadlock
     };
 }
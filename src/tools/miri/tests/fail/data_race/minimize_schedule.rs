@@ -0,0 +1,34 @@
+// Same as `replay_schedule.rs`, but the trace has preemptions that are not needed for the race.
+//@compile-flags: -Zmiri-preemption-rate=0 -Zmiri-disable-stacked-borrows
+//@compile-flags: -Zmiri-disable-weak-memory-emulation -Zmiri-address-reuse-cross-thread-rate=0
+//@compile-flags: -Zmiri-minimize-schedule=tests/fail/data_race/minimize_schedule.trace
+// Do not overwrite the input trace.
+//@compile-flags: -Zmiri-record-schedule=/dev/null
+//@ignore-host-windows
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{spawn, yield_now};
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static FLAG: AtomicBool = AtomicBool::new(false);
+static mut DATA: u32 = 0;
+
+fn main() {
+    let j = spawn(|| {
+        STARTED.store(true, Ordering::Relaxed);
+        unsafe { DATA = 1 }
+        // Give the scheduler plenty of chances to preempt this thread.
+        for i in 0..50_000 {
+            std::hint::black_box(i);
+        }
+        FLAG.store(true, Ordering::Release);
+    });
+
+    while !STARTED.load(Ordering::Relaxed) {
+        yield_now();
+    }
+    if !FLAG.load(Ordering::Acquire) {
+        let _val = unsafe { DATA }; //~ERROR: Data race detected between (1) non-atomic write on thread `unnamed-1` and (2) non-atomic read on thread `main`
+    }
+    j.join().unwrap();
+}
@@ -7,6 +7,7 @@
 fn main() {
     test_read_write();
     test_race();
+    test_blocking_read();
 }
 
 fn read_bytes<const N: usize>(fd: i32, buf: &mut [u8; N]) -> i32 {
@@ -106,3 +107,20 @@ fn test_race() {
     thread::yield_now();
     thread1.join().unwrap();
 }
+
+fn test_blocking_read() {
+    let flags = libc::EFD_CLOEXEC;
+    let fd = unsafe { libc::eventfd(0, flags) };
+    let thread1 = thread::spawn(move || {
+        let mut buf: [u8; 8] = [0; 8];
+        // This blocks until the main thread writes to the counter.
+        let res = read_bytes(fd, &mut buf);
+        assert_eq!(res, 8);
+        assert_eq!(u64::from_ne_bytes(buf), 1);
+    });
+    // Let the other thread block on the read.
+    thread::yield_now();
+    let res = write_bytes(fd, 1_u64.to_ne_bytes());
+    assert_eq!(res, 8);
+    thread1.join().unwrap();
+}
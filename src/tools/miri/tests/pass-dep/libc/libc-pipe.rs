@@ -0,0 +1,181 @@
+//@ignore-target-windows: No libc pipe on Windows
+// test_blocking_read and test_poll_wakeup depend on a deterministic schedule.
+//@compile-flags: -Zmiri-preemption-rate=0
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn main() {
+    test_pipe();
+    #[cfg(not(target_vendor = "apple"))]
+    test_pipe2_nonblocking();
+    test_blocking_read();
+    test_poll();
+    test_poll_wakeup();
+    #[cfg(target_os = "linux")]
+    test_ppoll();
+    test_select();
+}
+
+fn new_pipe() -> [i32; 2] {
+    let mut fds = [-1, -1];
+    let res = unsafe { libc::pipe(fds.as_mut_ptr()) };
+    assert_eq!(res, 0);
+    fds
+}
+
+fn read_bytes<const N: usize>(fd: i32, buf: &mut [u8; N]) -> isize {
+    unsafe { libc::read(fd, buf.as_mut_ptr().cast(), N) }
+}
+
+fn write_bytes(fd: i32, data: &[u8]) -> isize {
+    unsafe { libc::write(fd, data.as_ptr().cast(), data.len()) }
+}
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap()
+}
+
+fn test_pipe() {
+    let [read_fd, write_fd] = new_pipe();
+    assert_eq!(write_bytes(write_fd, b"abcde"), 5);
+    let mut buf = [0; 3];
+    assert_eq!(read_bytes(read_fd, &mut buf), 3);
+    assert_eq!(&buf, b"abc");
+    assert_eq!(read_bytes(read_fd, &mut buf), 2);
+    assert_eq!(&buf[..2], b"de");
+
+    // Reading after the write end is closed indicates end-of-file.
+    assert_eq!(unsafe { libc::close(write_fd) }, 0);
+    assert_eq!(read_bytes(read_fd, &mut buf), 0);
+    assert_eq!(unsafe { libc::close(read_fd) }, 0);
+
+    // Writing after the read end is closed fails.
+    let [read_fd, write_fd] = new_pipe();
+    assert_eq!(unsafe { libc::close(read_fd) }, 0);
+    assert_eq!(write_bytes(write_fd, b"abc"), -1);
+    assert_eq!(errno(), libc::EPIPE);
+    assert_eq!(unsafe { libc::close(write_fd) }, 0);
+}
+
+#[cfg(not(target_vendor = "apple"))]
+fn test_pipe2_nonblocking() {
+    let mut fds = [-1, -1];
+    let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC) };
+    assert_eq!(res, 0);
+    let mut buf = [0; 3];
+    assert_eq!(read_bytes(fds[0], &mut buf), -1);
+    assert_eq!(errno(), libc::EAGAIN);
+}
+
+fn test_blocking_read() {
+    let [read_fd, write_fd] = new_pipe();
+    let reader = thread::spawn(move || {
+        let mut buf = [0; 3];
+        // This blocks until the main thread writes.
+        assert_eq!(read_bytes(read_fd, &mut buf), 3);
+        assert_eq!(&buf, b"abc");
+        // This blocks until the main thread closes the write end.
+        assert_eq!(read_bytes(read_fd, &mut buf), 0);
+    });
+    // Let the other thread block on the read.
+    thread::yield_now();
+    assert_eq!(write_bytes(write_fd, b"abc"), 3);
+    thread::yield_now();
+    assert_eq!(unsafe { libc::close(write_fd) }, 0);
+    reader.join().unwrap();
+}
+
+fn test_poll() {
+    let [read_fd, write_fd] = new_pipe();
+    let mut fds = [
+        libc::pollfd { fd: read_fd, events: libc::POLLIN, revents: 0 },
+        libc::pollfd { fd: write_fd, events: libc::POLLOUT, revents: 0 },
+    ];
+    // Only the write end is ready.
+    assert_eq!(unsafe { libc::poll(fds.as_mut_ptr(), 2, 0) }, 1);
+    assert_eq!(fds[0].revents, 0);
+    assert_eq!(fds[1].revents, libc::POLLOUT);
+
+    // Nothing becomes ready, so `poll` gives up after the timeout.
+    let start = Instant::now();
+    assert_eq!(unsafe { libc::poll(fds.as_mut_ptr(), 1, 10) }, 0);
+    assert!(start.elapsed() >= Duration::from_millis(10));
+
+    assert_eq!(write_bytes(write_fd, b"abc"), 3);
+    assert_eq!(unsafe { libc::poll(fds.as_mut_ptr(), 1, -1) }, 1);
+    assert_eq!(fds[0].revents, libc::POLLIN);
+
+    // Closing the write end hangs up the read end.
+    assert_eq!(unsafe { libc::close(write_fd) }, 0);
+    assert_eq!(unsafe { libc::poll(fds.as_mut_ptr(), 1, -1) }, 1);
+    assert_eq!(fds[0].revents, libc::POLLIN | libc::POLLHUP);
+}
+
+fn test_poll_wakeup() {
+    let [read_fd, write_fd] = new_pipe();
+    let poller = thread::spawn(move || {
+        let mut fds = [libc::pollfd { fd: read_fd, events: libc::POLLIN, revents: 0 }];
+        // This blocks until the main thread writes.
+        assert_eq!(unsafe { libc::poll(fds.as_mut_ptr(), 1, -1) }, 1);
+        assert_eq!(fds[0].revents, libc::POLLIN);
+    });
+    // Let the other thread block on `poll`.
+    thread::yield_now();
+    assert_eq!(write_bytes(write_fd, b"abc"), 3);
+    poller.join().unwrap();
+}
+
+#[cfg(target_os = "linux")]
+fn test_ppoll() {
+    let [read_fd, write_fd] = new_pipe();
+    let mut fds = [libc::pollfd { fd: read_fd, events: libc::POLLIN, revents: 0 }];
+    let timeout = libc::timespec { tv_sec: 0, tv_nsec: 1_000_000 };
+    assert_eq!(unsafe { libc::ppoll(fds.as_mut_ptr(), 1, &timeout, ptr::null()) }, 0);
+
+    assert_eq!(write_bytes(write_fd, b"abc"), 3);
+    assert_eq!(unsafe { libc::ppoll(fds.as_mut_ptr(), 1, ptr::null(), ptr::null()) }, 1);
+    assert_eq!(fds[0].revents, libc::POLLIN);
+
+    let invalid = libc::timespec { tv_sec: -1, tv_nsec: 0 };
+    assert_eq!(unsafe { libc::ppoll(fds.as_mut_ptr(), 1, &invalid, ptr::null()) }, -1);
+    assert_eq!(errno(), libc::EINVAL);
+}
+
+fn test_select() {
+    let [read_fd, write_fd] = new_pipe();
+    let nfds = read_fd.max(write_fd) + 1;
+    let mut readfds = unsafe {
+        let mut readfds = MaybeUninit::<libc::fd_set>::uninit();
+        libc::FD_ZERO(readfds.as_mut_ptr());
+        readfds.assume_init()
+    };
+    let mut timeout = libc::timeval { tv_sec: 0, tv_usec: 0 };
+
+    // Nothing is ready yet, so `select` clears the set.
+    unsafe { libc::FD_SET(read_fd, &mut readfds) };
+    let res = unsafe {
+        libc::select(nfds, &mut readfds, ptr::null_mut(), ptr::null_mut(), &mut timeout)
+    };
+    assert_eq!(res, 0);
+    assert!(!unsafe { libc::FD_ISSET(read_fd, &readfds) });
+
+    assert_eq!(write_bytes(write_fd, b"abc"), 3);
+    unsafe { libc::FD_SET(read_fd, &mut readfds) };
+    let res = unsafe {
+        libc::select(nfds, &mut readfds, ptr::null_mut(), ptr::null_mut(), ptr::null_mut())
+    };
+    assert_eq!(res, 1);
+    assert!(unsafe { libc::FD_ISSET(read_fd, &readfds) });
+
+    // Closed file descriptors are rejected.
+    assert_eq!(unsafe { libc::close(write_fd) }, 0);
+    let mut writefds = readfds;
+    unsafe { libc::FD_SET(write_fd, &mut writefds) };
+    let res = unsafe {
+        libc::select(nfds, ptr::null_mut(), &mut writefds, ptr::null_mut(), &mut timeout)
+    };
+    assert_eq!(res, -1);
+    assert_eq!(errno(), libc::EBADF);
+}
@@ -0,0 +1,32 @@
+// Same as `fail/data_race/replay_schedule.rs`, but the trace only preempts the main thread, which
+// does not lead to a race.
+//@compile-flags: -Zmiri-preemption-rate=0 -Zmiri-disable-stacked-borrows
+//@compile-flags: -Zmiri-disable-weak-memory-emulation -Zmiri-address-reuse-cross-thread-rate=0
+//@compile-flags: -Zmiri-replay-schedule=tests/pass/concurrency/replay_schedule.trace
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{spawn, yield_now};
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static FLAG: AtomicBool = AtomicBool::new(false);
+static mut DATA: u32 = 0;
+
+fn main() {
+    let j = spawn(|| {
+        STARTED.store(true, Ordering::Relaxed);
+        unsafe { DATA = 1 }
+        // Give the scheduler plenty of chances to preempt this thread.
+        for i in 0..50_000 {
+            std::hint::black_box(i);
+        }
+        FLAG.store(true, Ordering::Release);
+    });
+
+    while !STARTED.load(Ordering::Relaxed) {
+        yield_now();
+    }
+    if !FLAG.load(Ordering::Acquire) {
+        let _val = unsafe { DATA };
+    }
+    j.join().unwrap();
+}
@@ -0,0 +1,159 @@
+//@only-target-windows: Uses win32 api functions
+// We are making scheduler assumptions here.
+//@compile-flags: -Zmiri-preemption-rate=0
+
+use std::ffi::c_void;
+use std::ptr;
+use std::thread;
+
+type HANDLE = *mut c_void;
+type NTSTATUS = i32;
+
+const STATUS_SUCCESS: NTSTATUS = 0;
+const STATUS_TIMEOUT: NTSTATUS = 0x102;
+
+// These are not documented and hence not in windows-sys.
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtCreateKeyedEvent(
+        keyed_event_handle: *mut HANDLE,
+        desired_access: u32,
+        object_attributes: *mut c_void,
+        flags: u32,
+    ) -> NTSTATUS;
+    fn NtReleaseKeyedEvent(
+        event_handle: HANDLE,
+        key: *mut c_void,
+        alertable: u8,
+        timeout: *mut i64,
+    ) -> NTSTATUS;
+    fn NtWaitForKeyedEvent(
+        event_handle: HANDLE,
+        key: *mut c_void,
+        alertable: u8,
+        timeout: *mut i64,
+    ) -> NTSTATUS;
+    fn CloseHandle(handle: HANDLE) -> i32;
+}
+
+#[derive(Copy, Clone)]
+struct SendPtr<T>(*mut T);
+
+unsafe impl<T> Send for SendPtr<T> {}
+
+fn create_keyed_event() -> HANDLE {
+    let mut handle = ptr::null_mut();
+    assert_eq!(unsafe { NtCreateKeyedEvent(&mut handle, 0, ptr::null_mut(), 0) }, STATUS_SUCCESS);
+    handle
+}
+
+/// Waiting blocks until another thread releases the key, and the release happens-before the
+/// wait returns.
+fn wait_then_release() {
+    let handle = SendPtr(create_keyed_event());
+    let mut data = 0;
+    let data_ptr = SendPtr(&mut data as *mut i32);
+
+    let waiter = thread::spawn(move || unsafe {
+        let handle = handle;
+        let data_ptr = data_ptr;
+        assert_eq!(
+            NtWaitForKeyedEvent(handle.0, data_ptr.0.cast(), 0, ptr::null_mut()),
+            STATUS_SUCCESS
+        );
+        assert_eq!(*data_ptr.0, 42);
+    });
+
+    // Make sure the waiter is blocked.
+    thread::yield_now();
+
+    unsafe {
+        *data_ptr.0 = 42;
+        assert_eq!(
+            NtReleaseKeyedEvent(handle.0, data_ptr.0.cast(), 0, ptr::null_mut()),
+            STATUS_SUCCESS
+        );
+    }
+
+    waiter.join().unwrap();
+    assert_eq!(unsafe { CloseHandle(handle.0) }, 1);
+}
+
+/// Releasing blocks until another thread waits for the key, and the release happens-before the
+/// wait returns.
+fn release_then_wait() {
+    let handle = SendPtr(create_keyed_event());
+    let mut data = 0;
+    let data_ptr = SendPtr(&mut data as *mut i32);
+
+    let releaser = thread::spawn(move || unsafe {
+        let handle = handle;
+        let data_ptr = data_ptr;
+        *data_ptr.0 = 42;
+        assert_eq!(
+            NtReleaseKeyedEvent(handle.0, data_ptr.0.cast(), 0, ptr::null_mut()),
+            STATUS_SUCCESS
+        );
+    });
+
+    // Make sure the releaser is blocked.
+    thread::yield_now();
+
+    unsafe {
+        assert_eq!(
+            NtWaitForKeyedEvent(handle.0, data_ptr.0.cast(), 0, ptr::null_mut()),
+            STATUS_SUCCESS
+        );
+        assert_eq!(*data_ptr.0, 42);
+    }
+
+    releaser.join().unwrap();
+}
+
+/// Waiting for a key does not get released by a different key.
+fn different_keys() {
+    let handle = SendPtr(create_keyed_event());
+    let mut keys = [0u8; 2];
+    let key1 = SendPtr(&mut keys[0] as *mut u8);
+    let key2 = SendPtr(&mut keys[1] as *mut u8);
+
+    let waiter = thread::spawn(move || unsafe {
+        let (handle, key1) = (handle, key1);
+        assert_eq!(
+            NtWaitForKeyedEvent(handle.0, key1.0.cast(), 0, ptr::null_mut()),
+            STATUS_SUCCESS
+        );
+    });
+
+    thread::yield_now();
+
+    unsafe {
+        // Nobody waits for `key2`, so this times out.
+        let mut timeout = -1000;
+        assert_eq!(NtReleaseKeyedEvent(handle.0, key2.0.cast(), 0, &mut timeout), STATUS_TIMEOUT);
+        assert_eq!(
+            NtReleaseKeyedEvent(handle.0, key1.0.cast(), 0, ptr::null_mut()),
+            STATUS_SUCCESS
+        );
+    }
+
+    waiter.join().unwrap();
+}
+
+fn wait_timeout() {
+    let handle = create_keyed_event();
+    let mut key = 0u8;
+
+    // 10ms, in units of 100ns. Negative means relative.
+    let mut timeout = -100_000;
+    let status =
+        unsafe { NtWaitForKeyedEvent(handle, (&mut key as *mut u8).cast(), 0, &mut timeout) };
+    assert_eq!(status, STATUS_TIMEOUT);
+}
+
+fn main() {
+    wait_then_release();
+    release_then_wait();
+    different_keys();
+    wait_timeout();
+}
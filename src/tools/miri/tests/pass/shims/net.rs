@@ -0,0 +1,132 @@
+//@ignore-target-windows: only the Unix socket shims are implemented
+//@revisions: isolation host
+//@[host]compile-flags: -Zmiri-disable-isolation
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+
+fn main() {
+    test_tcp();
+    test_tcp_nonblocking();
+    test_tcp_refused();
+    test_udp();
+    test_udp_connected();
+}
+
+fn localhost() -> SocketAddr {
+    SocketAddr::from((Ipv4Addr::LOCALHOST, 0))
+}
+
+fn test_tcp() {
+    let listener = TcpListener::bind(localhost()).unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert!(addr.ip().is_loopback());
+    assert_ne!(addr.port(), 0);
+
+    let client = thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_nodelay(true).unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), addr);
+        stream.write_all(b"ping").unwrap();
+        let mut buf = [0; 4];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"pong");
+        stream.local_addr().unwrap()
+    });
+
+    // This blocks until the client connects.
+    let (mut stream, peer_addr) = listener.accept().unwrap();
+    assert_eq!(stream.local_addr().unwrap(), addr);
+    assert_eq!(stream.peer_addr().unwrap(), peer_addr);
+    let mut buf = [0; 4];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+    stream.write_all(b"pong").unwrap();
+
+    let client_addr = client.join().unwrap();
+    assert_eq!(client_addr, peer_addr);
+    // The client closed the connection.
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind(localhost()).unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    client.set_nonblocking(true).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(client.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    // Connecting does not wait for the connection to be accepted, so `accept` may not see it
+    // right away on the host.
+    let mut server = loop {
+        match listener.accept() {
+            Ok((server, _)) => break server,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::yield_now(),
+            Err(err) => panic!("{err}"),
+        }
+    };
+    server.write_all(b"data").unwrap();
+    drop(server);
+    let mut data = Vec::new();
+    loop {
+        match client.read_to_end(&mut data) {
+            Ok(_) => break,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::yield_now(),
+            Err(err) => panic!("{err}"),
+        }
+    }
+    assert_eq!(data, b"data");
+}
+
+fn test_tcp_refused() {
+    let listener = TcpListener::bind(localhost()).unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+}
+
+fn test_udp() {
+    let socket1 = UdpSocket::bind(localhost()).unwrap();
+    let socket2 = UdpSocket::bind(localhost()).unwrap();
+    let addr1 = socket1.local_addr().unwrap();
+    let addr2 = socket2.local_addr().unwrap();
+    assert_ne!(addr1, addr2);
+
+    assert_eq!(socket1.send_to(b"hello", addr2).unwrap(), 5);
+    let mut buf = [0; 16];
+    let (len, from) = socket2.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"hello");
+    assert_eq!(from, addr1);
+
+    socket2.set_nonblocking(true).unwrap();
+    assert_eq!(socket2.recv_from(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_udp_connected() {
+    let socket1 = UdpSocket::bind(localhost()).unwrap();
+    let socket2 = UdpSocket::bind(localhost()).unwrap();
+    let addr1 = socket1.local_addr().unwrap();
+    let addr2 = socket2.local_addr().unwrap();
+
+    socket1.connect(addr2).unwrap();
+    assert_eq!(socket1.peer_addr().unwrap(), addr2);
+    socket2.connect(addr1).unwrap();
+
+    let receiver = thread::spawn(move || {
+        let mut buf = [0; 16];
+        // This blocks until the datagram arrives.
+        let len = socket2.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"hello");
+        socket2.send(b"world").unwrap();
+    });
+
+    socket1.send(b"hello").unwrap();
+    let mut buf = [0; 16];
+    let len = socket1.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"world");
+    receiver.join().unwrap();
+}
@@ -0,0 +1,25 @@
+//@only-target-windows: tests the Windows file system shims
+//@compile-flags: -Zmiri-isolation-error=warn-nobacktrace
+
+use std::fs::{self, File};
+use std::io::ErrorKind;
+
+fn main() {
+    // test `CreateFileW`
+    assert_eq!(File::create("foo.txt").unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    // test `DeleteFileW`
+    assert_eq!(fs::remove_file("foo.txt").unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    // test `MoveFileExW`
+    assert_eq!(fs::rename("a.txt", "b.txt").unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    // test `CreateDirectoryW`
+    assert_eq!(fs::create_dir("foo/bar").unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    // test `RemoveDirectoryW`
+    assert_eq!(fs::remove_dir("foo/bar").unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    // test `FindFirstFileW`
+    assert_eq!(fs::read_dir("foo/bar").unwrap_err().kind(), ErrorKind::PermissionDenied);
+}
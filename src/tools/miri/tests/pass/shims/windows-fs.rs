@@ -0,0 +1,268 @@
+//@only-target-windows: tests the Windows file system shims
+//@compile-flags: -Zmiri-disable-isolation
+
+#![feature(io_error_more)]
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{create_dir, read_dir, remove_dir, remove_file, rename, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+
+#[path = "../../utils/mod.rs"]
+mod utils;
+
+fn main() {
+    test_file();
+    test_file_create_new();
+    test_seek();
+    test_metadata();
+    test_file_set_len();
+    test_file_sync();
+    test_errors();
+    test_rename();
+    test_directory();
+}
+
+fn test_file() {
+    let bytes = b"Hello, World!\n";
+    let path = utils::prepare("miri_test_windows_fs_file.txt");
+
+    // Test creating, writing and closing a file (closing is tested when `file` is dropped).
+    let mut file = File::create(&path).unwrap();
+    // Writing 0 bytes should not change the file contents.
+    file.write(&mut []).unwrap();
+    assert_eq!(file.metadata().unwrap().len(), 0);
+
+    file.write(bytes).unwrap();
+    assert_eq!(file.metadata().unwrap().len(), bytes.len() as u64);
+    drop(file);
+
+    // Test opening, reading and closing a file.
+    let mut file = File::open(&path).unwrap();
+    let mut contents = Vec::new();
+    // Reading 0 bytes should not move the file pointer.
+    file.read(&mut []).unwrap();
+    // Reading until EOF should get the whole text.
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(bytes, contents.as_slice());
+    // Writing to a file opened for reading only should fail.
+    assert_eq!(file.write(bytes).unwrap_err().kind(), ErrorKind::PermissionDenied);
+    drop(file);
+
+    // Test that truncating an existing file works.
+    let file = File::create(&path).unwrap();
+    assert_eq!(file.metadata().unwrap().len(), 0);
+    drop(file);
+
+    // Removing file should succeed.
+    remove_file(&path).unwrap();
+}
+
+fn test_file_create_new() {
+    let path = utils::prepare("miri_test_windows_fs_file_create_new.txt");
+
+    // Creating a new file that doesn't yet exist should succeed.
+    OpenOptions::new().write(true).create_new(true).open(&path).unwrap();
+    // Creating a new file that already exists should fail.
+    assert_eq!(
+        ErrorKind::AlreadyExists,
+        OpenOptions::new().write(true).create_new(true).open(&path).unwrap_err().kind()
+    );
+    // Optionally creating a new file that already exists should succeed.
+    OpenOptions::new().write(true).create(true).open(&path).unwrap();
+
+    // Clean up
+    remove_file(&path).unwrap();
+}
+
+fn test_seek() {
+    let bytes = b"Hello, entire World!\n";
+    let path = utils::prepare_with_content("miri_test_windows_fs_seek.txt", bytes);
+
+    let mut file = File::open(&path).unwrap();
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(bytes, contents.as_slice());
+    // Test that seeking to the beginning and reading until EOF gets the text again.
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(bytes, contents.as_slice());
+    // Test seeking relative to the end of the file.
+    file.seek(SeekFrom::End(-1)).unwrap();
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(&bytes[bytes.len() - 1..], contents.as_slice());
+    // Test seeking relative to the current position.
+    file.seek(SeekFrom::Start(5)).unwrap();
+    file.seek(SeekFrom::Current(-3)).unwrap();
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(&bytes[2..], contents.as_slice());
+    // Seeking before the start of the file should fail.
+    assert!(file.seek(SeekFrom::Current(-100)).is_err());
+
+    // Removing file should succeed.
+    remove_file(&path).unwrap();
+}
+
+fn test_metadata() {
+    let bytes = b"Hello, meta-World!\n";
+    let path = utils::prepare_with_content("miri_test_windows_fs_metadata.txt", bytes);
+
+    // Test that metadata of an absolute path is correct.
+    let metadata = std::fs::metadata(&path).unwrap();
+    assert!(metadata.is_file());
+    assert!(!metadata.is_dir());
+    assert_eq!(metadata.len(), bytes.len() as u64);
+    assert!(!metadata.permissions().readonly());
+    metadata.modified().unwrap();
+
+    // Test that metadata of an open file is correct.
+    let file = File::open(&path).unwrap();
+    let metadata = file.metadata().unwrap();
+    assert!(metadata.is_file());
+    assert_eq!(metadata.len(), bytes.len() as u64);
+    drop(file);
+
+    // Test that metadata of a directory is correct.
+    let metadata = std::fs::metadata(utils::tmp()).unwrap();
+    assert!(metadata.is_dir());
+    assert!(!metadata.is_file());
+
+    // Removing file should succeed.
+    remove_file(&path).unwrap();
+}
+
+fn test_file_set_len() {
+    let bytes = b"Hello, World!\n";
+    let path = utils::prepare_with_content("miri_test_windows_fs_set_len.txt", bytes);
+
+    // Test extending the file.
+    let mut file = OpenOptions::new().read(true).write(true).open(&path).unwrap();
+    let bytes_extended = b"Hello, World!\n\x00\x00\x00\x00\x00\x00";
+    file.set_len(20).unwrap();
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(bytes_extended, contents.as_slice());
+
+    // Test truncating the file.
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.set_len(10).unwrap();
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(&bytes[..10], contents.as_slice());
+
+    // Can't use set_len on a file not opened for writing.
+    let file = OpenOptions::new().read(true).open(&path).unwrap();
+    assert_eq!(ErrorKind::PermissionDenied, file.set_len(14).unwrap_err().kind());
+
+    remove_file(&path).unwrap();
+}
+
+fn test_file_sync() {
+    let bytes = b"Hello, World!\n";
+    let path = utils::prepare_with_content("miri_test_windows_fs_sync.txt", bytes);
+
+    // Test that we can call sync_data and sync_all on a file opened for writing.
+    let file = OpenOptions::new().write(true).open(&path).unwrap();
+    file.sync_data().unwrap();
+    file.sync_all().unwrap();
+
+    // Test that we can't call sync_data and sync_all on a file opened for reading.
+    let file = File::open(&path).unwrap();
+    assert_eq!(file.sync_data().unwrap_err().kind(), ErrorKind::PermissionDenied);
+    assert_eq!(file.sync_all().unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    remove_file(&path).unwrap();
+}
+
+fn test_errors() {
+    let bytes = b"Hello, World!\n";
+    let path = utils::prepare("miri_test_windows_fs_errors.txt");
+
+    // The following tests also check that `GetLastError` and `RtlNtStatusToDosError` are working
+    // properly.
+    // Opening a non-existing file should fail with a "not found" error.
+    assert_eq!(ErrorKind::NotFound, File::open(&path).unwrap_err().kind());
+    // Make sure we can also format this.
+    format!("{0}: {0:?}", File::open(&path).unwrap_err());
+    // Removing a non-existing file should fail with a "not found" error.
+    assert_eq!(ErrorKind::NotFound, remove_file(&path).unwrap_err().kind());
+    // Reading the metadata of a non-existing file should fail with a "not found" error.
+    assert_eq!(ErrorKind::NotFound, std::fs::metadata(&path).unwrap_err().kind());
+    // Writing to a non-existing file in a non-existing directory should fail.
+    let nested = utils::prepare("miri_test_windows_fs_errors_dir").join("file.txt");
+    assert_eq!(ErrorKind::NotFound, std::fs::write(&nested, bytes).unwrap_err().kind());
+}
+
+fn test_rename() {
+    // Renaming a file should succeed.
+    let path1 = utils::prepare("miri_test_windows_fs_rename_source.txt");
+    let path2 = utils::prepare("miri_test_windows_fs_rename_destination.txt");
+
+    let file = File::create(&path1).unwrap();
+    drop(file);
+
+    // Renaming should succeed.
+    rename(&path1, &path2).unwrap();
+    // Check that the old file path isn't present.
+    assert_eq!(ErrorKind::NotFound, path1.metadata().unwrap_err().kind());
+    // Check that the file has moved successfully.
+    assert!(path2.metadata().unwrap().is_file());
+
+    // Renaming a nonexistent file should fail.
+    assert_eq!(ErrorKind::NotFound, rename(&path1, &path2).unwrap_err().kind());
+
+    // Renaming onto an existing file should replace it.
+    File::create(&path1).unwrap().write_all(b"new contents").unwrap();
+    rename(&path1, &path2).unwrap();
+    assert_eq!(std::fs::read(&path2).unwrap(), b"new contents");
+
+    remove_file(&path2).unwrap();
+}
+
+fn test_directory() {
+    let dir_path = utils::prepare("miri_test_windows_fs_dir");
+    // Creating a directory should succeed.
+    create_dir(&dir_path).unwrap();
+    // Test that the metadata of a directory is correct.
+    assert!(dir_path.metadata().unwrap().is_dir());
+    // Creating a directory when it already exists should fail.
+    assert_eq!(ErrorKind::AlreadyExists, create_dir(&dir_path).unwrap_err().kind());
+
+    // Create some files and dirs inside the directory
+    let path_1 = dir_path.join("test_file_1");
+    drop(File::create(&path_1).unwrap());
+    let path_2 = dir_path.join("test_file_2");
+    drop(File::create(&path_2).unwrap());
+    let dir_1 = dir_path.join("test_dir_1");
+    create_dir(&dir_1).unwrap();
+    // Test that read_dir metadata calls succeed
+    assert_eq!(
+        HashMap::from([
+            (OsString::from("test_file_1"), true),
+            (OsString::from("test_file_2"), true),
+            (OsString::from("test_dir_1"), false)
+        ]),
+        read_dir(&dir_path)
+            .unwrap()
+            .map(|e| {
+                let e = e.unwrap();
+                (e.file_name(), e.metadata().unwrap().is_file())
+            })
+            .collect::<HashMap<_, _>>()
+    );
+    // Deleting the directory should fail, since it is not empty.
+    assert_eq!(ErrorKind::DirectoryNotEmpty, remove_dir(&dir_path).unwrap_err().kind());
+    // Clean up the files in the directory
+    remove_file(&path_1).unwrap();
+    remove_file(&path_2).unwrap();
+    remove_dir(&dir_1).unwrap();
+    // Now there should be nothing left in the directory.
+    assert_eq!(read_dir(&dir_path).unwrap().count(), 0);
+    // Deleting the directory should succeed.
+    remove_dir(&dir_path).unwrap();
+    // Reading the contents of a deleted directory should fail.
+    assert_eq!(ErrorKind::NotFound, read_dir(&dir_path).unwrap_err().kind());
+}
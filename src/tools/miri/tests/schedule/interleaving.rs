@@ -0,0 +1,26 @@
+// Run by `record_and_replay_schedule` in `tests/ui.rs`: the order in which the threads take the
+// lock depends on the schedule, so replaying a recorded trace must print the same order again.
+
+use std::sync::Mutex;
+use std::thread;
+
+static ORDER: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+fn main() {
+    let threads: Vec<_> = (0..3)
+        .map(|id| {
+            thread::spawn(move || {
+                for _ in 0..5 {
+                    ORDER.lock().unwrap().push(id);
+                    for i in 0..10 {
+                        std::hint::black_box(i);
+                    }
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    println!("{:?}", ORDER.lock().unwrap());
+}
@@ -255,6 +255,49 @@ fn ui(
         .with_context(|| format!("ui tests in {path} for {target} failed"))
 }
 
+/// Runs `tests/schedule/interleaving.rs` once while recording its schedule and then replays the
+/// trace with a different seed, which must lead to the same output.
+fn record_and_replay_schedule(target: &str, tmpdir: &Path) -> Result<()> {
+    let msg = format!("## Recording and replaying a schedule for {target}");
+    eprintln!("{}", msg.green().bold());
+
+    let trace = tmpdir.join("interleaving.trace");
+    let run = |seed: u64, schedule_flag: OsString| -> Result<std::process::Output> {
+        let mut cmd = Command::new(miri_path());
+        cmd.arg(format!(
+            "--sysroot={}",
+            env::var("MIRI_SYSROOT").expect("MIRI_SYSROOT must be set to run the ui test suite")
+        ));
+        cmd.args(["--edition=2021", "--target", target]);
+        cmd.args(["-Zmiri-preemption-rate=0.5", &format!("-Zmiri-seed={seed}")]);
+        cmd.arg(schedule_flag);
+        cmd.arg("tests/schedule/interleaving.rs");
+        let output = cmd.output().context("failed to run miri")?;
+        assert!(
+            output.status.success(),
+            "miri failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(output)
+    };
+
+    let mut record = OsString::from("-Zmiri-record-schedule=");
+    record.push(&trace);
+    let recorded = run(1, record)?;
+    let contents = std::fs::read_to_string(&trace).context("failed to read the recorded trace")?;
+    assert!(contents.contains("preempt "), "the recorded trace has no preemptions:\n{contents}");
+
+    let mut replay = OsString::from("-Zmiri-replay-schedule=");
+    replay.push(&trace);
+    let replayed = run(2, replay)?;
+    assert_eq!(
+        String::from_utf8_lossy(&replayed.stdout),
+        String::from_utf8_lossy(&recorded.stdout),
+        "replaying the trace led to a different interleaving"
+    );
+    Ok(())
+}
+
 fn get_target() -> String {
     env::var("MIRI_TEST_TARGET").ok().unwrap_or_else(get_host)
 }
@@ -301,6 +344,7 @@ fn main() -> Result<()> {
             tmpdir.path(),
         )?;
     }
+    record_and_replay_schedule(&target, tmpdir.path())?;
 
     Ok(())
 }
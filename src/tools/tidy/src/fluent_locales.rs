@@ -0,0 +1,157 @@
+//! Checks that the translations of the diagnostics in `src/etc/locale/$locale/$crate.ftl` only
+//! translate messages and attributes that exist in `compiler/$crate/messages.ftl`, and only use
+//! the arguments of those messages.
+//!
+//! Translations may be incomplete: a message or attribute that is not translated is shown in
+//! English.
+
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// The attributes of a message, and the arguments and messages it refers to.
+#[derive(Default)]
+struct MessageInfo {
+    has_value: bool,
+    attributes: BTreeSet<String>,
+    variables: BTreeSet<String>,
+    references: BTreeSet<String>,
+}
+
+fn parse_messages(
+    filename: &str,
+    contents: &str,
+    bad: &mut bool,
+) -> Option<HashMap<String, MessageInfo>> {
+    let resource = match fluent_syntax::parser::parse(contents) {
+        Ok(resource) => resource,
+        Err((_, errors)) => {
+            for error in errors {
+                tidy_error!(bad, "{filename}: failed to parse: {error}");
+            }
+            return None;
+        }
+    };
+    let mut messages = HashMap::new();
+    for entry in &resource.body {
+        let Entry::Message(message) = entry else { continue };
+        let mut info = MessageInfo { has_value: message.value.is_some(), ..Default::default() };
+        if let Some(value) = &message.value {
+            collect_pattern(value, &mut info);
+        }
+        for attr in &message.attributes {
+            info.attributes.insert(attr.id.name.to_owned());
+            collect_pattern(&attr.value, &mut info);
+        }
+        messages.insert(message.id.name.to_owned(), info);
+    }
+    Some(messages)
+}
+
+fn collect_pattern(pattern: &Pattern<&str>, info: &mut MessageInfo) {
+    for element in &pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            collect_expression(expression, info);
+        }
+    }
+}
+
+fn collect_expression(expression: &Expression<&str>, info: &mut MessageInfo) {
+    match expression {
+        Expression::Select { selector, variants } => {
+            collect_inline_expression(selector, info);
+            for variant in variants {
+                collect_pattern(&variant.value, info);
+            }
+        }
+        Expression::Inline(inline) => collect_inline_expression(inline, info),
+    }
+}
+
+fn collect_inline_expression(expression: &InlineExpression<&str>, info: &mut MessageInfo) {
+    match expression {
+        InlineExpression::VariableReference { id } => {
+            info.variables.insert(id.name.to_owned());
+        }
+        InlineExpression::MessageReference { id, .. } => {
+            info.references.insert(id.name.to_owned());
+        }
+        InlineExpression::FunctionReference { arguments, .. } => {
+            for argument in &arguments.positional {
+                collect_inline_expression(argument, info);
+            }
+            for argument in &arguments.named {
+                collect_inline_expression(&argument.value, info);
+            }
+        }
+        InlineExpression::Placeable { expression } => collect_expression(expression, info),
+        _ => {}
+    }
+}
+
+fn check_locale_file(path: &Path, compiler_path: &Path, bad: &mut bool) {
+    let filename = path.display().to_string();
+    let Some(krate) = path.file_stem().and_then(|stem| stem.to_str()) else { return };
+    let english_path = compiler_path.join(krate).join("messages.ftl");
+    let Ok(english) = fs::read_to_string(&english_path) else {
+        tidy_error!(bad, "{filename}: there is no `{}`", english_path.display());
+        return;
+    };
+    let contents = t!(fs::read_to_string(path));
+
+    let (Some(english), Some(translated)) = (
+        parse_messages(&english_path.display().to_string(), &english, bad),
+        parse_messages(&filename, &contents, bad),
+    ) else {
+        return;
+    };
+
+    for (id, translation) in &translated {
+        let Some(message) = english.get(id) else {
+            tidy_error!(bad, "{filename}: message `{id}` does not exist in `{krate}`");
+            continue;
+        };
+        if translation.has_value && !message.has_value {
+            tidy_error!(bad, "{filename}: message `{id}` has no value in `{krate}`");
+        }
+        for attr in translation.attributes.difference(&message.attributes) {
+            tidy_error!(bad, "{filename}: attribute `{id}.{attr}` does not exist in `{krate}`");
+        }
+        // The arguments of a diagnostic are available to the message and all of its attributes.
+        for variable in translation.variables.difference(&message.variables) {
+            tidy_error!(
+                bad,
+                "{filename}: message `{id}` uses `${variable}`, which it does not have"
+            );
+        }
+        for reference in &translation.references {
+            if !english.contains_key(reference) {
+                tidy_error!(
+                    bad,
+                    "{filename}: message `{id}` refers to `{reference}`, which does not exist \
+                     in `{krate}`"
+                );
+            }
+        }
+    }
+}
+
+pub fn check(locale_path: &Path, compiler_path: &Path, bad: &mut bool) {
+    let Ok(locales) = fs::read_dir(locale_path) else { return };
+    for locale in locales {
+        let locale = t!(locale).path();
+        if !locale.is_dir() {
+            tidy_error!(bad, "{}: expected a directory for each locale", locale.display());
+            continue;
+        }
+        for file in t!(fs::read_dir(&locale)) {
+            let path = t!(file).path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("ftl") {
+                tidy_error!(bad, "{}: expected only `.ftl` files", path.display());
+                continue;
+            }
+            check_locale_file(&path, compiler_path, bad);
+        }
+    }
+}
@@ -72,6 +72,7 @@ fn tidy_error(args: &str) -> std::io::Result<()> {
 pub mod extdeps;
 pub mod features;
 pub mod fluent_alphabetical;
+pub mod fluent_locales;
 pub mod fluent_period;
 mod fluent_used;
 pub(crate) mod iter_header;
@@ -116,6 +116,7 @@ macro_rules! check {
         check!(error_codes, &root_path, &[&compiler_path, &librustdoc_path], verbose);
         check!(fluent_alphabetical, &compiler_path, bless);
         check!(fluent_period, &compiler_path);
+        check!(fluent_locales, &src_path.join("etc/locale"), &compiler_path);
         check!(target_policy, &root_path);
 
         // Checks that only make sense for the std libs.
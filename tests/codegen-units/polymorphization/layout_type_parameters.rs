@@ -0,0 +1,79 @@
+//@ compile-flags:-Zpolymorphize=layout -Zprint-mono-items=lazy -Copt-level=1
+
+#![crate_type = "rlib"]
+
+// This test checks that `-Zpolymorphize=layout` merges the instances whose type parameters do not
+// affect the layout of the values they are used in.
+
+use std::marker::PhantomData;
+
+pub struct Tagged<T> {
+    pub value: u32,
+    pub tag: PhantomData<T>,
+}
+
+// Function uses type parameter in `PhantomData`.
+pub fn phantom<T>(marker: PhantomData<T>) -> PhantomData<T> {
+    marker
+}
+
+//~ MONO_ITEM fn phantom::<()>
+
+// Function uses type parameter in a struct that only uses it in `PhantomData`.
+pub fn phantom_field<T>(tagged: &Tagged<T>) -> u32 {
+    tagged.value
+}
+
+//~ MONO_ITEM fn phantom_field::<()>
+
+// Function uses type parameter as the pointee type of thin pointers.
+pub fn pointer<T>(value: &T) -> *const T {
+    value
+}
+
+//~ MONO_ITEM fn pointer::<()>
+
+// Function uses type parameter in the layout of a dereferenced pointer.
+pub fn deref<T: Copy>(value: &T) -> T {
+    *value
+}
+
+//~ MONO_ITEM fn deref::<u32>
+//~ MONO_ITEM fn deref::<u64>
+
+// Function uses type parameter in the layout of an argument.
+pub fn used_layout<T>(value: Option<T>) -> Option<T> {
+    value
+}
+
+//~ MONO_ITEM fn used_layout::<u32>
+//~ MONO_ITEM fn used_layout::<u64>
+
+// Function uses type parameter in substitutions to another function.
+pub fn used_substs<T>(marker: PhantomData<T>) -> PhantomData<T> {
+    phantom::<T>(marker)
+}
+
+//~ MONO_ITEM fn used_substs::<u32>
+//~ MONO_ITEM fn used_substs::<u64>
+
+pub fn dispatch<T: Copy>(value: T) {
+    let _ = phantom::<T>(PhantomData);
+    let _ = phantom_field::<T>(&Tagged { value: 1, tag: PhantomData });
+    let _ = pointer::<T>(&value);
+    let _ = deref::<T>(&value);
+    let _ = used_layout::<T>(None);
+    let _ = used_substs::<T>(PhantomData);
+}
+
+//~ MONO_ITEM fn dispatch::<u32>
+//~ MONO_ITEM fn dispatch::<u64>
+
+pub fn foo() {
+    // Generate two copies of each function to check that where the type parameter does not affect
+    // the layout, there is only a single copy.
+    dispatch::<u32>(1);
+    dispatch::<u64>(2);
+}
+
+//~ MONO_ITEM fn foo @@ layout_type_parameters-cgu.0[External]
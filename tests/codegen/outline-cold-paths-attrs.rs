@@ -0,0 +1,29 @@
+// Checks that the outlined panic path of a generic function is cold and never inlined, even when
+// the function is `#[inline(always)]` and `#[optimize(speed)]`, so that the instantiations still
+// share it after the LLVM passes ran.
+
+//@ compile-flags: -O -C codegen-units=1 -C symbol-mangling-version=v0
+
+#![crate_type = "lib"]
+#![feature(optimize_attribute)]
+
+#[inline(always)]
+#[optimize(speed)]
+fn get<T: Copy>(slice: &[T], index: usize) -> T {
+    if index >= slice.len() {
+        panic!("index out of bounds");
+    }
+    slice[index]
+}
+
+// CHECK-DAG: call {{.*}}9cold_path
+// CHECK-DAG: define {{.*}}9cold_path{{.*}} #[[COLD:[0-9]+]]
+// CHECK: attributes #[[COLD]] = { {{.*}}cold{{.*}}noinline
+
+pub fn get_u8(slice: &[u8], index: usize) -> u8 {
+    get(slice, index)
+}
+
+pub fn get_u16(slice: &[u16], index: usize) -> u16 {
+    get(slice, index)
+}
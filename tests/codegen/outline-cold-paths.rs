@@ -0,0 +1,29 @@
+// Checks that the instantiations of a generic function share a single copy of its outlined panic
+// path.
+
+//@ compile-flags: -O -C no-prepopulate-passes -C codegen-units=1 -C symbol-mangling-version=v0
+
+#![crate_type = "lib"]
+
+#[inline(never)]
+fn get<T: Copy>(slice: &[T], index: usize) -> T {
+    if index >= slice.len() {
+        panic!("index out of bounds");
+    }
+    slice[index]
+}
+
+// CHECK: define {{.*}}9cold_path
+// CHECK-NOT: define {{.*}}9cold_path
+
+pub fn get_u8(slice: &[u8], index: usize) -> u8 {
+    get(slice, index)
+}
+
+pub fn get_u16(slice: &[u16], index: usize) -> u16 {
+    get(slice, index)
+}
+
+pub fn get_u32(slice: &[u32], index: usize) -> u32 {
+    get(slice, index)
+}
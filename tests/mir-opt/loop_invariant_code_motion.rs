@@ -0,0 +1,164 @@
+//@ test-mir-pass: LoopInvariantCodeMotion
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::cell::Cell;
+use core::intrinsics::mir::*;
+
+// EMIT_MIR loop_invariant_code_motion.slice_len.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "initial")]
+fn slice_len(x: &[u32], n: usize) -> usize {
+    // CHECK-LABEL: fn slice_len(
+    mir! {
+        let i: usize;
+        let len: usize;
+        let double: usize;
+        let cond: bool;
+        let half: usize;
+        {
+            // CHECK: bb0: {
+            // CHECK: [[len:_.*]] = Len((*_1));
+            // CHECK-NEXT: [[double:_.*]] = Mul([[len]], const 2_usize);
+            // CHECK-NEXT: goto -> bb1;
+            i = 0;
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            // CHECK: bb1: {
+            // CHECK: Lt(
+            cond = i < n;
+            match cond { true => bb2, _ => bb3 }
+        }
+        bb2 = {
+            // CHECK: bb2: {
+            // CHECK-NOT: Len(
+            // CHECK-NOT: Mul(
+            // CHECK: Div(_2, const 2_usize);
+            // CHECK: _0 = Add(_0, [[double]]);
+            len = Len(*x);
+            double = len * 2;
+            half = n / 2;
+            RET = RET + double;
+            i = i + 1;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    }
+}
+
+// EMIT_MIR loop_invariant_code_motion.freeze_deref.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "initial")]
+fn freeze_deref(x: &(u32, u32), n: usize) -> u32 {
+    // CHECK-LABEL: fn freeze_deref(
+    mir! {
+        let i: usize;
+        let a: u32;
+        let cond: bool;
+        {
+            // CHECK: bb0: {
+            // CHECK: [[a:_.*]] = ((*_1).0: u32);
+            // CHECK-NEXT: goto -> bb1;
+            i = 0;
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            cond = i < n;
+            match cond { true => bb2, _ => bb3 }
+        }
+        bb2 = {
+            // CHECK: bb2: {
+            // CHECK-NOT: (*_1)
+            // CHECK: _0 = Add(_0, [[a]]);
+            a = (*x).0;
+            RET = RET + a;
+            i = i + 1;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    }
+}
+
+// The pointee may be mutated through the shared reference, so even its `Freeze` fields are not
+// invariant.
+// EMIT_MIR loop_invariant_code_motion.non_freeze_deref.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "initial")]
+fn non_freeze_deref(x: &(Cell<u32>, u32), n: usize) -> u32 {
+    // CHECK-LABEL: fn non_freeze_deref(
+    mir! {
+        let i: usize;
+        let a: u32;
+        let cond: bool;
+        {
+            // CHECK: bb0: {
+            // CHECK-NOT: (*_1)
+            // CHECK: goto -> bb1;
+            i = 0;
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            cond = i < n;
+            match cond { true => bb2, _ => bb3 }
+        }
+        bb2 = {
+            // CHECK: bb2: {
+            // CHECK: [[a:_.*]] = ((*_1).1: u32);
+            // CHECK: _0 = Add(_0, [[a]]);
+            a = (*x).1;
+            RET = RET + a;
+            i = i + 1;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    }
+}
+
+// EMIT_MIR loop_invariant_code_motion.discriminant.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "runtime", phase = "initial")]
+fn discriminant(x: &Option<u32>, n: usize) -> isize {
+    // CHECK-LABEL: fn discriminant(
+    mir! {
+        let i: usize;
+        let d: isize;
+        let cond: bool;
+        {
+            // CHECK: bb0: {
+            // CHECK: [[d:_.*]] = discriminant((*_1));
+            // CHECK-NEXT: goto -> bb1;
+            i = 0;
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            cond = i < n;
+            match cond { true => bb2, _ => bb3 }
+        }
+        bb2 = {
+            // CHECK: bb2: {
+            // CHECK-NOT: discriminant(
+            // CHECK: _0 = Add(_0, [[d]]);
+            d = Discriminant(*x);
+            RET = RET + d;
+            i = i + 1;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    }
+}
+
+fn main() {
+    slice_len(&[1, 2, 3], 4);
+    freeze_deref(&(1, 2), 4);
+    non_freeze_deref(&(Cell::new(1), 2), 4);
+    discriminant(&Some(1), 4);
+}
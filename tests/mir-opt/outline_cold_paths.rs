@@ -0,0 +1,21 @@
+//@ test-mir-pass: OutlineColdPaths
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+
+#[cold]
+#[inline(never)]
+fn fail(_index: usize) -> ! {
+    loop {}
+}
+
+// EMIT_MIR outline_cold_paths.get.OutlineColdPaths.diff
+pub fn get<T: Copy>(index: usize, slice: &[T]) -> T {
+    // CHECK-LABEL: fn get(
+    // CHECK: bb4: {
+    // CHECK-NEXT: {{_.*}} = const {get as fn(usize) -> !}(_1)
+    // CHECK-NOT: fail(
+    if index < slice.len() { slice[index] } else { fail(index) }
+}
+
+fn main() {
+    get(1, &[1, 2, 3]);
+}
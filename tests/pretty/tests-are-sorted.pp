@@ -27,6 +27,7 @@ pub const m_test: test::TestDescAndFn =
             no_run: false,
             should_panic: test::ShouldPanic::No,
             test_type: test::TestType::Unknown,
+            timeout: ::core::option::Option::None,
         },
         testfn: test::StaticTestFn(#[coverage(off)] ||
                 test::assert_test_result(m_test())),
@@ -51,6 +52,7 @@ pub const z_test: test::TestDescAndFn =
             no_run: false,
             should_panic: test::ShouldPanic::No,
             test_type: test::TestType::Unknown,
+            timeout: ::core::option::Option::None,
         },
         testfn: test::StaticTestFn(#[coverage(off)] ||
                 test::assert_test_result(z_test())),
@@ -76,6 +78,7 @@ pub const a_test: test::TestDescAndFn =
             no_run: false,
             should_panic: test::ShouldPanic::No,
             test_type: test::TestType::Unknown,
+            timeout: ::core::option::Option::None,
         },
         testfn: test::StaticTestFn(#[coverage(off)] ||
                 test::assert_test_result(a_test())),
@@ -0,0 +1,11 @@
+#![warn(unused_mut, unused_parens)]
+
+static answer: i32 = 42;
+
+fn main() {
+    let x = answer;
+    let _ = x;
+    loop {
+        break;
+    }
+}
@@ -0,0 +1,11 @@
+#![warn(unused_mut, unused_parens)]
+
+static answer: i32 = 42;
+
+fn main() {
+    let mut x = answer;
+    let _ = (x);
+    while true {
+        break;
+    }
+}
@@ -0,0 +1,18 @@
+// Checks that `-Z apply-suggestions` rewrites the source file in place. The maybe-incorrect
+// suggestion to rename `answer` is not applied, and compiling the rewritten file again does not
+// change it any more.
+
+use run_make_support::{diff, fs_wrapper, rustc};
+
+fn main() {
+    rustc()
+        .input("main.rs")
+        .arg("-Zapply-suggestions")
+        .run()
+        .assert_stderr_contains("applied 3 suggestions to `main.rs`");
+    diff().expected_file("fixed.rs").actual_file("main.rs").run();
+
+    let fixed = fs_wrapper::read_to_string("main.rs");
+    rustc().input("main.rs").arg("-Zapply-suggestions").run().assert_stderr_not_contains("applied");
+    diff().expected_text("fixed", fixed).actual_file("main.rs").run();
+}
@@ -0,0 +1,9 @@
+#![crate_type = "lib"]
+
+pub fn make_u8() -> u8 {
+    upstream::make()
+}
+
+pub fn make_u32() -> u32 {
+    upstream::make()
+}
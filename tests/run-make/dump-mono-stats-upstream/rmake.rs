@@ -0,0 +1,50 @@
+// Checks that `-Zdump-mono-stats` reports the crate that defines each generic function, that each
+// crate only reports the copies it instantiates, and that the copies reused from upstream crates
+// are not counted.
+
+use run_make_support::{fs_wrapper, rustc};
+
+fn main() {
+    rustc()
+        .input("upstream.rs")
+        .arg("-Zshare-generics=yes")
+        .arg("-Zdump-mono-stats")
+        .arg("-Zdump-mono-stats-format=json")
+        .run();
+    let upstream = fs_wrapper::read_to_string("upstream.codegen_items.json");
+    // Every copy is instantiated by the crate being compiled, so there is no column for it.
+    assert!(!upstream.contains("instantiating_crate"));
+    assert!(
+        upstream.contains(r#""name":"make","defining_crate":"upstream","instantiation_count":1"#)
+    );
+
+    // Without sharing generics, the downstream crate emits its own copies of `make::<u8>` and
+    // `make::<u32>`.
+    rustc()
+        .input("downstream.rs")
+        .extern_("upstream", "libupstream.rlib")
+        .arg("-Zshare-generics=no")
+        .arg("-Zdump-mono-stats=unshared")
+        .arg("-Zdump-mono-stats-format=json")
+        .run();
+    let unshared = fs_wrapper::read_to_string("unshared/downstream.codegen_items.json");
+    assert!(!unshared.contains("instantiating_crate"));
+    assert!(unshared.contains(
+        r#""name":"upstream::make","defining_crate":"upstream","instantiation_count":2"#
+    ));
+    assert!(unshared.contains(r#""name":"make_u32","defining_crate":"downstream""#));
+
+    // When sharing generics, `make::<u8>` is reused from the upstream crate, so the downstream
+    // crate only instantiates `make::<u32>`.
+    rustc()
+        .input("downstream.rs")
+        .extern_("upstream", "libupstream.rlib")
+        .arg("-Zshare-generics=yes")
+        .arg("-Zdump-mono-stats=shared")
+        .arg("-Zdump-mono-stats-format=json")
+        .run();
+    let shared = fs_wrapper::read_to_string("shared/downstream.codegen_items.json");
+    assert!(shared.contains(
+        r#""name":"upstream::make","defining_crate":"upstream","instantiation_count":1"#
+    ));
+}
@@ -0,0 +1,9 @@
+#![crate_type = "rlib"]
+
+pub fn make<T: Default>() -> T {
+    T::default()
+}
+
+pub fn make_u8() -> u8 {
+    make()
+}
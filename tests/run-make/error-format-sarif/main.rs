@@ -0,0 +1,12 @@
+fn unused_variable() {
+    let unused = 1;
+}
+
+fn mismatched_types() -> u32 {
+    "not a number"
+}
+
+fn main() {
+    unused_variable();
+    mismatched_types();
+}
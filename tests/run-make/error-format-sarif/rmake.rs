@@ -0,0 +1,53 @@
+// Checks that `--error-format=sarif` prints a single SARIF log with a result for each error and
+// warning, and that `--error-format=pretty-sarif` prints the same log over several lines.
+
+use run_make_support::rustc;
+
+fn main() {
+    let sarif = rustc()
+        .input("main.rs")
+        .crate_name("sarif_test")
+        .arg("-Zunstable-options")
+        .error_format("sarif")
+        .run_fail()
+        .stderr_utf8();
+
+    // The whole log is a single document, written once at the end of the compilation.
+    assert_eq!(sarif.lines().count(), 1, "{sarif}");
+    assert!(sarif.starts_with(
+        r#"{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","runs":[{"#
+    ));
+    assert!(sarif.contains(r#""automationDetails":{"id":"rustc/sarif_test/"}"#));
+    assert!(sarif.contains(r#""columnKind":"unicodeCodePoints""#));
+
+    // The error code and the lint name become rules, with a link to the error code index.
+    assert!(sarif.contains(r#"{"id":"E0308","fullDescription":{"text":"#));
+    assert!(sarif.contains(r#""helpUri":"https://doc.rust-lang.org/error_codes/E0308.html""#));
+    assert!(sarif.contains(r#"{"id":"unused_variables"}"#));
+
+    // Each diagnostic becomes a result pointing at its primary span.
+    assert!(sarif.contains(r#""ruleId":"E0308""#));
+    assert!(sarif.contains(r#""level":"error","message":{"text":"mismatched types"#));
+    assert!(sarif.contains(r#""ruleId":"unused_variables""#));
+    assert!(sarif.contains(r#""level":"warning","message":{"text":"unused variable: `unused`"#));
+    assert!(sarif.contains(
+        r#"{"physicalLocation":{"artifactLocation":{"uri":"main.rs","uriBaseId":"%SRCROOT%"},"region":{"startLine":2,"startColumn":9,"endLine":2,"endColumn":15,"byteOffset":31,"byteLength":6}}}"#
+    ));
+
+    // Suggestions become fixes.
+    assert!(sarif.contains(r#""insertedContent":{"text":"_unused"}"#));
+
+    // The summary of the errors is not a result of its own.
+    assert!(!sarif.contains("aborting due to"));
+
+    let pretty = rustc()
+        .input("main.rs")
+        .crate_name("sarif_test")
+        .arg("-Zunstable-options")
+        .error_format("pretty-sarif")
+        .run_fail()
+        .stderr_utf8();
+    assert!(pretty.lines().count() > 1);
+    assert!(pretty.contains(r#""$schema": "https://json.schemastore.org/sarif-2.1.0.json""#));
+    assert!(pretty.contains(r#""ruleId": "E0308""#));
+}
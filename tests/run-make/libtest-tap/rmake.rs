@@ -0,0 +1,32 @@
+// Checks the output of libtest's TAP formatter, with and without the
+// captured output of successful tests.
+
+//@ ignore-cross-compile
+// Reason: the compiled code is ran
+//@ needs-unwind
+// Reason: contains a should_panic test
+
+use run_make_support::{bin_name, cmd, cwd, diff, rustc};
+
+fn run_tests(extra_args: &[&str]) -> String {
+    cmd(cwd().join(bin_name("f")))
+        .env("RUST_BACKTRACE", "0")
+        .args(&["-Zunstable-options", "--test-threads=1", "--format=tap"])
+        .args(extra_args)
+        .run_fail()
+        .stdout_utf8()
+}
+
+fn main() {
+    rustc().arg("--test").input("f.rs").run();
+    diff()
+        .expected_file("output-default.tap")
+        .actual_text("actual-default", run_tests(&[]))
+        .normalize(r#"finished in \d+\.\d+s"#, "finished in $$TIME")
+        .run();
+    diff()
+        .expected_file("output-stdout-success.tap")
+        .actual_text("actual-stdout-success", run_tests(&["--show-output"]))
+        .normalize(r#"finished in \d+\.\d+s"#, "finished in $$TIME")
+        .run();
+}
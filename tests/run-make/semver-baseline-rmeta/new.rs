@@ -0,0 +1,5 @@
+#![crate_type = "lib"]
+
+pub fn kept() -> u32 {
+    2
+}
@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+
+pub fn removed() {}
+
+pub fn kept() -> u32 {
+    1
+}
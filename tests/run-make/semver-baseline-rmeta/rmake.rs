@@ -0,0 +1,24 @@
+// Checks that `-Zsemver-baseline` compares against the metadata of a previous release of the
+// same crate, and that the baseline is not recorded as a dependency of the new release, so that
+// crates using the new release can be built without the baseline.
+
+use run_make_support::{fs_wrapper, run, rustc};
+
+fn main() {
+    fs_wrapper::create_dir("old");
+    rustc().input("old.rs").crate_name("foo").metadata("old").emit("metadata").out_dir("old").run();
+
+    rustc()
+        .input("new.rs")
+        .crate_name("foo")
+        .metadata("new")
+        .extern_("foo_old", "old/libfoo.rmeta")
+        .arg("-Zsemver-baseline=foo_old")
+        .run()
+        .assert_stderr_contains("warning: major change: function `removed` was removed")
+        .assert_stderr_contains("found 1 major change and no minor changes compared to `foo`");
+
+    fs_wrapper::remove_dir_all("old");
+    rustc().input("user.rs").extern_("foo", "libfoo.rlib").run();
+    run("user");
+}
@@ -0,0 +1,9 @@
+// Uses a diagnostic of `rustc_hir_typeck`, which has a bundled Spanish translation.
+
+struct Foo {
+    val: (),
+}
+
+fn main() {
+    let _ = Foo { val: (), val: () };
+}
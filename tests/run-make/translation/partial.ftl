@@ -0,0 +1,2 @@
+# The attributes of the message are not translated, so the fallback attributes are used.
+hir_typeck_field_multiply_specified_in_initializer = this is a test message
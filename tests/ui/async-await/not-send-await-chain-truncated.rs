@@ -0,0 +1,65 @@
+//@ edition:2018
+
+// Checks that only the first futures of a long chain of awaits are explained, followed by the
+// number of futures that the chain continues through.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn yield_now() {}
+
+async fn f0() {
+    let rc = Rc::new(());
+    yield_now().await;
+    drop(rc);
+}
+
+async fn f1() {
+    f0().await;
+}
+
+async fn f2() {
+    f1().await;
+}
+
+async fn f3() {
+    f2().await;
+}
+
+async fn f4() {
+    f3().await;
+}
+
+async fn f5() {
+    f4().await;
+}
+
+async fn f6() {
+    f5().await;
+}
+
+async fn f7() {
+    f6().await;
+}
+
+async fn f8() {
+    f7().await;
+}
+
+async fn f9() {
+    f8().await;
+}
+
+async fn f10() {
+    f9().await;
+}
+
+async fn f11() {
+    f10().await;
+}
+
+fn main() {
+    is_send(f11());
+    //~^ ERROR future cannot be sent between threads safely
+}
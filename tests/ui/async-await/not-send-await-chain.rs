@@ -0,0 +1,29 @@
+//@ edition:2018
+
+// Checks that the whole chain of futures holding a non-`Send` value across an await is
+// explained, from the value up to the future that is required to be `Send`.
+
+use std::rc::Rc;
+
+fn is_send<T: Send>(_: T) {}
+
+async fn yield_now() {}
+
+async fn inner() {
+    let rc = Rc::new(());
+    yield_now().await;
+    drop(rc);
+}
+
+async fn middle() {
+    inner().await;
+}
+
+async fn outer() {
+    middle().await;
+}
+
+fn main() {
+    is_send(outer());
+    //~^ ERROR future cannot be sent between threads safely
+}
@@ -0,0 +1,5 @@
+// check that `future_size_limit` is feature-gated
+
+#![future_size_limit = "42"] //~ ERROR the `#[future_size_limit]` attribute is an experimental feature
+
+fn main() {}
@@ -0,0 +1,13 @@
+//@ check-pass
+//@ compile-flags: -Zapply-suggestions=dry-run
+
+// Checks that `-Z apply-suggestions=dry-run` prints the machine-applicable suggestions as a diff
+// instead of writing them to the source file.
+
+#![warn(unused_mut, unused_parens)]
+
+fn main() {
+    let mut x = 1; //~ WARN variable does not need to be mutable
+    let _ = (x); //~ WARN unnecessary parentheses around assigned value
+    while true {} //~ WARN denote infinite loops with `loop { ... }`
+}
@@ -0,0 +1,16 @@
+//@ revisions: machine_applicable maybe_incorrect
+//@ check-pass
+//@[machine_applicable] compile-flags: -Zapply-suggestions=dry-run
+//@[maybe_incorrect] compile-flags: -Zapply-suggestions=maybe-incorrect,dry-run
+
+// Checks that the maybe-incorrect suggestions are only applied with
+// `-Z apply-suggestions=maybe-incorrect`.
+
+#![warn(unused_mut)]
+
+static foo: i32 = 1; //~ WARN static variable `foo` should have an upper case name
+
+fn main() {
+    let mut x = foo; //~ WARN variable does not need to be mutable
+    let _ = x;
+}
@@ -0,0 +1,11 @@
+//@ check-pass
+//@ compile-flags: -Zapply-suggestions=dry-run
+
+// Checks that a suggestion that overlaps with a suggestion accepted before it is not applied.
+
+#![warn(unused_parens)]
+
+fn main() {
+    while (true) {} //~ WARN unnecessary parentheses around `while` condition
+    //~^ WARN denote infinite loops with `loop { ... }`
+}
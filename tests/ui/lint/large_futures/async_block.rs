@@ -0,0 +1,12 @@
+#![deny(large_futures)]
+#![feature(large_futures)]
+#![future_size_limit = "1000"]
+//@ build-fail
+//@ edition:2018
+//@ compile-flags: -Zmir-opt-level=0
+
+async fn wait() {}
+
+fn main() {
+    let _ = async { let buf = [0u8; 2048]; wait().await; drop(buf) }; //~ ERROR this future is 2050 bytes large
+}
@@ -0,0 +1,25 @@
+#![deny(large_futures)]
+#![feature(large_futures)]
+#![future_size_limit = "4096"]
+//@ build-pass
+//@ edition:2018
+//@ compile-flags: -Zmir-opt-level=0
+
+// Futures up to the limit are fine.
+
+async fn wait() {}
+
+async fn big() {
+    let buf = [0u8; 2048];
+    wait().await;
+    drop(buf);
+}
+
+fn main() {
+    let _ = big();
+    let _ = async {
+        let buf = [0u8; 4000];
+        wait().await;
+        drop(buf);
+    };
+}
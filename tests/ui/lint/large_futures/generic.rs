@@ -0,0 +1,41 @@
+#![deny(large_futures)]
+#![feature(large_futures)]
+#![future_size_limit = "1000"]
+//@ build-fail
+//@ edition:2018
+//@ compile-flags: -Zmir-opt-level=0
+
+// The lint fires after monomorphization, so only the large instantiation is linted.
+
+trait Make {
+    fn make() -> Self;
+}
+
+impl Make for u8 {
+    fn make() -> Self {
+        0
+    }
+}
+
+impl Make for [u8; 2048] {
+    fn make() -> Self {
+        [0; 2048]
+    }
+}
+
+async fn wait() {}
+
+async fn hold<T: Make>() {
+    let value = T::make();
+    wait().await;
+    drop(value);
+}
+
+fn create<T: Make>() {
+    let _ = hold::<T>(); //~ ERROR this future is 2050 bytes large
+}
+
+fn main() {
+    create::<u8>();
+    create::<[u8; 2048]>();
+}
@@ -0,0 +1,18 @@
+#![deny(large_futures)]
+#![feature(large_futures)]
+#![future_size_limit = "1000"]
+//@ build-fail
+//@ edition:2018
+//@ compile-flags: -Zmir-opt-level=0
+
+async fn wait() {}
+
+async fn big() {
+    let buf = [0u8; 2048];
+    wait().await;
+    drop(buf);
+}
+
+fn main() {
+    let _ = big(); //~ ERROR this future is 2050 bytes large
+}
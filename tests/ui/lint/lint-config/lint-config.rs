@@ -0,0 +1,32 @@
+// Checks the crate-wide levels and the path overrides of a `--lint-config` file.
+
+//@ compile-flags: -Zunstable-options --lint-config {{src-base}}/lint/lint-config/lint-config.toml
+
+#![crate_type = "lib"]
+
+fn unused() {} //~ ERROR function `unused` is never used
+
+pub struct bad_name; //~ ERROR type `bad_name` should have an upper camel case name
+
+pub fn BadName() {} //~ WARN function `BadName` should have a snake case name
+
+mod generated {
+    fn unused() {}
+
+    pub mod nested {
+        fn unused() {}
+
+        pub fn BadName() {}
+    }
+
+    #[deny(dead_code)]
+    pub mod denied {
+        fn unused() {} //~ ERROR function `unused` is never used
+    }
+}
+
+pub mod ffi {
+    pub fn CFunction() {}
+
+    fn unused() {} //~ ERROR function `unused` is never used
+}
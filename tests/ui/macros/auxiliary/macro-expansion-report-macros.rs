@@ -0,0 +1,23 @@
+//@ force-host
+//@ no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+#[proc_macro]
+pub fn make_unit(_: TokenStream) -> TokenStream {
+    "pub struct Unit;".parse().unwrap()
+}
+
+#[proc_macro_attribute]
+pub fn identity(_: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+#[proc_macro_derive(Marker)]
+pub fn derive_marker(_: TokenStream) -> TokenStream {
+    "pub struct Marked;".parse().unwrap()
+}
@@ -0,0 +1,17 @@
+// Check the JSON output of `-Zmacro-expansion-report`.
+
+//@ check-pass
+//@ compile-flags: -Zmacro-expansion-report=json
+//@ normalize-stdout-test: "\"time_ms\":[0-9.e-]+" -> "\"time_ms\":$$TIME"
+
+#![crate_type = "lib"]
+
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+pub fn f(x: u32) -> u32 {
+    square!(x + 1)
+}
@@ -0,0 +1,31 @@
+// Check that `-Zmacro-expansion-report` reports the three kinds of procedural macros. Each
+// revision expands a single macro, so that the order of the report doesn't depend on timing.
+
+//@ check-pass
+//@ revisions: bang attr derive
+//@ aux-build: macro-expansion-report-macros.rs
+//@ compile-flags: -Zmacro-expansion-report
+//@ normalize-stdout-test: " +[0-9]+\.[0-9]{3}ms" -> " $$TIME"
+
+#![crate_type = "lib"]
+
+extern crate macro_expansion_report_macros;
+
+#[cfg(bang)]
+mod bang {
+    macro_expansion_report_macros::make_unit!();
+}
+
+#[cfg(attr)]
+mod attr {
+    // The item is passed on unchanged, so its tokens are counted.
+    #[macro_expansion_report_macros::identity]
+    pub fn f() {}
+}
+
+#[cfg(derive)]
+mod derive {
+    // Only the tokens produced by the derive are counted, not the item it is applied to.
+    #[derive(macro_expansion_report_macros::Marker)]
+    pub struct Derived;
+}
@@ -0,0 +1,19 @@
+// Check that `-Zmacro-expansion-report` prints the time spent expanding each macro and the number
+// of tokens it produced, per macro and per invocation site.
+
+//@ check-pass
+//@ compile-flags: -Zmacro-expansion-report
+//@ normalize-stdout-test: " +[0-9]+\.[0-9]{3}ms" -> " $$TIME"
+
+#![crate_type = "lib"]
+
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+pub fn f(x: u32) -> u32 {
+    // The matched `$x` fragments count as one token each.
+    square!(x + 1)
+}
@@ -0,0 +1,8 @@
+//@ compile-flags: -O
+
+pub fn get<T: Copy>(slice: &[T], index: usize) -> T {
+    if index >= slice.len() {
+        panic!("index out of bounds");
+    }
+    slice[index]
+}
@@ -0,0 +1,14 @@
+//@ run-fail
+//@ check-run-results
+//@ compile-flags: -O
+//@ aux-build:generic_get.rs
+//@ exec-env:RUST_BACKTRACE=0
+
+// Checks the location of a panic in the outlined path of a generic function from another crate,
+// which is instantiated by this crate.
+
+extern crate generic_get;
+
+fn main() {
+    generic_get::get(&[1u32, 2, 3], 7);
+}
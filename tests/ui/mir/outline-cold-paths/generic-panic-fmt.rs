@@ -0,0 +1,19 @@
+//@ run-fail
+//@ check-run-results
+//@ compile-flags: -O
+//@ exec-env:RUST_BACKTRACE=0
+
+// Checks the message and the location of a panic that formats an argument of a generic function
+// when optimizing.
+
+fn get<T: Copy>(slice: &[T], index: usize) -> T {
+    if index >= slice.len() {
+        panic!("index {index} is out of bounds");
+    }
+    slice[index]
+}
+
+fn main() {
+    get(&[1u8, 2, 3], 1);
+    get(&[1u16, 2, 3], 7);
+}
@@ -0,0 +1,20 @@
+//@ revisions: unwrap expect
+//@ run-fail
+//@ check-run-results
+//@ compile-flags: -O
+//@ exec-env:RUST_BACKTRACE=0
+
+// Checks the message and the location of the panics of `Option::unwrap` and `Option::expect`
+// in a generic function when optimizing.
+
+fn first<T: Copy>(slice: &[T]) -> T {
+    #[cfg(unwrap)]
+    return *slice.first().unwrap();
+    #[cfg(expect)]
+    return *slice.first().expect("the slice is empty");
+}
+
+fn main() {
+    first(&[1u8]);
+    first::<u16>(&[]);
+}
@@ -0,0 +1,30 @@
+//@ revisions: on layout
+//@ build-pass
+//@[on] compile-flags: -Copt-level=2 -Zpolymorphize
+//@[layout] compile-flags: -Copt-level=2 -Zpolymorphize=layout
+
+// Checks that the outlined panic paths of a generic function and of a closure in it, which have
+// no generic arguments, are not polymorphized.
+
+fn get<T: Copy>(slice: &[T], index: usize) -> T {
+    if index >= slice.len() {
+        panic!("index {index} is out of bounds");
+    }
+    slice[index]
+}
+
+fn get_with<T: Copy>(slice: &[T]) -> impl Fn(usize) -> T + '_ {
+    move |index| {
+        if index >= slice.len() {
+            panic!("index {index} is out of bounds");
+        }
+        slice[index]
+    }
+}
+
+fn main() {
+    get(&[1u8, 2, 3], 1);
+    get(&[1u16, 2, 3], 2);
+    get_with(&[1u32, 2, 3])(0);
+    get_with(&[1u64, 2, 3])(1);
+}
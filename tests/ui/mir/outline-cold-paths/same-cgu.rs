@@ -0,0 +1,23 @@
+//@ run-fail
+//@ check-run-results
+//@ needs-unwind
+//@ compile-flags: -O -Ccodegen-units=1
+//@ exec-env:RUST_BACKTRACE=0
+
+// Checks that two instantiations of a generic function in the same codegen unit, which share the
+// outlined panic path, both report the panic at its location.
+
+use std::panic;
+
+fn get<T: Copy>(slice: &[T], index: usize) -> T {
+    if index >= slice.len() {
+        panic!("index out of bounds");
+    }
+    slice[index]
+}
+
+fn main() {
+    let result = panic::catch_unwind(|| get(&[1u8, 2, 3], 3));
+    assert!(result.is_err());
+    get(&[1u16, 2, 3], 7);
+}
@@ -0,0 +1,113 @@
+//@ build-fail
+//@ compile-flags:-Zpolymorphize=layout
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+
+// This test checks that `-Zpolymorphize=layout` considers the type parameters that do not
+// affect the layout of the values they are used in as unused.
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+struct Tagged<T> {
+    value: u32,
+    tag: PhantomData<T>,
+}
+
+struct Wrapper<T>(Tagged<T>);
+
+struct Node<T> {
+    value: u32,
+    next: *const Node<T>,
+}
+
+struct Dropped<T>(PhantomData<T>);
+
+impl<T> Drop for Dropped<T> {
+    fn drop(&mut self) {}
+}
+
+// Function uses generic parameter in `PhantomData`.
+#[rustc_polymorphize_error]
+pub fn phantom<T>(marker: PhantomData<T>) -> PhantomData<T> {
+    //~^ ERROR item has unused generic parameters
+    marker
+}
+
+// Function uses generic parameter in a struct that only uses it in `PhantomData`.
+#[rustc_polymorphize_error]
+fn phantom_field<T>(tagged: Tagged<T>) -> u32 {
+    //~^ ERROR item has unused generic parameters
+    tagged.value
+}
+
+// Function uses generic parameter in a struct that only uses it in such a struct.
+#[rustc_polymorphize_error]
+fn phantom_nested<T>(wrapper: &Wrapper<T>) -> u32 {
+    //~^ ERROR item has unused generic parameters
+    wrapper.0.value
+}
+
+// Function uses generic parameter as the pointee type of thin pointers.
+#[rustc_polymorphize_error]
+pub fn pointer<T>(value: &T) -> *const T {
+    //~^ ERROR item has unused generic parameters
+    value
+}
+
+// Function uses generic parameter in a struct that only uses it behind a pointer.
+#[rustc_polymorphize_error]
+fn pointer_field<T>(node: &Node<T>) -> u32 {
+    //~^ ERROR item has unused generic parameters
+    node.value
+}
+
+// Function uses generic parameter in the layout of a dereferenced pointer.
+#[rustc_polymorphize_error]
+pub fn used_deref<T: Copy>(value: &T) -> T {
+    *value
+}
+
+// Function uses generic parameter as the pointee type of wide pointers.
+#[rustc_polymorphize_error]
+pub fn used_unsized_pointer<T: ?Sized>(value: &T) -> *const T {
+    value
+}
+
+// Function uses generic parameter in the layout of an argument.
+#[rustc_polymorphize_error]
+pub fn used_layout<T>(value: Option<T>) -> Option<T> {
+    value
+}
+
+// Function uses generic parameter in substitutions to another function.
+#[rustc_polymorphize_error]
+pub fn used_substs<T>(marker: PhantomData<T>) -> PhantomData<T> {
+    phantom::<T>(marker)
+}
+
+// Function uses generic parameter in a type with a destructor.
+#[rustc_polymorphize_error]
+fn used_drop<T>(dropped: Dropped<T>) {
+    drop(dropped);
+}
+
+// Function uses generic parameter in a vtable.
+#[rustc_polymorphize_error]
+pub fn used_vtable<'a, T: 'a>(marker: &'a PhantomData<T>) -> &'a dyn Debug {
+    marker
+}
+
+fn main() {
+    phantom::<u32>(PhantomData);
+    phantom_field::<u32>(Tagged { value: 3, tag: PhantomData });
+    phantom_nested::<u32>(&Wrapper(Tagged { value: 3, tag: PhantomData }));
+    pointer::<u32>(&3);
+    pointer_field::<u32>(&Node { value: 3, next: std::ptr::null() });
+    used_deref::<u32>(&3);
+    used_unsized_pointer::<str>("3");
+    used_layout::<u32>(None);
+    used_substs::<u32>(PhantomData);
+    used_drop::<u32>(Dropped(PhantomData));
+    used_vtable::<u32>(&PhantomData);
+}
@@ -0,0 +1,17 @@
+//@ compile-flags: -Z print-type-sizes -Z print-type-sizes-format=json --crate-type lib
+//@ compile-flags: -Z print-type-sizes-threshold=8193
+//@ edition:2021
+//@ build-pass
+//@ ignore-pass
+
+// This file illustrates the JSON output for coroutines, using the async fn from `async.rs`.
+// The threshold filters out everything but the future of `test`.
+
+#![allow(dropping_copy_types)]
+
+async fn wait() {}
+
+pub async fn test(arg: [u8; 8192]) {
+    wait().await;
+    drop(arg);
+}
@@ -0,0 +1,25 @@
+//@ compile-flags: -Z print-type-sizes -Z print-type-sizes-format=json --crate-type=lib
+//@ compile-flags: -Z print-type-sizes-threshold=9
+//@ build-pass
+//@ ignore-pass
+
+// This file illustrates the JSON output, using the types from `padding.rs`.
+// The threshold filters out `S`, which is only 8 bytes large.
+
+#![allow(dead_code)]
+
+struct S {
+    a: bool,
+    b: bool,
+    g: i32,
+}
+
+enum E1 {
+    A(i32, i8),
+    B(S),
+}
+
+enum E2 {
+    A(i8, i32),
+    B(S),
+}
@@ -0,0 +1,48 @@
+#![crate_type = "lib"]
+
+pub fn removed() {}
+
+pub fn changed_sig(x: u32) -> u32 {
+    x
+}
+
+pub fn new_bound<T>(x: T) -> T {
+    x
+}
+
+pub struct Wrapper(pub u32);
+
+pub fn unwrap(w: Wrapper) -> u32 {
+    w.0
+}
+
+pub enum Grows {
+    A,
+}
+
+pub enum Closes {
+    A,
+}
+
+#[non_exhaustive]
+pub enum Open {
+    A,
+}
+
+pub struct Handle {
+    pub id: u32,
+}
+
+pub trait Tr {
+    fn required(&self);
+}
+
+pub mod inner {
+    pub const LIMIT: u32 = 1;
+}
+
+pub struct Defaulted<T>(pub T);
+
+pub struct Borrowed<'a> {
+    r: &'a u32,
+}
@@ -0,0 +1,66 @@
+// Check that `-Zsemver-baseline` reports the changes to the public API compared to a previous
+// release, and whether they are major or minor changes.
+
+//@ check-pass
+//@ aux-crate:baseline=semver-baseline.rs
+// The baseline is the same crate, built with a different `-C metadata`.
+//@ compile-flags: -Zsemver-baseline=baseline -Cmetadata=new
+
+#![crate_type = "lib"]
+
+use std::marker::PhantomData;
+
+pub fn changed_sig(x: u64) -> u32 {
+    x as u32
+}
+
+pub fn new_bound<T: Clone>(x: T) -> T {
+    x
+}
+
+// Unchanged, the paths into the baseline must compare equal to the local paths.
+pub struct Wrapper(pub u32);
+
+pub fn unwrap(w: Wrapper) -> u32 {
+    w.0
+}
+
+pub enum Grows {
+    A,
+    B,
+}
+
+#[non_exhaustive]
+pub enum Closes {
+    A,
+}
+
+#[non_exhaustive]
+pub enum Open {
+    A,
+    B,
+}
+
+pub struct Handle {
+    pub id: u32,
+    marker: PhantomData<*const ()>,
+}
+
+pub trait Tr {
+    fn required(&self);
+    fn provided(&self) {}
+    fn also_required(&self);
+}
+
+pub mod inner {
+    pub const LIMIT: u64 = 1;
+}
+
+pub fn added() {}
+
+pub struct Defaulted<T = u32>(pub T);
+
+pub struct Borrowed<'a, 'b> {
+    r: &'a u32,
+    s: &'b u32,
+}
@@ -0,0 +1,15 @@
+//@ compile-flags: --test
+
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout(0)] //~ ERROR `#[test_timeout(..)]` expects a positive number of seconds
+fn zero() {}
+
+#[test]
+#[test_timeout("10")] //~ ERROR `#[test_timeout(..)]` expects a positive number of seconds
+fn string() {}
+
+#[test]
+#[test_timeout(10, 20)] //~ ERROR `#[test_timeout(..)]` expects a positive number of seconds
+fn two_args() {}
@@ -0,0 +1,29 @@
+//@ no-prefer-dynamic
+//@ compile-flags: --test -Cpanic=abort -Zpanic_abort_tests
+//@ run-flags: --test-threads=1
+//@ run-fail
+//@ check-run-results
+//@ exec-env:RUST_BACKTRACE=0
+//@ normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+//@ ignore-android #120567
+//@ ignore-wasm no panic or subprocess support
+//@ ignore-emscripten no panic or subprocess support
+//@ ignore-sgx no subprocess support
+
+#![cfg(test)]
+#![feature(test_timeout)]
+
+#[test]
+#[test_timeout(1)]
+fn it_hangs() {
+    println!("waiting forever");
+    loop {
+        std::thread::park();
+    }
+}
+
+#[test]
+fn it_works() {
+    assert_eq!(1 + 1, 2);
+}
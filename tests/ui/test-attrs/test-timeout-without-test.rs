@@ -0,0 +1,21 @@
+//@ compile-flags: --test
+
+#![feature(test_timeout)]
+#![deny(unused_attributes)]
+#![allow(dead_code)]
+
+#[test_timeout(10)] //~ ERROR `#[test_timeout]` only has an effect on `#[test]` functions
+fn not_a_test() {}
+
+#[test_timeout(10)] //~ ERROR `#[test_timeout]` only has an effect on `#[test]` functions
+struct NotAFunction;
+
+#[test]
+#[test_timeout(10)]
+fn a_test() {}
+
+mod inner {
+    #[test]
+    #[test_timeout(10)]
+    fn a_test() {}
+}
@@ -0,0 +1 @@
+pub const BAZ: &str = "baz";
@@ -0,0 +1,5 @@
+// Check that `-Zextern-usage-report=json` requires JSON diagnostics.
+
+//@ compile-flags: -Zextern-usage-report=json --error-format=short
+
+fn main() {}
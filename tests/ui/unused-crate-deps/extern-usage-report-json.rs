@@ -0,0 +1,22 @@
+// Check that `-Zextern-usage-report=json` emits a single JSON message with the usage of every
+// `--extern` crate.
+
+//@ edition:2018
+//@ check-pass
+//@ compile-flags: --test -Zextern-usage-report=json --error-format=json
+//@ aux-crate:bar=bar.rs
+//@ aux-crate:baz=baz.rs
+//@ aux-crate:foo=foo.rs
+//@ aux-crate:macros=macros.rs
+
+fn main() {
+    println!("{} {}", foo::FOO, macros::answer!());
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar::BAR, "bar");
+    }
+}
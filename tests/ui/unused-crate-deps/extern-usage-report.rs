@@ -0,0 +1,22 @@
+// Check that `-Zextern-usage-report` notes the `--extern` crates that are unused, only used by
+// tests, or only used for their macros.
+
+//@ edition:2018
+//@ check-pass
+//@ compile-flags: --test -Zextern-usage-report
+//@ aux-crate:bar=bar.rs
+//@ aux-crate:baz=baz.rs
+//@ aux-crate:foo=foo.rs
+//@ aux-crate:macros=macros.rs
+
+fn main() {
+    println!("{} {}", foo::FOO, macros::answer!());
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_bar() {
+        assert_eq!(bar::BAR, "bar");
+    }
+}